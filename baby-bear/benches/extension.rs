@@ -1,9 +1,11 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use p3_baby_bear::BabyBear;
 use p3_field::extension::BinomialExtensionField;
+use p3_field::{ExtensionField, Field, PackedValue};
 use p3_field_testing::bench_func::{
     benchmark_inv, benchmark_mul_latency, benchmark_mul_throughput, benchmark_square,
 };
+use rand::Rng;
 
 type EF4 = BinomialExtensionField<BabyBear, 4>;
 type EF5 = BinomialExtensionField<BabyBear, 5>;
@@ -29,9 +31,47 @@ fn bench_qunitic_extension(c: &mut Criterion) {
     benchmark_mul_latency::<EF5, L_REPS>(c, name);
 }
 
+/// Compare a FRI-style fold, `lo + hi + beta * (lo - hi)`, over scalar and packed quartic
+/// extension elements.
+fn bench_packed_quartic_fold(c: &mut Criterion) {
+    type P = <EF4 as ExtensionField<BabyBear>>::ExtensionPacking;
+    const N: usize = 1 << 12;
+    let width = <BabyBear as Field>::Packing::WIDTH;
+
+    let mut rng = rand::thread_rng();
+    let lo: Vec<EF4> = (0..N).map(|_| rng.gen()).collect();
+    let hi: Vec<EF4> = (0..N).map(|_| rng.gen()).collect();
+    let beta: EF4 = rng.gen();
+
+    c.bench_function("fold/BinomialExtensionField<BabyBear, 4>", |b| {
+        b.iter(|| {
+            lo.iter()
+                .zip(&hi)
+                .map(|(&l, &h)| l + h + beta * (l - h))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let pack = <EF4 as ExtensionField<BabyBear>>::pack_ext_slice;
+    let packed_lo: Vec<P> = lo.chunks_exact(width).map(pack).collect();
+    let packed_hi: Vec<P> = hi.chunks_exact(width).map(pack).collect();
+    let packed_beta = pack(&vec![beta; width]);
+
+    c.bench_function("fold/packed BinomialExtensionField<BabyBear, 4>", |b| {
+        b.iter(|| {
+            packed_lo
+                .iter()
+                .zip(&packed_hi)
+                .map(|(&l, &h)| l + h + packed_beta * (l - h))
+                .collect::<Vec<_>>()
+        })
+    });
+}
+
 criterion_group!(
     bench_babybear_ef,
     bench_quartic_extension,
-    bench_qunitic_extension
+    bench_qunitic_extension,
+    bench_packed_quartic_fold
 );
 criterion_main!(bench_babybear_ef);
@@ -0,0 +1,83 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use p3_baby_bear::{
+    BabyBear, BabyBearInternalLayerParameters, Poseidon2InternalLayerBabyBear,
+    BABYBEAR_RC16_INTERNAL, BABYBEAR_RC24_INTERNAL, BABYBEAR_S_BOX_DEGREE,
+};
+use p3_field::{Field, FieldAlgebra};
+use p3_monty_31::InternalLayerBaseParameters;
+use p3_poseidon2::{InternalLayer, InternalLayerConstructor};
+use rand::Rng;
+
+type F = BabyBear;
+type P = <F as Field>::Packing;
+
+/// Benchmark the internal (partial round) layers of Poseidon2 on their own, both a single
+/// application of the linear layer and the full run of partial rounds.
+fn bench_internal_layer(c: &mut Criterion) {
+    let mut rng = rand::thread_rng();
+
+    let state_16: [F; 16] = rng.gen();
+    c.bench_function("internal_linear_layer::<BabyBear, 16>", |b| {
+        b.iter(|| {
+            let mut state = state_16;
+            let part_sum: F = state[1..].iter().cloned().sum();
+            let full_sum = part_sum + state[0];
+            state[0] = part_sum - state[0];
+            BabyBearInternalLayerParameters::internal_layer_mat_mul(&mut state, full_sum);
+            state
+        })
+    });
+
+    let state_24: [F; 24] = rng.gen();
+    c.bench_function("internal_linear_layer::<BabyBear, 24>", |b| {
+        b.iter(|| {
+            let mut state = state_24;
+            let part_sum: F = state[1..].iter().cloned().sum();
+            let full_sum = part_sum + state[0];
+            state[0] = part_sum - state[0];
+            BabyBearInternalLayerParameters::internal_layer_mat_mul(&mut state, full_sum);
+            state
+        })
+    });
+    c.bench_function("generic_internal_linear_layer::<BabyBear, 24>", |b| {
+        b.iter(|| {
+            let mut state = state_24;
+            BabyBearInternalLayerParameters::generic_internal_linear_layer(&mut state);
+            state
+        })
+    });
+
+    let layer_16 =
+        <Poseidon2InternalLayerBabyBear<16> as InternalLayerConstructor<P>>::new_from_constants(
+            BABYBEAR_RC16_INTERNAL.to_vec(),
+        );
+    bench_partial_rounds::<F, 16>(c, "BabyBear", &layer_16, state_16);
+    bench_partial_rounds::<P, 16>(c, "BabyBear::Packing", &layer_16, rng.gen());
+
+    let layer_24 =
+        <Poseidon2InternalLayerBabyBear<24> as InternalLayerConstructor<P>>::new_from_constants(
+            BABYBEAR_RC24_INTERNAL.to_vec(),
+        );
+    bench_partial_rounds::<F, 24>(c, "BabyBear", &layer_24, state_24);
+    bench_partial_rounds::<P, 24>(c, "BabyBear::Packing", &layer_24, rng.gen());
+}
+
+fn bench_partial_rounds<FA, const WIDTH: usize>(
+    c: &mut Criterion,
+    name: &str,
+    layer: &impl InternalLayer<FA, WIDTH, BABYBEAR_S_BOX_DEGREE>,
+    input: [FA; WIDTH],
+) where
+    FA: FieldAlgebra + Copy,
+{
+    c.bench_function(&format!("partial_rounds::<{name}, {WIDTH}>"), |b| {
+        b.iter(|| {
+            let mut state = input;
+            layer.permute_state(&mut state);
+            state
+        })
+    });
+}
+
+criterion_group!(babybear_poseidon2, bench_internal_layer);
+criterion_main!(babybear_poseidon2);
@@ -87,6 +87,16 @@ impl TwoAdicData for BabyBearParameters {
     ]);
 }
 
+impl BinomialExtensionData<3> for BabyBearParameters {
+    const W: BabyBear = BabyBear::new(2);
+    const DTH_ROOT: BabyBear = BabyBear::new(1314723123);
+    const EXT_GENERATOR: [BabyBear; 3] = BabyBear::new_array([9, 1, 0]);
+    const EXT_TWO_ADICITY: usize = 27;
+
+    type ArrayLike = [[BabyBear; 3]; 0];
+    const TWO_ADIC_EXTENSION_GENERATORS: Self::ArrayLike = [];
+}
+
 impl BinomialExtensionData<4> for BabyBearParameters {
     const W: BabyBear = BabyBear::new(11);
     const DTH_ROOT: BabyBear = BabyBear::new(1728404513);
@@ -108,12 +118,15 @@ impl BinomialExtensionData<5> for BabyBearParameters {
     const TWO_ADIC_EXTENSION_GENERATORS: Self::ArrayLike = [];
 }
 
+#[cfg(all(test, feature = "bytemuck"))]
+p3_field_testing::test_field_bytemuck!(crate::BabyBear, u32, 0x78000001);
+
 #[cfg(test)]
 mod tests {
     use core::array;
 
     use p3_field::{PrimeField32, PrimeField64, TwoAdicField};
-    use p3_field_testing::{test_field, test_field_dft, test_two_adic_field};
+    use p3_field_testing::{test_field, test_field_dft, test_prime_field_32, test_two_adic_field};
 
     use super::*;
 
@@ -222,9 +235,14 @@ mod tests {
         let m2_serialized = serde_json::to_string(&m2).unwrap();
         let m2_deserialized: F = serde_json::from_str(&m2_serialized).unwrap();
         assert_eq!(m2, m2_deserialized);
+
+        // Elements are serialized as their canonical values, and non-canonical values are rejected.
+        assert_eq!(serde_json::to_string(&f_p_minus_1).unwrap(), "2013265920");
+        assert!(serde_json::from_str::<F>("2013265921").is_err());
     }
 
     test_field!(crate::BabyBear);
+    test_prime_field_32!(crate::BabyBear);
     test_two_adic_field!(crate::BabyBear);
 
     test_field_dft!(radix2dit, crate::BabyBear, p3_dft::Radix2Dit<_>);
@@ -235,4 +253,105 @@ mod tests {
         crate::BabyBear,
         p3_monty_31::dft::RecursiveDft<_>
     );
+    test_field_dft!(
+        recur_bowers,
+        crate::BabyBear,
+        p3_monty_31::dft::RecursiveBowers<_>
+    );
+    test_field_dft!(
+        dispatcher,
+        crate::BabyBear,
+        p3_monty_31::dft::DftDispatcher<_>
+    );
+
+    #[test]
+    fn recursive_bowers_matches_radix_2_dit() {
+        use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+        use p3_matrix::dense::RowMajorMatrix;
+        use p3_matrix::Matrix;
+        use p3_monty_31::dft::{RecursiveBowers, RecursiveDft};
+
+        let mut rng = rand::thread_rng();
+        let bowers = RecursiveBowers::default();
+        let dit = Radix2Dit::default();
+        let recursive = RecursiveDft::default();
+
+        // Cover the unrolled small sizes, the iterative kernels and the recursive ones.
+        for log_h in 0..13 {
+            let h = 1 << log_h;
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 5);
+
+            // RecursiveBowers returns natural order; RecursiveDft a bit-reversed view of it.
+            let dft = bowers.dft_batch(mat.clone());
+            assert_eq!(dft, dit.dft_batch(mat.clone()));
+            assert_eq!(dft, recursive.dft_batch(mat.clone()).to_row_major_matrix());
+            assert_eq!(bowers.idft_batch(mat.clone()), dit.idft_batch(mat.clone()));
+
+            for added_bits in 0..3 {
+                let shift = F::GENERATOR;
+                assert_eq!(
+                    bowers.coset_lde_batch(mat.clone(), added_bits, shift),
+                    dit.coset_lde_batch(mat.clone(), added_bits, shift)
+                );
+                assert_eq!(
+                    bowers.lde_batch(mat.clone(), added_bits),
+                    dit.lde_batch(mat.clone(), added_bits)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dft_dispatcher_routes_agree() {
+        use p3_dft::TwoAdicSubgroupDft;
+        use p3_matrix::bitrev::BitReversableMatrix;
+        use p3_matrix::dense::RowMajorMatrix;
+        use p3_matrix::Matrix;
+        use p3_monty_31::dft::{DftDispatcher, DftRoute};
+
+        let mut rng = rand::thread_rng();
+        let routes: [DftDispatcher<F>; 5] = [
+            DftDispatcher::new(|_, _| DftRoute::Radix2Dit),
+            DftDispatcher::new(|_, _| DftRoute::Radix2DitParallel),
+            DftDispatcher::new(|_, _| DftRoute::Radix2Bowers),
+            DftDispatcher::new(|_, _| DftRoute::Recursive),
+            DftDispatcher::new(|_, _| DftRoute::RecursiveBowers),
+        ];
+        for log_h in 0..10 {
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, 1 << log_h, 5);
+            let shift = F::GENERATOR;
+            let [expected, rest @ ..] = &routes;
+            let dft = expected.dft_batch(mat.clone()).to_row_major_matrix();
+            let bit_reversed_dft = expected
+                .dft_batch(mat.clone())
+                .bit_reverse_rows()
+                .to_row_major_matrix();
+            let idft = expected.idft_batch(mat.clone());
+            let lde = expected.lde_batch(mat.clone(), 2).to_row_major_matrix();
+            let coset_lde = expected
+                .coset_lde_batch(mat.clone(), 1, shift)
+                .to_row_major_matrix();
+            for dispatcher in rest {
+                assert_eq!(dispatcher.dft_batch(mat.clone()).to_row_major_matrix(), dft);
+                assert_eq!(
+                    dispatcher
+                        .dft_batch(mat.clone())
+                        .bit_reverse_rows()
+                        .to_row_major_matrix(),
+                    bit_reversed_dft
+                );
+                assert_eq!(dispatcher.idft_batch(mat.clone()), idft);
+                assert_eq!(
+                    dispatcher.lde_batch(mat.clone(), 2).to_row_major_matrix(),
+                    lde
+                );
+                assert_eq!(
+                    dispatcher
+                        .coset_lde_batch(mat.clone(), 1, shift)
+                        .to_row_major_matrix(),
+                    coset_lde
+                );
+            }
+        }
+    }
 }
@@ -1,10 +1,47 @@
+#[cfg(test)]
+mod test_cubic_extension {
+    use p3_field::extension::{BinomialExtensionField, HasFrobenius};
+    use p3_field::{FieldAlgebra, PrimeField64, TwoAdicField};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
+    use rand::random;
+
+    use crate::BabyBear;
+
+    type F = BabyBear;
+    type EF = BinomialExtensionField<F, 3>;
+
+    test_field!(super::EF);
+    test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
+
+    #[test]
+    fn frobenius_has_order_three() {
+        for _ in 0..100 {
+            let x: EF = random();
+            assert_eq!(x.repeated_frobenius(3), x);
+            assert_eq!(x.frobenius().frobenius().frobenius(), x);
+            assert_eq!(x.frobenius(), x.exp_u64(F::ORDER_U64));
+        }
+    }
+
+    #[test]
+    fn two_adic_generator_order() {
+        let g = EF::two_adic_generator(EF::TWO_ADICITY);
+        assert_eq!(g.exp_power_of_2(EF::TWO_ADICITY), EF::ONE);
+        assert_ne!(g.exp_power_of_2(EF::TWO_ADICITY - 1), EF::ONE);
+    }
+}
+
 #[cfg(test)]
 mod test_quartic_extension {
     use alloc::format;
+    use alloc::vec::Vec;
 
     use p3_field::extension::BinomialExtensionField;
     use p3_field::{FieldAlgebra, FieldExtensionAlgebra};
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
+    use rand::random;
+    use serde::{Deserialize, Serialize};
 
     use crate::BabyBear;
 
@@ -13,6 +50,7 @@ mod test_quartic_extension {
 
     test_field!(super::EF);
     test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
 
     #[test]
     fn display() {
@@ -28,12 +66,103 @@ mod test_quartic_extension {
             "2 + X + 2 X^3"
         );
     }
+
+    /// Shaped like the opened values of a STARK proof.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct OpenedValues {
+        trace_local: Vec<EF>,
+        quotient_chunks: Vec<Vec<EF>>,
+    }
+
+    #[test]
+    fn base_slice_views() {
+        use p3_field::extension::{
+            as_base_slice, as_base_slice_mut, from_base_slice, from_base_slice_mut,
+        };
+
+        fn coeffs_of(x: &EF) -> &[F] {
+            x.as_base_slice()
+        }
+
+        let mut xs: Vec<EF> = (0..7).map(|_| random()).collect();
+        let coeffs: Vec<F> = xs.iter().flat_map(|x| coeffs_of(x).to_vec()).collect();
+        assert_eq!(as_base_slice(&xs), coeffs);
+        assert_eq!(from_base_slice::<F, 4>(&coeffs).unwrap(), xs);
+        assert_eq!(from_base_slice::<F, 4>(as_base_slice(&xs)).unwrap(), xs);
+        assert!(from_base_slice::<F, 4>(&[]).unwrap().is_empty());
+
+        // Lengths which are not a multiple of the degree are rejected.
+        for len in [1, 2, 3, 5, 27] {
+            assert!(from_base_slice::<F, 4>(&coeffs[..len]).is_none());
+        }
+        // So are offset sub-slices of the wrong length, while offset ones of the right length
+        // have no alignment requirement beyond that of the base field.
+        assert!(from_base_slice::<F, 4>(&coeffs[1..]).is_none());
+        assert_eq!(
+            coeffs_of(&from_base_slice::<F, 4>(&coeffs[1..25]).unwrap()[0]),
+            &coeffs[1..5]
+        );
+
+        let y: EF = random();
+        as_base_slice_mut(&mut xs)[4..8].copy_from_slice(coeffs_of(&y));
+        assert_eq!(xs[1], y);
+
+        let mut coeffs = coeffs;
+        from_base_slice_mut::<F, 4>(&mut coeffs).unwrap()[2] = y;
+        assert_eq!(&coeffs[8..12], coeffs_of(&y));
+        assert!(from_base_slice_mut::<F, 4>(&mut coeffs[..6]).is_none());
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let x: EF = random();
+        let json = serde_json::to_string(&x).unwrap();
+        assert_eq!(serde_json::from_str::<EF>(&json).unwrap(), x);
+        let bytes = postcard::to_allocvec(&x).unwrap();
+        assert_eq!(postcard::from_bytes::<EF>(&bytes).unwrap(), x);
+
+        let xs: Vec<EF> = (0..10).map(|_| random()).collect();
+        let json = serde_json::to_string(&xs).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<EF>>(&json).unwrap(), xs);
+        let bytes = postcard::to_allocvec(&xs).unwrap();
+        assert_eq!(postcard::from_bytes::<Vec<EF>>(&bytes).unwrap(), xs);
+
+        let opened = OpenedValues {
+            trace_local: (0..3).map(|_| random()).collect(),
+            quotient_chunks: (0..2).map(|_| (0..4).map(|_| random()).collect()).collect(),
+        };
+        let json = serde_json::to_string(&opened).unwrap();
+        assert_eq!(serde_json::from_str::<OpenedValues>(&json).unwrap(), opened);
+        let bytes = postcard::to_allocvec(&opened).unwrap();
+        assert_eq!(
+            postcard::from_bytes::<OpenedValues>(&bytes).unwrap(),
+            opened
+        );
+    }
+
+    #[test]
+    fn serde_base_element_is_padded() {
+        let x: F = random();
+        assert_eq!(
+            serde_json::to_string(&EF::from_base(x)).unwrap(),
+            serde_json::to_string(&[x, F::ZERO, F::ZERO, F::ZERO]).unwrap()
+        );
+    }
+
+    #[test]
+    fn serde_rejects_wrong_coefficient_count() {
+        let coeffs: Vec<F> = (0..5).map(|_| random()).collect();
+        for len in [0, 3, 5] {
+            let json = serde_json::to_string(&coeffs[..len]).unwrap();
+            assert!(serde_json::from_str::<EF>(&json).is_err());
+        }
+    }
 }
 
 #[cfg(test)]
 mod test_quintic_extension {
     use p3_field::extension::BinomialExtensionField;
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
 
     use crate::BabyBear;
 
@@ -42,4 +171,5 @@ mod test_quintic_extension {
 
     test_field!(super::EF);
     test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
 }
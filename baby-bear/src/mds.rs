@@ -7,7 +7,7 @@
 //! database.
 
 use p3_mds::util::first_row_to_first_col;
-use p3_monty_31::{MDSUtils, MdsMatrixMontyField31};
+use p3_monty_31::{MDSUtils, MDSUtils24, MdsMatrixMontyField31};
 
 #[derive(Clone, Default)]
 pub struct MDSBabyBearData;
@@ -18,12 +18,6 @@ impl MDSUtils for MDSBabyBearData {
         first_row_to_first_col(&[1, 1, 2, 1, 8, 9, 10, 7, 5, 9, 4, 10]);
     const MATRIX_CIRC_MDS_16_COL: [i64; 16] =
         first_row_to_first_col(&[1, 1, 51, 1, 11, 17, 2, 1, 101, 63, 15, 2, 67, 22, 13, 3]);
-    const MATRIX_CIRC_MDS_24_COL: [i64; 24] = first_row_to_first_col(&[
-        0x2D0AAAAB, 0x64850517, 0x17F5551D, 0x04ECBEB5, 0x6D91A8D5, 0x60703026, 0x18D6F3CA,
-        0x729601A7, 0x77CDA9E2, 0x3C0F5038, 0x26D52A61, 0x0360405D, 0x68FC71C8, 0x2495A71D,
-        0x5D57AFC2, 0x1689DD98, 0x3C2C3DBE, 0x0C23DC41, 0x0524C7F2, 0x6BE4DF69, 0x0A6E572C,
-        0x5C7790FA, 0x17E118F6, 0x0878A07F,
-    ]);
     const MATRIX_CIRC_MDS_32_COL: [i64; 32] = first_row_to_first_col(&[
         0x0BC00000, 0x2BED8F81, 0x337E0652, 0x4C4535D1, 0x4AF2DC32, 0x2DB4050F, 0x676A7CE3,
         0x3A06B68E, 0x5E95C1B1, 0x2C5F54A0, 0x2332F13D, 0x58E757F1, 0x3AA6DCCE, 0x607EE630,
@@ -45,6 +39,15 @@ impl MDSUtils for MDSBabyBearData {
     ]);
 }
 
+impl MDSUtils24 for MDSBabyBearData {
+    const MATRIX_CIRC_MDS_24_COL: [i64; 24] = first_row_to_first_col(&[
+        0x2D0AAAAB, 0x64850517, 0x17F5551D, 0x04ECBEB5, 0x6D91A8D5, 0x60703026, 0x18D6F3CA,
+        0x729601A7, 0x77CDA9E2, 0x3C0F5038, 0x26D52A61, 0x0360405D, 0x68FC71C8, 0x2495A71D,
+        0x5D57AFC2, 0x1689DD98, 0x3C2C3DBE, 0x0C23DC41, 0x0524C7F2, 0x6BE4DF69, 0x0A6E572C,
+        0x5C7790FA, 0x17E118F6, 0x0878A07F,
+    ]);
+}
+
 pub type MdsMatrixBabyBear = MdsMatrixMontyField31<MDSBabyBearData>;
 
 #[cfg(test)]
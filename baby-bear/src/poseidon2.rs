@@ -20,7 +20,7 @@ use p3_monty_31::{
     GenericPoseidon2LinearLayersMonty31, InternalLayerBaseParameters, InternalLayerParameters,
     MontyField31, Poseidon2ExternalLayerMonty31, Poseidon2InternalLayerMonty31,
 };
-use p3_poseidon2::Poseidon2;
+use p3_poseidon2::{ExternalLayerConstants, Poseidon2};
 
 use crate::{BabyBear, BabyBearParameters};
 
@@ -34,7 +34,7 @@ pub type Poseidon2ExternalLayerBabyBear<const WIDTH: usize> =
 ///
 /// As p - 1 = 15 * 2^{27} the neither 3 nor 5 satisfy gcd(p - 1, D) = 1.
 /// Instead we use the next smallest available value, namely 7.
-const BABYBEAR_S_BOX_DEGREE: u64 = 7;
+pub const BABYBEAR_S_BOX_DEGREE: u64 = 7;
 
 /// An implementation of the Poseidon2 hash function specialised to run on the current architecture.
 ///
@@ -136,20 +136,13 @@ impl InternalLayerBaseParameters<BabyBearParameters, 16> for BabyBearInternalLay
         state[6] = sum - state[6].halve();
         state[7] = sum - (state[7].double() + state[7]);
         state[8] = sum - state[8].double().double();
-        state[9] = state[9].mul_2exp_neg_n(8);
-        state[9] += sum;
-        state[10] = state[10].mul_2exp_neg_n(2);
-        state[10] += sum;
-        state[11] = state[11].mul_2exp_neg_n(3);
-        state[11] += sum;
-        state[12] = state[12].mul_2exp_neg_n(27);
-        state[12] += sum;
-        state[13] = state[13].mul_2exp_neg_n(8);
-        state[13] = sum - state[13];
-        state[14] = state[14].mul_2exp_neg_n(4);
-        state[14] = sum - state[14];
-        state[15] = state[15].mul_2exp_neg_n(27);
-        state[15] = sum - state[15];
+        state[9] = state[9].mul_2exp_neg_n_add(8, sum);
+        state[10] = state[10].mul_2exp_neg_n_add(2, sum);
+        state[11] = state[11].mul_2exp_neg_n_add(3, sum);
+        state[12] = state[12].mul_2exp_neg_n_add(27, sum);
+        state[13] = (-state[13]).mul_2exp_neg_n_add(8, sum);
+        state[14] = (-state[14]).mul_2exp_neg_n_add(4, sum);
+        state[15] = (-state[15]).mul_2exp_neg_n_add(27, sum);
     }
 
     fn generic_internal_linear_layer<FA>(state: &mut [FA; 16])
@@ -198,36 +191,21 @@ impl InternalLayerBaseParameters<BabyBearParameters, 24> for BabyBearInternalLay
         state[6] = sum - state[6].halve();
         state[7] = sum - (state[7].double() + state[7]);
         state[8] = sum - state[8].double().double();
-        state[9] = state[9].mul_2exp_neg_n(8);
-        state[9] += sum;
-        state[10] = state[10].mul_2exp_neg_n(2);
-        state[10] += sum;
-        state[11] = state[11].mul_2exp_neg_n(3);
-        state[11] += sum;
-        state[12] = state[12].mul_2exp_neg_n(4);
-        state[12] += sum;
-        state[13] = state[13].mul_2exp_neg_n(7);
-        state[13] += sum;
-        state[14] = state[14].mul_2exp_neg_n(9);
-        state[14] += sum;
-        state[15] = state[15].mul_2exp_neg_n(27);
-        state[15] += sum;
-        state[16] = state[16].mul_2exp_neg_n(8);
-        state[16] = sum - state[16];
-        state[17] = state[17].mul_2exp_neg_n(2);
-        state[17] = sum - state[17];
-        state[18] = state[18].mul_2exp_neg_n(3);
-        state[18] = sum - state[18];
-        state[19] = state[19].mul_2exp_neg_n(4);
-        state[19] = sum - state[19];
-        state[20] = state[20].mul_2exp_neg_n(5);
-        state[20] = sum - state[20];
-        state[21] = state[21].mul_2exp_neg_n(6);
-        state[21] = sum - state[21];
-        state[22] = state[22].mul_2exp_neg_n(7);
-        state[22] = sum - state[22];
-        state[23] = state[23].mul_2exp_neg_n(27);
-        state[23] = sum - state[23];
+        state[9] = state[9].mul_2exp_neg_n_add(8, sum);
+        state[10] = state[10].mul_2exp_neg_n_add(2, sum);
+        state[11] = state[11].mul_2exp_neg_n_add(3, sum);
+        state[12] = state[12].mul_2exp_neg_n_add(4, sum);
+        state[13] = state[13].mul_2exp_neg_n_add(7, sum);
+        state[14] = state[14].mul_2exp_neg_n_add(9, sum);
+        state[15] = state[15].mul_2exp_neg_n_add(27, sum);
+        state[16] = (-state[16]).mul_2exp_neg_n_add(8, sum);
+        state[17] = (-state[17]).mul_2exp_neg_n_add(2, sum);
+        state[18] = (-state[18]).mul_2exp_neg_n_add(3, sum);
+        state[19] = (-state[19]).mul_2exp_neg_n_add(4, sum);
+        state[20] = (-state[20]).mul_2exp_neg_n_add(5, sum);
+        state[21] = (-state[21]).mul_2exp_neg_n_add(6, sum);
+        state[22] = (-state[22]).mul_2exp_neg_n_add(7, sum);
+        state[23] = (-state[23]).mul_2exp_neg_n_add(27, sum);
     }
 
     fn generic_internal_linear_layer<FA>(state: &mut [FA; 24])
@@ -258,9 +236,165 @@ impl InternalLayerBaseParameters<BabyBearParameters, 24> for BabyBearInternalLay
 impl InternalLayerParameters<BabyBearParameters, 16> for BabyBearInternalLayerParameters {}
 impl InternalLayerParameters<BabyBearParameters, 24> for BabyBearInternalLayerParameters {}
 
+/// Create a width 16 Poseidon2 permutation on BabyBear with fixed round constants.
+///
+/// These are the constants `Poseidon2BabyBear::<16>::new_from_rng_128` samples from
+/// `Xoroshiro128Plus::seed_from_u64(1)`, as described on `Poseidon2::new_from_rng`. They are
+/// stored as canonical values, which `new_array` converts to MONTY form at compile time.
+pub fn default_babybear_poseidon2_16() -> Poseidon2BabyBear<16> {
+    Poseidon2::new(
+        ExternalLayerConstants::new(
+            BABYBEAR_RC16_EXTERNAL_INITIAL.to_vec(),
+            BABYBEAR_RC16_EXTERNAL_FINAL.to_vec(),
+        ),
+        BABYBEAR_RC16_INTERNAL.to_vec(),
+    )
+}
+
+/// Create a width 24 Poseidon2 permutation on BabyBear with fixed round constants, sampled as
+/// for [`default_babybear_poseidon2_16`].
+pub fn default_babybear_poseidon2_24() -> Poseidon2BabyBear<24> {
+    Poseidon2::new(
+        ExternalLayerConstants::new(
+            BABYBEAR_RC24_EXTERNAL_INITIAL.to_vec(),
+            BABYBEAR_RC24_EXTERNAL_FINAL.to_vec(),
+        ),
+        BABYBEAR_RC24_INTERNAL.to_vec(),
+    )
+}
+
+/// Initial external round constants for the width 16 Poseidon2 permutation on BabyBear.
+///
+/// See [`default_babybear_poseidon2_16`] for how these were generated.
+pub const BABYBEAR_RC16_EXTERNAL_INITIAL: [[BabyBear; 16]; 4] = [
+    BabyBear::new_array([
+        0x4ec2680c, 0x110279cb, 0x332d1f04, 0x07da39a8, 0x20d60d25, 0x06837f03, 0x5c499950,
+        0x11d53b04, 0x4769dfb8, 0x4d9fb1bf, 0x21dd1576, 0x58811e55, 0x4d9aa329, 0x2b412236,
+        0x3822164a, 0x1345ce59,
+    ]),
+    BabyBear::new_array([
+        0x3e8b66a4, 0x3104f884, 0x53cf147f, 0x4c3c5d00, 0x0cce8acb, 0x35eb8ab9, 0x4eb90117,
+        0x00b0ab74, 0x3dc5ced0, 0x57e20602, 0x5543d924, 0x06a06354, 0x4305bf7b, 0x0516d38b,
+        0x288628b2, 0x0e801d9d,
+    ]),
+    BabyBear::new_array([
+        0x4fc7d3f6, 0x5ac756e3, 0x6dbc7a1d, 0x22768c25, 0x02d798fd, 0x6ab7f8c8, 0x610a3fa1,
+        0x664b611a, 0x61807b01, 0x4277c420, 0x429c112e, 0x2873398e, 0x74fd36a3, 0x5180e3b0,
+        0x00b25b05, 0x744737a6,
+    ]),
+    BabyBear::new_array([
+        0x1f828b47, 0x73760efb, 0x571450e7, 0x471cdeb5, 0x48d335c3, 0x46913bad, 0x19d6c553,
+        0x72f92fbd, 0x1e25480b, 0x110700ef, 0x3e6c0276, 0x363288de, 0x4b0a4dd6, 0x338b375d,
+        0x124cd27e, 0x1e178795,
+    ]),
+];
+
+/// Terminal external round constants for the width 16 Poseidon2 permutation on BabyBear.
+///
+/// See [`default_babybear_poseidon2_16`] for how these were generated.
+pub const BABYBEAR_RC16_EXTERNAL_FINAL: [[BabyBear; 16]; 4] = [
+    BabyBear::new_array([
+        0x5777f011, 0x68948ceb, 0x19c2a7bc, 0x5691b910, 0x3491dc1c, 0x20c91a20, 0x56442fd6,
+        0x37fe675f, 0x6a4822d6, 0x458da37c, 0x06688b5a, 0x2299fbff, 0x776837e8, 0x32ee44aa,
+        0x37b964b7, 0x3b4b31b4,
+    ]),
+    BabyBear::new_array([
+        0x6dbd1269, 0x1a1f74c6, 0x5c6b1db1, 0x3670308a, 0x23d18114, 0x22bfe022, 0x4b432285,
+        0x064e58ed, 0x108af480, 0x0a298030, 0x15ce7f03, 0x5831987c, 0x56e222ba, 0x61818a80,
+        0x1dc0806d, 0x2c2e3a27,
+    ]),
+    BabyBear::new_array([
+        0x26d36bf6, 0x1bb4b661, 0x0558a76b, 0x1575d881, 0x0dba9003, 0x69afcec8, 0x52436deb,
+        0x2c6805c3, 0x2f4b7a6e, 0x512367cc, 0x560dc002, 0x03139b8a, 0x2b987eca, 0x40d2c58a,
+        0x4beed74d, 0x12925c15,
+    ]),
+    BabyBear::new_array([
+        0x29e264aa, 0x57cff845, 0x07dfd045, 0x505cd248, 0x1c6b0406, 0x55c4a053, 0x00252590,
+        0x506ba70e, 0x747edfda, 0x690819df, 0x4bc54e23, 0x5ef7512c, 0x33870d43, 0x084b39d1,
+        0x3ec935d6, 0x19340ffb,
+    ]),
+];
+
+/// Internal round constants for the width 16 Poseidon2 permutation on BabyBear.
+///
+/// See [`default_babybear_poseidon2_16`] for how these were generated.
+pub const BABYBEAR_RC16_INTERNAL: [BabyBear; 13] = BabyBear::new_array([
+    0x1801ccd8, 0x131d9e83, 0x42ec25ee, 0x05fc787d, 0x0c1356db, 0x491aae7c, 0x40e3021a, 0x03d25f0a,
+    0x068bdafc, 0x2b32678f, 0x631ce19f, 0x2f8cc233, 0x401ce61f,
+]);
+
+/// Initial external round constants for the width 24 Poseidon2 permutation on BabyBear.
+///
+/// See [`default_babybear_poseidon2_24`] for how these were generated.
+pub const BABYBEAR_RC24_EXTERNAL_INITIAL: [[BabyBear; 24]; 4] = [
+    BabyBear::new_array([
+        0x4ec2680c, 0x110279cb, 0x332d1f04, 0x07da39a8, 0x20d60d25, 0x06837f03, 0x5c499950,
+        0x11d53b04, 0x4769dfb8, 0x4d9fb1bf, 0x21dd1576, 0x58811e55, 0x4d9aa329, 0x2b412236,
+        0x3822164a, 0x1345ce59, 0x3e8b66a4, 0x3104f884, 0x53cf147f, 0x4c3c5d00, 0x0cce8acb,
+        0x35eb8ab9, 0x4eb90117, 0x00b0ab74,
+    ]),
+    BabyBear::new_array([
+        0x3dc5ced0, 0x57e20602, 0x5543d924, 0x06a06354, 0x4305bf7b, 0x0516d38b, 0x288628b2,
+        0x0e801d9d, 0x4fc7d3f6, 0x5ac756e3, 0x6dbc7a1d, 0x22768c25, 0x02d798fd, 0x6ab7f8c8,
+        0x610a3fa1, 0x664b611a, 0x61807b01, 0x4277c420, 0x429c112e, 0x2873398e, 0x74fd36a3,
+        0x5180e3b0, 0x00b25b05, 0x744737a6,
+    ]),
+    BabyBear::new_array([
+        0x1f828b47, 0x73760efb, 0x571450e7, 0x471cdeb5, 0x48d335c3, 0x46913bad, 0x19d6c553,
+        0x72f92fbd, 0x1e25480b, 0x110700ef, 0x3e6c0276, 0x363288de, 0x4b0a4dd6, 0x338b375d,
+        0x124cd27e, 0x1e178795, 0x5777f011, 0x68948ceb, 0x19c2a7bc, 0x5691b910, 0x3491dc1c,
+        0x20c91a20, 0x56442fd6, 0x37fe675f,
+    ]),
+    BabyBear::new_array([
+        0x6a4822d6, 0x458da37c, 0x06688b5a, 0x2299fbff, 0x776837e8, 0x32ee44aa, 0x37b964b7,
+        0x3b4b31b4, 0x6dbd1269, 0x1a1f74c6, 0x5c6b1db1, 0x3670308a, 0x23d18114, 0x22bfe022,
+        0x4b432285, 0x064e58ed, 0x108af480, 0x0a298030, 0x15ce7f03, 0x5831987c, 0x56e222ba,
+        0x61818a80, 0x1dc0806d, 0x2c2e3a27,
+    ]),
+];
+
+/// Terminal external round constants for the width 24 Poseidon2 permutation on BabyBear.
+///
+/// See [`default_babybear_poseidon2_24`] for how these were generated.
+pub const BABYBEAR_RC24_EXTERNAL_FINAL: [[BabyBear; 24]; 4] = [
+    BabyBear::new_array([
+        0x26d36bf6, 0x1bb4b661, 0x0558a76b, 0x1575d881, 0x0dba9003, 0x69afcec8, 0x52436deb,
+        0x2c6805c3, 0x2f4b7a6e, 0x512367cc, 0x560dc002, 0x03139b8a, 0x2b987eca, 0x40d2c58a,
+        0x4beed74d, 0x12925c15, 0x29e264aa, 0x57cff845, 0x07dfd045, 0x505cd248, 0x1c6b0406,
+        0x55c4a053, 0x00252590, 0x506ba70e,
+    ]),
+    BabyBear::new_array([
+        0x747edfda, 0x690819df, 0x4bc54e23, 0x5ef7512c, 0x33870d43, 0x084b39d1, 0x3ec935d6,
+        0x19340ffb, 0x1801ccd8, 0x131d9e83, 0x42ec25ee, 0x05fc787d, 0x0c1356db, 0x491aae7c,
+        0x40e3021a, 0x03d25f0a, 0x068bdafc, 0x2b32678f, 0x631ce19f, 0x2f8cc233, 0x401ce61f,
+        0x5de64a96, 0x15cd53a1, 0x24021ad3,
+    ]),
+    BabyBear::new_array([
+        0x7466b2ab, 0x5dfba9de, 0x3f3b5642, 0x4dd9bbc1, 0x09cde2aa, 0x73827615, 0x677a602b,
+        0x69bff5df, 0x6bca4452, 0x538eacbb, 0x21695e2f, 0x48fd28a5, 0x53db5c4c, 0x39ab7abe,
+        0x60226ca8, 0x6f39ce27, 0x6dd72702, 0x61c72ca5, 0x0b2abe90, 0x3673352a, 0x36298c76,
+        0x050de59d, 0x4169c3ee, 0x63258d2a,
+    ]),
+    BabyBear::new_array([
+        0x59c45549, 0x3eb0408a, 0x72ce8221, 0x7372c616, 0x346f1d76, 0x42b0e84c, 0x271cb214,
+        0x0f64f596, 0x2dec45df, 0x027fc1a0, 0x3c938abf, 0x61bad871, 0x6e5fd31d, 0x6f36a6d4,
+        0x544b3f0e, 0x18f27fa1, 0x34451992, 0x2417883f, 0x5157a5b6, 0x2eeb111e, 0x150135d7,
+        0x355925a3, 0x33329a06, 0x460cb30c,
+    ]),
+];
+
+/// Internal round constants for the width 24 Poseidon2 permutation on BabyBear.
+///
+/// See [`default_babybear_poseidon2_24`] for how these were generated.
+pub const BABYBEAR_RC24_INTERNAL: [BabyBear; 21] = BabyBear::new_array([
+    0x24d2af3b, 0x766abfc3, 0x4a4bbf41, 0x018dedd7, 0x037a4705, 0x27666a5d, 0x3475e251, 0x0f0cb909,
+    0x68acf372, 0x22cec228, 0x164774dc, 0x59034d05, 0x752865fd, 0x064e74ad, 0x5233240a, 0x39c32a85,
+    0x089480d7, 0x3c439665, 0x70908112, 0x2c664e9e, 0x647b04ac,
+]);
+
 #[cfg(test)]
 mod tests {
-    use p3_field::FieldAlgebra;
+    use p3_field::{FieldAlgebra, PackedValue};
     use p3_symmetric::Permutation;
     use rand::{Rng, SeedableRng};
     use rand_xoshiro::Xoroshiro128Plus;
@@ -278,7 +412,7 @@ mod tests {
     /// vector([BB.random_element() for t in range(16)]).
     #[test]
     fn test_poseidon2_width_16_random() {
-        let mut input: [F; 16] = [
+        let input: [F; 16] = [
             894848333, 1437655012, 1200606629, 1690012884, 71131202, 1749206695, 1717947831,
             120589055, 19776022, 42382981, 1831865506, 724844064, 171220207, 1299207443, 227047920,
             1783754913,
@@ -294,9 +428,10 @@ mod tests {
 
         let mut rng = Xoroshiro128Plus::seed_from_u64(1);
         let perm = Poseidon2BabyBear::new_from_rng_128(&mut rng);
+        assert_eq!(perm.permute(input), expected);
 
-        perm.permute_mut(&mut input);
-        assert_eq!(input, expected);
+        // The default constants were sampled the same way, so give the same output.
+        assert_eq!(default_babybear_poseidon2_16().permute(input), expected);
     }
 
     /// Test on a roughly random input.
@@ -305,7 +440,7 @@ mod tests {
     /// vector([BB.random_element() for t in range(24)]).
     #[test]
     fn test_poseidon2_width_24_random() {
-        let mut input: [F; 24] = [
+        let input: [F; 24] = [
             886409618, 1327899896, 1902407911, 591953491, 648428576, 1844789031, 1198336108,
             355597330, 1799586834, 59617783, 790334801, 1968791836, 559272107, 31054313,
             1042221543, 474748436, 135686258, 263665994, 1962340735, 1741539604, 449439011,
@@ -323,10 +458,9 @@ mod tests {
 
         let mut rng = Xoroshiro128Plus::seed_from_u64(1);
         let perm = Poseidon2BabyBear::new_from_rng_128(&mut rng);
+        assert_eq!(perm.permute(input), expected);
 
-        perm.permute_mut(&mut input);
-
-        assert_eq!(input, expected);
+        assert_eq!(default_babybear_poseidon2_24().permute(input), expected);
     }
 
     /// Test the generic internal layer against the optimized internal layer
@@ -366,4 +500,61 @@ mod tests {
 
         assert_eq!(input1, input2);
     }
+
+    /// Multiply `state` by the dense matrix `1 + Diag(diag)`, where `1` is the all ones matrix.
+    fn dense_internal_linear_layer<const WIDTH: usize>(
+        state: &[F; WIDTH],
+        diag: [F; WIDTH],
+    ) -> [F; WIDTH] {
+        let matrix: [[F; WIDTH]; WIDTH] = core::array::from_fn(|i| {
+            core::array::from_fn(|j| if i == j { F::ONE + diag[i] } else { F::ONE })
+        });
+        matrix.map(|row| row.iter().zip(state).map(|(&m, &x)| m * x).sum())
+    }
+
+    /// Test the optimized width 24 internal layer against an explicit dense matrix multiply.
+    #[test]
+    fn test_internal_linear_layer_24_matches_dense_matrix() {
+        let mut rng = rand::thread_rng();
+        let mut input: [F; 24] = rng.gen();
+        let expected = dense_internal_linear_layer(&input, INTERNAL_DIAG_MONTY_24);
+
+        let part_sum: F = input[1..].iter().cloned().sum();
+        let full_sum = part_sum + input[0];
+        input[0] = part_sum - input[0];
+        BabyBearInternalLayerParameters::internal_layer_mat_mul(&mut input, full_sum);
+
+        assert_eq!(input, expected);
+    }
+
+    #[test]
+    fn test_mul_2exp_neg_n_add() {
+        let mut rng = rand::thread_rng();
+        let edges = [F::ZERO, F::ONE, F::NEG_ONE];
+        for n in 0..33 {
+            for _ in 0..100 {
+                let (x, sum): (F, F) = rng.gen();
+                assert_eq!(x.mul_2exp_neg_n_add(n, sum), sum + x.mul_2exp_neg_n(n));
+            }
+            for (x, sum) in edges.into_iter().flat_map(|x| edges.map(|sum| (x, sum))) {
+                assert_eq!(x.mul_2exp_neg_n_add(n, sum), sum + x.mul_2exp_neg_n(n));
+            }
+        }
+    }
+
+    /// Test the packed width 24 permutation against the scalar one, lane by lane.
+    #[test]
+    fn test_poseidon2_width_24_packed_matches_scalar() {
+        type P = <F as Field>::Packing;
+
+        let mut rng = rand::thread_rng();
+        let perm = default_babybear_poseidon2_24();
+        let input: [P; 24] = rng.gen();
+        let output = perm.permute(input);
+
+        for lane in 0..P::WIDTH {
+            let expected = perm.permute(input.map(|x| x.as_slice()[lane]));
+            assert_eq!(output.map(|x| x.as_slice()[lane]), expected);
+        }
+    }
 }
@@ -287,7 +287,7 @@ impl TwoAdicField for Bn254Fr {
 #[cfg(test)]
 mod tests {
     use num_traits::One;
-    use p3_field_testing::test_field;
+    use p3_field_testing::{test_field, test_prime_field};
 
     use super::*;
 
@@ -379,4 +379,5 @@ mod tests {
     }
 
     test_field!(crate::Bn254Fr);
+    test_prime_field!(crate::Bn254Fr);
 }
@@ -0,0 +1,59 @@
+//! Proof-of-work grinding on thread pools of increasing size, with `grind` and with
+//! `grind_parallel` searching over as many threads as the pool has. With near-linear scaling,
+//! each doubling of the pool halves the time.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use p3_baby_bear::{default_babybear_poseidon2_16, BabyBear, Poseidon2BabyBear};
+use p3_challenger::{CanObserve, DuplexChallenger, GrindingChallenger};
+use p3_field::FieldAlgebra;
+use rayon::ThreadPoolBuilder;
+
+const BITS: usize = 18;
+
+fn bench_grinding(c: &mut Criterion) {
+    type F = BabyBear;
+    type Challenger = DuplexChallenger<F, Poseidon2BabyBear<16>, 16, 8>;
+    let challenger = Challenger::new(default_babybear_poseidon2_16());
+
+    let mut group = c.benchmark_group(format!("grind {BITS} bits"));
+    group.sample_size(10);
+    let max_threads = rayon::current_num_threads();
+    for num_threads in (0..).map(|i| 1 << i).take_while(|&n| n <= max_threads) {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("grind threads", num_threads),
+            &num_threads,
+            |b, _| {
+                // The work to find a witness depends on the state, so grind from a different state
+                // each iteration. Every pool size sees the same sequence of states.
+                let mut round = 0;
+                b.iter(|| {
+                    round += 1;
+                    let mut challenger = challenger.clone();
+                    challenger.observe(F::from_canonical_u32(round));
+                    pool.install(|| challenger.grind(BITS))
+                });
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("grind_parallel threads", num_threads),
+            &num_threads,
+            |b, &num_threads| {
+                let mut round = 0;
+                b.iter(|| {
+                    round += 1;
+                    let mut challenger = challenger.clone();
+                    challenger.observe(F::from_canonical_u32(round));
+                    pool.install(|| challenger.grind_parallel(BITS, num_threads))
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_grinding);
+criterion_main!(benches);
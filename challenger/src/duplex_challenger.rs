@@ -2,7 +2,7 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 use p3_field::{ExtensionField, Field, PrimeField64};
-use p3_symmetric::{CryptographicPermutation, Hash};
+use p3_symmetric::{CryptographicPermutation, Hash, MerkleCap};
 
 use crate::{CanObserve, CanSample, CanSampleBits, FieldChallenger};
 
@@ -51,6 +51,36 @@ where
     }
 }
 
+impl<F, P, const WIDTH: usize, const RATE: usize> DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: PrimeField64,
+    P: CryptographicPermutation<[F; WIDTH]>,
+{
+    /// Start a sub-transcript separated from this one by `domain_tag`, e.g. one per table when
+    /// several tables are proven together.
+    ///
+    /// The fork adds one to the last element of the capacity before observing `domain_tag` as by
+    /// `observe_u64`. Observations only ever overwrite the rate, so no transcript, this one
+    /// included, can reach the state of a fork by observing. As long as the permutation behaves
+    /// like a random one, a fork's challenges are computationally independent of this
+    /// challenger's and of those of forks with other tags. The fork is a copy, so this challenger
+    /// is unchanged.
+    pub fn fork(&self, domain_tag: u64) -> Self {
+        assert!(RATE < WIDTH, "forking needs a capacity");
+        let mut fork = self.clone();
+        fork.sponge_state[WIDTH - 1] += F::ONE;
+        fork.observe_u64(domain_tag);
+        fork
+    }
+
+    /// The result of `fork` with each of the tags `0, ..., n - 1`, in order.
+    pub fn fork_many(&self, n: usize) -> Vec<Self> {
+        (0..n as u64)
+            .map(|domain_tag| self.fork(domain_tag))
+            .collect()
+    }
+}
+
 impl<F, P, const WIDTH: usize, const RATE: usize> FieldChallenger<F>
     for DuplexChallenger<F, P, WIDTH, RATE>
 where
@@ -103,6 +133,19 @@ where
     }
 }
 
+impl<F, P, const N: usize, const WIDTH: usize, const RATE: usize> CanObserve<MerkleCap<F, F, N>>
+    for DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: Copy,
+    P: CryptographicPermutation<[F; WIDTH]>,
+{
+    fn observe(&mut self, cap: MerkleCap<F, F, N>) {
+        for root in cap {
+            self.observe(root);
+        }
+    }
+}
+
 // for TrivialPcs
 impl<F, P, const WIDTH: usize, const RATE: usize> CanObserve<Vec<Vec<F>>>
     for DuplexChallenger<F, P, WIDTH, RATE>
@@ -147,12 +190,20 @@ where
     F: PrimeField64,
     P: CryptographicPermutation<[F; WIDTH]>,
 {
+    /// Sample field elements until one is less than the largest multiple of `2^bits` which is at
+    /// most the order, and return its low `bits` bits. Taking the low bits of any element would
+    /// favour the values below `order % 2^bits`.
     fn sample_bits(&mut self, bits: usize) -> usize {
         debug_assert!(bits < (usize::BITS as usize));
         debug_assert!((1 << bits) < F::ORDER_U64);
-        let rand_f: F = self.sample();
-        let rand_usize = rand_f.as_canonical_u64() as usize;
-        rand_usize & ((1 << bits) - 1)
+        let bound = F::ORDER_U64 - F::ORDER_U64 % (1 << bits);
+        loop {
+            let rand_f: F = self.sample();
+            let rand_u64 = rand_f.as_canonical_u64();
+            if rand_u64 < bound {
+                return (rand_u64 & ((1 << bits) - 1)) as usize;
+            }
+        }
     }
 }
 
@@ -160,7 +211,8 @@ where
 mod tests {
     use core::iter;
 
-    use p3_field::FieldAlgebra;
+    use p3_baby_bear::{default_babybear_poseidon2_16, BabyBear, Poseidon2BabyBear};
+    use p3_field::{FieldAlgebra, PrimeField32};
     use p3_goldilocks::Goldilocks;
     use p3_symmetric::Permutation;
 
@@ -201,4 +253,107 @@ mod tests {
         let samples = <Chal as CanSample<F>>::sample_vec(&mut duplex_challenger, 16);
         assert_eq!(samples, expected_samples);
     }
+
+    #[test]
+    fn observing_a_cap_observes_its_roots() {
+        let roots: Vec<Hash<F, F, 4>> = (0..4)
+            .map(|i| core::array::from_fn(|j| F::from_canonical_usize(4 * i + j)).into())
+            .collect();
+
+        let mut by_cap = DuplexChallenger::<F, _, WIDTH, RATE>::new(TestPermutation {});
+        by_cap.observe(MerkleCap::from(roots.clone()));
+        let mut by_roots = DuplexChallenger::<F, _, WIDTH, RATE>::new(TestPermutation {});
+        for root in roots.clone() {
+            by_roots.observe(root);
+        }
+        assert_eq!(
+            <_ as CanSample<F>>::sample_vec(&mut by_cap, 8),
+            <_ as CanSample<F>>::sample_vec(&mut by_roots, 8)
+        );
+
+        // A cap of height 0 is observed exactly like its root.
+        let mut by_cap = DuplexChallenger::<F, _, WIDTH, RATE>::new(TestPermutation {});
+        by_cap.observe(MerkleCap::from(roots[0]));
+        let mut by_root = DuplexChallenger::<F, _, WIDTH, RATE>::new(TestPermutation {});
+        by_root.observe(roots[0]);
+        assert_eq!(
+            <_ as CanSample<F>>::sample_vec(&mut by_cap, 8),
+            <_ as CanSample<F>>::sample_vec(&mut by_root, 8)
+        );
+    }
+
+    #[test]
+    fn sample_bits_rejects_elements_past_a_multiple_of_the_range() {
+        // With the test permutation, the first samples after observing `x_0, ..., x_11` are
+        // `x_8, ..., x_11`, then zeros.
+        let challenger_after = |last: [u64; 4]| {
+            let mut challenger = DuplexChallenger::<F, _, WIDTH, RATE>::new(TestPermutation {});
+            for x in [0; 8].into_iter().chain(last) {
+                challenger.observe(F::from_canonical_u64(x));
+            }
+            challenger
+        };
+        let p = F::ORDER_U64;
+
+        // `2^63` is the largest multiple of `2^63` below the order, so the first two are redrawn.
+        let mut challenger = challenger_after([p - 1, (1 << 63) + 7, 0x1234, (1 << 63) - 1]);
+        assert_eq!(challenger.sample_bits(63), 0x1234);
+        assert_eq!(challenger.sample_bits(4), 15);
+        assert_eq!(challenger.sample_bits(4), 0);
+
+        // For up to 32 bits, only `p - 1` is redrawn.
+        let mut challenger = challenger_after([p - 1, 9, p - 2, 0]);
+        assert_eq!(challenger.sample_bits(4), 9);
+        assert_eq!(challenger.sample_bits(32), ((p - 2) & 0xffff_ffff) as usize);
+    }
+
+    #[test]
+    fn sample_bits_is_uniform() {
+        // BabyBear's order is `2^30 + c` with `c = 939524097`. Taking the low 30 bits of a random
+        // element would give a value below `c` with probability `2c / p`, about 0.933, rather
+        // than `c / 2^30`, or 0.875.
+        type Challenger = DuplexChallenger<BabyBear, Poseidon2BabyBear<16>, 16, 8>;
+        let mut challenger = Challenger::new(default_babybear_poseidon2_16());
+        let c = (BabyBear::ORDER_U32 - (1 << 30)) as usize;
+        let num_samples = 10_000;
+        let num_below_c = (0..num_samples)
+            .filter(|_| challenger.sample_bits(30) < c)
+            .count();
+        let fraction = num_below_c as f64 / num_samples as f64;
+        // The standard deviation is about 0.0033.
+        assert!(
+            (fraction - 0.875).abs() < 0.015,
+            "fraction below c: {fraction}"
+        );
+    }
+
+    #[test]
+    fn forks_are_separated_from_each_other_and_from_the_parent() {
+        type Challenger = DuplexChallenger<BabyBear, Poseidon2BabyBear<16>, 16, 8>;
+        let mut parent = Challenger::new(default_babybear_poseidon2_16());
+        parent.observe(BabyBear::from_canonical_u32(42));
+        let samples =
+            |challenger: &Challenger| -> [BabyBear; 8] { challenger.clone().sample_array() };
+
+        let forks = parent.fork_many(3);
+        assert_eq!(forks.len(), 3);
+        for (tag, fork) in forks.iter().enumerate() {
+            // Forking is deterministic.
+            assert_eq!(samples(fork), samples(&parent.fork(tag as u64)));
+            assert_ne!(samples(fork), samples(&parent));
+            // Observing the tag on the parent does not give the fork's state.
+            let mut observed_tag = parent.clone();
+            observed_tag.observe_u64(tag as u64);
+            assert_ne!(samples(fork), samples(&observed_tag));
+        }
+        assert_ne!(samples(&forks[0]), samples(&forks[1]));
+        assert_ne!(samples(&forks[1]), samples(&forks[2]));
+
+        // Using a fork leaves the parent as it was.
+        let expected = samples(&parent);
+        let mut fork = parent.fork(0);
+        fork.observe(BabyBear::ONE);
+        let _: BabyBear = fork.sample();
+        assert_eq!(samples(&parent), expected);
+    }
 }
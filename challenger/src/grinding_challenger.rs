@@ -1,3 +1,5 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
 use p3_field::{Field, PrimeField, PrimeField32, PrimeField64};
 use p3_maybe_rayon::prelude::*;
 use p3_symmetric::CryptographicPermutation;
@@ -10,6 +12,11 @@ pub trait GrindingChallenger:
 {
     type Witness: Field;
 
+    /// Find a witness for which `check_witness` passes, and observe it.
+    ///
+    /// The challengers in this crate search the candidates in parallel when the `parallel`
+    /// feature is enabled, and return the smallest witness that passes either way, so the
+    /// witness does not depend on the number of threads.
     fn grind(&mut self, bits: usize) -> Self::Witness;
 
     #[must_use]
@@ -17,6 +24,37 @@ pub trait GrindingChallenger:
         self.observe(witness);
         self.sample_bits(bits) == 0
     }
+
+    /// Find the smallest witness for which `check_witness` passes, without observing it.
+    ///
+    /// The candidates are split between `num_threads` searches, the `i`'th checking every
+    /// `num_threads`'th candidate from `i`, on the rayon pool if the `parallel` feature is
+    /// enabled and one after another if not. A search stops at its first witness, or once its
+    /// candidates are larger than a witness another search has found, so the result does not
+    /// depend on `num_threads`.
+    fn grind_parallel(&self, bits: usize, num_threads: usize) -> Self::Witness
+    where
+        Self::Witness: PrimeField64,
+    {
+        assert!(num_threads > 0, "grinding needs at least one thread");
+        let order = Self::Witness::ORDER_U64;
+        let smallest = AtomicU64::new(order);
+        (0..num_threads as u64).into_par_iter().for_each(|first| {
+            for candidate in (first..order).step_by(num_threads) {
+                if candidate >= smallest.load(Ordering::Relaxed) {
+                    break;
+                }
+                let witness = Self::Witness::from_canonical_u64(candidate);
+                if self.clone().check_witness(bits, witness) {
+                    smallest.fetch_min(candidate, Ordering::Relaxed);
+                    break;
+                }
+            }
+        });
+        let smallest = smallest.into_inner();
+        assert!(smallest < order, "failed to find witness");
+        Self::Witness::from_canonical_u64(smallest)
+    }
 }
 
 impl<F, P, const WIDTH: usize, const RATE: usize> GrindingChallenger
@@ -29,13 +67,7 @@ where
 
     #[instrument(name = "grind for proof-of-work witness", skip_all)]
     fn grind(&mut self, bits: usize) -> Self::Witness {
-        let witness = (0..F::ORDER_U64)
-            .into_par_iter()
-            .map(|i| F::from_canonical_u64(i))
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
-            .expect("failed to find witness");
-        assert!(self.check_witness(bits, witness));
-        witness
+        grind_by_search(self, bits)
     }
 }
 
@@ -50,12 +82,85 @@ where
 
     #[instrument(name = "grind for proof-of-work witness", skip_all)]
     fn grind(&mut self, bits: usize) -> Self::Witness {
-        let witness = (0..F::ORDER_U64)
-            .into_par_iter()
-            .map(F::from_canonical_u64)
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
-            .expect("failed to find witness");
-        assert!(self.check_witness(bits, witness));
-        witness
+        grind_by_search(self, bits)
+    }
+}
+
+/// Find the smallest witness with `grind_parallel`, on as many threads as the rayon pool has, and
+/// observe it.
+pub(crate) fn grind_by_search<C, F>(challenger: &mut C, bits: usize) -> F
+where
+    C: GrindingChallenger<Witness = F>,
+    F: PrimeField64,
+{
+    let witness = challenger.grind_parallel(bits, current_num_threads());
+    assert!(challenger.check_witness(bits, witness));
+    witness
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::{default_babybear_poseidon2_16, BabyBear, Poseidon2BabyBear};
+    use p3_field::FieldAlgebra;
+
+    use super::*;
+    use crate::CanSample;
+
+    type F = BabyBear;
+    type Challenger = DuplexChallenger<F, Poseidon2BabyBear<16>, 16, 8>;
+
+    fn challenger() -> Challenger {
+        let mut challenger = Challenger::new(default_babybear_poseidon2_16());
+        challenger.observe(F::from_canonical_u32(42));
+        challenger
+    }
+
+    #[test]
+    fn grinding_finds_the_smallest_witness() {
+        for bits in [0, 1, 8, 12] {
+            let mut prover = challenger();
+            let witness = prover.grind(bits);
+
+            let mut verifier = challenger();
+            assert!(verifier.check_witness(bits, witness));
+            // Both challengers have observed the witness.
+            assert_eq!(prover.sample(), verifier.sample());
+
+            let smallest = (0..)
+                .map(F::from_canonical_u64)
+                .find(|&w| challenger().check_witness(bits, w))
+                .unwrap();
+            assert_eq!(witness, smallest);
+        }
+    }
+
+    #[test]
+    fn parallel_grinding_matches_serial_grinding() {
+        for bits in [0, 1, 8, 12] {
+            let serial = challenger().grind_parallel(bits, 1);
+            assert!(challenger().check_witness(bits, serial));
+            for num_threads in [2, 3, 8] {
+                assert_eq!(challenger().grind_parallel(bits, num_threads), serial);
+            }
+            // Only `grind` observes the witness.
+            let mut grinder = challenger();
+            assert_eq!(grinder.grind(bits), serial);
+            let mut searcher = challenger();
+            searcher.grind_parallel(bits, 4);
+            assert_ne!(grinder.sample(), searcher.sample());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn grinding_does_not_depend_on_thread_count() {
+        let expected = challenger().grind(10);
+        for num_threads in [1, 2, 3, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            assert_eq!(pool.install(|| challenger().grind(10)), expected);
+        }
     }
 }
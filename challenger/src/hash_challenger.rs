@@ -5,6 +5,13 @@ use p3_symmetric::CryptographicHasher;
 
 use crate::{CanObserve, CanSample};
 
+/// A challenger which hashes its transcript with `H`.
+///
+/// Observed values are buffered until the next sample, which hashes the last digest followed by
+/// them. The samples are the elements of the new digest, from last to first; once they run out,
+/// the digest alone is hashed for more. The first hash starts from the initial state instead of
+/// a digest. Each observed value is hashed once, along with one digest per hash, so the hashing
+/// is linear in the length of the transcript.
 #[derive(Clone, Debug)]
 pub struct HashChallenger<T, H, const OUT_LEN: usize>
 where
@@ -40,6 +47,39 @@ where
     }
 }
 
+impl<H, const OUT_LEN: usize> HashChallenger<u8, H, OUT_LEN>
+where
+    H: CryptographicHasher<u8, [u8; OUT_LEN]> + Clone,
+{
+    /// Branch off a transcript tagged with `domain_tag`.
+    ///
+    /// A `HashChallenger` is just the bytes it has observed, so a fork is a copy of this
+    /// challenger which has also observed `FORK_SEPARATOR` and the 8 little-endian bytes of
+    /// `domain_tag`. If the hash behaves like a random function, forks with different tags give
+    /// computationally independent challenges.
+    ///
+    /// There is no hidden state to separate a fork from its parent: this challenger reaches the
+    /// state of a fork by observing those same bytes. A protocol which forks should never have the
+    /// parent observe data that can start with `FORK_SEPARATOR`, or the parent's challenges may
+    /// coincide with a fork's.
+    pub fn fork(&self, domain_tag: u64) -> Self {
+        let mut fork = self.clone();
+        fork.observe_slice(FORK_SEPARATOR);
+        fork.observe_slice(&domain_tag.to_le_bytes());
+        fork
+    }
+
+    /// The forks tagged `0` to `n - 1`.
+    pub fn fork_many(&self, n: usize) -> Vec<Self> {
+        (0..n as u64)
+            .map(|domain_tag| self.fork(domain_tag))
+            .collect()
+    }
+}
+
+/// The bytes a `HashChallenger` over bytes observes before the tag of a fork.
+pub const FORK_SEPARATOR: &[u8] = b"p3-challenger fork";
+
 impl<T, H, const OUT_LEN: usize> CanObserve<T> for HashChallenger<T, H, OUT_LEN>
 where
     T: Clone,
@@ -83,8 +123,12 @@ where
 
 #[cfg(test)]
 mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
     use p3_field::FieldAlgebra;
     use p3_goldilocks::Goldilocks;
+    use p3_keccak::Keccak256Hash;
 
     use super::*;
 
@@ -168,4 +212,87 @@ mod tests {
             [F::from_canonical_u8(new_expected_sum)]
         )
     }
+
+    #[test]
+    fn fork_is_the_parent_after_observing_the_tag() {
+        type Challenger = HashChallenger<u8, Keccak256Hash, 32>;
+        let mut parent = Challenger::new(vec![1, 2, 3], Keccak256Hash);
+        parent.observe(4);
+        let challenges =
+            |challenger: &Challenger| -> [u8; 32] { challenger.clone().sample_array() };
+
+        for tag in [0, 1, u64::MAX] {
+            let mut by_hand = parent.clone();
+            by_hand.observe_slice(FORK_SEPARATOR);
+            by_hand.observe_slice(&tag.to_le_bytes());
+            assert_eq!(challenges(&parent.fork(tag)), challenges(&by_hand));
+        }
+
+        let forks = parent.fork_many(3);
+        assert_eq!(challenges(&forks[2]), challenges(&parent.fork(2)));
+        assert_ne!(challenges(&forks[0]), challenges(&forks[1]));
+        assert_ne!(challenges(&forks[0]), challenges(&parent));
+
+        let before = challenges(&parent);
+        let mut fork = parent.fork(7);
+        fork.observe(5);
+        fork.sample();
+        assert_eq!(challenges(&parent), before);
+    }
+
+    /// Keccak-256, counting the bytes it hashes.
+    #[derive(Clone, Default)]
+    struct CountingKeccak(Arc<AtomicUsize>);
+
+    impl CryptographicHasher<u8, [u8; 32]> for CountingKeccak {
+        fn hash_iter<I>(&self, input: I) -> [u8; 32]
+        where
+            I: IntoIterator<Item = u8>,
+        {
+            let input = input.into_iter().collect::<Vec<_>>();
+            self.0.fetch_add(input.len(), Ordering::Relaxed);
+            Keccak256Hash.hash_iter(input)
+        }
+    }
+
+    #[test]
+    fn hashing_is_linear_in_the_transcript() {
+        // Each round observes 10 bytes and samples 3, so after the first round, which hashes the
+        // initial state, each hashes a digest and the round's bytes.
+        let hashed_bytes = |num_rounds: usize| {
+            let hasher = CountingKeccak::default();
+            let mut challenger = HashChallenger::<u8, _, 32>::new(vec![0; 5], hasher.clone());
+            for round in 0..num_rounds {
+                challenger.observe_slice(&[round as u8; 10]);
+                let _: [u8; 3] = challenger.sample_array();
+            }
+            hasher.0.load(Ordering::Relaxed)
+        };
+        for num_rounds in [1, 10, 100, 1000] {
+            assert_eq!(
+                hashed_bytes(num_rounds),
+                5 + 10 * num_rounds + 32 * (num_rounds - 1)
+            );
+        }
+    }
+
+    #[test]
+    fn observations_after_samples_change_later_samples() {
+        let transcript = |extra: Option<u8>| {
+            let mut challenger = HashChallenger::<u8, _, 32>::new(vec![1, 2, 3], Keccak256Hash);
+            let first: [u8; 4] = challenger.sample_array();
+            if let Some(extra) = extra {
+                challenger.observe(extra);
+            }
+            let second: [u8; 4] = challenger.sample_array();
+            (first, second)
+        };
+        // Deterministic.
+        assert_eq!(transcript(Some(7)), transcript(Some(7)));
+        // An observation does not change the samples before it.
+        assert_eq!(transcript(Some(7)).0, transcript(None).0);
+        // It does change those after it, even though the digest had samples left.
+        assert_ne!(transcript(Some(7)).1, transcript(None).1);
+        assert_ne!(transcript(Some(7)).1, transcript(Some(8)).1);
+    }
 }
@@ -17,7 +17,7 @@ pub use duplex_challenger::*;
 pub use grinding_challenger::*;
 pub use hash_challenger::*;
 pub use multi_field_challenger::*;
-use p3_field::{Field, FieldExtensionAlgebra};
+use p3_field::{Field, FieldExtensionAlgebra, PrimeField};
 pub use serializing_challenger::*;
 
 pub trait CanObserve<T> {
@@ -60,6 +60,58 @@ pub trait FieldChallenger<F: Field>:
         let vec = self.sample_vec(EF::D);
         EF::from_base_slice(&vec)
     }
+
+    /// Observe `value` as `ceil(64 / b)` field elements, each holding `b` of its bits, least
+    /// significant first. `b` is one fewer than the number of bits of the order of `F`, and at
+    /// most 64, e.g. 30 for BabyBear, KoalaBear and Mersenne31 and 63 for Goldilocks. As each
+    /// element is then less than the order, distinct values are observed differently, unlike with
+    /// `F::from_wrapped_u64`.
+    fn observe_u64(&mut self, value: u64)
+    where
+        F: PrimeField,
+    {
+        let limb_bits = limb_bits::<F>();
+        let mask = (1_u128 << limb_bits) - 1;
+        for i in 0..64_usize.div_ceil(limb_bits) {
+            let limb = (u128::from(value) >> (i * limb_bits)) & mask;
+            self.observe(F::from_canonical_u64(limb as u64));
+        }
+    }
+
+    /// Observe the length of `bytes` as by `observe_u64`, then the bytes, read as a little-endian
+    /// string of bits and cut into field elements of `b` bits each, as in `observe_u64`, the last
+    /// one padded with zeros.
+    ///
+    /// E.g. a BabyBear challenger observes four `0xff` bytes as `[4, 0, 0, 2^30 - 1, 3]`. As the
+    /// length comes first, byte strings which differ only by trailing zeros are observed
+    /// differently.
+    fn observe_bytes(&mut self, bytes: &[u8])
+    where
+        F: PrimeField,
+    {
+        self.observe_u64(bytes.len() as u64);
+        let limb_bits = limb_bits::<F>();
+        let mask = (1_u128 << limb_bits) - 1;
+        let mut bits = 0_u128;
+        let mut num_bits = 0;
+        for &byte in bytes {
+            bits |= u128::from(byte) << num_bits;
+            num_bits += 8;
+            while num_bits >= limb_bits {
+                self.observe(F::from_canonical_u64((bits & mask) as u64));
+                bits >>= limb_bits;
+                num_bits -= limb_bits;
+            }
+        }
+        if num_bits > 0 {
+            self.observe(F::from_canonical_u64(bits as u64));
+        }
+    }
+}
+
+/// The number of bits `observe_u64` and `observe_bytes` pack into each element of `F`.
+fn limb_bits<F: PrimeField>() -> usize {
+    (F::bits() - 1).min(64)
 }
 
 impl<C, T> CanObserve<T> for &mut C
@@ -123,4 +175,109 @@ where
     fn sample_ext_element<EF: FieldExtensionAlgebra<F>>(&mut self) -> EF {
         (**self).sample_ext_element()
     }
+
+    #[inline(always)]
+    fn observe_u64(&mut self, value: u64)
+    where
+        F: PrimeField,
+    {
+        (**self).observe_u64(value)
+    }
+
+    #[inline(always)]
+    fn observe_bytes(&mut self, bytes: &[u8])
+    where
+        F: PrimeField,
+    {
+        (**self).observe_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_baby_bear::{default_babybear_poseidon2_16, BabyBear, Poseidon2BabyBear};
+    use p3_field::{PrimeField32, PrimeField64};
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+    use crate::DuplexChallenger;
+
+    /// Records what it observes.
+    struct Transcript<F>(Vec<F>);
+
+    impl<F> CanObserve<F> for Transcript<F> {
+        fn observe(&mut self, value: F) {
+            self.0.push(value);
+        }
+    }
+
+    impl<F> CanSample<F> for Transcript<F> {
+        fn sample(&mut self) -> F {
+            unreachable!("Transcript only records observations")
+        }
+    }
+
+    impl<F> CanSampleBits<usize> for Transcript<F> {
+        fn sample_bits(&mut self, _bits: usize) -> usize {
+            unreachable!("Transcript only records observations")
+        }
+    }
+
+    impl<F: Field> FieldChallenger<F> for Transcript<F> {}
+
+    fn observed<F: PrimeField64>(observe: impl FnOnce(&mut Transcript<F>)) -> Vec<u64> {
+        let mut transcript = Transcript(vec![]);
+        observe(&mut transcript);
+        transcript.0.iter().map(|x| x.as_canonical_u64()).collect()
+    }
+
+    #[test]
+    fn bytes_and_integers_are_packed_into_limbs() {
+        type F = BabyBear;
+        let max_limb = (1 << 30) - 1;
+        assert_eq!(
+            observed::<F>(|t| t.observe_u64(u64::MAX)),
+            [max_limb, max_limb, 15]
+        );
+        assert_eq!(
+            observed::<F>(|t| t.observe_bytes(&[0xff; 4])),
+            [4, 0, 0, max_limb, 3]
+        );
+        assert_eq!(observed::<F>(|t| t.observe_bytes(&[])), [0, 0, 0]);
+        assert_eq!(
+            observed::<Goldilocks>(|t| t.observe_bytes(&[1, 2, 3, 4, 5, 6, 7, 8])),
+            [8, 0, 0x0807_0605_0403_0201, 0]
+        );
+    }
+
+    #[test]
+    fn byte_strings_equal_under_naive_packing_give_different_challenges() {
+        type F = BabyBear;
+        type Challenger = DuplexChallenger<F, Poseidon2BabyBear<16>, 16, 8>;
+        let sample_after = |bytes: &[u8]| {
+            let mut challenger = Challenger::new(default_babybear_poseidon2_16());
+            challenger.observe_bytes(bytes);
+            challenger.sample_array::<4>()
+        };
+
+        // Packed without their lengths, these would be observed alike, as a string of zeros, or
+        // as the element 1.
+        assert_ne!(sample_after(&[]), sample_after(&[0]));
+        assert_ne!(sample_after(&[0]), sample_after(&[0, 0]));
+        assert_ne!(sample_after(&[1]), sample_after(&[1, 0]));
+
+        // Reduced modulo the order, these would be observed alike.
+        let p = F::ORDER_U32 as u64;
+        let sample_after_u64 = |value: u64| {
+            let mut challenger = Challenger::new(default_babybear_poseidon2_16());
+            challenger.observe_u64(value);
+            challenger.sample_array::<4>()
+        };
+        assert_ne!(sample_after_u64(5), sample_after_u64(5 + p));
+
+        assert_eq!(sample_after(b"protocol v1"), sample_after(b"protocol v1"));
+        assert_eq!(sample_after_u64(5), sample_after_u64(5));
+    }
 }
@@ -2,8 +2,11 @@ use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 
-use p3_field::{reduce_32, split_32, ExtensionField, Field, PrimeField, PrimeField32};
-use p3_symmetric::{CryptographicPermutation, Hash};
+use p3_field::extension::{BinomialExtensionField, BinomiallyExtendable};
+use p3_field::{
+    reduce_32, split_32, ExtensionField, Field, FieldExtensionAlgebra, PrimeField, PrimeField32,
+};
+use p3_symmetric::{CryptographicPermutation, Hash, MerkleCap};
 
 use crate::{CanObserve, CanSample, CanSampleBits, FieldChallenger};
 
@@ -134,6 +137,34 @@ where
     }
 }
 
+impl<F, PF, const N: usize, P, const WIDTH: usize, const RATE: usize>
+    CanObserve<MerkleCap<F, PF, N>> for MultiField32Challenger<F, PF, P, WIDTH, RATE>
+where
+    F: PrimeField32,
+    PF: PrimeField,
+    P: CryptographicPermutation<[PF; WIDTH]>,
+{
+    fn observe(&mut self, cap: MerkleCap<F, PF, N>) {
+        for root in cap {
+            self.observe(root);
+        }
+    }
+}
+
+/// Observes the `D` coefficients of the element over `F`, constant term first, exactly as
+/// `observe_ext_element` does.
+impl<F, PF, P, const D: usize, const WIDTH: usize, const RATE: usize>
+    CanObserve<BinomialExtensionField<F, D>> for MultiField32Challenger<F, PF, P, WIDTH, RATE>
+where
+    F: PrimeField32 + BinomiallyExtendable<D>,
+    PF: PrimeField,
+    P: CryptographicPermutation<[PF; WIDTH]>,
+{
+    fn observe(&mut self, value: BinomialExtensionField<F, D>) {
+        self.observe_slice(value.as_base_slice());
+    }
+}
+
 // for TrivialPcs
 impl<F, PF, P, const WIDTH: usize, const RATE: usize> CanObserve<Vec<Vec<F>>>
     for MultiField32Challenger<F, PF, P, WIDTH, RATE>
@@ -151,6 +182,8 @@ where
     }
 }
 
+/// Samples the `EF::D` coefficients of the element over `F`, constant term first, each as a
+/// sample of `F`.
 impl<F, EF, PF, P, const WIDTH: usize, const RATE: usize> CanSample<EF>
     for MultiField32Challenger<F, PF, P, WIDTH, RATE>
 where
@@ -189,3 +222,102 @@ where
         rand_usize & ((1 << bits) - 1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::Goldilocks;
+    use p3_symmetric::Permutation;
+
+    use super::*;
+
+    type F = BabyBear;
+    type EF = BinomialExtensionField<F, 4>;
+    type PF = Goldilocks;
+
+    const WIDTH: usize = 8;
+    const RATE: usize = 4;
+
+    /// Rotates the state, then replaces each element by its sum with those before it, so that the
+    /// outputs depend on the order of the inputs.
+    #[derive(Clone)]
+    struct TestPermutation;
+
+    impl Permutation<[PF; WIDTH]> for TestPermutation {
+        fn permute_mut(&self, input: &mut [PF; WIDTH]) {
+            input.rotate_left(1);
+            let mut sum = PF::ZERO;
+            for x in input.iter_mut() {
+                sum += *x;
+                *x = sum;
+            }
+        }
+    }
+
+    impl CryptographicPermutation<[PF; WIDTH]> for TestPermutation {}
+
+    type Challenger = MultiField32Challenger<F, PF, TestPermutation, WIDTH, RATE>;
+
+    fn challenger() -> Challenger {
+        Challenger::new(TestPermutation).unwrap()
+    }
+
+    fn ext(coeffs: [u32; 4]) -> EF {
+        EF::from_base_fn(|i| F::from_canonical_u32(coeffs[i]))
+    }
+
+    #[test]
+    fn observing_an_extension_element_observes_its_coefficients() {
+        let values = [ext([1, 2, 3, 4]), ext([5, 6, 7, 8]), ext([9, 10, 11, 12])];
+
+        let mut by_element = challenger();
+        let mut by_coefficients = challenger();
+        let mut by_observe_ext_element = challenger();
+        for value in values {
+            by_element.observe(value);
+            by_coefficients.observe_slice(value.as_base_slice());
+            by_observe_ext_element.observe_ext_element(value);
+        }
+        let expected: [F; 8] = by_coefficients.sample_array();
+        let by_element: [F; 8] = by_element.sample_array();
+        let by_observe_ext_element: [F; 8] = by_observe_ext_element.sample_array();
+        assert_eq!(by_element, expected);
+        assert_eq!(by_observe_ext_element, expected);
+
+        // The order of the coefficients matters.
+        let mut reversed = challenger();
+        for value in values {
+            let mut coeffs = value.as_base_slice().to_vec();
+            coeffs.reverse();
+            reversed.observe_slice(&coeffs);
+        }
+        let reversed: [F; 8] = reversed.sample_array();
+        assert_ne!(reversed, expected);
+    }
+
+    #[test]
+    fn extension_challenges_are_deterministic_and_sampled_in_order() {
+        let sample_after = |value: EF| -> EF {
+            let mut challenger = challenger();
+            challenger.observe(value);
+            challenger.sample()
+        };
+        assert_eq!(
+            sample_after(ext([1, 2, 3, 4])),
+            sample_after(ext([1, 2, 3, 4]))
+        );
+        assert_ne!(
+            sample_after(ext([1, 2, 3, 4])),
+            sample_after(ext([1, 2, 3, 5]))
+        );
+
+        let mut by_element = challenger();
+        let mut by_coefficients = challenger();
+        by_element.observe(ext([1, 2, 3, 4]));
+        by_coefficients.observe(ext([1, 2, 3, 4]));
+        let challenge: EF = by_element.sample();
+        let coeffs: [F; 4] = by_coefficients.sample_array();
+        assert_eq!(challenge.as_base_slice(), coeffs);
+    }
+}
@@ -1,12 +1,13 @@
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 
+use p3_field::codec::{encode_iter, encode_iter_u32};
 use p3_field::{ExtensionField, PrimeField32, PrimeField64};
-use p3_maybe_rayon::prelude::*;
-use p3_symmetric::{CryptographicHasher, Hash};
+use p3_symmetric::{CryptographicHasher, Hash, MerkleCap};
 use p3_util::log2_ceil_u64;
 use tracing::instrument;
 
+use crate::grinding_challenger::grind_by_search;
 use crate::{
     CanObserve, CanSample, CanSampleBits, FieldChallenger, GrindingChallenger, HashChallenger,
 };
@@ -62,8 +63,9 @@ where
 
 impl<F: PrimeField32, Inner: CanObserve<u8>> CanObserve<F> for SerializingChallenger32<F, Inner> {
     fn observe(&mut self, value: F) {
-        self.inner
-            .observe_slice(&value.to_unique_u32().to_le_bytes());
+        for byte in encode_iter_u32([value]) {
+            self.inner.observe(byte);
+        }
     }
 }
 
@@ -87,6 +89,20 @@ impl<F: PrimeField32, const N: usize, Inner: CanObserve<u8>> CanObserve<Hash<F,
     }
 }
 
+impl<F, W, const N: usize, Inner> CanObserve<MerkleCap<F, W, N>>
+    for SerializingChallenger32<F, Inner>
+where
+    F: PrimeField32,
+    Inner: CanObserve<u8>,
+    Self: CanObserve<Hash<F, W, N>>,
+{
+    fn observe(&mut self, cap: MerkleCap<F, W, N>) {
+        for root in cap {
+            self.observe(root);
+        }
+    }
+}
+
 impl<F, EF, Inner> CanSample<EF> for SerializingChallenger32<F, Inner>
 where
     F: PrimeField32,
@@ -133,13 +149,7 @@ where
 
     #[instrument(name = "grind for proof-of-work witness", skip_all)]
     fn grind(&mut self, bits: usize) -> Self::Witness {
-        let witness = (0..F::ORDER_U64)
-            .into_par_iter()
-            .map(|i| F::from_canonical_u64(i))
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
-            .expect("failed to find witness");
-        assert!(self.check_witness(bits, witness));
-        witness
+        grind_by_search(self, bits)
     }
 }
 
@@ -171,8 +181,9 @@ where
 
 impl<F: PrimeField64, Inner: CanObserve<u8>> CanObserve<F> for SerializingChallenger64<F, Inner> {
     fn observe(&mut self, value: F) {
-        self.inner
-            .observe_slice(&value.to_unique_u64().to_le_bytes());
+        for byte in encode_iter([value]) {
+            self.inner.observe(byte);
+        }
     }
 }
 
@@ -196,6 +207,20 @@ impl<F: PrimeField64, const N: usize, Inner: CanObserve<u8>> CanObserve<Hash<F,
     }
 }
 
+impl<F, W, const N: usize, Inner> CanObserve<MerkleCap<F, W, N>>
+    for SerializingChallenger64<F, Inner>
+where
+    F: PrimeField64,
+    Inner: CanObserve<u8>,
+    Self: CanObserve<Hash<F, W, N>>,
+{
+    fn observe(&mut self, cap: MerkleCap<F, W, N>) {
+        for root in cap {
+            self.observe(root);
+        }
+    }
+}
+
 impl<F, EF, Inner> CanSample<EF> for SerializingChallenger64<F, Inner>
 where
     F: PrimeField64,
@@ -243,13 +268,7 @@ where
 
     #[instrument(name = "grind for proof-of-work witness", skip_all)]
     fn grind(&mut self, bits: usize) -> Self::Witness {
-        let witness = (0..F::ORDER_U64)
-            .into_par_iter()
-            .map(|i| F::from_canonical_u64(i))
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
-            .expect("failed to find witness");
-        assert!(self.check_witness(bits, witness));
-        witness
+        grind_by_search(self, bits)
     }
 }
 
@@ -259,3 +278,72 @@ where
     Inner: CanSample<u8> + CanObserve<u8> + Clone + Send + Sync,
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::Goldilocks;
+    use p3_keccak::Keccak256Hash;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    #[test]
+    fn keccak_goldilocks_challenges_are_pinned() {
+        let mut challenger = SerializingChallenger64::<F, _>::from_hasher(vec![], Keccak256Hash);
+        challenger.observe_slice(&[F::ONE, F::TWO, F::NEG_ONE]);
+        let samples: Vec<F> = challenger.sample_vec(4);
+        let expected = [
+            0x2a29d5ef14341278,
+            0x20b2cc3b9581c30a,
+            0xfb80fbc42df18f2e,
+            0x7cbed7fea083d08e,
+        ];
+        assert_eq!(samples, expected.map(F::from_canonical_u64));
+
+        challenger.observe(F::from_canonical_u8(42));
+        let samples: Vec<F> = challenger.sample_vec(2);
+        let expected = [0x86234be8efc8a67d, 0x13a498ffe754ea26];
+        assert_eq!(samples, expected.map(F::from_canonical_u64));
+        assert_eq!(challenger.sample_bits(16), 3145);
+    }
+
+    /// Returns the bytes it was given, in order.
+    #[derive(Clone)]
+    struct Replay(Vec<u8>);
+
+    impl Replay {
+        fn of(values: &[u64]) -> Self {
+            Self(values.iter().flat_map(|v| v.to_le_bytes()).collect())
+        }
+    }
+
+    impl CanObserve<u8> for Replay {
+        fn observe(&mut self, _value: u8) {}
+    }
+
+    impl CanSample<u8> for Replay {
+        fn sample(&mut self) -> u8 {
+            self.0.remove(0)
+        }
+    }
+
+    #[test]
+    fn samples_past_the_order_are_redrawn() {
+        let p = F::ORDER_U64;
+        let mut challenger =
+            SerializingChallenger64::<F, _>::new(Replay::of(&[p, u64::MAX, p - 1, p + 1, 0]));
+        let first: F = challenger.sample();
+        assert_eq!(first, F::NEG_ONE);
+        let second: F = challenger.sample();
+        assert_eq!(second, F::ZERO);
+        assert!(challenger.inner.0.is_empty());
+
+        // Bits are taken straight from the bytes, which are uniform, so need no redraws.
+        let mut challenger = SerializingChallenger64::<F, _>::new(Replay::of(&[u64::MAX]));
+        assert_eq!(challenger.sample_bits(32), 0xffff_ffff);
+    }
+}
@@ -17,6 +17,8 @@ fn bench_lde(c: &mut Criterion) {
     let mut g = c.benchmark_group("lde");
     g.sample_size(10);
     lde_cfft(&mut g, log_n, log_w);
+    // A single column, where the blocks of the last few layers are narrower than a packed vector.
+    lde_cfft(&mut g, log_n, 0);
     lde_twoadic::<BabyBear, Radix2Dit<_>, _>(&mut g, log_n, log_w);
     lde_twoadic::<BabyBear, Radix2DitParallel<_>, _>(&mut g, log_n, log_w);
     lde_twoadic::<BabyBear, Radix2Bowers, _>(&mut g, log_n, log_w);
@@ -5,7 +5,9 @@ use itertools::{iterate, izip, Itertools};
 use p3_commit::PolynomialSpace;
 use p3_dft::{divide_by_height, Butterfly, DifButterfly, DitButterfly};
 use p3_field::extension::ComplexExtendable;
-use p3_field::{batch_multiplicative_inverse, ExtensionField, Field};
+use p3_field::{
+    batch_multiplicative_inverse, ExtensionField, Field, FieldAlgebra, PackedField, PackedValue,
+};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
@@ -191,14 +193,84 @@ impl<F: ComplexExtendable> CircleEvaluations<F, RowMajorMatrix<F>> {
 }
 
 #[inline]
-fn serial_layer<F: Field, B: Butterfly<F>>(values: &mut [F], twiddles: &[B]) {
+fn serial_layer<F: Field, B: LaneButterfly<F>>(values: &mut [F], twiddles: &[B]) {
     let blk_sz = values.len() / twiddles.len();
+    let half_blk_sz = blk_sz / 2;
+    if half_blk_sz < F::Packing::WIDTH
+        && half_blk_sz.is_power_of_two()
+        && values.len() % (2 * F::Packing::WIDTH) == 0
+    {
+        narrow_layer(values, twiddles, half_blk_sz);
+        return;
+    }
     for (&t, blk) in izip!(twiddles, values.chunks_exact_mut(blk_sz)) {
         let (lo, hi) = blk.split_at_mut(blk_sz / 2);
         t.apply_to_rows(lo, hi);
     }
 }
 
+/// A layer whose blocks are narrower than a packed vector, so `apply_to_rows` would fall back to
+/// scalar butterflies.
+///
+/// Instead, each pair of packed vectors is interleaved so that the first holds the low halves of
+/// its blocks and the second the high halves, and the butterflies are applied with each lane's
+/// own twiddle.
+#[inline]
+fn narrow_layer<F: Field, B: LaneButterfly<F>>(
+    values: &mut [F],
+    twiddles: &[B],
+    half_blk_sz: usize,
+) {
+    let blks_per_vector = F::Packing::WIDTH / (2 * half_blk_sz);
+    let packed = F::Packing::pack_slice_mut(values);
+    for (pair, blks) in izip!(
+        packed.chunks_exact_mut(2),
+        twiddles.chunks_exact(2 * blks_per_vector)
+    ) {
+        let (lo, hi) = pair[0].interleave(pair[1], half_blk_sz);
+        // Chunk `2m` of `lo` is the low half of block `m` of the first vector, and chunk `2m + 1`
+        // that of block `m` of the second.
+        let twiddle = F::Packing::from_fn(|lane| {
+            let chunk = lane / half_blk_sz;
+            blks[(chunk % 2) * blks_per_vector + chunk / 2].twiddle()
+        });
+        let (lo, hi) = B::apply_with_lanes(lo, hi, twiddle);
+        (pair[0], pair[1]) = lo.interleave(hi, half_blk_sz);
+    }
+}
+
+/// A butterfly which can also be applied with a different twiddle in each lane.
+trait LaneButterfly<F: Field>: Butterfly<F> {
+    fn twiddle(&self) -> F;
+
+    fn apply_with_lanes<PF: PackedField<Scalar = F>>(x_1: PF, x_2: PF, twiddle: PF) -> (PF, PF);
+}
+
+impl<F: Field> LaneButterfly<F> for DitButterfly<F> {
+    #[inline]
+    fn twiddle(&self) -> F {
+        self.0
+    }
+
+    #[inline]
+    fn apply_with_lanes<PF: PackedField<Scalar = F>>(x_1: PF, x_2: PF, twiddle: PF) -> (PF, PF) {
+        let x_2_twiddle = x_2 * twiddle;
+        (x_1 + x_2_twiddle, x_1 - x_2_twiddle)
+    }
+}
+
+impl<F: Field> LaneButterfly<F> for DifButterfly<F> {
+    #[inline]
+    fn twiddle(&self) -> F {
+        self.0
+    }
+
+    #[inline]
+    fn apply_with_lanes<PF: PackedField<Scalar = F>>(x_1: PF, x_2: PF, twiddle: PF) -> (PF, PF) {
+        (x_1 + x_2, (x_1 - x_2) * twiddle)
+    }
+}
+
 #[inline]
 #[instrument(level = "debug", skip_all, fields(log_blks = log2_strict_usize(twiddles.len())))]
 fn par_within_blk_layer<F: Field, B: Butterfly<F>>(values: &mut [F], twiddles: &[B]) {
@@ -251,17 +323,26 @@ fn compute_twiddles<F: ComplexExtendable>(domain: CircleDomain<F>) -> Vec<Vec<F>
         for i in 0..(domain.log_n - 2) {
             let prev = twiddles.last().unwrap();
             assert_eq!(prev.len(), 1 << (domain.log_n - 2 - i));
-            let cur = prev
-                .iter()
-                .step_by(2)
-                .map(|x| x.square().double() - F::ONE)
-                .collect_vec();
+            let mut cur = prev.iter().step_by(2).copied().collect_vec();
+            double_x_coords(&mut cur);
             twiddles.push(cur);
         }
     }
     twiddles
 }
 
+/// Replace each `x` by `2x^2 - 1`, the x-coordinate of a point with x-coordinate `x` doubled.
+#[inline]
+fn double_x_coords<F: Field>(xs: &mut [F]) {
+    let (packed, suffix) = F::Packing::pack_slice_with_suffix_mut(xs);
+    packed
+        .iter_mut()
+        .for_each(|x| *x = x.square().double() - F::Packing::ONE);
+    suffix
+        .iter_mut()
+        .for_each(|x| *x = x.square().double() - F::ONE);
+}
+
 pub fn circle_basis<F: Field>(p: Point<F>, log_n: usize) -> Vec<F> {
     let mut b = vec![F::ONE, p.y];
     let mut x = p.x;
@@ -311,6 +392,57 @@ mod tests {
         }
     }
 
+    /// Evaluate with unpacked butterflies and unpacked twiddle computation.
+    fn evaluate_scalar(
+        domain: CircleDomain<F>,
+        mut coeffs: RowMajorMatrix<F>,
+    ) -> RowMajorMatrix<F> {
+        let mut pts = domain.coset0().collect_vec();
+        reverse_slice_index_bits(&mut pts);
+        let mut layers = vec![pts.iter().map(|p| p.y).collect_vec()];
+        if domain.log_n >= 2 {
+            layers.push(pts.iter().step_by(2).map(|p| p.x).collect_vec());
+            for _ in 0..(domain.log_n - 2) {
+                let prev = layers.last().unwrap();
+                let cur = prev
+                    .iter()
+                    .step_by(2)
+                    .map(|x| x.square().double() - F::ONE)
+                    .collect_vec();
+                layers.push(cur);
+            }
+        }
+        for ts in layers.iter().rev() {
+            let blk_sz = coeffs.values.len() / ts.len();
+            for (&t, blk) in izip!(ts, coeffs.values.chunks_exact_mut(blk_sz)) {
+                let (lo, hi) = blk.split_at_mut(blk_sz / 2);
+                for (x_1, x_2) in lo.iter_mut().zip(hi) {
+                    (*x_1, *x_2) = DitButterfly(t).apply::<F>(*x_1, *x_2);
+                }
+            }
+        }
+        coeffs
+    }
+
+    #[test]
+    fn packed_cfft_matches_scalar() {
+        for (log_n, width) in iproduct!([1, 2, 3, 5, 8, 12, 16], [1, 3]) {
+            let domain = CircleDomain::<F>::standard(log_n);
+            let coeffs = RowMajorMatrix::<F>::rand(&mut thread_rng(), 1 << log_n, width);
+            let evals = CircleEvaluations::evaluate(domain, coeffs.clone());
+            assert_eq!(
+                evals.clone().to_cfft_order(),
+                evaluate_scalar(domain, coeffs.clone()),
+                "log_n = {log_n}, width = {width}"
+            );
+            assert_eq!(
+                evals.interpolate(),
+                coeffs,
+                "log_n = {log_n}, width = {width}"
+            );
+        }
+    }
+
     #[test]
     fn test_extrapolation() {
         for (log_n, log_blowup) in iproduct!(2..5, [1, 2, 3]) {
@@ -482,14 +482,16 @@ where
 
 #[cfg(test)]
 mod tests {
-    use p3_challenger::{HashChallenger, SerializingChallenger32};
+    use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger32};
     use p3_commit::ExtensionMmcs;
     use p3_field::extension::BinomialExtensionField;
     use p3_fri::create_test_fri_config;
     use p3_keccak::Keccak256Hash;
     use p3_merkle_tree::MerkleTreeMmcs;
-    use p3_mersenne_31::Mersenne31;
-    use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
+    use p3_mersenne_31::{default_mersenne31_poseidon2_16, Mersenne31, Poseidon2Mersenne31};
+    use p3_symmetric::{
+        CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
+    };
     use rand::{Rng, SeedableRng};
     use rand_chacha::ChaCha8Rng;
 
@@ -554,4 +556,62 @@ mod tests {
         )
         .expect("verify err");
     }
+
+    #[test]
+    fn circle_pcs_poseidon2() {
+        // The same smoke test, with Merkle trees and the challenger built from Poseidon2.
+
+        let mut rng = ChaCha8Rng::from_seed([0; 32]);
+
+        type Val = Mersenne31;
+        type Challenge = BinomialExtensionField<Mersenne31, 3>;
+
+        type Perm = Poseidon2Mersenne31<16>;
+        let perm = default_mersenne31_poseidon2_16();
+
+        type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+        type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+        type ValMmcs =
+            MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+        let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+
+        type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+        let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+        type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+
+        let fri_config = create_test_fri_config(challenge_mmcs);
+
+        type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
+        let pcs = Pcs {
+            mmcs: val_mmcs,
+            fri_config,
+            _phantom: PhantomData,
+        };
+
+        let log_n = 10;
+
+        let d = <Pcs as p3_commit::Pcs<Challenge, Challenger>>::natural_domain_for_degree(
+            &pcs,
+            1 << log_n,
+        );
+
+        let evals = RowMajorMatrix::rand(&mut rng, 1 << log_n, 2);
+
+        let (comm, data) =
+            <Pcs as p3_commit::Pcs<Challenge, Challenger>>::commit(&pcs, vec![(d, evals)]);
+
+        let zeta: Challenge = rng.gen();
+
+        let mut chal = Challenger::new(perm.clone());
+        let (values, proof) = pcs.open(vec![(&data, vec![vec![zeta]])], &mut chal);
+
+        let mut chal = Challenger::new(perm);
+        pcs.verify(
+            vec![(comm, vec![(d, vec![(zeta, values[0][0][0].clone())])])],
+            &proof,
+            &mut chal,
+        )
+        .expect("verify err");
+    }
 }
@@ -28,6 +28,10 @@ where
     Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
+    assert!(
+        config.log_folding_factor == 1 && config.folding_schedule.is_empty(),
+        "circle FRI can only fold by 2 each round"
+    );
     // check sorted descending
     assert!(inputs
         .iter()
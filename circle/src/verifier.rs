@@ -25,6 +25,10 @@ where
     Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
+    assert!(
+        config.log_folding_factor == 1 && config.folding_schedule.is_empty(),
+        "circle FRI can only fold by 2 each round"
+    );
     let betas: Vec<Challenge> = proof
         .commit_phase_commits
         .iter()
@@ -46,9 +50,13 @@ where
 
     let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
 
-    for qp in &proof.query_proofs {
+    for (query, qp) in proof.query_proofs.iter().enumerate() {
         let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
-        let ro = open_input(index, &qp.input_proof).map_err(FriError::InputError)?;
+        let ro = open_input(index, &qp.input_proof).map_err(|error| FriError::InputError {
+            query,
+            index,
+            error,
+        })?;
 
         debug_assert!(
             ro.iter().tuple_windows().all(|((l, _), (r, _))| l > r),
@@ -58,7 +66,8 @@ where
         let folded_eval = verify_query(
             g,
             config,
-            index >> g.extra_query_index_bits(),
+            query,
+            index,
             izip!(
                 &betas,
                 &proof.commit_phase_commits,
@@ -69,7 +78,7 @@ where
         )?;
 
         if folded_eval != proof.final_poly {
-            return Err(FriError::FinalPolyMismatch);
+            return Err(FriError::FinalPolyMismatch { query, index });
         }
     }
 
@@ -85,7 +94,8 @@ type CommitStep<'a, F, M> = (
 fn verify_query<'a, G, F, M>(
     g: &G,
     config: &FriConfig<M>,
-    mut index: usize,
+    query: usize,
+    sampled_index: usize,
     steps: impl Iterator<Item = CommitStep<'a, F, M>>,
     reduced_openings: Vec<(usize, F)>,
     log_max_height: usize,
@@ -95,10 +105,13 @@ where
     M: Mmcs<F> + 'a,
     G: FriGenericConfig<F>,
 {
+    let mut index = sampled_index >> g.extra_query_index_bits();
     let mut folded_eval = F::ZERO;
     let mut ro_iter = reduced_openings.into_iter().peekable();
 
-    for (log_folded_height, (&beta, comm, opening)) in izip!((0..log_max_height).rev(), steps) {
+    for (round, (log_folded_height, (&beta, comm, opening))) in
+        izip!((0..log_max_height).rev(), steps).enumerate()
+    {
         if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_folded_height + 1) {
             folded_eval += ro;
         }
@@ -122,7 +135,12 @@ where
                 &[evals.clone()],
                 &opening.opening_proof,
             )
-            .map_err(FriError::CommitPhaseMmcsError)?;
+            .map_err(|error| FriError::CommitPhaseMmcsError {
+                query,
+                index: sampled_index,
+                round,
+                error,
+            })?;
 
         index = index_pair;
 
@@ -130,10 +148,10 @@ where
     }
 
     debug_assert!(index < config.blowup(), "index was {}", index);
-    debug_assert!(
-        ro_iter.next().is_none(),
-        "verifier reduced_openings were not in descending order?"
-    );
+    // Every input must have been added in by now. Any left over is out of order or too short.
+    if ro_iter.next().is_some() {
+        return Err(FriError::InvalidProofShape);
+    }
 
     Ok(folded_eval)
 }
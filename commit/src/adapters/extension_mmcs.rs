@@ -5,6 +5,7 @@ use core::ops::Deref;
 use p3_field::{ExtensionField, Field};
 use p3_matrix::extension::FlatMatrixView;
 use p3_matrix::{Dimensions, Matrix};
+use serde::Deserializer;
 
 use crate::Mmcs;
 
@@ -34,6 +35,8 @@ where
     type Proof = InnerMmcs::Proof;
     type Error = InnerMmcs::Error;
 
+    const DIGEST_WIDTH: usize = InnerMmcs::DIGEST_WIDTH;
+
     fn commit<M: Matrix<EF>>(&self, inputs: Vec<M>) -> (Self::Commitment, Self::ProverData<M>) {
         self.inner
             .commit(inputs.into_iter().map(FlatMatrixView::new).collect())
@@ -60,6 +63,20 @@ where
             .collect()
     }
 
+    fn commitment_cap_height(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner
+            .commitment_cap_height(&base_dimensions::<F, EF>(dimensions))
+    }
+
+    fn deserialize_commitment<'de, D: Deserializer<'de>>(
+        &self,
+        dimensions: &[Dimensions],
+        deserializer: D,
+    ) -> Result<Self::Commitment, D::Error> {
+        self.inner
+            .deserialize_commitment(&base_dimensions::<F, EF>(dimensions), deserializer)
+    }
+
     fn verify_batch(
         &self,
         commit: &Self::Commitment,
@@ -77,14 +94,23 @@ where
                     .collect()
             })
             .collect();
-        let base_dimensions = dimensions
-            .iter()
-            .map(|dim| Dimensions {
-                width: dim.width * EF::D,
-                height: dim.height,
-            })
-            .collect::<Vec<_>>();
-        self.inner
-            .verify_batch(commit, &base_dimensions, index, &opened_base_values, proof)
+        self.inner.verify_batch(
+            commit,
+            &base_dimensions::<F, EF>(dimensions),
+            index,
+            &opened_base_values,
+            proof,
+        )
     }
 }
+
+/// The dimensions of the matrices of base field elements the inner MMCS commits to.
+fn base_dimensions<F: Field, EF: ExtensionField<F>>(dimensions: &[Dimensions]) -> Vec<Dimensions> {
+    dimensions
+        .iter()
+        .map(|dim| Dimensions {
+            width: dim.width * EF::D,
+            height: dim.height,
+        })
+        .collect()
+}
@@ -5,7 +5,7 @@ use core::fmt::Debug;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Dimensions, Matrix};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// A "Mixed Matrix Commitment Scheme" (MMCS) is a generalization of a vector commitment scheme.
 ///
@@ -22,6 +22,9 @@ pub trait Mmcs<T: Send + Sync>: Clone {
     type Proof: Clone + Serialize + DeserializeOwned;
     type Error: Debug;
 
+    /// The number of elements in each digest of a commitment.
+    const DIGEST_WIDTH: usize;
+
     fn commit<M: Matrix<T>>(&self, inputs: Vec<M>) -> (Self::Commitment, Self::ProverData<M>);
 
     fn commit_matrix<M: Matrix<T>>(&self, input: M) -> (Self::Commitment, Self::ProverData<M>) {
@@ -63,6 +66,25 @@ pub trait Mmcs<T: Send + Sync>: Clone {
             .unwrap_or_else(|| panic!("No committed matrices?"))
     }
 
+    /// The height of the Merkle cap of a commitment to matrices of the given dimensions, i.e. the
+    /// log of the number of digests in it, for an MMCS which commits to Merkle caps; 0 otherwise.
+    fn commitment_cap_height(&self, _dimensions: &[Dimensions]) -> usize {
+        0
+    }
+
+    /// Decode a commitment to matrices of the given dimensions, as encoded by its `Serialize`.
+    ///
+    /// The encoding of a commitment need not record its size: a Merkle cap is just its digests, so
+    /// its `Deserialize` reads a cap of height 0, and a taller cap can only be read knowing the
+    /// height, which follows from the dimensions. By default this reads it with `Deserialize`.
+    fn deserialize_commitment<'de, D: Deserializer<'de>>(
+        &self,
+        _dimensions: &[Dimensions],
+        deserializer: D,
+    ) -> Result<Self::Commitment, D::Error> {
+        Self::Commitment::deserialize(deserializer)
+    }
+
     /// Verify a batch opening.
     /// `index` is the row index we're opening for each matrix, following the same
     /// semantics as `open_batch`.
@@ -1,12 +1,15 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use p3_baby_bear::BabyBear;
-use p3_dft::{Radix2Bowers, Radix2Dit, Radix2DitParallel, TwoAdicSubgroupDft};
+use p3_dft::{
+    Radix2Bowers, Radix2Dit, Radix2DitParallel, Radix2DitParallelConfig, SplitRadixDit,
+    TwoAdicSubgroupDft,
+};
 use p3_field::extension::Complex;
-use p3_field::TwoAdicField;
+use p3_field::{Field, FieldAlgebra, TwoAdicField};
 use p3_goldilocks::Goldilocks;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_mersenne_31::{Mersenne31, Mersenne31ComplexRadix2Dit, Mersenne31Dft};
-use p3_monty_31::dft::RecursiveDft;
+use p3_monty_31::dft::{RecursiveBowers, RecursiveDft};
 use p3_util::pretty_name;
 use rand::distributions::{Distribution, Standard};
 use rand::thread_rng;
@@ -22,11 +25,13 @@ fn bench_fft(c: &mut Criterion) {
 
     fft::<BabyBear, Radix2Dit<_>, BATCH_SIZE>(c, log_sizes);
     fft::<BabyBear, RecursiveDft<_>, BATCH_SIZE>(c, log_sizes);
+    fft::<BabyBear, RecursiveBowers<_>, BATCH_SIZE>(c, log_sizes);
     fft::<BabyBear, Radix2Bowers, BATCH_SIZE>(c, log_sizes);
     fft::<BabyBear, Radix2DitParallel<_>, BATCH_SIZE>(c, log_sizes);
     fft::<Goldilocks, Radix2Dit<_>, BATCH_SIZE>(c, log_sizes);
     fft::<Goldilocks, Radix2Bowers, BATCH_SIZE>(c, log_sizes);
     fft::<Goldilocks, Radix2DitParallel<_>, BATCH_SIZE>(c, log_sizes);
+    fft::<Goldilocks, SplitRadixDit<_>, BATCH_SIZE>(c, log_sizes);
     fft::<Complex<Mersenne31>, Radix2Dit<_>, BATCH_SIZE>(c, log_half_sizes);
     fft::<Complex<Mersenne31>, Radix2Bowers, BATCH_SIZE>(c, log_half_sizes);
     fft::<Complex<Mersenne31>, Radix2DitParallel<_>, BATCH_SIZE>(c, log_half_sizes);
@@ -38,10 +43,14 @@ fn bench_fft(c: &mut Criterion) {
     ifft::<Goldilocks, Radix2Dit<_>, BATCH_SIZE>(c, log_sizes);
 
     coset_lde::<BabyBear, RecursiveDft<_>, BATCH_SIZE>(c, log_sizes);
+    coset_lde::<BabyBear, RecursiveBowers<_>, BATCH_SIZE>(c, log_sizes);
     coset_lde::<BabyBear, Radix2Dit<_>, BATCH_SIZE>(c, log_sizes);
     coset_lde::<BabyBear, Radix2Bowers, BATCH_SIZE>(c, log_sizes);
     coset_lde::<BabyBear, Radix2DitParallel<_>, BATCH_SIZE>(c, log_sizes);
+    coset_lde::<Goldilocks, Radix2Dit<_>, BATCH_SIZE>(c, log_sizes);
     coset_lde::<Goldilocks, Radix2Bowers, BATCH_SIZE>(c, log_sizes);
+    coset_lde::<Goldilocks, Radix2DitParallel<_>, BATCH_SIZE>(c, log_sizes);
+    coset_lde::<Goldilocks, SplitRadixDit<_>, BATCH_SIZE>(c, log_sizes);
 }
 
 fn fft<F, Dft, const BATCH_SIZE: usize>(c: &mut Criterion, log_sizes: &[usize])
@@ -157,5 +166,144 @@ where
     }
 }
 
-criterion_group!(benches, bench_fft);
+/// Compare `coset_ldes_batch` with one `coset_lde_batch` per shift.
+fn bench_coset_ldes(c: &mut Criterion) {
+    type F = BabyBear;
+    let dft = Radix2DitParallel::<F>::default();
+    let shifts: [F; 4] = core::array::from_fn(|i| F::GENERATOR.exp_u64(i as u64 + 1));
+    let messages = RowMajorMatrix::<F>::rand(&mut thread_rng(), 1 << 18, 64);
+
+    let mut group = c.benchmark_group(format!(
+        "coset_ldes/{}/Radix2DitParallel/h=2^18/ncols=64/shifts=4",
+        pretty_name::<F>()
+    ));
+    group.sample_size(10);
+    group.bench_function("coset_ldes_batch", |b| {
+        b.iter(|| dft.coset_ldes_batch(messages.clone(), &shifts));
+    });
+    group.bench_function("coset_lde_batch_per_shift", |b| {
+        b.iter(|| {
+            shifts
+                .iter()
+                .map(|&shift| dft.coset_lde_batch(messages.clone(), 0, shift))
+                .collect::<Vec<_>>()
+        });
+    });
+    group.finish();
+}
+
+/// Sweep the `Radix2DitParallel` tuning parameters over a tiny, a tall and a wide matrix.
+fn bench_parallel_config(c: &mut Criterion) {
+    type F = BabyBear;
+    let configs = [
+        ("default", Radix2DitParallelConfig::default()),
+        (
+            "min_rows=256",
+            Radix2DitParallelConfig {
+                min_rows_per_task: 256,
+                ..Default::default()
+            },
+        ),
+        (
+            "min_rows=4096",
+            Radix2DitParallelConfig {
+                min_rows_per_task: 4096,
+                ..Default::default()
+            },
+        ),
+        (
+            "max_threads=4",
+            Radix2DitParallelConfig {
+                max_threads: Some(4),
+                ..Default::default()
+            },
+        ),
+        (
+            "column_batch=64",
+            Radix2DitParallelConfig {
+                column_batch_width: Some(64),
+                ..Default::default()
+            },
+        ),
+    ];
+    let shapes = [(8, 16), (20, 16), (14, 1024)];
+
+    let mut rng = thread_rng();
+    for (log_h, width) in shapes {
+        let mut group = c.benchmark_group(format!(
+            "coset_lde/{}/Radix2DitParallel/config/h=2^{log_h}/ncols={width}",
+            pretty_name::<F>()
+        ));
+        group.sample_size(10);
+        let messages = RowMajorMatrix::<F>::rand(&mut rng, 1 << log_h, width);
+        for (name, config) in configs {
+            let dft = Radix2DitParallel::new(config);
+            group.bench_with_input(BenchmarkId::from_parameter(name), &dft, |b, dft| {
+                b.iter(|| {
+                    dft.coset_lde_batch(messages.clone(), 1, F::GENERATOR);
+                });
+            });
+        }
+        group.finish();
+    }
+}
+
+/// Goldilocks LDEs with the AVX-512 packing, on matrices from a single packed value per row up to
+/// the batch width used above.
+///
+/// Only built with the `nightly-features` feature on AVX-512 targets, e.g. with
+/// `RUSTFLAGS="-Ctarget-cpu=native" cargo +nightly bench --features nightly-features --bench fft`.
+#[cfg(all(
+    feature = "nightly-features",
+    target_arch = "x86_64",
+    target_feature = "avx512f"
+))]
+fn bench_goldilocks_avx512_lde(c: &mut Criterion) {
+    use p3_field::PackedValue;
+    use p3_goldilocks::PackedGoldilocksAVX512;
+
+    type F = Goldilocks;
+    // Fails to build unless the DFTs below run on the AVX-512 packing.
+    let _: fn(PackedGoldilocksAVX512) -> <F as Field>::Packing = |x| x;
+
+    let mut rng = thread_rng();
+    for log_h in [16, 20] {
+        for width in [PackedGoldilocksAVX512::WIDTH, 64, 256] {
+            let mut group = c.benchmark_group(format!(
+                "coset_lde/{}/avx512/h=2^{log_h}/ncols={width}",
+                pretty_name::<F>()
+            ));
+            group.sample_size(10);
+            let messages = RowMajorMatrix::<F>::rand(&mut rng, 1 << log_h, width);
+            group.bench_function("Radix2DitParallel", |b| {
+                let dft = Radix2DitParallel::<F>::default();
+                b.iter(|| dft.coset_lde_batch(messages.clone(), 1, F::GENERATOR));
+            });
+            group.bench_function("Radix2Bowers", |b| {
+                b.iter(|| Radix2Bowers.coset_lde_batch(messages.clone(), 1, F::GENERATOR));
+            });
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, bench_fft, bench_coset_ldes, bench_parallel_config);
+#[cfg(all(
+    feature = "nightly-features",
+    target_arch = "x86_64",
+    target_feature = "avx512f"
+))]
+criterion_group!(avx512_benches, bench_goldilocks_avx512_lde);
+
+#[cfg(not(all(
+    feature = "nightly-features",
+    target_arch = "x86_64",
+    target_feature = "avx512f"
+)))]
 criterion_main!(benches);
+#[cfg(all(
+    feature = "nightly-features",
+    target_arch = "x86_64",
+    target_feature = "avx512f"
+))]
+criterion_main!(benches, avx512_benches);
@@ -3,19 +3,27 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "out-of-core")]
+extern crate std;
 
 mod butterflies;
 mod naive;
+#[cfg(feature = "out-of-core")]
+mod out_of_core;
 mod radix_2_bowers;
 mod radix_2_dit;
 mod radix_2_dit_parallel;
+mod split_radix_dit;
 mod traits;
 mod util;
 
 pub use butterflies::*;
 pub use naive::*;
+#[cfg(feature = "out-of-core")]
+pub use out_of_core::*;
 pub use radix_2_bowers::*;
 pub use radix_2_dit::*;
 pub use radix_2_dit_parallel::*;
+pub use split_radix_dit::*;
 pub use traits::*;
 pub use util::*;
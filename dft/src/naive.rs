@@ -7,6 +7,13 @@ use p3_util::log2_strict_usize;
 
 use crate::TwoAdicSubgroupDft;
 
+/// The DFT computed directly from its definition, in `O(n^2)` time.
+///
+/// Each output row `i` is the sum over input rows `j` of `g^(ij)` times row `j`, where `g` is the
+/// two-adic generator of the matrix height; every other transform comes from the trait's default
+/// methods. This is far too slow for real use, but simple enough to serve as the reference that
+/// the fast implementations are tested against, e.g. with `test_all_transforms_match_naive` from
+/// `p3-field-testing`.
 #[derive(Default, Clone, Debug)]
 pub struct NaiveDft;
 
@@ -15,6 +22,9 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for NaiveDft {
     fn dft_batch(&self, mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
         let w = mat.width();
         let h = mat.height();
+        if h == 0 {
+            return mat;
+        }
         let log_h = log2_strict_usize(h);
         let g = F::two_adic_generator(log_h);
 
@@ -0,0 +1,295 @@
+//! A DFT wrapper which keeps large matrices in memory-mapped scratch files.
+//!
+//! `OutOfCoreDft` stages the input matrix in a temporary file, then transforms it one block of
+//! columns at a time, with the block width chosen so that each block's working set fits in the
+//! memory budget. The results are written into a second memory-mapped file, which backs the
+//! returned matrix, so the full LDE is never held in memory at once; the kernel pages it in and
+//! out as needed.
+//!
+//! The I/O pattern per block is one strided read of the staged input (each row contributes one
+//! contiguous run of the block's columns) and one strided write of the output in the same shape.
+//! Wider blocks mean longer runs, so giving the transform as much memory as possible reduces the
+//! number of passes over both files as well as the number of seeks within them.
+
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::slice;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bytemuck::Zeroable;
+use memmap2::MmapMut;
+use p3_field::TwoAdicField;
+use p3_matrix::dense::{DenseMatrix, DenseStorage, RowMajorMatrix};
+use p3_matrix::Matrix;
+use tracing::instrument;
+
+use crate::TwoAdicSubgroupDft;
+
+/// The default memory budget of `OutOfCoreDft`, 1 GiB.
+pub const DEFAULT_MEMORY_BUDGET: usize = 1 << 30;
+
+/// A buffer of field elements stored in a memory-mapped temporary file.
+///
+/// New buffers are zero-filled, so `F` must be `Zeroable` for their contents to be valid. The file
+/// has no name on platforms which allow it, and is deleted when the buffer is dropped
+/// otherwise.
+#[derive(Debug)]
+pub struct MmapBuffer<F> {
+    mmap: MmapMut,
+    len: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: Copy + Zeroable> MmapBuffer<F> {
+    /// Create a buffer of `len` elements in a temporary file in `dir`, or in the system's
+    /// temporary directory if `dir` is `None`.
+    ///
+    /// Every element starts out as `F::zeroed()`.
+    fn new(len: usize, dir: Option<&Path>) -> io::Result<Self> {
+        let file = match dir {
+            Some(dir) => tempfile::tempfile_in(dir)?,
+            None => tempfile::tempfile()?,
+        };
+        let bytes = len
+            .checked_mul(size_of::<F>())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "buffer size overflows"))?;
+        file.set_len(bytes as u64)?;
+        // SAFETY: The file was just created and is private to this buffer, so nothing else can
+        // modify or truncate it while it is mapped.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        Ok(Self {
+            mmap,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<F: Zeroable> Borrow<[F]> for MmapBuffer<F> {
+    fn borrow(&self) -> &[F] {
+        // SAFETY: The mapping is page aligned and `len * size_of::<F>()` bytes long. Its bytes
+        // are either the zeros the file was created with, which are a valid `F` as `F` is
+        // `Zeroable`, or were written through `borrow_mut` as values of type `F`.
+        unsafe { slice::from_raw_parts(self.mmap.as_ptr().cast(), self.len) }
+    }
+}
+
+impl<F: Zeroable> BorrowMut<[F]> for MmapBuffer<F> {
+    fn borrow_mut(&mut self) -> &mut [F] {
+        // SAFETY: As above, and the mapping is uniquely borrowed.
+        unsafe { slice::from_raw_parts_mut(self.mmap.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl<F: Clone + Send + Sync + Zeroable> DenseStorage<F> for MmapBuffer<F> {
+    fn to_vec(self) -> Vec<F> {
+        <Self as Borrow<[F]>>::borrow(&self).to_vec()
+    }
+}
+
+/// A matrix stored in a memory-mapped temporary file.
+pub type MmapMatrix<F> = DenseMatrix<F, MmapBuffer<F>>;
+
+/// A wrapper around `Dft` which transforms matrices in blocks of columns, keeping the inputs and
+/// outputs in memory-mapped scratch files.
+///
+/// See the module documentation for the I/O pattern. All methods panic if the scratch files
+/// cannot be created.
+#[derive(Clone, Debug)]
+pub struct OutOfCoreDft<Dft> {
+    inner: Dft,
+    memory_budget: usize,
+    scratch_dir: Option<PathBuf>,
+}
+
+impl<Dft: Default> Default for OutOfCoreDft<Dft> {
+    fn default() -> Self {
+        Self::new(Dft::default(), DEFAULT_MEMORY_BUDGET)
+    }
+}
+
+impl<Dft> OutOfCoreDft<Dft> {
+    /// Wrap `inner`, giving each block transform at most `memory_budget` bytes of working memory.
+    ///
+    /// A single column is always transformed as one block, even if that exceeds the budget.
+    pub const fn new(inner: Dft, memory_budget: usize) -> Self {
+        Self {
+            inner,
+            memory_budget,
+            scratch_dir: None,
+        }
+    }
+
+    /// Create scratch files in `dir` rather than the system's temporary directory.
+    #[must_use]
+    pub fn with_scratch_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.scratch_dir = Some(dir.into());
+        self
+    }
+
+    /// The number of columns to transform at once, for a matrix of the given width whose height
+    /// grows from `in_height` to `out_height`.
+    ///
+    /// Each block holds its input columns and its output columns, and the inner DFT may need one
+    /// more copy of the output, e.g. to undo a bit reversal.
+    pub fn column_block_width<F>(
+        &self,
+        in_height: usize,
+        out_height: usize,
+        width: usize,
+    ) -> usize {
+        let bytes_per_column = (in_height + 2 * out_height) * size_of::<F>();
+        (self.memory_budget / bytes_per_column.max(1)).clamp(1, width.max(1))
+    }
+
+    fn buffer<F: Copy + Zeroable>(&self, len: usize) -> MmapBuffer<F> {
+        MmapBuffer::new(len, self.scratch_dir.as_deref())
+            .expect("failed to create a memory-mapped scratch file")
+    }
+
+    /// Stage `mat` in a scratch file, apply `transform` to each block of columns, and gather the
+    /// outputs, of height `mat.height() << added_bits`, in another scratch file.
+    #[instrument(skip_all, fields(dims = %mat.dimensions(), added_bits = added_bits))]
+    fn by_column_blocks<F, M>(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        transform: impl Fn(RowMajorMatrix<F>) -> M,
+    ) -> MmapMatrix<F>
+    where
+        F: TwoAdicField + Zeroable,
+        M: Matrix<F>,
+    {
+        let (height, width) = (mat.height(), mat.width());
+        let out_height = height << added_bits;
+
+        // Move the input out of memory.
+        let mut input = self.buffer::<F>(mat.values.len());
+        <MmapBuffer<F> as BorrowMut<[F]>>::borrow_mut(&mut input).copy_from_slice(&mat.values);
+        drop(mat);
+        let input = <MmapBuffer<F> as Borrow<[F]>>::borrow(&input);
+
+        let mut output = self.buffer::<F>(out_height * width);
+        let output_values = <MmapBuffer<F> as BorrowMut<[F]>>::borrow_mut(&mut output);
+
+        if width > 0 {
+            let block_width = self.column_block_width::<F>(height, out_height, width);
+            for start in (0..width).step_by(block_width) {
+                let end = (start + block_width).min(width);
+                let block = input
+                    .chunks_exact(width)
+                    .flat_map(|row| row[start..end].iter().copied())
+                    .collect();
+                let evals =
+                    transform(RowMajorMatrix::new(block, end - start)).to_row_major_matrix();
+                debug_assert_eq!(evals.height(), out_height);
+                for (dst, src) in output_values
+                    .chunks_exact_mut(width)
+                    .zip(evals.row_slices())
+                {
+                    dst[start..end].copy_from_slice(src);
+                }
+            }
+        }
+
+        DenseMatrix::new(output, width)
+    }
+}
+
+impl<F, Dft> TwoAdicSubgroupDft<F> for OutOfCoreDft<Dft>
+where
+    F: TwoAdicField + Zeroable,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    type Evaluations = MmapMatrix<F>;
+
+    fn dft_batch(&self, mat: RowMajorMatrix<F>) -> Self::Evaluations {
+        self.by_column_blocks(mat, 0, |block| self.inner.dft_batch(block))
+    }
+
+    fn lde_batch(&self, mat: RowMajorMatrix<F>, added_bits: usize) -> Self::Evaluations {
+        self.coset_lde_batch(mat, added_bits, F::ONE)
+    }
+
+    fn coset_lde_batch(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+    ) -> Self::Evaluations {
+        self.by_column_blocks(mat, added_bits, |block| {
+            self.inner.coset_lde_batch(block, added_bits, shift)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::Field;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::Radix2DitParallel;
+
+    type F = BabyBear;
+
+    #[test]
+    fn matches_in_memory_dft() {
+        let inner = Radix2DitParallel::<F>::default();
+        // Small enough that a 2^10 x 37 LDE with 2 added bits is split into many blocks.
+        let dft = OutOfCoreDft::new(inner.clone(), 64 << 10);
+        assert!(dft.column_block_width::<F>(1 << 10, 1 << 12, 37) < 37);
+
+        let mut rng = thread_rng();
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 1 << 10, 37);
+
+        assert_eq!(
+            dft.coset_lde_batch(mat.clone(), 2, F::GENERATOR)
+                .to_row_major_matrix(),
+            inner
+                .coset_lde_batch(mat.clone(), 2, F::GENERATOR)
+                .to_row_major_matrix()
+        );
+        assert_eq!(
+            dft.lde_batch(mat.clone(), 1).to_row_major_matrix(),
+            inner.lde_batch(mat.clone(), 1).to_row_major_matrix()
+        );
+        assert_eq!(
+            dft.dft_batch(mat.clone()).to_row_major_matrix(),
+            inner.dft_batch(mat.clone()).to_row_major_matrix()
+        );
+        assert_eq!(
+            dft.idft_batch(dft.dft_batch(mat.clone()).to_row_major_matrix()),
+            mat
+        );
+    }
+
+    #[test]
+    fn blocks_respect_a_1_gib_budget() {
+        let dft = OutOfCoreDft::new(Radix2DitParallel::<F>::default(), 1 << 30);
+
+        // A 2^24 x 200 trace with blowup 4 has 50 GiB of LDE, so must go one column at a time.
+        let (height, width, added_bits) = (1 << 24, 200, 2);
+        let out_height = height << added_bits;
+        let block_width = dft.column_block_width::<F>(height, out_height, width);
+        let block_bytes = block_width * (height + 2 * out_height) * size_of::<F>();
+        assert!(block_bytes <= 1 << 30, "{block_bytes} bytes per block");
+        assert_eq!(block_width, 1);
+
+        // A matrix whose LDE fits is transformed in a single block.
+        assert_eq!(dft.column_block_width::<F>(1 << 12, 1 << 14, 64), 64);
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 1 << 12, 64);
+        let lde = dft.coset_lde_batch(mat.clone(), 2, F::GENERATOR);
+        assert_eq!((lde.height(), lde.width()), (1 << 14, 64));
+        assert_eq!(
+            lde.to_row_major_matrix(),
+            Radix2DitParallel::default()
+                .coset_lde_batch(mat, 2, F::GENERATOR)
+                .to_row_major_matrix()
+        );
+    }
+}
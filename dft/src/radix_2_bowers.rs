@@ -21,6 +21,9 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Bowers {
     type Evaluations = RowMajorMatrix<F>;
 
     fn dft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        if mat.height() == 0 {
+            return mat;
+        }
         reverse_matrix_index_bits(&mut mat);
         bowers_g(&mut mat.as_view_mut());
         mat
@@ -28,6 +31,9 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Bowers {
 
     /// Compute the inverse DFT of each column in `mat`.
     fn idft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        if mat.height() == 0 {
+            return mat;
+        }
         bowers_g_t(&mut mat.as_view_mut());
         divide_by_height(&mut mat);
         reverse_matrix_index_bits(&mut mat);
@@ -35,6 +41,9 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Bowers {
     }
 
     fn lde_batch(&self, mut mat: RowMajorMatrix<F>, added_bits: usize) -> RowMajorMatrix<F> {
+        if mat.height() == 0 {
+            return mat;
+        }
         bowers_g_t(&mut mat.as_view_mut());
         divide_by_height(&mut mat);
         mat = mat.bit_reversed_zero_pad(added_bits);
@@ -50,6 +59,9 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Bowers {
         shift: F,
     ) -> RowMajorMatrix<F> {
         let h = mat.height();
+        if h == 0 {
+            return mat;
+        }
         let h_inv = F::from_canonical_usize(h).inverse();
 
         bowers_g_t(&mut mat.as_view_mut());
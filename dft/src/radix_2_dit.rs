@@ -19,12 +19,10 @@ pub struct Radix2Dit<F: TwoAdicField> {
     twiddles: RefCell<BTreeMap<usize, Vec<F>>>,
 }
 
-impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Dit<F> {
-    type Evaluations = RowMajorMatrix<F>;
-
-    fn dft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
-        let h = mat.height();
-        let log_h = log2_strict_usize(h);
+impl<F: TwoAdicField> Radix2Dit<F> {
+    /// Transform each column of `mat` in place. Its height must be nonzero.
+    fn dft_view(&self, mut mat: RowMajorMatrixViewMut<'_, F>) {
+        let log_h = log2_strict_usize(mat.height());
 
         // Compute twiddle factors, or take memoized ones if already available.
         let mut twiddles_ref_mut = self.twiddles.borrow_mut();
@@ -36,10 +34,26 @@ impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Dit<F> {
         // DIT butterfly
         reverse_matrix_index_bits(&mut mat);
         for layer in 0..log_h {
-            dit_layer(&mut mat.as_view_mut(), layer, twiddles);
+            dit_layer(&mut mat, layer, twiddles);
+        }
+    }
+}
+
+impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for Radix2Dit<F> {
+    type Evaluations = RowMajorMatrix<F>;
+
+    fn dft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        if mat.height() > 0 {
+            self.dft_view(mat.as_view_mut());
         }
         mat
     }
+
+    fn dft_in_place(&self, vec: &mut [F]) {
+        if !vec.is_empty() {
+            self.dft_view(RowMajorMatrixViewMut::new_col(vec));
+        }
+    }
 }
 
 /// One layer of a DIT butterfly network.
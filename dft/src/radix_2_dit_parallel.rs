@@ -26,6 +26,9 @@ use crate::TwoAdicSubgroupDft;
 /// communication.
 #[derive(Default, Clone, Debug)]
 pub struct Radix2DitParallel<F> {
+    /// How the butterfly network is split into parallel tasks.
+    config: Radix2DitParallelConfig,
+
     /// Twiddles based on roots of unity, used in the forward DFT.
     twiddles: RefCell<BTreeMap<usize, VectorPair<F>>>,
 
@@ -37,6 +40,56 @@ pub struct Radix2DitParallel<F> {
     inverse_twiddles: RefCell<BTreeMap<usize, VectorPair<F>>>,
 }
 
+/// Tuning parameters for `Radix2DitParallel`.
+///
+/// Each half of the butterfly network is naturally split into independent blocks of rows, one
+/// task per block. These parameters merge neighbouring blocks into larger tasks, which avoids
+/// scheduling overhead on small matrices, and can split wide matrices into batches of columns. The
+/// default leaves the natural split and the full width, and no setting changes the output.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Radix2DitParallelConfig {
+    /// The minimum number of rows each task works on. This is rounded up to a power of two, and
+    /// capped at the height of the matrix.
+    pub min_rows_per_task: usize,
+
+    /// If set, matrices wider than this are transformed in batches of at most this many columns,
+    /// one batch after another.
+    pub column_batch_width: Option<usize>,
+
+    /// If set, an upper bound on the number of tasks, and so of threads, each half of the network
+    /// is split into.
+    pub max_threads: Option<usize>,
+}
+
+impl Radix2DitParallelConfig {
+    /// The number of rows each task works on, for a matrix of height `h` whose network is naturally
+    /// split into blocks of `block_height` rows. Both must be powers of two.
+    fn task_height(&self, block_height: usize, h: usize) -> usize {
+        let mut rows = block_height.max(self.min_rows_per_task.next_power_of_two());
+        if let Some(max_threads) = self.max_threads {
+            rows = rows.max(h.div_ceil(max_threads.max(1)).next_power_of_two());
+        }
+        rows.min(h)
+    }
+}
+
+impl<F> Radix2DitParallel<F> {
+    /// Create a DFT which splits its work according to `config`.
+    pub fn new(config: Radix2DitParallelConfig) -> Self {
+        Self {
+            config,
+            twiddles: RefCell::default(),
+            coset_twiddles: RefCell::default(),
+            inverse_twiddles: RefCell::default(),
+        }
+    }
+
+    /// The tuning parameters of this DFT.
+    pub const fn config(&self) -> &Radix2DitParallelConfig {
+        &self.config
+    }
+}
+
 /// A pair of vectors, one with twiddle factors in their natural order, the other bit-reversed.
 #[derive(Default, Clone, Debug)]
 struct VectorPair<F> {
@@ -103,7 +156,14 @@ impl<F: TwoAdicField + Ord> TwoAdicSubgroupDft<F> for Radix2DitParallel<F> {
     type Evaluations = BitReversedMatrixView<RowMajorMatrix<F>>;
 
     fn dft_batch(&self, mut mat: RowMajorMatrix<F>) -> Self::Evaluations {
+        if let Some(batch_width) = self.column_batch_width(mat.width()) {
+            return in_column_batches(&mat, batch_width, |batch| self.dft_batch(batch));
+        }
+
         let h = mat.height();
+        if h == 0 {
+            return mat.bit_reverse_rows();
+        }
         let log_h = log2_strict_usize(h);
 
         // Compute twiddle factors, or take memoized ones if already available.
@@ -116,11 +176,13 @@ impl<F: TwoAdicField + Ord> TwoAdicSubgroupDft<F> for Radix2DitParallel<F> {
 
         // The first half looks like a normal DIT.
         reverse_matrix_index_bits(&mut mat);
-        first_half(&mut mat, mid, &twiddles.twiddles);
+        let task_height = self.config.task_height(1 << mid, h);
+        first_half(&mut mat, mid, task_height, &twiddles.twiddles);
 
         // For the second half, we flip the DIT, working in bit-reversed order.
         reverse_matrix_index_bits(&mut mat);
-        second_half(&mut mat, mid, &twiddles.bitrev_twiddles, None);
+        let task_height = self.config.task_height(1 << (log_h - mid), h);
+        second_half(&mut mat, mid, task_height, &twiddles.bitrev_twiddles, None);
 
         mat.bit_reverse_rows()
     }
@@ -132,8 +194,17 @@ impl<F: TwoAdicField + Ord> TwoAdicSubgroupDft<F> for Radix2DitParallel<F> {
         added_bits: usize,
         shift: F,
     ) -> Self::Evaluations {
+        if let Some(batch_width) = self.column_batch_width(mat.width()) {
+            return in_column_batches(&mat, batch_width, |batch| {
+                self.coset_lde_batch(batch, added_bits, shift)
+            });
+        }
+
         let w = mat.width;
         let h = mat.height();
+        if h == 0 {
+            return mat.bit_reverse_rows();
+        }
         let log_h = log2_strict_usize(h);
         let mid = log_h.div_ceil(2);
 
@@ -144,13 +215,21 @@ impl<F: TwoAdicField + Ord> TwoAdicSubgroupDft<F> for Radix2DitParallel<F> {
 
         // The first half looks like a normal DIT.
         reverse_matrix_index_bits(&mut mat);
-        first_half(&mut mat, mid, &inverse_twiddles.twiddles);
+        let task_height = self.config.task_height(1 << mid, h);
+        first_half(&mut mat, mid, task_height, &inverse_twiddles.twiddles);
 
         // For the second half, we flip the DIT, working in bit-reversed order.
         reverse_matrix_index_bits(&mut mat);
         // We'll also scale by 1/h, as per the usual inverse DFT algorithm.
         let scale = Some(F::from_canonical_usize(h).inverse());
-        second_half(&mut mat, mid, &inverse_twiddles.bitrev_twiddles, scale);
+        let task_height = self.config.task_height(1 << (log_h - mid), h);
+        second_half(
+            &mut mat,
+            mid,
+            task_height,
+            &inverse_twiddles.bitrev_twiddles,
+            scale,
+        );
         // We skip the final bit-reversal, since the next FFT expects bit-reversed input.
 
         let lde_elems = w * (h << added_bits);
@@ -188,6 +267,47 @@ impl<F: TwoAdicField + Ord> TwoAdicSubgroupDft<F> for Radix2DitParallel<F> {
     }
 }
 
+impl<F> Radix2DitParallel<F> {
+    /// The width of the column batches to split a matrix of width `width` into, if any.
+    fn column_batch_width(&self, width: usize) -> Option<usize> {
+        self.config
+            .column_batch_width
+            .map(|batch_width| batch_width.max(1))
+            .filter(|&batch_width| width > batch_width)
+    }
+}
+
+/// Transform `mat` in batches of at most `batch_width` columns, and interleave the outputs.
+#[instrument(level = "debug", skip_all)]
+fn in_column_batches<F: Copy + Send + Sync>(
+    mat: &RowMajorMatrix<F>,
+    batch_width: usize,
+    mut transform: impl FnMut(RowMajorMatrix<F>) -> BitReversedMatrixView<RowMajorMatrix<F>>,
+) -> BitReversedMatrixView<RowMajorMatrix<F>> {
+    let width = mat.width();
+    // The outputs are all in the same bit-reversed order, so can be interleaved as they are.
+    let outputs = (0..width)
+        .step_by(batch_width)
+        .map(|start| {
+            let end = (start + batch_width).min(width);
+            let batch = mat
+                .row_slices()
+                .flat_map(|row| row[start..end].iter().copied())
+                .collect();
+            transform(RowMajorMatrix::new(batch, end - start)).inner
+        })
+        .collect_vec();
+
+    let height = outputs[0].height();
+    let mut values = Vec::with_capacity(height * width);
+    for r in 0..height {
+        for output in &outputs {
+            values.extend_from_slice(&output.values[r * output.width..(r + 1) * output.width]);
+        }
+    }
+    BitReversalPerm::new_view(RowMajorMatrix::new(values, width))
+}
+
 #[instrument(level = "debug", skip_all)]
 fn coset_dft<F: TwoAdicField + Ord>(
     dft: &Radix2DitParallel<F>,
@@ -202,13 +322,17 @@ fn coset_dft<F: TwoAdicField + Ord>(
         .entry((log_h, shift))
         .or_insert_with(|| compute_coset_twiddles(log_h, shift));
 
+    let h = mat.height();
+
     // The first half looks like a normal DIT.
-    first_half_general(mat, mid, twiddles);
+    let task_height = dft.config.task_height(1 << mid, h);
+    first_half_general(mat, mid, task_height, twiddles);
 
     // For the second half, we flip the DIT, working in bit-reversed order.
     reverse_matrix_index_bits(mat);
 
-    second_half_general(mat, mid, twiddles);
+    let task_height = dft.config.task_height(1 << (log_h - mid), h);
+    second_half_general(mat, mid, task_height, twiddles);
 }
 
 /// Like `coset_dft`, except out-of-place.
@@ -240,8 +364,11 @@ fn coset_dft_oop<F: TwoAdicField + Ord>(
         .entry((log_h, shift))
         .or_insert_with(|| compute_coset_twiddles(log_h, shift));
 
+    let h = src.height();
+
     // The first half looks like a normal DIT.
-    first_half_general_oop(src, dst_maybe, mid, twiddles);
+    let task_height = dft.config.task_height(1 << mid, h);
+    first_half_general_oop(src, dst_maybe, mid, task_height, twiddles);
 
     // dst is now initialized.
     let dst = unsafe {
@@ -253,16 +380,25 @@ fn coset_dft_oop<F: TwoAdicField + Ord>(
     // For the second half, we flip the DIT, working in bit-reversed order.
     reverse_matrix_index_bits(dst);
 
-    second_half_general(dst, mid, twiddles);
+    let task_height = dft.config.task_height(1 << (log_h - mid), h);
+    second_half_general(dst, mid, task_height, twiddles);
 }
 
 /// This can be used as the first half of a DIT butterfly network.
+///
+/// The blocks of this half have `2^mid` rows, and each task works on `task_height` rows, which
+/// must be a power-of-two multiple of that; the same holds for the other halves below.
 #[instrument(level = "debug", skip_all)]
-fn first_half<F: Field>(mat: &mut RowMajorMatrix<F>, mid: usize, twiddles: &[F]) {
+fn first_half<F: Field>(
+    mat: &mut RowMajorMatrix<F>,
+    mid: usize,
+    task_height: usize,
+    twiddles: &[F],
+) {
     let log_h = log2_strict_usize(mat.height());
 
     // max block size: 2^mid
-    mat.par_row_chunks_exact_mut(1 << mid)
+    mat.par_row_chunks_exact_mut(task_height)
         .for_each(|mut submat| {
             let mut backwards = false;
             for layer in 0..mid {
@@ -285,10 +421,11 @@ fn first_half<F: Field>(mat: &mut RowMajorMatrix<F>, mid: usize, twiddles: &[F])
 fn first_half_general<F: Field>(
     mat: &mut RowMajorMatrixViewMut<F>,
     mid: usize,
+    task_height: usize,
     twiddles: &[Vec<F>],
 ) {
     let log_h = log2_strict_usize(mat.height());
-    mat.par_row_chunks_exact_mut(1 << mid)
+    mat.par_row_chunks_exact_mut(task_height)
         .for_each(|mut submat| {
             let mut backwards = false;
             for layer in 0..mid {
@@ -313,11 +450,12 @@ fn first_half_general_oop<F: Field>(
     src: &RowMajorMatrixView<F>,
     dst_maybe: &mut RowMajorMatrixViewMut<MaybeUninit<F>>,
     mid: usize,
+    task_height: usize,
     twiddles: &[Vec<F>],
 ) {
     let log_h = log2_strict_usize(src.height());
-    src.par_row_chunks_exact(1 << mid)
-        .zip(dst_maybe.par_row_chunks_exact_mut(1 << mid))
+    src.par_row_chunks_exact(task_height)
+        .zip(dst_maybe.par_row_chunks_exact_mut(task_height))
         .for_each(|(src_submat, mut dst_submat_maybe)| {
             debug_assert_eq!(src_submat.dimensions(), dst_submat_maybe.dimensions());
 
@@ -363,21 +501,23 @@ fn first_half_general_oop<F: Field>(
 fn second_half<F: Field>(
     mat: &mut RowMajorMatrix<F>,
     mid: usize,
+    task_height: usize,
     twiddles_rev: &[F],
     scale: Option<F>,
 ) {
     let log_h = log2_strict_usize(mat.height());
 
     // max block size: 2^(log_h - mid)
-    mat.par_row_chunks_exact_mut(1 << (log_h - mid))
+    mat.par_row_chunks_exact_mut(task_height)
         .enumerate()
-        .for_each(|(thread, mut submat)| {
+        .for_each(|(task, mut submat)| {
             let mut backwards = false;
             if let Some(scale) = scale {
                 submat.scale(scale);
             }
             for layer in mid..log_h {
-                let first_block = thread << (layer - mid);
+                // The index of the first block of this layer in the submatrix.
+                let first_block = (task * task_height) >> (log_h - layer);
                 dit_layer_rev(
                     &mut submat,
                     log_h,
@@ -396,16 +536,17 @@ fn second_half<F: Field>(
 fn second_half_general<F: Field>(
     mat: &mut RowMajorMatrixViewMut<F>,
     mid: usize,
+    task_height: usize,
     twiddles_rev: &[Vec<F>],
 ) {
     let log_h = log2_strict_usize(mat.height());
-    mat.par_row_chunks_exact_mut(1 << (log_h - mid))
+    mat.par_row_chunks_exact_mut(task_height)
         .enumerate()
-        .for_each(|(thread, mut submat)| {
+        .for_each(|(task, mut submat)| {
             let mut backwards = false;
             for layer in mid..log_h {
                 let layer_rev = log_h - 1 - layer;
-                let first_block = thread << (layer - mid);
+                let first_block = (task * task_height) >> (log_h - layer);
                 dit_layer_rev(
                     &mut submat,
                     log_h,
@@ -517,3 +658,97 @@ fn dit_layer_rev<F: Field>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_matrix::dense::RowMajorMatrix;
+    use rand::thread_rng;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn configs_agree() {
+        let configs = [
+            Radix2DitParallelConfig {
+                min_rows_per_task: 4,
+                ..Default::default()
+            },
+            Radix2DitParallelConfig {
+                min_rows_per_task: 1 << 20,
+                ..Default::default()
+            },
+            Radix2DitParallelConfig {
+                max_threads: Some(1),
+                ..Default::default()
+            },
+            Radix2DitParallelConfig {
+                max_threads: Some(3),
+                ..Default::default()
+            },
+            Radix2DitParallelConfig {
+                column_batch_width: Some(1),
+                ..Default::default()
+            },
+            Radix2DitParallelConfig {
+                min_rows_per_task: 16,
+                column_batch_width: Some(3),
+                max_threads: Some(2),
+            },
+        ];
+
+        let mut rng = thread_rng();
+        let default_dft = Radix2DitParallel::<F>::default();
+        for log_h in 0..9 {
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, 1 << log_h, 7);
+            let dft = default_dft.dft_batch(mat.clone()).to_row_major_matrix();
+            let idft = default_dft.idft_batch(mat.clone());
+            let lde = default_dft
+                .coset_lde_batch(mat.clone(), 2, F::GENERATOR)
+                .to_row_major_matrix();
+
+            for config in configs {
+                let tuned_dft = Radix2DitParallel::new(config);
+                assert_eq!(tuned_dft.config(), &config);
+                assert_eq!(
+                    tuned_dft.dft_batch(mat.clone()).to_row_major_matrix(),
+                    dft,
+                    "dft_batch with {config:?}, log_h = {log_h}"
+                );
+                assert_eq!(
+                    tuned_dft.idft_batch(mat.clone()),
+                    idft,
+                    "idft_batch with {config:?}, log_h = {log_h}"
+                );
+                assert_eq!(
+                    tuned_dft
+                        .coset_lde_batch(mat.clone(), 2, F::GENERATOR)
+                        .to_row_major_matrix(),
+                    lde,
+                    "coset_lde_batch with {config:?}, log_h = {log_h}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn task_height() {
+        let config = Radix2DitParallelConfig {
+            min_rows_per_task: 5,
+            ..Default::default()
+        };
+        assert_eq!(config.task_height(2, 64), 8);
+        assert_eq!(config.task_height(16, 64), 16);
+        assert_eq!(config.task_height(1, 4), 4);
+
+        let config = Radix2DitParallelConfig {
+            max_threads: Some(3),
+            ..Default::default()
+        };
+        // 64 rows over at most 3 tasks needs 32 rows per task.
+        assert_eq!(config.task_height(2, 64), 32);
+        assert_eq!(Radix2DitParallelConfig::default().task_height(2, 64), 2);
+    }
+}
@@ -0,0 +1,193 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use itertools::izip;
+use p3_field::{Field, PackedField, PackedValue, TwoAdicField};
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::util::reverse_matrix_index_bits;
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::*;
+use p3_util::log2_strict_usize;
+
+use crate::butterflies::{Butterfly, DitButterfly, TwiddleFreeButterfly};
+use crate::TwoAdicSubgroupDft;
+
+/// Blocks with fewer rows than this are transformed with radix-2 DIT layers.
+const BASE_CASE_SIZE: usize = 32;
+
+/// The split-radix DIT FFT algorithm.
+///
+/// A DFT of size `n` is split into one DFT of size `n/2`, over the inputs with even indices, and
+/// two of size `n/4`, over the inputs with indices `1` and `3` mod 4. After a bit reversal, these
+/// are the first half and the last two quarters of the block, so the recursion runs in place.
+///
+/// Over the complex numbers this saves about a quarter of the multiplications of radix-2. Over a
+/// prime field the multiplication by the fourth root of unity in each combination is not free, so
+/// most of the gain comes from the depth-first recursion, which keeps blocks in cache.
+#[derive(Default, Clone, Debug)]
+pub struct SplitRadixDit<F> {
+    /// Memoized twiddle factors for each length log_n.
+    twiddles: RefCell<BTreeMap<usize, Vec<F>>>,
+}
+
+impl<F: TwoAdicField> TwoAdicSubgroupDft<F> for SplitRadixDit<F> {
+    type Evaluations = RowMajorMatrix<F>;
+
+    fn dft_batch(&self, mut mat: RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        let h = mat.height();
+        if h == 0 {
+            return mat;
+        }
+        let log_h = log2_strict_usize(h);
+
+        // Compute twiddle factors, or take memoized ones if already available.
+        let mut twiddles_ref_mut = self.twiddles.borrow_mut();
+        let twiddles = twiddles_ref_mut.entry(log_h).or_insert_with(|| {
+            let root = F::two_adic_generator(log_h);
+            root.powers().take(1 << log_h).collect()
+        });
+
+        reverse_matrix_index_bits(&mut mat);
+        split_radix(&mut mat.as_view_mut(), twiddles, 1);
+        mat
+    }
+}
+
+/// Transform a block of bit-reversed rows in place. The twiddle `w^k` for the block's size is
+/// `twiddles[k * stride]`.
+fn split_radix<F: Field>(mat: &mut RowMajorMatrixViewMut<'_, F>, twiddles: &[F], stride: usize) {
+    let n = mat.height();
+    if n < BASE_CASE_SIZE {
+        radix_2_layers(mat, twiddles, stride);
+        return;
+    }
+
+    let quarter = n / 4;
+    let (mut evens, mut odds) = mat.split_rows_mut(n / 2);
+    let (mut ones, mut threes) = odds.split_rows_mut(quarter);
+    join(
+        || split_radix(&mut evens, twiddles, 2 * stride),
+        || {
+            join(
+                || split_radix(&mut ones, twiddles, 4 * stride),
+                || split_radix(&mut threes, twiddles, 4 * stride),
+            )
+        },
+    );
+
+    let (mut evens_lo, mut evens_hi) = evens.split_rows_mut(quarter);
+    let i = twiddles[quarter * stride];
+    evens_lo
+        .par_rows_mut()
+        .zip(evens_hi.par_rows_mut())
+        .zip(ones.par_rows_mut())
+        .zip(threes.par_rows_mut())
+        .enumerate()
+        .for_each(|(k, (((e_lo, e_hi), one), three))| {
+            let w_1 = twiddles[k * stride];
+            let w_3 = twiddles[3 * k * stride];
+            split_radix_rows(e_lo, e_hi, one, three, w_1, w_3, i);
+        });
+}
+
+/// Combine row `k` of each quarter of a block of size `n`, where `w_1 = w^k`, `w_3 = w^(3k)` and
+/// `i = w^(n/4)`:
+///
+/// ```text
+/// X[k]          = E[k]       + (w_1 O_1[k] + w_3 O_3[k])
+/// X[k + n/2]    = E[k]       - (w_1 O_1[k] + w_3 O_3[k])
+/// X[k + n/4]    = E[k + n/4] + i (w_1 O_1[k] - w_3 O_3[k])
+/// X[k + 3n/4]   = E[k + n/4] - i (w_1 O_1[k] - w_3 O_3[k])
+/// ```
+#[inline]
+fn split_radix_rows<F: Field>(
+    e_lo: &mut [F],
+    e_hi: &mut [F],
+    one: &mut [F],
+    three: &mut [F],
+    w_1: F,
+    w_3: F,
+    i: F,
+) {
+    let (e_lo_packed, e_lo_suffix) = F::Packing::pack_slice_with_suffix_mut(e_lo);
+    let (e_hi_packed, e_hi_suffix) = F::Packing::pack_slice_with_suffix_mut(e_hi);
+    let (one_packed, one_suffix) = F::Packing::pack_slice_with_suffix_mut(one);
+    let (three_packed, three_suffix) = F::Packing::pack_slice_with_suffix_mut(three);
+    for (a, b, c, d) in izip!(e_lo_packed, e_hi_packed, one_packed, three_packed) {
+        split_radix_butterfly(a, b, c, d, w_1, w_3, i);
+    }
+    for (a, b, c, d) in izip!(e_lo_suffix, e_hi_suffix, one_suffix, three_suffix) {
+        split_radix_butterfly(a, b, c, d, w_1, w_3, i);
+    }
+}
+
+#[inline(always)]
+fn split_radix_butterfly<PF: PackedField>(
+    e_lo: &mut PF,
+    e_hi: &mut PF,
+    one: &mut PF,
+    three: &mut PF,
+    w_1: PF::Scalar,
+    w_3: PF::Scalar,
+    i: PF::Scalar,
+) {
+    let x = *one * w_1;
+    let y = *three * w_3;
+    let sum = x + y;
+    let diff = (x - y) * i;
+    (*e_lo, *one) = (*e_lo + sum, *e_lo - sum);
+    (*e_hi, *three) = (*e_hi + diff, *e_hi - diff);
+}
+
+/// Transform a small block of bit-reversed rows with radix-2 DIT layers.
+fn radix_2_layers<F: Field>(mat: &mut RowMajorMatrixViewMut<'_, F>, twiddles: &[F], stride: usize) {
+    let n = mat.height();
+    let log_n = log2_strict_usize(n);
+    for layer in 0..log_n {
+        let half_block_size = 1 << layer;
+        // The twiddles for this layer are powers of a root of unity of order 2 * half_block_size.
+        let layer_stride = stride << (log_n - 1 - layer);
+        mat.par_row_chunks_exact_mut(2 * half_block_size)
+            .for_each(|mut block_chunks| {
+                let (mut hi_chunks, mut lo_chunks) = block_chunks.split_rows_mut(half_block_size);
+                hi_chunks
+                    .par_rows_mut()
+                    .zip(lo_chunks.par_rows_mut())
+                    .enumerate()
+                    .for_each(|(ind, (hi_chunk, lo_chunk))| {
+                        if ind == 0 {
+                            TwiddleFreeButterfly.apply_to_rows(hi_chunk, lo_chunk)
+                        } else {
+                            DitButterfly(twiddles[ind * layer_stride])
+                                .apply_to_rows(hi_chunk, lo_chunk)
+                        }
+                    });
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_goldilocks::Goldilocks;
+    use p3_matrix::dense::RowMajorMatrix;
+    use rand::thread_rng;
+
+    use crate::{NaiveDft, Radix2Dit, SplitRadixDit, TwoAdicSubgroupDft};
+
+    #[test]
+    fn matches_radix_2_dit_and_naive() {
+        type F = Goldilocks;
+        let mut rng = thread_rng();
+        let dft = SplitRadixDit::<F>::default();
+        for log_h in 1..=14 {
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, 1 << log_h, 3);
+            let expected = Radix2Dit::default().dft_batch(mat.clone());
+            assert_eq!(dft.dft_batch(mat.clone()), expected, "log_h = {log_h}");
+            // The naive DFT is quadratic, so only check it on the smaller sizes.
+            if log_h <= 10 {
+                assert_eq!(NaiveDft.dft_batch(mat), expected, "log_h = {log_h}");
+            }
+        }
+    }
+}
@@ -1,10 +1,18 @@
 use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::iter;
 
-use p3_field::TwoAdicField;
+use itertools::Itertools;
+use p3_field::{ExtensionField, TwoAdicField};
 use p3_matrix::bitrev::BitReversableMatrix;
+use p3_matrix::col_major::ColMajorMatrix;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::util::swap_rows;
 use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::*;
+use p3_util::{log2_ceil_usize, log2_strict_usize, reverse_bits_len};
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
 
 use crate::util::{coset_shift_cols, divide_by_height};
 
@@ -20,11 +28,85 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
             .values
     }
 
+    /// Compute the discrete Fourier transform (DFT) of `vec` in place.
+    ///
+    /// The default copies `vec` into `dft` and the evaluations back; implementations which can
+    /// transform a column where it lies override this to skip both copies.
+    fn dft_in_place(&self, vec: &mut [F]) {
+        let evals = self.dft(vec.to_vec());
+        vec.copy_from_slice(&evals);
+    }
+
     /// Compute the discrete Fourier transform (DFT) of each column in `mat`.
     /// This is the only method an implementer needs to define, all other
     /// methods can be derived from this one.
+    ///
+    /// An empty matrix, such as one with no columns, must be returned as it is.
     fn dft_batch(&self, mat: RowMajorMatrix<F>) -> Self::Evaluations;
 
+    /// Compute the DFT of each column in `cols`, a column-major matrix, in natural order.
+    ///
+    /// Each column is transformed by itself with `dft`, so the columns are never gathered into a
+    /// row-major matrix. The columns are split between threads, each of which works with its own
+    /// clone of `self`, as the twiddle caches of most implementations cannot be shared.
+    ///
+    /// Fails if the columns do not all have the same length.
+    fn dft_cols(&self, cols: Vec<Vec<F>>) -> Result<Vec<Vec<F>>, RaggedColumns>
+    where
+        Self: Send,
+    {
+        if let Some(first) = cols.first() {
+            let expected_len = first.len();
+            if let Some((col, c)) = cols.iter().find_position(|c| c.len() != expected_len) {
+                return Err(RaggedColumns {
+                    col,
+                    len: c.len(),
+                    expected_len,
+                });
+            }
+        }
+
+        let chunk_len = cols.len().div_ceil(current_num_threads()).max(1);
+        let chunks: Vec<(Self, Vec<Vec<F>>)> = cols
+            .into_iter()
+            .chunks(chunk_len)
+            .into_iter()
+            .map(|chunk| (self.clone(), chunk.collect()))
+            .collect();
+        Ok(chunks
+            .into_par_iter()
+            .flat_map_iter(|(dft, chunk)| chunk.into_iter().map(move |col| dft.dft(col)))
+            .collect())
+    }
+
+    /// Compute the DFT of each column of a column-major matrix, in natural order, without
+    /// transposing it.
+    ///
+    /// Each column is transformed where it lies in the matrix with `dft_in_place`. As in
+    /// `dft_cols`, the columns are split between threads, each with its own clone of `self`.
+    fn dft_col_major(&self, mut mat: ColMajorMatrix<F>) -> ColMajorMatrix<F>
+    where
+        Self: Send,
+    {
+        let h = mat.height();
+        if h == 0 {
+            return mat;
+        }
+        let chunk_len = mat.width().div_ceil(current_num_threads()).max(1) * h;
+        let dfts = (0..mat.values.len().div_ceil(chunk_len))
+            .map(|_| self.clone())
+            .collect_vec();
+        mat.values
+            .par_chunks_mut(chunk_len)
+            .zip(dfts)
+            .for_each(|(cols, dft)| {
+                for col in cols.chunks_exact_mut(h) {
+                    dft.dft_in_place(col);
+                }
+            });
+        mat
+    }
+
     /// Compute the "coset DFT" of `vec`. This can be viewed as interpolation onto a coset of a
     /// multiplicative subgroup, rather than the subgroup itself.
     fn coset_dft(&self, vec: Vec<F>, shift: F) -> Vec<F> {
@@ -64,6 +146,30 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
         dft
     }
 
+    /// Compute the inverse DFT of `vec`, whose entries lie in an extension of `F`.
+    fn idft_algebra<EF: ExtensionField<F>>(&self, vec: Vec<EF>) -> Vec<EF> {
+        self.idft_algebra_batch(RowMajorMatrix::new_col(vec)).values
+    }
+
+    /// Compute the inverse DFT of each column in `mat`, whose entries lie in an extension of `F`.
+    ///
+    /// The DFT is `F`-linear, so this runs the base field inverse DFT on the matrix of
+    /// coefficients, with one column per coefficient of each original column, and reassembles the
+    /// results.
+    fn idft_algebra_batch<EF: ExtensionField<F>>(
+        &self,
+        mat: RowMajorMatrix<EF>,
+    ) -> RowMajorMatrix<EF> {
+        let width = mat.width();
+        let coeffs = self.idft_batch(mat.flatten_to_base());
+        let values = coeffs
+            .values
+            .chunks_exact(EF::D)
+            .map(EF::from_base_slice)
+            .collect();
+        RowMajorMatrix::new(values, width)
+    }
+
     /// Compute the "coset iDFT" of `vec`. This can be viewed as an inverse operation of
     /// "coset DFT", that interpolates over a coset of a multiplicative subgroup, rather than
     /// subgroup itself.
@@ -97,6 +203,35 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
         self.dft_batch(coeffs)
     }
 
+    /// Compute the low-degree extension of each column in `mat` to exactly `target_height` rows,
+    /// which need not be a power of two, e.g. for a blowup of 3 or 6.
+    ///
+    /// The columns are extended onto the subgroup of order `target_height.next_power_of_two()`,
+    /// and row `i` of the result is the evaluation at `g^i`, where `g` is that subgroup's
+    /// generator: the result is the first `target_height` rows of the natural-order LDE, and the
+    /// remaining points of the subgroup are dropped.
+    ///
+    /// Panics if `target_height` is less than the height of `mat`.
+    fn lde_batch_to_height(
+        &self,
+        mat: RowMajorMatrix<F>,
+        target_height: usize,
+    ) -> RowMajorMatrix<F> {
+        let h = mat.height();
+        assert!(
+            target_height >= h,
+            "target height {target_height} is less than the matrix height {h}"
+        );
+        if h == 0 {
+            return mat;
+        }
+        let added_bits = log2_ceil_usize(target_height) - log2_strict_usize(h);
+        let w = mat.width();
+        let mut lde = self.lde_batch(mat, added_bits).to_row_major_matrix();
+        lde.values.truncate(target_height * w);
+        lde
+    }
+
     /// Compute the low-degree extension of each column in `mat` onto a coset of a larger subgroup.
     fn coset_lde(&self, vec: Vec<F>, added_bits: usize, shift: F) -> Vec<F> {
         self.coset_lde_batch(RowMajorMatrix::new(vec, 1), added_bits, shift)
@@ -123,4 +258,413 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
         );
         self.coset_dft_batch(coeffs, shift)
     }
+
+    /// Compute the low-degree extension of each column in `mat` onto a coset of a larger subgroup,
+    /// in bit-reversed order, as a sequence of blocks of `chunk_height` rows.
+    ///
+    /// The concatenated blocks are `coset_lde_batch(mat, added_bits, shift)` with its rows in
+    /// bit-reversed order, which is the order the PCS commits to. In that order, each block is
+    /// the evaluation over a coset `t K` of the subgroup `K` of order `chunk_height`, and each
+    /// aligned run of `2^e` blocks over a coset of the subgroup of order `2^e chunk_height`. The
+    /// coefficients are reduced modulo `x^n - t^n` for these cosets down a binary tree, halving `n`
+    /// at each level, so a block only costs the reductions on its path from the nearest ancestor of
+    /// the height of `mat` and a `coset_dft_batch` of its own height. Overall this does
+    /// `O(w n log(h / chunk_height))` reductions for an LDE of height `n` and width `w` of a matrix
+    /// of height `h`, on top of the per-block transforms.
+    ///
+    /// The inverse transform is done up front, but each block is only evaluated when the iterator
+    /// reaches it, so no more than twice the coefficients and one block are held in memory at
+    /// once, however large the LDE.
+    ///
+    /// Panics unless `chunk_height` is a power of two no greater than the height of the LDE.
+    fn coset_lde_bit_reversed_row_chunks(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+        chunk_height: usize,
+    ) -> impl Iterator<Item = RowMajorMatrix<F>> + '_ {
+        let lde_height = mat.height() << added_bits;
+        assert!(
+            chunk_height.is_power_of_two() && chunk_height <= lde_height,
+            "chunk height {chunk_height} must be a power of two no greater than {lde_height}"
+        );
+        let w = mat.width();
+        let log_chunk_height = log2_strict_usize(chunk_height);
+        let log_num_chunks = log2_strict_usize(lde_height / chunk_height);
+        // The cosets of this height are the roots of the tree, and the coefficients are already
+        // reduced modulo each of them.
+        let log_root_height = log2_strict_usize(mat.height()).max(log_chunk_height);
+        let g = F::two_adic_generator(log2_strict_usize(lde_height));
+        let coeffs = self.idft_batch(mat);
+
+        let mut roots = 0..lde_height >> log_root_height;
+        // The nodes still to visit, each as the coefficients reduced modulo `x^n - t^n`, `t` and
+        // `log2(n)`, with the next one on top.
+        let mut stack: Vec<(Vec<F>, F, usize)> = Vec::new();
+        iter::from_fn(move || loop {
+            let (mut rem, t, log_n) = match stack.pop() {
+                Some(node) => node,
+                None => {
+                    // Block `chunk` in bit-reversed order is the coset of `K` through the point
+                    // `shift g^(bitrev(chunk))`.
+                    let chunk = roots.next()? << (log_root_height - log_chunk_height);
+                    let t = shift * g.exp_u64(reverse_bits_len(chunk, log_num_chunks) as u64);
+                    (coeffs.values.clone(), t, log_root_height)
+                }
+            };
+            if log_n == log_chunk_height {
+                rem.resize(chunk_height * w, F::ZERO);
+                return Some(
+                    self.coset_dft_batch(RowMajorMatrix::new(rem, w), t)
+                        .bit_reverse_rows()
+                        .to_row_major_matrix(),
+                );
+            }
+            // With `n = 2m` and `u = t^m`, `x^n - t^n = (x^m - u) (x^m + u)`, and the second factor
+            // is that of the coset through `t` times a primitive `n`-th root of unity.
+            let u = t.exp_power_of_2(log_n - 1);
+            let hi = rem.split_off(w << (log_n - 1));
+            let mut rem_minus = rem.clone();
+            for ((plus, minus), &coeff) in rem.iter_mut().zip(&mut rem_minus).zip(&hi) {
+                let term = coeff * u;
+                *plus += term;
+                *minus -= term;
+            }
+            stack.push((rem_minus, t * F::two_adic_generator(log_n), log_n - 1));
+            stack.push((rem, t, log_n - 1));
+        })
+    }
+
+    /// Evaluate the interpolant of each column in `mat` over several cosets `shift * H` of the
+    /// subgroup `H` of the same height, one output matrix per shift.
+    ///
+    /// This interpolates once, and shares the coefficients between all cosets, rather than calling
+    /// `coset_lde_batch(mat, 0, shift)` per shift.
+    fn coset_ldes_batch(&self, mat: RowMajorMatrix<F>, shifts: &[F]) -> Vec<RowMajorMatrix<F>> {
+        let coeffs = self.idft_batch(mat);
+        shifts
+            .iter()
+            .map(|&shift| {
+                self.coset_dft_batch(coeffs.clone(), shift)
+                    .to_row_major_matrix()
+            })
+            .collect()
+    }
+
+    /// Compute a blinded low-degree extension of each column in `mat` onto a coset of a larger
+    /// subgroup, for use in zero-knowledge openings.
+    ///
+    /// For a matrix of height `h`, this appends `h` uniformly random coefficients to the
+    /// interpolant of each column, in the form `r(x) (x^h - 1)` with `deg r < h`. The extended
+    /// polynomials have degree less than `2h`, so `added_bits` must be at least one, and still agree
+    /// with the columns of `mat` on the original subgroup, as `x^h - 1` vanishes there.
+    fn coset_lde_batch_zk<R: Rng + ?Sized>(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+        rng: &mut R,
+    ) -> Self::Evaluations
+    where
+        Standard: Distribution<F>,
+    {
+        assert!(
+            added_bits > 0,
+            "a blinded LDE needs at least one added bit to fit the random coefficients"
+        );
+        let h = mat.height();
+        let w = mat.width();
+        let mut coeffs = self.idft_batch(mat);
+        // PANICS: possible panic if the new resized length overflows
+        coeffs.values.resize(
+            coeffs
+                .values
+                .len()
+                .checked_shl(added_bits.try_into().unwrap())
+                .unwrap(),
+            F::ZERO,
+        );
+
+        // Add r(x) x^h - r(x), one random polynomial r per column.
+        let (low, high) = coeffs.values.split_at_mut(h * w);
+        for (lo, hi) in low.iter_mut().zip(&mut high[..h * w]) {
+            let r: F = rng.gen();
+            *lo -= r;
+            *hi += r;
+        }
+        self.coset_dft_batch(coeffs, shift)
+    }
+}
+
+/// An error returned by `dft_cols` when the columns do not all have the same length.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RaggedColumns {
+    /// The index of the first column whose length differs from that of column 0.
+    pub col: usize,
+    /// The length of that column.
+    pub len: usize,
+    /// The length of column 0.
+    pub expected_len: usize,
+}
+
+impl Display for RaggedColumns {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "column {} has length {}, but column 0 has length {}",
+            self.col, self.len, self.expected_len
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::{ExtensionField, FieldAlgebra, TwoAdicField};
+    use p3_goldilocks::Goldilocks;
+    use p3_matrix::bitrev::BitReversableMatrix;
+    use p3_matrix::col_major::ColMajorMatrix;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_matrix::{Dimensions, Matrix};
+    use p3_util::log2_ceil_usize;
+    use rand::distributions::{Distribution, Standard};
+    use rand::thread_rng;
+
+    use crate::{
+        NaiveDft, Radix2Bowers, Radix2Dit, Radix2DitParallel, RaggedColumns, TwoAdicSubgroupDft,
+    };
+
+    /// The inverse DFT computed directly in the extension field, from its definition.
+    fn naive_idft_algebra<F: TwoAdicField, EF: ExtensionField<F>>(
+        mat: &RowMajorMatrix<EF>,
+    ) -> RowMajorMatrix<EF> {
+        let (h, w) = (mat.height(), mat.width());
+        let omega_inv = F::two_adic_generator(p3_util::log2_strict_usize(h)).inverse();
+        let h_inv = F::from_canonical_usize(h).inverse();
+        let mut res = RowMajorMatrix::new(vec![EF::ZERO; h * w], w);
+        for j in 0..h {
+            for i in 0..h {
+                let x = omega_inv.exp_u64((i * j) as u64) * h_inv;
+                for c in 0..w {
+                    res.values[j * w + c] += mat.values[i * w + c] * x;
+                }
+            }
+        }
+        res
+    }
+
+    fn idft_algebra_matches_naive<F, EF, Dft>()
+    where
+        F: TwoAdicField,
+        EF: ExtensionField<F>,
+        Standard: Distribution<EF>,
+        Dft: TwoAdicSubgroupDft<F>,
+    {
+        let dft = Dft::default();
+        let mut rng = thread_rng();
+        for log_h in 0..7 {
+            let h = 1 << log_h;
+            let mat = RowMajorMatrix::<EF>::rand(&mut rng, h, 3);
+            assert_eq!(
+                dft.idft_algebra_batch(mat.clone()),
+                naive_idft_algebra::<F, EF>(&mat)
+            );
+
+            let col = mat.values[..h].to_vec();
+            let expected = naive_idft_algebra::<F, EF>(&RowMajorMatrix::new_col(col.clone()));
+            assert_eq!(dft.idft_algebra(col), expected.values);
+        }
+    }
+
+    #[test]
+    fn idft_algebra_baby_bear() {
+        type F = BabyBear;
+        idft_algebra_matches_naive::<F, BinomialExtensionField<F, 4>, Radix2Dit<F>>();
+        idft_algebra_matches_naive::<F, BinomialExtensionField<F, 4>, Radix2DitParallel<F>>();
+        idft_algebra_matches_naive::<F, BinomialExtensionField<F, 4>, Radix2Bowers>();
+        idft_algebra_matches_naive::<F, BinomialExtensionField<F, 5>, Radix2DitParallel<F>>();
+    }
+
+    #[test]
+    fn idft_algebra_goldilocks() {
+        type F = Goldilocks;
+        idft_algebra_matches_naive::<F, BinomialExtensionField<F, 2>, Radix2Dit<F>>();
+        idft_algebra_matches_naive::<F, BinomialExtensionField<F, 2>, Radix2DitParallel<F>>();
+    }
+
+    fn dft_cols_matches_dft_batch<Dft: TwoAdicSubgroupDft<BabyBear> + Send>() {
+        let dft = Dft::default();
+        let mut rng = thread_rng();
+        for log_h in 0..8 {
+            let h = 1 << log_h;
+            let mat = RowMajorMatrix::<BabyBear>::rand(&mut rng, h, 7);
+            let cols: Vec<Vec<_>> = (0..mat.width())
+                .map(|c| {
+                    mat.values
+                        .iter()
+                        .skip(c)
+                        .step_by(mat.width())
+                        .copied()
+                        .collect()
+                })
+                .collect();
+            let col_major = ColMajorMatrix::from_row_major(&mat);
+            let expected = dft.dft_batch(mat).to_row_major_matrix();
+            assert_eq!(dft.dft_col_major(col_major).to_row_major_matrix(), expected);
+
+            let evals = dft.dft_cols(cols).unwrap();
+            assert_eq!(evals.len(), expected.width());
+            for (c, col) in evals.iter().enumerate() {
+                let expected_col: Vec<_> = expected
+                    .values
+                    .iter()
+                    .skip(c)
+                    .step_by(expected.width())
+                    .copied()
+                    .collect();
+                assert_eq!(col, &expected_col);
+            }
+        }
+    }
+
+    #[test]
+    fn dft_cols_baby_bear() {
+        type F = BabyBear;
+        dft_cols_matches_dft_batch::<Radix2Dit<F>>();
+        dft_cols_matches_dft_batch::<Radix2DitParallel<F>>();
+        dft_cols_matches_dft_batch::<Radix2Bowers>();
+    }
+
+    /// Evaluate the interpolant of each column of `mat` at `x`, with Horner's rule.
+    fn naive_eval<F: TwoAdicField>(mat: &RowMajorMatrix<F>, x: F) -> Vec<F> {
+        let coeffs = NaiveDft.idft_batch(mat.clone());
+        (0..coeffs.width())
+            .map(|c| {
+                coeffs
+                    .values
+                    .iter()
+                    .skip(c)
+                    .step_by(coeffs.width())
+                    .rev()
+                    .fold(F::ZERO, |acc, &coeff| acc * x + coeff)
+            })
+            .collect()
+    }
+
+    fn lde_to_height_matches_naive<F, Dft>()
+    where
+        F: TwoAdicField,
+        Standard: Distribution<F>,
+        Dft: TwoAdicSubgroupDft<F>,
+    {
+        let dft = Dft::default();
+        let mut rng = thread_rng();
+        for log_h in 0..5 {
+            let h = 1 << log_h;
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
+            for target_height in [h, h + 1, 3 * h, 6 * h] {
+                let lde = dft.lde_batch_to_height(mat.clone(), target_height);
+                assert_eq!(lde.height(), target_height);
+                let g = F::two_adic_generator(log2_ceil_usize(target_height));
+                for (i, x) in g.powers().take(target_height).enumerate() {
+                    assert_eq!(
+                        lde.row_slice(i).to_vec(),
+                        naive_eval(&mat, x),
+                        "h = {h}, target_height = {target_height}, row {i}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lde_to_height_baby_bear() {
+        type F = BabyBear;
+        lde_to_height_matches_naive::<F, Radix2Dit<F>>();
+        lde_to_height_matches_naive::<F, Radix2DitParallel<F>>();
+        lde_to_height_matches_naive::<F, Radix2Bowers>();
+    }
+
+    #[test]
+    #[should_panic(expected = "less than the matrix height")]
+    fn lde_to_height_rejects_shrinking() {
+        let mat = RowMajorMatrix::<BabyBear>::rand(&mut thread_rng(), 8, 1);
+        Radix2Dit::default().lde_batch_to_height(mat, 4);
+    }
+
+    fn coset_lde_bit_reversed_row_chunks_match_coset_lde<F, Dft>()
+    where
+        F: TwoAdicField,
+        Standard: Distribution<F>,
+        Dft: TwoAdicSubgroupDft<F>,
+    {
+        let dft = Dft::default();
+        let mut rng = thread_rng();
+        let (log_h, added_bits) = (8, 2);
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 1 << log_h, 5);
+        let expected = dft
+            .coset_lde_batch(mat.clone(), added_bits, F::GENERATOR)
+            .bit_reverse_rows()
+            .to_row_major_matrix();
+        for log_chunk_height in [0, 4, log_h, log_h + added_bits] {
+            let chunk_height = 1 << log_chunk_height;
+            let mut values = Vec::with_capacity(expected.values.len());
+            for chunk in dft.coset_lde_bit_reversed_row_chunks(
+                mat.clone(),
+                added_bits,
+                F::GENERATOR,
+                chunk_height,
+            ) {
+                // Only one block of the LDE is ever materialized.
+                assert_eq!(
+                    chunk.dimensions(),
+                    Dimensions {
+                        width: mat.width(),
+                        height: chunk_height,
+                    }
+                );
+                values.extend(chunk.values);
+            }
+            assert_eq!(
+                RowMajorMatrix::new(values, mat.width()),
+                expected,
+                "chunk height {chunk_height}"
+            );
+        }
+    }
+
+    #[test]
+    fn coset_lde_bit_reversed_row_chunks_baby_bear() {
+        type F = BabyBear;
+        coset_lde_bit_reversed_row_chunks_match_coset_lde::<F, Radix2Dit<F>>();
+        coset_lde_bit_reversed_row_chunks_match_coset_lde::<F, Radix2DitParallel<F>>();
+        coset_lde_bit_reversed_row_chunks_match_coset_lde::<F, Radix2Bowers>();
+    }
+
+    #[test]
+    fn dft_cols_rejects_ragged_columns() {
+        type F = BabyBear;
+        let dft = Radix2Dit::<F>::default();
+        assert_eq!(dft.dft_cols(vec![]), Ok(vec![]));
+
+        let cols = vec![
+            vec![F::ONE; 8],
+            vec![F::ONE; 8],
+            vec![F::ONE; 4],
+            vec![F::ONE; 2],
+        ];
+        assert_eq!(
+            dft.dft_cols(cols),
+            Err(RaggedColumns {
+                col: 2,
+                len: 4,
+                expected_len: 8,
+            })
+        );
+    }
 }
@@ -1,25 +1,61 @@
+use alloc::vec::Vec;
 use core::borrow::BorrowMut;
+use core::mem;
+use core::ops::MulAssign;
 
 use p3_field::Field;
 use p3_matrix::dense::{DenseMatrix, DenseStorage, RowMajorMatrix};
 use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::*;
 use tracing::instrument;
 
-/// Divide each coefficient of the given matrix by its height.
+/// The number of rows `coset_shift_cols` gives to each task; each one starts its own powers of
+/// the shift.
+const COSET_SHIFT_CHUNK_ROWS: usize = 1 << 10;
+
+/// Divide each coefficient of the given matrix by its height. An empty matrix is left as it is.
 #[instrument(skip_all, fields(dims = %mat.dimensions()))]
 pub fn divide_by_height<F: Field, S: DenseStorage<F> + BorrowMut<[F]>>(
     mat: &mut DenseMatrix<F, S>,
 ) {
-    mat.scale(F::from_canonical_usize(mat.height()).inverse())
+    let h = mat.height();
+    if h > 0 {
+        mat.scale(F::from_canonical_usize(h).inverse())
+    }
 }
 
 /// Multiply each element of row `i` of `mat` by `shift**i`.
+///
+/// When the width is a multiple of the packing width, the rows are multiplied as packed values.
 pub(crate) fn coset_shift_cols<F: Field>(mat: &mut RowMajorMatrix<F>, shift: F) {
-    mat.rows_mut()
-        .zip(shift.powers())
-        .for_each(|(row, weight)| {
-            row.iter_mut().for_each(|coeff| {
-                *coeff *= weight;
-            })
+    let owned = mem::replace(mat, RowMajorMatrix::new(Vec::new(), 0));
+    *mat = match owned.pack_columns() {
+        Ok(mut packed) => {
+            scale_rows_by_powers(&mut packed, shift);
+            packed.unpack_columns()
+        }
+        Err(mut owned) => {
+            scale_rows_by_powers(&mut owned, shift);
+            owned
+        }
+    };
+}
+
+fn scale_rows_by_powers<F: Field, V: Copy + Send + Sync + MulAssign<F>>(
+    mat: &mut RowMajorMatrix<V>,
+    shift: F,
+) {
+    mat.par_row_chunks_mut(COSET_SHIFT_CHUNK_ROWS)
+        .enumerate()
+        .for_each(|(i, mut chunk)| {
+            let first_weight = shift.exp_u64((i * COSET_SHIFT_CHUNK_ROWS) as u64);
+            chunk
+                .rows_mut()
+                .zip(shift.shifted_powers(first_weight))
+                .for_each(|(row, weight)| {
+                    row.iter_mut().for_each(|coeff| {
+                        *coeff *= weight;
+                    })
+                });
         });
 }
@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use clap::Parser;
 use p3_baby_bear::{BabyBear, GenericPoseidon2LinearLayersBabyBear, Poseidon2BabyBear};
 use p3_blake3_air::Blake3Air;
-use p3_dft::Radix2DitParallel;
+use p3_dft::{Radix2DitParallel, SplitRadixDit};
 use p3_examples::airs::ProofObjective;
 use p3_examples::dfts::DftChoice;
 use p3_examples::parsers::{DftOptions, FieldOptions, MerkleHashOptions, ProofOptions};
@@ -117,6 +117,7 @@ fn main() {
                     DftChoice::Recursive(RecursiveDft::new(trace_height << 1))
                 }
                 DftOptions::Radix2DitParallel => DftChoice::Parallel(Radix2DitParallel::default()),
+                DftOptions::SplitRadixDit => DftChoice::SplitRadix(SplitRadixDit::default()),
             };
 
             match args.merkle_hash {
@@ -173,6 +174,7 @@ fn main() {
                     DftChoice::Recursive(RecursiveDft::new(trace_height << 1))
                 }
                 DftOptions::Radix2DitParallel => DftChoice::Parallel(Radix2DitParallel::default()),
+                DftOptions::SplitRadixDit => DftChoice::SplitRadix(SplitRadixDit::default()),
             };
 
             match args.merkle_hash {
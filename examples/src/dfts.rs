@@ -1,7 +1,8 @@
-use p3_dft::{Radix2DitParallel, TwoAdicSubgroupDft};
+use p3_dft::{Radix2DitParallel, SplitRadixDit, TwoAdicSubgroupDft};
 use p3_field::TwoAdicField;
-use p3_matrix::bitrev::BitReversedMatrixView;
+use p3_matrix::bitrev::{BitReversableMatrix, BitReversedMatrixView};
 use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::util::reverse_matrix_index_bits;
 use p3_monty_31::dft::RecursiveDft;
 
 /// An enum containing several different options for discrete Fourier Transform.
@@ -11,6 +12,7 @@ use p3_monty_31::dft::RecursiveDft;
 pub enum DftChoice<F> {
     Recursive(RecursiveDft<F>),
     Parallel(Radix2DitParallel<F>),
+    SplitRadix(SplitRadixDit<F>),
 }
 
 impl<F: Default> Default for DftChoice<F> {
@@ -32,6 +34,7 @@ where
         match self {
             DftChoice::<F>::Recursive(inner_dft) => inner_dft.dft_batch(mat),
             DftChoice::<F>::Parallel(inner_dft) => inner_dft.dft_batch(mat),
+            DftChoice::<F>::SplitRadix(inner_dft) => bit_reversed_view(inner_dft.dft_batch(mat)),
         }
     }
 
@@ -40,6 +43,9 @@ where
         match self {
             DftChoice::<F>::Recursive(inner_dft) => inner_dft.coset_dft_batch(mat, shift),
             DftChoice::<F>::Parallel(inner_dft) => inner_dft.coset_dft_batch(mat, shift),
+            DftChoice::<F>::SplitRadix(inner_dft) => {
+                bit_reversed_view(inner_dft.coset_dft_batch(mat, shift))
+            }
         }
     }
 
@@ -57,6 +63,17 @@ where
             DftChoice::<F>::Parallel(inner_dft) => {
                 inner_dft.coset_lde_batch(mat, added_bits, shift)
             }
+            DftChoice::<F>::SplitRadix(inner_dft) => {
+                bit_reversed_view(inner_dft.coset_lde_batch(mat, added_bits, shift))
+            }
         }
     }
 }
+
+/// Present evaluations in natural order as a bit-reversed view, the form the other choices return.
+fn bit_reversed_view<F: Clone + Send + Sync>(
+    mut mat: RowMajorMatrix<F>,
+) -> BitReversedMatrixView<RowMajorMatrix<F>> {
+    reverse_matrix_index_bits(&mut mat);
+    mat.bit_reverse_rows()
+}
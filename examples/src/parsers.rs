@@ -25,6 +25,7 @@ pub enum ProofOptions {
 pub enum DftOptions {
     Radix2DitParallel,
     RecursiveDft,
+    SplitRadixDit,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -111,7 +112,11 @@ impl ValueEnum for ProofOptions {
 
 impl ValueEnum for DftOptions {
     fn value_variants<'a>() -> &'a [Self] {
-        &[DftOptions::Radix2DitParallel, DftOptions::RecursiveDft]
+        &[
+            DftOptions::Radix2DitParallel,
+            DftOptions::RecursiveDft,
+            DftOptions::SplitRadixDit,
+        ]
     }
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
@@ -124,6 +129,9 @@ impl ValueEnum for DftOptions {
                 2,
                 Some(vec![("radix2ditparallel", 6), ("parallel", 1)]),
             ),
+            DftOptions::SplitRadixDit => {
+                get_aliases("split-radix-dit", 1, Some(vec![("splitradixdit", 6)]))
+            }
         })
     }
 }
@@ -1,9 +1,144 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
 use p3_dft::{NaiveDft, TwoAdicSubgroupDft};
 use p3_field::TwoAdicField;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use rand::distributions::{Distribution, Standard};
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+
+/// The largest height checked; the naive LDEs are quadratic in the extended height.
+const MAX_LOG_HEIGHT: usize = 6;
+
+/// The largest number of added bits checked in the LDEs.
+const MAX_ADDED_BITS: usize = 2;
+
+/// Check every transform of `Dft` against `NaiveDft`, panicking on the first mismatch.
+///
+/// This covers `dft`, `idft`, `lde` and `coset_lde` and their coset and batch variants, on
+/// heights from 1 up to `2^6` (in particular the edge cases 1 and 2), on matrices of width 1 and 3,
+/// and on an empty matrix of width 0, which every transform must return unchanged.
+pub fn test_all_transforms_match_naive<F, Dft>()
+where
+    F: TwoAdicField,
+    Standard: Distribution<F>,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    let dft = Dft::default();
+    let naive = NaiveDft;
+    let shift = F::GENERATOR;
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for log_h in 0..=MAX_LOG_HEIGHT {
+        let h = 1 << log_h;
+        for w in [1, 3] {
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, h, w);
+            let check = |name: &str, actual: RowMajorMatrix<F>, expected: RowMajorMatrix<F>| {
+                assert_eq!(
+                    actual, expected,
+                    "{name} differs from NaiveDft on a {h}x{w} matrix"
+                );
+            };
+
+            check(
+                "dft_batch",
+                dft.dft_batch(mat.clone()).to_row_major_matrix(),
+                naive.dft_batch(mat.clone()),
+            );
+            check(
+                "idft_batch",
+                dft.idft_batch(mat.clone()),
+                naive.idft_batch(mat.clone()),
+            );
+            check(
+                "coset_dft_batch",
+                dft.coset_dft_batch(mat.clone(), shift)
+                    .to_row_major_matrix(),
+                naive.coset_dft_batch(mat.clone(), shift),
+            );
+            check(
+                "coset_idft_batch",
+                dft.coset_idft_batch(mat.clone(), shift),
+                naive.coset_idft_batch(mat.clone(), shift),
+            );
+            for added_bits in 0..=MAX_ADDED_BITS {
+                check(
+                    "lde_batch",
+                    dft.lde_batch(mat.clone(), added_bits).to_row_major_matrix(),
+                    naive.lde_batch(mat.clone(), added_bits),
+                );
+                check(
+                    "coset_lde_batch",
+                    dft.coset_lde_batch(mat.clone(), added_bits, shift)
+                        .to_row_major_matrix(),
+                    naive.coset_lde_batch(mat.clone(), added_bits, shift),
+                );
+            }
+        }
+
+        let col: Vec<F> = RowMajorMatrix::rand(&mut rng, h, 1).values;
+        assert_eq!(
+            dft.dft(col.clone()),
+            naive.dft(col.clone()),
+            "dft, height {h}"
+        );
+        assert_eq!(
+            dft.idft(col.clone()),
+            naive.idft(col.clone()),
+            "idft, height {h}"
+        );
+        assert_eq!(
+            dft.coset_dft(col.clone(), shift),
+            naive.coset_dft(col.clone(), shift),
+            "coset_dft, height {h}"
+        );
+        assert_eq!(
+            dft.coset_idft(col.clone(), shift),
+            naive.coset_idft(col.clone(), shift),
+            "coset_idft, height {h}"
+        );
+        assert_eq!(
+            dft.lde(col.clone(), 1),
+            naive.lde(col.clone(), 1),
+            "lde, height {h}"
+        );
+        assert_eq!(
+            dft.coset_lde(col.clone(), 1, shift),
+            naive.coset_lde(col, 1, shift),
+            "coset_lde, height {h}"
+        );
+    }
+
+    let empty = RowMajorMatrix::<F>::new(vec![], 0);
+    let check_empty = |name: &str, actual: RowMajorMatrix<F>| {
+        assert_eq!(actual, empty, "{name} changed an empty matrix");
+    };
+    check_empty(
+        "dft_batch",
+        dft.dft_batch(empty.clone()).to_row_major_matrix(),
+    );
+    check_empty("idft_batch", dft.idft_batch(empty.clone()));
+    check_empty(
+        "coset_dft_batch",
+        dft.coset_dft_batch(empty.clone(), shift)
+            .to_row_major_matrix(),
+    );
+    check_empty(
+        "coset_idft_batch",
+        dft.coset_idft_batch(empty.clone(), shift),
+    );
+    check_empty(
+        "lde_batch",
+        dft.lde_batch(empty.clone(), 1).to_row_major_matrix(),
+    );
+    check_empty(
+        "coset_lde_batch",
+        dft.coset_lde_batch(empty.clone(), 1, shift)
+            .to_row_major_matrix(),
+    );
+}
 
 pub fn test_dft_matches_naive<F, Dft>()
 where
@@ -110,6 +245,92 @@ where
     }
 }
 
+pub fn test_coset_ldes_match_coset_lde<F, Dft>()
+where
+    F: TwoAdicField,
+    Standard: Distribution<F>,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    let dft = Dft::default();
+    let mut rng = thread_rng();
+    let shifts = [
+        F::ONE,
+        F::GENERATOR,
+        F::GENERATOR.square(),
+        F::GENERATOR.inverse(),
+    ];
+    for log_h in 0..8 {
+        let h = 1 << log_h;
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
+        let ldes = dft.coset_ldes_batch(mat.clone(), &shifts);
+        assert_eq!(ldes.len(), shifts.len());
+        for (lde, &shift) in ldes.into_iter().zip(&shifts) {
+            let expected = dft.coset_lde_batch(mat.clone(), 0, shift);
+            assert_eq!(lde, expected.to_row_major_matrix());
+        }
+    }
+    assert!(dft
+        .coset_ldes_batch(RowMajorMatrix::<F>::rand(&mut rng, 4, 3), &[])
+        .is_empty());
+}
+
+pub fn test_coset_lde_zk_matches_trace<F, Dft>()
+where
+    F: TwoAdicField,
+    Standard: Distribution<F>,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    let dft = Dft::default();
+    let mut rng = thread_rng();
+    for log_h in 0..5 {
+        let h = 1 << log_h;
+        for added_bits in 1..3 {
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
+            let lde = |seed| {
+                dft.coset_lde_batch_zk(
+                    mat.clone(),
+                    added_bits,
+                    F::ONE,
+                    &mut StdRng::seed_from_u64(seed),
+                )
+                .to_row_major_matrix()
+            };
+            let (lde_0, lde_1) = (lde(0), lde(1));
+            assert_eq!(lde_0.height(), h << added_bits);
+            assert_eq!(lde_0.width(), mat.width());
+
+            // Every 2^added_bits-th point of the extended subgroup is in the original subgroup.
+            for r in 0..h {
+                assert_eq!(
+                    lde_0.row_slice(r << added_bits).to_vec(),
+                    mat.row_slice(r).to_vec()
+                );
+                assert_eq!(
+                    lde_1.row_slice(r << added_bits).to_vec(),
+                    mat.row_slice(r).to_vec()
+                );
+            }
+            assert_ne!(lde_0, lde_1, "blinding does not depend on the randomness");
+
+            // The blinded LDE is the evaluation of a polynomial of degree less than 2h.
+            let coeffs = dft.idft_batch(lde_0);
+            assert!(coeffs.values[2 * h * mat.width()..]
+                .iter()
+                .all(|x| x.is_zero()));
+
+            // And the same holds on a coset, where the blinding is the same.
+            let coset_lde = dft.coset_lde_batch_zk(
+                mat.clone(),
+                added_bits,
+                F::GENERATOR,
+                &mut StdRng::seed_from_u64(0),
+            );
+            let coeffs_0 = dft.coset_idft_batch(coset_lde.to_row_major_matrix(), F::GENERATOR);
+            assert_eq!(coeffs_0, coeffs);
+        }
+    }
+}
+
 pub fn test_dft_idft_consistency<F, Dft>()
 where
     F: TwoAdicField,
@@ -161,6 +382,21 @@ macro_rules! test_field_dft {
                 $crate::test_coset_lde_matches_naive::<$field, $dft>();
             }
 
+            #[test]
+            fn coset_ldes_match_coset_lde() {
+                $crate::test_coset_ldes_match_coset_lde::<$field, $dft>();
+            }
+
+            #[test]
+            fn coset_lde_zk_matches_trace() {
+                $crate::test_coset_lde_zk_matches_trace::<$field, $dft>();
+            }
+
+            #[test]
+            fn all_transforms_match_naive() {
+                $crate::test_all_transforms_match_naive::<$field, $dft>();
+            }
+
             #[test]
             fn dft_idft_consistency() {
                 $crate::test_dft_idft_consistency::<$field, $dft>();
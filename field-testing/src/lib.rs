@@ -8,13 +8,22 @@ pub mod bench_func;
 pub mod dft_testing;
 pub mod packedfield_testing;
 
+use alloc::vec;
+use alloc::vec::Vec;
+
 pub use bench_func::*;
 pub use dft_testing::*;
 use num_bigint::BigUint;
-use num_traits::identities::One;
+use num_traits::identities::{One, Zero};
+use p3_field::codec;
+use p3_field::extension::HasFrobenius;
 use p3_field::{
-    cyclic_subgroup_coset_known_order, cyclic_subgroup_known_order, two_adic_coset_zerofier,
-    two_adic_subgroup_zerofier, ExtensionField, Field, TwoAdicField,
+    batch_multiplicative_inverse, batch_multiplicative_inverse_allowing_zero, batch_try_inverse,
+    batch_try_inverse_in_place, cyclic_subgroup_coset_known_order, cyclic_subgroup_known_order,
+    eval_poly, eval_poly_packed, exp_small, field_sum, naive_poly_mul, packed_dot_product,
+    sample_uniform, scale_slice_in_place, two_adic_coset_zerofier, two_adic_subgroup_zerofier,
+    ExtensionField, Field, FieldExtensionAlgebra, PackedValue, PrimeField, PrimeField32,
+    PrimeField64, SamePrimeField, TwoAdicField,
 };
 pub use packedfield_testing::*;
 use rand::distributions::{Distribution, Standard};
@@ -51,6 +60,20 @@ where
     );
 }
 
+pub fn test_field_axioms<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let mut samples = vec![F::ZERO, F::ONE, F::TWO, F::NEG_ONE];
+    samples.extend((0..16).map(|_| rng.gen::<F>()));
+    p3_field::testing::check_field_axioms(&samples);
+}
+
+pub fn test_two_adic_consistency<F: TwoAdicField>() {
+    p3_field::testing::check_two_adic_consistency::<F>();
+}
+
 pub fn test_inv_div<F: Field>()
 where
     Standard: Distribution<F>,
@@ -86,6 +109,246 @@ where
     }
 }
 
+pub fn test_batch_multiplicative_inverse<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    // Lengths of every remainder modulo the width of the packed inversion.
+    for n in [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 1023, 1024, 1025] {
+        let x: Vec<F> = (0..n)
+            .map(|_| rng.gen())
+            .filter(|x: &F| !x.is_zero())
+            .collect();
+        let expected: Vec<F> = x.iter().map(|x| x.inverse()).collect();
+        assert_eq!(batch_multiplicative_inverse(&x), expected);
+    }
+}
+
+pub fn test_batch_inverse_allowing_zero<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    for n in [0, 1, 2, 3, 4, 5, 31, 1023, 1024, 1025, 3000] {
+        for zero_probability in [0.0, 0.1, 0.5, 1.0] {
+            let x: Vec<F> = (0..n)
+                .map(|_| {
+                    if rng.gen_bool(zero_probability) {
+                        F::ZERO
+                    } else {
+                        rng.gen()
+                    }
+                })
+                .collect();
+            let expected: Vec<F> = x
+                .iter()
+                .map(|x| x.try_inverse().unwrap_or(F::ZERO))
+                .collect();
+            assert_eq!(batch_multiplicative_inverse_allowing_zero(&x), expected);
+        }
+    }
+}
+
+pub fn test_batch_try_inverse<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    for n in [0, 1, 2, 5, 1023, 1024, 1025, 3000] {
+        for zero_probability in [0.0, 0.1, 0.5, 1.0] {
+            let x: Vec<F> = (0..n)
+                .map(|_| {
+                    if rng.gen_bool(zero_probability) {
+                        F::ZERO
+                    } else {
+                        rng.gen()
+                    }
+                })
+                .collect();
+            let zeros: Vec<usize> = (0..n).filter(|&i| x[i].is_zero()).collect();
+
+            let inverses = batch_try_inverse(&x);
+            assert_eq!(inverses.len(), n);
+            for (i, (&x, inv)) in x.iter().zip(&inverses).enumerate() {
+                match inv {
+                    None => assert!(x.is_zero(), "None at nonzero position {i}"),
+                    Some(inv) => assert_eq!(x * *inv, F::ONE, "bad inverse at position {i}"),
+                }
+            }
+
+            let mut in_place = x.clone();
+            let result = batch_try_inverse_in_place(&mut in_place);
+            if zeros.is_empty() {
+                assert_eq!(result, Ok(()));
+            } else {
+                assert_eq!(result, Err(zeros));
+            }
+            let expected: Vec<F> = inverses.iter().map(|x| x.unwrap_or(F::ZERO)).collect();
+            assert_eq!(in_place, expected);
+        }
+    }
+}
+
+pub fn test_eval_poly<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let x: F = rng.gen();
+    assert_eq!(eval_poly(&[], x), F::ZERO);
+    assert_eq!(eval_poly_packed(&[], x), F::ZERO);
+
+    let c: F = rng.gen();
+    assert_eq!(eval_poly(&[c], x), c);
+    assert_eq!(eval_poly_packed(&[c], x), c);
+
+    for len in 0..=200 {
+        let poly: Vec<F> = (0..len).map(|_| rng.gen()).collect();
+        let x: F = rng.gen();
+        let expected = poly.iter().rev().fold(F::ZERO, |acc, &c| acc * x + c);
+        assert_eq!(eval_poly(&poly, x), expected, "mismatch at length {len}");
+        assert_eq!(
+            eval_poly_packed(&poly, x),
+            expected,
+            "packed mismatch at length {len}"
+        );
+    }
+
+    // `eval_poly` stays generic over algebras, such as the packing itself.
+    let poly: Vec<F::Packing> = (0..10)
+        .map(|_| F::Packing::from_fn(|_| rng.gen()))
+        .collect();
+    let x = F::Packing::from_fn(|_| rng.gen());
+    let evals = eval_poly(&poly, x);
+    for lane in 0..F::Packing::WIDTH {
+        let lane_poly: Vec<F> = poly.iter().map(|c| c.as_slice()[lane]).collect();
+        assert_eq!(
+            evals.as_slice()[lane],
+            eval_poly(&lane_poly, x.as_slice()[lane])
+        );
+    }
+}
+
+pub fn test_poly_mul<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let lens = [0, 1, 2, 31, 32, 33, 34, 50, 64, 65, 100, 127, 150, 200];
+    for &a_len in &lens {
+        for &b_len in &lens {
+            let a: Vec<F> = (0..a_len).map(|_| rng.gen()).collect();
+            let b: Vec<F> = (0..b_len).map(|_| rng.gen()).collect();
+            let product = naive_poly_mul(&a, &b);
+
+            if a.is_empty() || b.is_empty() {
+                assert!(product.is_empty());
+                continue;
+            }
+            let mut expected = vec![F::ZERO; a_len + b_len - 1];
+            for (i, &x) in a.iter().enumerate() {
+                for (j, &y) in b.iter().enumerate() {
+                    expected[i + j] += x * y;
+                }
+            }
+            assert_eq!(
+                product, expected,
+                "mismatch for lengths {a_len} and {b_len}"
+            );
+
+            let x: F = rng.gen();
+            assert_eq!(eval_poly(&product, x), eval_poly(&a, x) * eval_poly(&b, x));
+        }
+    }
+}
+
+pub fn test_packed_dot_product_and_sum<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    let width = F::Packing::WIDTH;
+    let lens = [
+        0,
+        1,
+        2,
+        width - 1,
+        width,
+        width + 1,
+        3 * width - 1,
+        3 * width + 1,
+        100,
+    ];
+    for n in lens {
+        // Sub-slices starting at every offset within a packed value, so that the heads of `a` and
+        // `b` are misaligned, by the same amount or by different amounts.
+        let a_buf: Vec<F> = (0..n + width).map(|_| rng.gen()).collect();
+        let b_buf: Vec<F> = (0..n + width).map(|_| rng.gen()).collect();
+        for offset in 0..width {
+            let a = &a_buf[offset..offset + n];
+            for b in [&b_buf[offset..offset + n], &b_buf[..n]] {
+                let expected_dot = a.iter().zip(b).fold(F::ZERO, |acc, (&x, &y)| acc + x * y);
+                assert_eq!(packed_dot_product(a, b), expected_dot);
+            }
+            let expected_sum = a.iter().fold(F::ZERO, |acc, &x| acc + x);
+            assert_eq!(field_sum(a), expected_sum);
+
+            let s: F = rng.gen();
+            let mut scaled = a_buf.clone();
+            scale_slice_in_place(s, &mut scaled[offset..offset + n]);
+            for (i, (&x, &y)) in scaled.iter().zip(&a_buf).enumerate() {
+                let in_range = (offset..offset + n).contains(&i);
+                assert_eq!(x, if in_range { y * s } else { y });
+            }
+        }
+    }
+}
+
+pub fn test_packed_dot_product_mismatched_lengths<F: Field>() {
+    packed_dot_product(&[F::ONE; 3], &[F::ONE; 4]);
+}
+
+pub fn test_exp_small<F: Field>()
+where
+    Standard: Distribution<F>,
+{
+    fn check<const N: u64, F: Field>(x: F, x_packed: F::Packing) {
+        assert_eq!(
+            exp_small::<N, _>(x),
+            x.exp_u64(N),
+            "exp_small::<{N}> is incorrect"
+        );
+        let expected = x_packed.as_slice().iter().map(|x| x.exp_u64(N));
+        assert!(
+            exp_small::<N, _>(x_packed)
+                .as_slice()
+                .iter()
+                .copied()
+                .eq(expected),
+            "exp_small::<{N}> is incorrect for packed values"
+        );
+    }
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..10 {
+        let x: F = rng.gen();
+        let x_packed = F::Packing::from_fn(|_| rng.gen());
+        check::<3, F>(x, x_packed);
+        check::<5, F>(x, x_packed);
+        check::<7, F>(x, x_packed);
+        check::<9, F>(x, x_packed);
+        check::<11, F>(x, x_packed);
+        check::<13, F>(x, x_packed);
+        check::<17, F>(x, x_packed);
+        // Exponents without a specialized chain.
+        check::<0, F>(x, x_packed);
+        check::<2, F>(x, x_packed);
+        check::<19, F>(x, x_packed);
+        check::<257, F>(x, x_packed);
+    }
+}
+
 pub fn test_multiplicative_group_factors<F: Field>() {
     let product: BigUint = F::multiplicative_group_factors()
         .into_iter()
@@ -94,6 +357,304 @@ pub fn test_multiplicative_group_factors<F: Field>() {
     assert_eq!(product + BigUint::one(), F::order());
 }
 
+/// Raise `x` to the power `n`, one `u64` digit at a time.
+fn exp_biguint<F: Field>(x: F, n: &BigUint) -> F {
+    n.iter_u64_digits().rev().fold(F::ONE, |acc, digit| {
+        acc.exp_power_of_2(64) * x.exp_u64(digit)
+    })
+}
+
+pub fn test_multiplicative_group_generator<F: Field>() {
+    let g = F::multiplicative_group_generator();
+    let group_order = F::order() - BigUint::one();
+    assert!(exp_biguint(g, &group_order).is_one());
+    for (q, _) in F::multiplicative_group_factors() {
+        assert!(
+            !exp_biguint(g, &(&group_order / &q)).is_one(),
+            "the generator is a {q}-th power"
+        );
+    }
+}
+
+pub fn test_from_canonical_biguint_checked<F: PrimeField>() {
+    let p = F::order();
+    let one = BigUint::one();
+    assert_eq!(
+        F::from_canonical_biguint_checked(&BigUint::from(0u32)),
+        Some(F::ZERO)
+    );
+    assert_eq!(F::from_canonical_biguint_checked(&one), Some(F::ONE));
+    assert_eq!(
+        F::from_canonical_biguint_checked(&(&p - &one)),
+        Some(F::NEG_ONE)
+    );
+    assert_eq!(F::from_canonical_biguint_checked(&p), None);
+    assert_eq!(F::from_canonical_biguint_checked(&(&p * 2u32 - &one)), None);
+
+    // For large fields this spans several limbs, exercising the digit-by-digit default.
+    let x = &p / 3u32;
+    let element = F::from_canonical_biguint_checked(&x).unwrap();
+    assert_eq!(element.as_canonical_biguint(), x);
+}
+
+/// Check that `x` has multiplicative order exactly `n`, given the prime factors of `p - 1`.
+fn assert_order_exactly<F: PrimeField>(x: F, n: u64, primes: &[u64]) {
+    assert!(x.exp_u64(n).is_one(), "x^{n} != 1");
+    for &q in primes.iter().filter(|&&q| n % q == 0) {
+        assert!(!x.exp_u64(n / q).is_one(), "x^({n}/{q}) == 1");
+    }
+}
+
+pub fn test_primitive_root_of_unity<F: PrimeField>() {
+    let group_order = F::order() - BigUint::one();
+    // Prime factors too large for a u64 cannot divide any n we test.
+    let primes: Vec<u64> = F::multiplicative_group_factors()
+        .iter()
+        .filter_map(|(q, _)| u64::try_from(q).ok())
+        .collect();
+
+    assert_eq!(F::primitive_root_of_unity(0), None);
+    assert_eq!(F::primitive_root_of_unity(1), Some(F::ONE));
+
+    for n in 1..200u64 {
+        let root = F::primitive_root_of_unity(n);
+        if (&group_order % n).is_zero() {
+            assert_order_exactly(root.unwrap(), n, &primes);
+        } else {
+            assert_eq!(root, None, "n = {n} does not divide p - 1");
+        }
+    }
+
+    // The largest power of two dividing p - 1, and three times that if possible.
+    let two_adicity = group_order.trailing_zeros().unwrap().min(60);
+    let n = 1 << two_adicity;
+    assert_order_exactly(F::primitive_root_of_unity(n).unwrap(), n, &primes);
+    if (&group_order % 3u32).is_zero() {
+        assert_order_exactly(F::primitive_root_of_unity(3 * n).unwrap(), 3 * n, &primes);
+    }
+
+    if let Ok(n) = u64::try_from(&group_order) {
+        let root = F::primitive_root_of_unity(n).unwrap();
+        assert_order_exactly(root, n, &primes);
+        assert_eq!(root, F::multiplicative_group_generator());
+        assert_eq!(F::primitive_root_of_unity(n + 1), None);
+    }
+}
+
+pub fn test_from_canonical_checked<F: PrimeField64>() {
+    let p = F::ORDER_U64;
+    assert_eq!(F::from_canonical_checked(0), Some(F::ZERO));
+    assert_eq!(F::from_canonical_checked(1), Some(F::ONE));
+    assert_eq!(F::from_canonical_checked(p - 1), Some(F::NEG_ONE));
+    assert_eq!(F::from_canonical_checked(p), None);
+    if let Some(two_p) = p.checked_mul(2) {
+        assert_eq!(F::from_canonical_checked(two_p - 1), None);
+    }
+    assert_eq!(F::from_canonical_checked(u64::MAX), None);
+
+    let x = p / 3;
+    assert_eq!(
+        F::from_canonical_checked(x).map(|x| x.as_canonical_u64()),
+        Some(x)
+    );
+}
+
+/// An RNG which replays a fixed list of words, counting how many it has handed out.
+struct ReplayRng<'a> {
+    words: &'a [u64],
+    calls: usize,
+}
+
+impl rand::RngCore for ReplayRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let word = self.words[self.calls];
+        self.calls += 1;
+        word
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+pub fn test_sample_uniform<F: PrimeField64>() {
+    // An all-ones word is never canonical once truncated, so it must be rejected, and the next
+    // draw used instead.
+    let words = [u64::MAX, u64::MAX, 0, u64::MAX];
+    let mut rng = ReplayRng {
+        words: &words,
+        calls: 0,
+    };
+    assert_eq!(sample_uniform::<F, _>(&mut rng), F::ZERO);
+    assert_eq!(rng.calls, 3);
+
+    // The largest canonical value is accepted first time.
+    let bits = 64 - (F::ORDER_U64 - 1).leading_zeros();
+    let words = [(F::ORDER_U64 - 1) << (64 - bits)];
+    let mut rng = ReplayRng {
+        words: &words,
+        calls: 0,
+    };
+    assert_eq!(sample_uniform::<F, _>(&mut rng), F::NEG_ONE);
+    assert_eq!(rng.calls, 1);
+
+    // Bucket samples by position in [0, p) and check every bucket gets about its share.
+    const BUCKETS: u64 = 16;
+    const SAMPLES: usize = 1 << 16;
+    let mut rng = rand::thread_rng();
+    let mut counts = [0usize; BUCKETS as usize];
+    for _ in 0..SAMPLES {
+        let x: F = sample_uniform(&mut rng);
+        let bucket = x.as_canonical_u64() as u128 * BUCKETS as u128 / F::ORDER_U64 as u128;
+        counts[bucket as usize] += 1;
+    }
+    let expected = SAMPLES / BUCKETS as usize;
+    for (i, &count) in counts.iter().enumerate() {
+        // Six standard deviations, where the standard deviation is about sqrt(expected).
+        assert!(
+            count.abs_diff(expected) < 6 * 64,
+            "bucket {i} has {count} samples, expected about {expected}"
+        );
+    }
+}
+
+pub fn test_sum_slice<F: PrimeField64>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    for len in (0..70).chain([1 << 10, (1 << 16) + 3]) {
+        let xs: Vec<F> = (0..len).map(|_| rng.gen()).collect();
+        let expected = xs.iter().fold(F::ZERO, |acc, &x| acc + x);
+        assert_eq!(F::sum_slice(&xs), expected, "mismatch at length {len}");
+
+        // The largest canonical value maximises the accumulator.
+        let xs = vec![F::NEG_ONE; len];
+        let expected = -F::from_canonical_u64(len as u64 % F::ORDER_U64);
+        assert_eq!(
+            F::sum_slice(&xs),
+            expected,
+            "mismatch for p - 1 at length {len}"
+        );
+    }
+}
+
+pub fn test_codec<F: PrimeField64>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    for n in [0, 1, 2, 7, 100] {
+        let elems: Vec<F> = (0..n).map(|_| rng.gen()).collect();
+        let bytes = codec::encode_slice(&elems);
+        assert_eq!(bytes.len(), 8 * n);
+        assert_eq!(codec::decode_slice::<F>(&bytes), Ok(elems));
+    }
+
+    // Extreme values survive the round trip.
+    let elems = [F::ZERO, F::ONE, F::NEG_ONE];
+    assert_eq!(
+        codec::decode_slice::<F>(&codec::encode_slice(&elems)).unwrap(),
+        elems
+    );
+
+    // p encodes zero non-canonically and must be rejected rather than reduced.
+    let mut bytes = codec::encode_slice(&[F::ONE, F::ONE]);
+    bytes[8..].copy_from_slice(&F::ORDER_U64.to_le_bytes());
+    assert_eq!(
+        codec::decode_slice::<F>(&bytes),
+        Err(codec::DecodeError::NonCanonical { index: 1 })
+    );
+    bytes[8..].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(
+        codec::decode_slice::<F>(&bytes),
+        Err(codec::DecodeError::NonCanonical { index: 1 })
+    );
+
+    let mut bytes = codec::encode_slice(&[F::ONE]);
+    bytes.push(0);
+    assert_eq!(
+        codec::decode_slice::<F>(&bytes),
+        Err(codec::DecodeError::TrailingBytes {
+            len: 9,
+            bytes_per_element: 8
+        })
+    );
+}
+
+pub fn test_canonical_u32_slices<F: PrimeField32>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    for len in (0..40).chain([1000]) {
+        let mut xs: Vec<F> = (0..len).map(|_| rng.gen()).collect();
+        xs.extend([F::ZERO, F::ONE, F::NEG_ONE]);
+
+        let canonical = F::to_canonical_u32_vec(&xs);
+        let expected: Vec<u32> = xs.iter().map(|x| x.as_canonical_u32()).collect();
+        assert_eq!(canonical, expected);
+
+        let back = F::from_canonical_u32_slice(&canonical);
+        let expected: Vec<F> = canonical
+            .iter()
+            .map(|&x| F::from_canonical_u32(x))
+            .collect();
+        assert_eq!(back, expected);
+        assert_eq!(back, xs);
+
+        let converted: Vec<F> = <F as SamePrimeField<F>>::convert_slice(&xs);
+        assert_eq!(converted, xs);
+        for &x in &xs {
+            assert_eq!(<F as SamePrimeField<F>>::convert(x), x);
+        }
+    }
+}
+
+pub fn test_codec_u32<F: PrimeField32>()
+where
+    Standard: Distribution<F>,
+{
+    let mut rng = rand::thread_rng();
+    for n in [0, 1, 2, 7, 100] {
+        let elems: Vec<F> = (0..n).map(|_| rng.gen()).collect();
+        let bytes = codec::encode_slice_u32(&elems);
+        assert_eq!(bytes.len(), 4 * n);
+        assert_eq!(codec::decode_slice_u32::<F>(&bytes), Ok(elems));
+    }
+
+    let mut bytes = codec::encode_slice_u32(&[F::NEG_ONE, F::ONE]);
+    assert_eq!(
+        codec::decode_slice_u32::<F>(&bytes).unwrap(),
+        [F::NEG_ONE, F::ONE]
+    );
+    bytes[4..].copy_from_slice(&F::ORDER_U32.to_le_bytes());
+    assert_eq!(
+        codec::decode_slice_u32::<F>(&bytes),
+        Err(codec::DecodeError::NonCanonical { index: 1 })
+    );
+
+    assert_eq!(
+        codec::decode_slice_u32::<F>(&[0; 6]),
+        Err(codec::DecodeError::TrailingBytes {
+            len: 6,
+            bytes_per_element: 4
+        })
+    );
+}
+
 pub fn test_two_adic_subgroup_zerofier<F: TwoAdicField>() {
     for log_n in 0..5 {
         let g = F::two_adic_generator(log_n);
@@ -115,6 +676,82 @@ pub fn test_two_adic_coset_zerofier<F: TwoAdicField>() {
     }
 }
 
+pub fn test_two_adic_coset_iter<F: TwoAdicField>() {
+    let mut rng = rand::thread_rng();
+    for log_n in 0..6 {
+        let n = 1 << log_n;
+        let shift = F::GENERATOR;
+        let coset = F::coset(shift, log_n);
+        assert_eq!(coset.len(), n);
+        let elements: Vec<F> = coset.clone().collect();
+
+        // The elements are distinct, and their 2^log_n-th powers agree with that of the shift.
+        assert_eq!(elements.len(), n);
+        for (i, &x) in elements.iter().enumerate() {
+            assert!(!elements[..i].contains(&x));
+            assert_eq!(x.exp_power_of_2(log_n), shift.exp_power_of_2(log_n));
+            assert!(coset.contains(x));
+        }
+        assert!(!coset.contains(F::ZERO));
+        if log_n < F::TWO_ADICITY {
+            // An element of the subgroup of order 2^(log_n + 1) lying outside H.
+            assert!(!coset.contains(shift * F::two_adic_generator(log_n + 1)));
+        }
+
+        // Subgroups start at one.
+        let subgroup: Vec<F> = F::subgroup(log_n).collect();
+        assert_eq!(subgroup[0], F::ONE);
+        assert_eq!(
+            subgroup,
+            cyclic_subgroup_known_order(F::two_adic_generator(log_n), n).collect::<Vec<_>>()
+        );
+
+        // Reverse iteration is the forward order reversed.
+        let mut reversed: Vec<F> = coset.clone().rev().collect();
+        reversed.reverse();
+        assert_eq!(reversed, elements);
+
+        // nth and nth_back agree with skipping.
+        for k in 0..=n {
+            let mut iter = coset.clone();
+            assert_eq!(iter.nth(k), elements.get(k).copied());
+            assert_eq!(iter.collect::<Vec<_>>(), elements[(k + 1).min(n)..]);
+
+            let mut iter = coset.clone();
+            assert_eq!(iter.nth_back(k), elements.iter().rev().nth(k).copied());
+            assert_eq!(
+                iter.collect::<Vec<_>>(),
+                elements[..n.saturating_sub(k + 1)]
+            );
+        }
+
+        // Mixing both ends.
+        let mut iter = coset.clone();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        loop {
+            let from_front: bool = rng.gen();
+            let x = if from_front {
+                iter.next()
+            } else {
+                iter.next_back()
+            };
+            let Some(x) = x else {
+                break;
+            };
+            if from_front {
+                front.push(x);
+            } else {
+                back.push(x);
+            }
+            assert_eq!(iter.len(), n - front.len() - back.len());
+        }
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, elements);
+    }
+}
+
 pub fn test_two_adic_generator_consistency<F: TwoAdicField>() {
     let log_n = F::TWO_ADICITY;
     let g = F::two_adic_generator(log_n);
@@ -133,6 +770,154 @@ pub fn test_ef_two_adic_generator_consistency<
     );
 }
 
+/// Check `EF::ExtensionPacking` lane by lane against `EF`, and that packing and unpacking
+/// extension elements round-trips.
+pub fn test_ext_packing<F: Field, EF: ExtensionField<F>>()
+where
+    Standard: Distribution<EF>,
+{
+    let width = F::Packing::WIDTH;
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        let xs: Vec<EF> = (0..width).map(|_| rng.gen()).collect();
+        let ys: Vec<EF> = (0..width).map(|_| rng.gen()).collect();
+        let base: F::Packing = F::Packing::from_fn(|_| rng.gen());
+        let x = EF::pack_ext_slice(&xs);
+        let y = EF::pack_ext_slice(&ys);
+        assert_eq!(EF::unpack_ext_packing(&x), xs);
+
+        let check = |packed: EF::ExtensionPacking, f: &dyn Fn(usize) -> EF, op: &str| {
+            let lanes = EF::unpack_ext_packing(&packed);
+            for (j, lane) in lanes.into_iter().enumerate() {
+                assert_eq!(lane, f(j), "packed extension {op} mismatch in lane {j}");
+            }
+        };
+        check(x + y, &|j| xs[j] + ys[j], "add");
+        check(x - y, &|j| xs[j] - ys[j], "sub");
+        check(x * y, &|j| xs[j] * ys[j], "mul");
+        check(-x, &|j| -xs[j], "neg");
+        check(x.square(), &|j| xs[j].square(), "square");
+        check(x * base, &|j| xs[j] * base.as_slice()[j], "base mul");
+        check(x + base, &|j| xs[j] + base.as_slice()[j], "base add");
+    }
+}
+
+pub fn test_frobenius_norm_trace<F: Field, EF: HasFrobenius<F>>()
+where
+    Standard: Distribution<F> + Distribution<EF>,
+{
+    let d = <EF as FieldExtensionAlgebra<F>>::D;
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        let x: EF = rng.gen();
+        let y: EF = rng.gen();
+        let a: F = rng.gen();
+
+        // Frobenius is a ring homomorphism fixing the base field, of order D.
+        assert_eq!((x * y).frobenius(), x.frobenius() * y.frobenius());
+        assert_eq!((x + y).frobenius(), x.frobenius() + y.frobenius());
+        assert_eq!(EF::from_base(a).frobenius(), EF::from_base(a));
+        assert_eq!(x.repeated_frobenius(d), x);
+        assert_eq!(
+            x.repeated_frobenius(2),
+            x.frobenius().frobenius(),
+            "repeated_frobenius disagrees with frobenius"
+        );
+        assert_eq!(x.frobenius_inv() * x, EF::ONE);
+
+        // Norm and trace agree with their definitions in terms of the Galois group.
+        let conjugates = x.galois_group();
+        let norm = conjugates.iter().copied().product::<EF>();
+        let trace = conjugates.iter().copied().sum::<EF>();
+        assert_eq!(EF::from_base(x.norm()), norm);
+        assert_eq!(EF::from_base(x.trace()), trace);
+
+        // Norm is multiplicative and trace is additive.
+        assert_eq!((x * y).norm(), x.norm() * y.norm());
+        assert_eq!((x + y).trace(), x.trace() + y.trace());
+
+        // For base field elements, the norm is a^D and the trace is D * a.
+        assert_eq!(EF::from_base(a).norm(), a.exp_u64(d as u64));
+        assert_eq!(EF::from_base(a).trace(), F::from_canonical_usize(d) * a);
+    }
+}
+
+/// Tests for the `bytemuck` casts of a field stored as `$word`s, with `$order` the order of the
+/// field, i.e. its smallest non-canonical word.
+///
+/// Invoke this behind the field crate's `bytemuck` feature. It expects the crate to depend on
+/// `bytemuck` and `p3-field`, and to link `alloc`.
+#[macro_export]
+macro_rules! test_field_bytemuck {
+    ($field:ty, $word:ty, $order:expr) => {
+        mod bytemuck_tests {
+            use alloc::vec;
+            use alloc::vec::Vec;
+            use core::mem::size_of;
+
+            use p3_field::{
+                cast_field_to_slice, cast_slice_to_field, cast_slice_to_field_checked, Field,
+                FieldAlgebra, NonCanonicalWords, PackedValue,
+            };
+
+            type F = $field;
+
+            #[test]
+            fn byte_buffer_round_trip() {
+                let elems: Vec<F> = (0..64)
+                    .map(|i| F::from_canonical_u32(i * 1000 + 7))
+                    .collect();
+                let bytes: &[u8] = bytemuck::cast_slice(&elems);
+                assert_eq!(bytes.len(), elems.len() * size_of::<$word>());
+
+                // Copy into word-aligned storage, as a GPU or memory-mapped buffer would provide.
+                let mut words: Vec<$word> = vec![0; elems.len()];
+                bytemuck::cast_slice_mut::<$word, u8>(&mut words).copy_from_slice(bytes);
+
+                let decoded: &[F] = cast_slice_to_field_checked(&words).unwrap();
+                assert_eq!(decoded, &elems[..]);
+                assert_eq!(cast_slice_to_field::<F>(&words), &elems[..]);
+                assert_eq!(cast_field_to_slice(decoded), &words[..]);
+            }
+
+            #[test]
+            fn non_canonical_words_are_reported() {
+                let order: $word = $order;
+                let words = [0, order, 1, order - 1, <$word>::MAX];
+                assert_eq!(
+                    cast_slice_to_field_checked::<F>(&words),
+                    Err(NonCanonicalWords {
+                        indices: vec![1, 4]
+                    })
+                );
+            }
+
+            #[test]
+            fn cast_to_packed() {
+                type P = <F as Field>::Packing;
+                let elems: Vec<F> = (0..64).map(F::from_canonical_u32).collect();
+                let packed: &[P] = bytemuck::cast_slice(&elems);
+                assert_eq!(packed.len(), elems.len() / P::WIDTH);
+                for (p, chunk) in packed.iter().zip(elems.chunks_exact(P::WIDTH)) {
+                    assert_eq!(p.as_slice(), chunk);
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_extension_frobenius {
+    ($field:ty, $ef:ty) => {
+        mod frobenius_tests {
+            #[test]
+            fn test_frobenius_norm_trace() {
+                $crate::test_frobenius_norm_trace::<$field, $ef>();
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! test_field {
     ($field:ty) => {
@@ -142,6 +927,10 @@ macro_rules! test_field {
                 $crate::test_add_neg_sub_mul::<$field>();
             }
             #[test]
+            fn test_field_axioms() {
+                $crate::test_field_axioms::<$field>();
+            }
+            #[test]
             fn test_inv_div() {
                 $crate::test_inv_div::<$field>();
             }
@@ -150,9 +939,110 @@ macro_rules! test_field {
                 $crate::test_inverse::<$field>();
             }
             #[test]
+            fn test_batch_multiplicative_inverse() {
+                $crate::test_batch_multiplicative_inverse::<$field>();
+            }
+            #[test]
+            fn test_batch_inverse_allowing_zero() {
+                $crate::test_batch_inverse_allowing_zero::<$field>();
+            }
+            #[test]
+            fn test_batch_try_inverse() {
+                $crate::test_batch_try_inverse::<$field>();
+            }
+            #[test]
+            fn test_eval_poly() {
+                $crate::test_eval_poly::<$field>();
+            }
+            #[test]
+            fn test_poly_mul() {
+                $crate::test_poly_mul::<$field>();
+            }
+            #[test]
+            fn test_packed_dot_product_and_sum() {
+                $crate::test_packed_dot_product_and_sum::<$field>();
+            }
+            #[test]
+            #[should_panic]
+            fn test_packed_dot_product_mismatched_lengths() {
+                $crate::test_packed_dot_product_mismatched_lengths::<$field>();
+            }
+            #[test]
+            fn test_exp_small() {
+                $crate::test_exp_small::<$field>();
+            }
+            #[test]
+            fn test_pack_slice_prefix_suffix() {
+                $crate::test_pack_slice_prefix_suffix::<<$field as p3_field::Field>::Packing>();
+            }
+            #[test]
             fn test_multiplicative_group_factors() {
                 $crate::test_multiplicative_group_factors::<$field>();
             }
+            #[test]
+            fn test_multiplicative_group_generator() {
+                $crate::test_multiplicative_group_generator::<$field>();
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_prime_field {
+    ($field:ty) => {
+        mod prime_field_tests {
+            #[test]
+            fn test_from_canonical_biguint_checked() {
+                $crate::test_from_canonical_biguint_checked::<$field>();
+            }
+            #[test]
+            fn test_primitive_root_of_unity() {
+                $crate::test_primitive_root_of_unity::<$field>();
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_prime_field_64 {
+    ($field:ty) => {
+        $crate::test_prime_field!($field);
+
+        mod prime_field_64_tests {
+            #[test]
+            fn test_from_canonical_checked() {
+                $crate::test_from_canonical_checked::<$field>();
+            }
+            #[test]
+            fn test_codec() {
+                $crate::test_codec::<$field>();
+            }
+            #[test]
+            fn test_sum_slice() {
+                $crate::test_sum_slice::<$field>();
+            }
+            #[test]
+            fn test_sample_uniform() {
+                $crate::test_sample_uniform::<$field>();
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_prime_field_32 {
+    ($field:ty) => {
+        $crate::test_prime_field_64!($field);
+
+        mod prime_field_32_tests {
+            #[test]
+            fn test_codec_u32() {
+                $crate::test_codec_u32::<$field>();
+            }
+            #[test]
+            fn test_canonical_u32_slices() {
+                $crate::test_canonical_u32_slices::<$field>();
+            }
         }
     };
 }
@@ -161,6 +1051,10 @@ macro_rules! test_field {
 macro_rules! test_two_adic_field {
     ($field:ty) => {
         mod two_adic_field_tests {
+            #[test]
+            fn test_two_adic_consistency() {
+                $crate::test_two_adic_consistency::<$field>();
+            }
             #[test]
             fn test_two_adic_field_subgroup_zerofier() {
                 $crate::test_two_adic_subgroup_zerofier::<$field>();
@@ -170,6 +1064,10 @@ macro_rules! test_two_adic_field {
                 $crate::test_two_adic_coset_zerofier::<$field>();
             }
             #[test]
+            fn test_two_adic_coset_iter() {
+                $crate::test_two_adic_coset_iter::<$field>();
+            }
+            #[test]
             fn test_two_adic_consisitency() {
                 $crate::test_two_adic_generator_consistency::<$field>();
             }
@@ -190,6 +1088,11 @@ macro_rules! test_two_adic_extension_field {
             fn test_ef_two_adic_generator_consistency() {
                 $crate::test_ef_two_adic_generator_consistency::<$field, $ef>();
             }
+
+            #[test]
+            fn test_ext_packing() {
+                $crate::test_ext_packing::<$field, $ef>();
+            }
         }
     };
 }
@@ -1,7 +1,7 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use p3_field::{Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue};
+use p3_field::{Field, FieldAlgebra, PackedChunk, PackedField, PackedFieldPow2, PackedValue};
 use rand::distributions::{Distribution, Standard};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha20Rng;
@@ -88,6 +88,32 @@ where
     }
 }
 
+pub fn test_transpose<PF>()
+where
+    PF: PackedFieldPow2 + Eq,
+    Standard: Distribution<PF::Scalar>,
+{
+    let width = PF::WIDTH;
+    let original: Vec<PF> = (0..width)
+        .map(|i| packed_from_random(0x7a2f0000 + i as u64))
+        .collect();
+
+    let mut block = original.clone();
+    PF::transpose(&mut block);
+    for i in 0..width {
+        for j in 0..width {
+            assert_eq!(
+                block[i].as_slice()[j],
+                original[j].as_slice()[i],
+                "transpose mismatch at ({i}, {j})"
+            );
+        }
+    }
+
+    PF::transpose(&mut block);
+    assert_eq!(block, original, "transposing twice is not the identity");
+}
+
 #[allow(clippy::eq_op)]
 pub fn test_add_neg<PF>(zeros: PF)
 where
@@ -418,6 +444,81 @@ where
     }
 }
 
+/// 64-bit words around the word boundaries and those of the Goldilocks field, `p = 2^64 - 2^32 + 1`,
+/// including the non-canonical representatives from `p` to `2^64 - 1`. These are meant as
+/// operands for `test_packed_edge_operands` for packings of 64-bit fields.
+pub const EDGE_VALS_U64: [u64; 12] = [
+    0x0000_0000_0000_0000,
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_0002,
+    0x0000_0000_FFFF_FFFF,
+    0x0000_0001_0000_0000,
+    0x7FFF_FFFF_FFFF_FFFF,
+    0x8000_0000_0000_0000,
+    0xFFFF_FFFE_FFFF_FFFF,
+    0xFFFF_FFFF_0000_0000,
+    0xFFFF_FFFF_0000_0001,
+    0xFFFF_FFFF_0000_0002,
+    0xFFFF_FFFF_FFFF_FFFF,
+];
+
+/// Compare every arithmetic operation of `PF` against the scalar field on all combinations of the
+/// given edge-case operands, arranged so that each operand appears in every lane.
+///
+/// Backends with non-canonical internal representations should include those representations
+/// (e.g. values between `p` and the top of the word) among the operands.
+pub fn test_packed_edge_operands<PF>(edges: &[PF::Scalar])
+where
+    PF: PackedField + Eq,
+{
+    let n = edges.len();
+    for j in 0..n {
+        for k in 0..n {
+            let x = PF::from_fn(|i| edges[(j + i) % n]);
+            let y = PF::from_fn(|i| edges[(k + 3 * i) % n]);
+            check_packed_ops_match_scalar(x, y);
+        }
+    }
+}
+
+/// Compare every arithmetic operation of `PF` against the scalar field on operands drawn by
+/// `sample`. Unlike the `Standard` distribution, `sample` may produce the non-canonical
+/// representatives a backend has to handle.
+pub fn test_packed_sampled_operands<PF>(mut sample: impl FnMut() -> PF::Scalar)
+where
+    PF: PackedField + Eq,
+{
+    for _ in 0..10_000 {
+        let x = PF::from_fn(|_| sample());
+        let y = PF::from_fn(|_| sample());
+        check_packed_ops_match_scalar(x, y);
+    }
+}
+
+fn check_packed_ops_match_scalar<PF>(x: PF, y: PF)
+where
+    PF: PackedField + Eq,
+{
+    let results = [
+        ("add", x + y),
+        ("sub", x - y),
+        ("mul", x * y),
+        ("neg", -x),
+        ("double", x.double()),
+        ("square", x.square()),
+    ];
+    for (i, (&a, &b)) in x.as_slice().iter().zip(y.as_slice()).enumerate() {
+        let expected = [a + b, a - b, a * b, -a, a.double(), a.square()];
+        for ((op, packed), scalar) in results.iter().zip(expected) {
+            assert_eq!(
+                packed.as_slice()[i],
+                scalar,
+                "{op} mismatch in lane {i} for operands {a:?} and {b:?}"
+            );
+        }
+    }
+}
+
 pub fn test_multiplicative_inverse<PF>()
 where
     PF: PackedField + Eq,
@@ -434,6 +535,68 @@ where
     );
 }
 
+/// Check the prefix/suffix packing helpers on sub-slices of every offset and length up to a few
+/// packing widths, so that misaligned heads and short slices are both exercised.
+pub fn test_pack_slice_prefix_suffix<PV>()
+where
+    PV: PackedValue,
+    PV::Value: core::fmt::Debug,
+    Standard: Distribution<PV::Value>,
+{
+    let mut rng = ChaCha20Rng::seed_from_u64(0x5eed);
+    let width = PV::WIDTH;
+    let mut buf: Vec<PV::Value> = (0..4 * width + 1).map(|_| rng.gen()).collect();
+    let original = buf.clone();
+
+    for start in 0..width {
+        for end in start..buf.len() {
+            let sub = &buf[start..end];
+
+            let (prefix, packed, suffix) = PV::pack_slice_prefix_suffix(sub);
+            assert!(prefix.len() < width);
+            assert!(suffix.len() < width);
+            let rejoined: Vec<_> = prefix
+                .iter()
+                .chain(PV::unpack_slice(packed))
+                .chain(suffix)
+                .copied()
+                .collect();
+            assert_eq!(rejoined, sub, "prefix/packed/suffix do not cover the slice");
+
+            let chunks: Vec<_> = PV::aligned_chunks(sub).collect();
+            assert_eq!(chunks.len(), PV::aligned_chunks(sub).len());
+            let rechunked: Vec<_> = chunks
+                .iter()
+                .flat_map(|chunk| match chunk {
+                    PackedChunk::Scalars(xs) => xs.to_vec(),
+                    PackedChunk::Packed(p) => p.as_slice().to_vec(),
+                })
+                .collect();
+            assert_eq!(rechunked, sub, "aligned chunks do not cover the slice");
+
+            let (with_suffix, short_suffix) = PV::pack_slice_with_suffix(sub);
+            assert_eq!(with_suffix.len(), sub.len() / width);
+            assert_eq!(short_suffix.len(), sub.len() % width);
+        }
+    }
+
+    // Writing through the mutable variant must touch exactly the given sub-slice.
+    for start in 0..width {
+        let end = buf.len() - start;
+        let sentinel: PV::Value = rng.gen();
+        let (prefix, packed, suffix) = PV::pack_slice_prefix_suffix_mut(&mut buf[start..end]);
+        prefix.iter_mut().for_each(|x| *x = sentinel);
+        packed
+            .iter_mut()
+            .for_each(|p| p.as_slice_mut().iter_mut().for_each(|x| *x = sentinel));
+        suffix.iter_mut().for_each(|x| *x = sentinel);
+        assert!(buf[start..end].iter().all(|&x| x == sentinel));
+        assert_eq!(buf[..start], original[..start]);
+        assert_eq!(buf[end..], original[end..]);
+        buf.copy_from_slice(&original);
+    }
+}
+
 #[macro_export]
 macro_rules! test_packed_field {
     ($packedfield:ty, $zeros:expr, $specials:expr) => {
@@ -464,6 +627,14 @@ macro_rules! test_packed_field {
             fn test_multiplicative_inverse() {
                 $crate::test_multiplicative_inverse::<$packedfield>();
             }
+            #[test]
+            fn test_pack_slice_prefix_suffix() {
+                $crate::test_pack_slice_prefix_suffix::<$packedfield>();
+            }
+            #[test]
+            fn test_transpose() {
+                $crate::test_transpose::<$packedfield>();
+            }
         }
     };
 }
@@ -15,7 +15,8 @@ use crate::{FieldAlgebra, FieldArray, PackedValue};
 /// compute WIDTH separate cumulative product arrays that only meet at the end.
 ///
 /// # Panics
-/// This will panic if any of the inputs is zero.
+/// This will panic if any of the inputs is zero. See `batch_multiplicative_inverse_allowing_zero`
+/// for a variant which maps zeros to zero.
 #[instrument(level = "debug", skip_all)]
 pub fn batch_multiplicative_inverse<F: Field>(x: &[F]) -> Vec<F> {
     // How many elements to invert in one thread.
@@ -33,25 +34,111 @@ pub fn batch_multiplicative_inverse<F: Field>(x: &[F]) -> Vec<F> {
     result
 }
 
+/// Like `batch_multiplicative_inverse`, but zeros are allowed in the input, and are mapped to zero
+/// in the output. All other elements are mapped to their inverses.
+///
+/// This still uses only `O(n)` multiplications and a single inversion (per thread), as zeros are
+/// simply skipped in the cumulative products.
+#[instrument(level = "debug", skip_all)]
+pub fn batch_multiplicative_inverse_allowing_zero<F: Field>(x: &[F]) -> Vec<F> {
+    // How many elements to invert in one thread.
+    const CHUNK_SIZE: usize = 1024;
+
+    let n = x.len();
+    let mut result = F::zero_vec(n);
+
+    x.par_chunks(CHUNK_SIZE)
+        .zip(result.par_chunks_mut(CHUNK_SIZE))
+        .for_each(|(x, result)| {
+            batch_multiplicative_inverse_allowing_zero_helper(x, result);
+        });
+
+    result
+}
+
+/// Batch inversion for inputs which may contain zeros, reporting which entries had no inverse.
+///
+/// Entry `i` of the output is `None` exactly when `x[i]` is zero, and `Some(x[i]^{-1})` otherwise.
+/// Like `batch_multiplicative_inverse_allowing_zero`, on which this is built, it uses `O(n)`
+/// multiplications and a single inversion per thread.
+#[instrument(level = "debug", skip_all)]
+pub fn batch_try_inverse<F: Field>(x: &[F]) -> Vec<Option<F>> {
+    batch_multiplicative_inverse_allowing_zero(x)
+        .into_iter()
+        .zip(x)
+        .map(|(inv, x)| (!x.is_zero()).then_some(inv))
+        .collect()
+}
+
+/// Invert every nonzero entry of `x` in place.
+///
+/// Zero entries are left as zero. If there were any, their positions are returned, in increasing
+/// order, as the error.
+///
+/// Each chunk of `x` serves as the buffer of cumulative products for its own inversion, so that
+/// only a chunk of the inputs is copied, on the stack, rather than all of `x` to the heap.
+#[instrument(level = "debug", skip_all)]
+pub fn batch_try_inverse_in_place<F: Field>(x: &mut [F]) -> Result<(), Vec<usize>> {
+    // How many elements to invert in one thread.
+    const CHUNK_SIZE: usize = 1024;
+
+    x.par_chunks_mut(CHUNK_SIZE).for_each(|chunk| {
+        let mut input = [F::ZERO; CHUNK_SIZE];
+        let input = &mut input[..chunk.len()];
+        input.copy_from_slice(chunk);
+        batch_multiplicative_inverse_allowing_zero_helper(input, chunk);
+    });
+
+    let zeros: Vec<usize> = x
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| x.is_zero().then_some(i))
+        .collect();
+    if zeros.is_empty() {
+        Ok(())
+    } else {
+        Err(zeros)
+    }
+}
+
+/// Like `batch_multiplicative_inverse_allowing_zero`, but writes the result to the given output
+/// buffer.
+fn batch_multiplicative_inverse_allowing_zero_helper<F: Field>(x: &[F], result: &mut [F]) {
+    assert_eq!(result.len(), x.len());
+
+    // result[i] holds the product of all nonzero x[j] with j < i.
+    let mut product = F::ONE;
+    for (r, &x) in result.iter_mut().zip(x) {
+        *r = product;
+        if !x.is_zero() {
+            product *= x;
+        }
+    }
+
+    let mut inv = product.inverse();
+    for (r, &x) in result.iter_mut().zip(x).rev() {
+        if x.is_zero() {
+            *r = F::ZERO;
+        } else {
+            *r *= inv;
+            inv *= x;
+        }
+    }
+}
+
 /// Like `batch_multiplicative_inverse`, but writes the result to the given output buffer.
 fn batch_multiplicative_inverse_helper<F: Field>(x: &[F], result: &mut [F]) {
     // Higher WIDTH increases instruction-level parallelism, but too high a value will cause us
     // to run out of registers.
     const WIDTH: usize = 4;
 
-    let n = x.len();
-    assert_eq!(result.len(), n);
-    if n % WIDTH != 0 {
-        // There isn't a very clean way to do this with FieldArray; for now just do it in serial.
-        // Another simple (though suboptimal) workaround would be to make two separate calls, one
-        // for the packed part and one for the remainder.
-        return batch_multiplicative_inverse_general(x, result, |x| x.inverse());
-    }
-
-    let x_packed = FieldArray::<F, 4>::pack_slice(x);
-    let result_packed = FieldArray::<F, 4>::pack_slice_mut(result);
+    assert_eq!(result.len(), x.len());
+    let (x_packed, x_suffix) = FieldArray::<F, WIDTH>::pack_slice_with_suffix(x);
+    let (result_packed, result_suffix) = FieldArray::<F, WIDTH>::pack_slice_with_suffix_mut(result);
 
     batch_multiplicative_inverse_general(x_packed, result_packed, |x_packed| x_packed.inverse());
+    // The fewer than `WIDTH` leftover elements cost one more inversion.
+    batch_multiplicative_inverse_general(x_suffix, result_suffix, |x| x.inverse());
 }
 
 /// A simple single-threaded implementation of Montgomery's trick. Since not all `FieldAlgebra`s
@@ -0,0 +1,68 @@
+//! Zero-copy reinterpretation of machine words as field elements, via `bytemuck`.
+//!
+//! Fields implementing [`FieldWord`] are `#[repr(transparent)]` wrappers around a single machine
+//! word, so a `&[u32]` or `&[u64]` (and, through `bytemuck::cast_slice`, a suitably aligned `&[u8]`)
+//! can be viewed as a slice of field elements without copying. Every bit pattern is memory-safe,
+//! but a field's arithmetic is only correct on the words it would produce itself; use
+//! [`cast_slice_to_field_checked`] when the words come from an untrusted source.
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use bytemuck::Pod;
+
+use crate::Field;
+
+/// A field whose elements are stored in memory exactly as a single `Word`.
+pub trait FieldWord: Field + Pod {
+    /// The in-memory representation of an element.
+    type Word: Pod;
+
+    /// Whether `word` is the canonical in-memory representation of some element.
+    ///
+    /// Note that the in-memory representation need not be the canonical value of the element;
+    /// for MONTY fields, for instance, it is the MONTY form.
+    fn is_canonical_word(word: Self::Word) -> bool;
+}
+
+/// An error returned when casting words which are not all canonical representations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonCanonicalWords {
+    /// The indices of the offending words, in increasing order.
+    pub indices: Vec<usize>,
+}
+
+impl Display for NonCanonicalWords {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "words at indices {:?} are not canonical", self.indices)
+    }
+}
+
+/// View a slice of words as a slice of field elements, without checking canonicity.
+#[inline]
+pub fn cast_slice_to_field<F: FieldWord>(words: &[F::Word]) -> &[F] {
+    bytemuck::cast_slice(words)
+}
+
+/// View a slice of words as a slice of field elements, failing if any word is not canonical.
+pub fn cast_slice_to_field_checked<F: FieldWord>(
+    words: &[F::Word],
+) -> Result<&[F], NonCanonicalWords> {
+    let indices: Vec<usize> = words
+        .iter()
+        .enumerate()
+        .filter(|&(_, &word)| !F::is_canonical_word(word))
+        .map(|(i, _)| i)
+        .collect();
+    if indices.is_empty() {
+        Ok(cast_slice_to_field(words))
+    } else {
+        Err(NonCanonicalWords { indices })
+    }
+}
+
+/// View a slice of field elements as a slice of their in-memory words.
+#[inline]
+pub fn cast_field_to_slice<F: FieldWord>(elems: &[F]) -> &[F::Word] {
+    bytemuck::cast_slice(elems)
+}
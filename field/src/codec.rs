@@ -0,0 +1,104 @@
+//! Injective encodings of slices of field elements as byte strings.
+//!
+//! Elements are written as their canonical representatives in little-endian order, using 8 bytes
+//! per element for `PrimeField64` and 4 bytes per element for the `_u32` variants. Decoding is the
+//! exact inverse: it rejects non-canonical values and inputs whose length is not a multiple of the
+//! element width, rather than reducing or truncating them, so every byte string decodes to at most
+//! one slice and every slice has exactly one encoding.
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::{PrimeField32, PrimeField64};
+
+/// An error returned when a byte string is not a valid encoding of field elements.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input length is not a multiple of the number of bytes per element.
+    TrailingBytes {
+        len: usize,
+        bytes_per_element: usize,
+    },
+    /// The element at `index` is not less than the field order.
+    NonCanonical { index: usize },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TrailingBytes {
+                len,
+                bytes_per_element,
+            } => write!(
+                f,
+                "input of {len} bytes is not a multiple of {bytes_per_element} bytes per element"
+            ),
+            Self::NonCanonical { index } => write!(f, "element {index} is not canonical"),
+        }
+    }
+}
+
+/// Encode field elements using 8 little-endian bytes per element.
+pub fn encode_slice<F: PrimeField64>(elems: &[F]) -> Vec<u8> {
+    encode_iter(elems.iter().copied()).collect()
+}
+
+/// The bytes of `encode_slice`, without collecting them, e.g. to feed them straight to a hasher.
+pub fn encode_iter<F: PrimeField64>(
+    elems: impl IntoIterator<Item = F>,
+) -> impl Iterator<Item = u8> {
+    elems
+        .into_iter()
+        .flat_map(|x| x.as_canonical_u64().to_le_bytes())
+}
+
+/// Decode field elements encoded by `encode_slice`.
+pub fn decode_slice<F: PrimeField64>(bytes: &[u8]) -> Result<Vec<F>, DecodeError> {
+    check_len(bytes, 8)?;
+    bytes
+        .chunks_exact(8)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let value = u64::from_le_bytes(chunk.try_into().unwrap());
+            F::from_canonical_checked(value).ok_or(DecodeError::NonCanonical { index })
+        })
+        .collect()
+}
+
+/// Encode field elements using 4 little-endian bytes per element.
+pub fn encode_slice_u32<F: PrimeField32>(elems: &[F]) -> Vec<u8> {
+    encode_iter_u32(elems.iter().copied()).collect()
+}
+
+/// The bytes of `encode_slice_u32`, without collecting them.
+pub fn encode_iter_u32<F: PrimeField32>(
+    elems: impl IntoIterator<Item = F>,
+) -> impl Iterator<Item = u8> {
+    elems
+        .into_iter()
+        .flat_map(|x| x.as_canonical_u32().to_le_bytes())
+}
+
+/// Decode field elements encoded by `encode_slice_u32`.
+pub fn decode_slice_u32<F: PrimeField32>(bytes: &[u8]) -> Result<Vec<F>, DecodeError> {
+    check_len(bytes, 4)?;
+    bytes
+        .chunks_exact(4)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let value = u32::from_le_bytes(chunk.try_into().unwrap());
+            F::from_canonical_checked(u64::from(value)).ok_or(DecodeError::NonCanonical { index })
+        })
+        .collect()
+}
+
+fn check_len(bytes: &[u8], bytes_per_element: usize) -> Result<(), DecodeError> {
+    if bytes.len() % bytes_per_element == 0 {
+        Ok(())
+    } else {
+        Err(DecodeError::TrailingBytes {
+            len: bytes.len(),
+            bytes_per_element,
+        })
+    }
+}
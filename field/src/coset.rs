@@ -0,0 +1,129 @@
+use core::iter::FusedIterator;
+
+use crate::TwoAdicField;
+
+/// An iterator over the elements `shift * g^i` of a coset `shift * H`, where `H` is the
+/// multiplicative subgroup of order `2^log_n` generated by `g = F::two_adic_generator(log_n)`.
+///
+/// The iterator is double-ended, and `nth`/`nth_back` jump to the requested element with a single
+/// exponentiation rather than stepping through the intermediate elements.
+#[derive(Clone, Debug)]
+pub struct CosetIter<F> {
+    shift: F,
+    generator: F,
+    generator_inv: F,
+    log_n: usize,
+    /// The index of the next element returned by `next`.
+    start: usize,
+    /// One past the index of the next element returned by `next_back`.
+    end: usize,
+    /// `shift * g^start`.
+    front: F,
+    /// `shift * g^(end - 1)`, or `shift * g^(-1)` if the coset has been exhausted from the back.
+    back: F,
+}
+
+impl<F: TwoAdicField> CosetIter<F> {
+    /// Creates an iterator over the coset `shift * H`, where `H` has order `2^log_n`.
+    ///
+    /// # Panics
+    /// Panics if `log_n > F::TWO_ADICITY` or `shift` is zero.
+    pub fn new(shift: F, log_n: usize) -> Self {
+        assert!(log_n <= F::TWO_ADICITY);
+        assert!(!shift.is_zero(), "a coset shift must be nonzero");
+        let generator = F::two_adic_generator(log_n);
+        let generator_inv = generator.inverse();
+        Self {
+            shift,
+            generator,
+            generator_inv,
+            log_n,
+            start: 0,
+            end: 1 << log_n,
+            front: shift,
+            back: shift * generator_inv,
+        }
+    }
+
+    /// The shift of the coset.
+    pub const fn shift(&self) -> F {
+        self.shift
+    }
+
+    /// The generator of the subgroup `H`.
+    pub const fn generator(&self) -> F {
+        self.generator
+    }
+
+    /// The base-two logarithm of the size of the coset.
+    pub const fn log_n(&self) -> usize {
+        self.log_n
+    }
+
+    /// Returns whether `x` lies in the coset `shift * H`. This refers to the whole coset, and does
+    /// not depend on how much of the iterator has been consumed.
+    pub fn contains(&self, x: F) -> bool {
+        // x is in shift * H iff (x / shift)^(2^log_n) = 1.
+        !x.is_zero() && x.exp_power_of_2(self.log_n) == self.shift.exp_power_of_2(self.log_n)
+    }
+
+    /// Returns `shift * g^i`.
+    fn element(&self, i: usize) -> F {
+        self.shift * self.generator.exp_u64(i as u64)
+    }
+}
+
+impl<F: TwoAdicField> Iterator for CosetIter<F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        if self.start == self.end {
+            return None;
+        }
+        let x = self.front;
+        self.start += 1;
+        self.front *= self.generator;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<F> {
+        if n >= self.end - self.start {
+            self.start = self.end;
+            return None;
+        }
+        self.start += n;
+        self.front = self.element(self.start);
+        self.next()
+    }
+}
+
+impl<F: TwoAdicField> DoubleEndedIterator for CosetIter<F> {
+    fn next_back(&mut self) -> Option<F> {
+        if self.start == self.end {
+            return None;
+        }
+        let x = self.back;
+        self.end -= 1;
+        self.back *= self.generator_inv;
+        Some(x)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<F> {
+        if n >= self.end - self.start {
+            self.end = self.start;
+            return None;
+        }
+        self.end -= n;
+        self.back = self.element(self.end - 1);
+        self.next_back()
+    }
+}
+
+impl<F: TwoAdicField> ExactSizeIterator for CosetIter<F> {}
+
+impl<F: TwoAdicField> FusedIterator for CosetIter<F> {}
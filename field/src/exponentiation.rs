@@ -17,6 +17,72 @@ const fn bits_u64(n: u64) -> usize {
     (64 - n.leading_zeros()) as usize
 }
 
+/// Compute `val^N` using a shortest addition chain for the small odd exponents commonly used as
+/// S-box degrees, namely 3, 5, 7, 9, 11, 13 and 17; see `exp_const_u64_by_chain`.
+///
+/// This is `val.exp_const_u64::<N>()`, so that packed fields with specialised code for some of
+/// these powers use it.
+#[inline(always)]
+pub fn exp_small<const N: u64, FA: FieldAlgebra>(val: FA) -> FA {
+    val.exp_const_u64::<N>()
+}
+
+/// Compute `val^N` using a shortest addition chain for the powers up to 7 and the small odd
+/// exponents commonly used as S-box degrees, namely 9, 11, 13 and 17. Other exponents fall back
+/// to `FieldAlgebra::exp_u64`.
+///
+/// This is the default `FieldAlgebra::exp_const_u64`, which implementations with specialised
+/// code for some powers fall back to for the others.
+///
+/// The number of squarings (S) and multiplications (M) used for each odd exponent is:
+/// - 3: 1S + 1M
+/// - 5: 2S + 1M
+/// - 7: 2S + 2M
+/// - 9: 3S + 1M
+/// - 11: 3S + 2M
+/// - 13: 3S + 2M
+/// - 17: 4S + 1M
+#[must_use]
+#[inline(always)]
+pub fn exp_const_u64_by_chain<const N: u64, FA: FieldAlgebra>(val: &FA) -> FA {
+    match N {
+        0 => FA::ONE,
+        1 => val.clone(),
+        2 => val.square(),
+        3 => val.cube(),
+        4 => val.square().square(),
+        5 => val.square().square() * val.clone(),
+        6 => val.square().cube(),
+        7 => {
+            let x2 = val.square();
+            let x3 = x2.clone() * val.clone();
+            let x4 = x2.square();
+            x3 * x4
+        }
+        9 => {
+            // 1 -> 2 -> 4 -> 8 -> 9
+            let x8 = val.exp_power_of_2(3);
+            x8 * val.clone()
+        }
+        11 => {
+            // 1 -> 2 -> 4 -> 5 -> 10 -> 11
+            let x5 = val.square().square() * val.clone();
+            x5.square() * val.clone()
+        }
+        13 => {
+            // 1 -> 2 -> 3 -> 6 -> 12 -> 13
+            let x3 = val.cube();
+            x3.square().square() * val.clone()
+        }
+        17 => {
+            // 1 -> 2 -> 4 -> 8 -> 16 -> 17
+            let x16 = val.exp_power_of_2(4);
+            x16 * val.clone()
+        }
+        _ => val.exp_u64(N),
+    }
+}
+
 pub fn exp_1717986917<FA: FieldAlgebra>(val: FA) -> FA {
     // Note that 5 * 1717986917 = 4*(2^31 - 2) + 1 = 1 mod p - 1.
     // Thus as a^{p - 1} = 1 for all a \in F_p, (a^{1717986917})^5 = a.
@@ -20,6 +20,16 @@ use crate::{
     field_to_array, ExtensionField, FieldAlgebra, FieldExtensionAlgebra, Packable, TwoAdicField,
 };
 
+/// An element of the binomial extension `FA[X]/(X^D - W)`.
+///
+/// The type is `repr(transparent)` over its array of `D` coefficients, lowest degree first. This
+/// layout is guaranteed, so a slice of `n` extension elements has exactly the layout of a slice of
+/// `D * n` base elements; see `as_base_slice` and `from_base_slice`.
+///
+/// `Serialize` and `Deserialize` treat an element as a tuple of its `D` coefficients, lowest
+/// degree first, each using the base field's own serde implementation. Deserialization fails
+/// unless exactly `D` coefficients are present. In particular, an element embedded from the base
+/// field serializes exactly like `[x, 0, ..., 0]`.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize, PartialOrd, Ord)]
 #[repr(transparent)] // to make the zero_vec implementation safe
 pub struct BinomialExtensionField<FA, const D: usize> {
@@ -30,6 +40,50 @@ pub struct BinomialExtensionField<FA, const D: usize> {
     pub(crate) value: [FA; D],
 }
 
+/// View a slice of extension field elements as the slice of their coefficients, without copying.
+///
+/// Element `i` of the input occupies entries `D * i..D * (i + 1)` of the output, lowest degree
+/// first.
+pub fn as_base_slice<FA, const D: usize>(slice: &[BinomialExtensionField<FA, D>]) -> &[FA] {
+    // SAFETY: `BinomialExtensionField<FA, D>` is a repr(transparent) wrapper around `[FA; D]`.
+    unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), slice.len() * D) }
+}
+
+/// The mutable analogue of `as_base_slice`.
+pub fn as_base_slice_mut<FA, const D: usize>(
+    slice: &mut [BinomialExtensionField<FA, D>],
+) -> &mut [FA] {
+    // SAFETY: `BinomialExtensionField<FA, D>` is a repr(transparent) wrapper around `[FA; D]`.
+    unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr().cast(), slice.len() * D) }
+}
+
+/// View a slice of coefficients as a slice of extension field elements, without copying.
+///
+/// This is the inverse of `as_base_slice`, and returns `None` unless the length of `slice` is a
+/// multiple of `D`.
+pub fn from_base_slice<FA, const D: usize>(
+    slice: &[FA],
+) -> Option<&[BinomialExtensionField<FA, D>]> {
+    if D == 0 || slice.len() % D != 0 {
+        return None;
+    }
+    // SAFETY: `BinomialExtensionField<FA, D>` is a repr(transparent) wrapper around `[FA; D]`,
+    // which has the same alignment as `FA`.
+    Some(unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), slice.len() / D) })
+}
+
+/// The mutable analogue of `from_base_slice`.
+pub fn from_base_slice_mut<FA, const D: usize>(
+    slice: &mut [FA],
+) -> Option<&mut [BinomialExtensionField<FA, D>]> {
+    if D == 0 || slice.len() % D != 0 {
+        return None;
+    }
+    // SAFETY: `BinomialExtensionField<FA, D>` is a repr(transparent) wrapper around `[FA; D]`,
+    // which has the same alignment as `FA`.
+    Some(unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr().cast(), slice.len() / D) })
+}
+
 impl<FA: FieldAlgebra, const D: usize> Default for BinomialExtensionField<FA, D> {
     fn default() -> Self {
         Self {
@@ -90,6 +144,25 @@ impl<F: BinomiallyExtendable<D>, const D: usize> HasFrobenius<F> for BinomialExt
 
     /// Algorithm 11.3.4 in Handbook of Elliptic and Hyperelliptic Curve Cryptography.
     fn frobenius_inv(&self) -> Self {
+        let (f, g) = self.conjugates_product_and_norm();
+        f * g.inverse()
+    }
+
+    fn norm(&self) -> F {
+        self.conjugates_product_and_norm().1
+    }
+
+    /// The trace of `X^i` vanishes for `0 < i < D`, as its conjugates are `X^i` scaled by each
+    /// of the `D`th roots of unity, so only the constant coefficient contributes.
+    fn trace(&self) -> F {
+        F::from_canonical_usize(D) * self.value[0]
+    }
+}
+
+impl<F: BinomiallyExtendable<D>, const D: usize> BinomialExtensionField<F, D> {
+    /// Returns `(f, g)` where `f` is the product of all Galois conjugates of `self` other than
+    /// `self` itself, and `g = self * f` is the norm of `self`.
+    fn conjugates_product_and_norm(&self) -> (Self, F) {
         // Writing 'a' for self, we need to compute a^(r-1):
         // r = n^D-1/n-1 = n^(D-1)+n^(D-2)+...+n
         let mut f = Self::ONE;
@@ -109,7 +182,7 @@ impl<F: BinomiallyExtendable<D>, const D: usize> HasFrobenius<F> for BinomialExt
         g += a[0] * b[0];
         debug_assert_eq!(Self::from(g), *self * f);
 
-        f * g.inverse()
+        (f, g)
     }
 }
 
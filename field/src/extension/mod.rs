@@ -53,6 +53,24 @@ pub trait HasFrobenius<F: Field>: ExtensionField<F> {
             .take(Self::D)
             .collect()
     }
+
+    /// The norm of `self` over `F`, i.e. the product of its Galois conjugates.
+    fn norm(&self) -> F {
+        self.galois_group()
+            .into_iter()
+            .product::<Self>()
+            .as_base()
+            .expect("Norm is not in the base field?")
+    }
+
+    /// The trace of `self` over `F`, i.e. the sum of its Galois conjugates.
+    fn trace(&self) -> F {
+        self.galois_group()
+            .into_iter()
+            .sum::<Self>()
+            .as_base()
+            .expect("Trace is not in the base field?")
+    }
 }
 
 /// Optional trait for implementing Two Adic Binomial Extension Field.
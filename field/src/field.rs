@@ -8,14 +8,14 @@ use core::slice;
 
 use itertools::Itertools;
 use num_bigint::BigUint;
-use num_traits::One;
+use num_traits::{One, Zero};
 use nums::{Factorizer, FactorizerFromSplitter, MillerRabin, PollardRho};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::exponentiation::exp_u64_by_squaring;
+use crate::exponentiation::{exp_const_u64_by_chain, exp_u64_by_squaring};
 use crate::packed::{PackedField, PackedValue};
-use crate::Packable;
+use crate::{CosetIter, Packable};
 
 /// A commutative algebra over a finite field.
 ///
@@ -168,26 +168,11 @@ pub trait FieldAlgebra:
     /// Exponentiation by a constant power.
     ///
     /// For a collection of small values we implement custom multiplication chain circuits which can be faster than the
-    /// simpler square and multiply approach.
+    /// simpler square and multiply approach; see `exp_const_u64_by_chain`.
     #[must_use]
     #[inline(always)]
     fn exp_const_u64<const POWER: u64>(&self) -> Self {
-        match POWER {
-            0 => Self::ONE,
-            1 => self.clone(),
-            2 => self.square(),
-            3 => self.cube(),
-            4 => self.square().square(),
-            5 => self.square().square() * self.clone(),
-            6 => self.square().cube(),
-            7 => {
-                let x2 = self.square();
-                let x3 = x2.clone() * self.clone();
-                let x4 = x2.square();
-                x3 * x4
-            }
-            _ => self.exp_u64(POWER),
-        }
+        exp_const_u64_by_chain::<POWER, Self>(self)
     }
 
     /// Compute self^{2^power_log} by repeated squaring.
@@ -335,6 +320,12 @@ pub trait Field:
 
     fn order() -> BigUint;
 
+    /// A generator of this field's multiplicative group, of order `order() - 1`. By default
+    /// this is `Field::GENERATOR`.
+    fn multiplicative_group_generator() -> Self {
+        Self::GENERATOR
+    }
+
     /// A list of (factor, exponent) pairs.
     fn multiplicative_group_factors() -> Vec<(BigUint, usize)> {
         let primality_test = MillerRabin { error_bits: 128 };
@@ -355,6 +346,53 @@ pub trait Field:
 
 pub trait PrimeField: Field + Ord {
     fn as_canonical_biguint(&self) -> BigUint;
+
+    /// Convert `n` into a field element, returning `None` if `n` is not canonical, i.e. if it is
+    /// not less than the order of the field.
+    ///
+    /// Unlike `from_canonical_u64`, this is safe to call on untrusted input.
+    fn from_canonical_checked(n: u64) -> Option<Self> {
+        (BigUint::from(n) < Self::order()).then(|| Self::from_wrapped_u64(n))
+    }
+
+    /// Convert `n` into a field element, returning `None` if `n` is not less than the order of
+    /// the field.
+    fn from_canonical_biguint_checked(n: &BigUint) -> Option<Self> {
+        if *n >= Self::order() {
+            return None;
+        }
+        let two_32 = Self::from_wrapped_u64(1 << 32);
+        let two_64 = two_32 * two_32;
+        let value = n.iter_u64_digits().rev().fold(Self::ZERO, |acc, digit| {
+            acc * two_64 + Self::from_wrapped_u64(digit)
+        });
+        Some(value)
+    }
+
+    /// Returns an element of multiplicative order exactly `n`, or `None` if `n` does not divide
+    /// the order of the multiplicative group.
+    ///
+    /// The root is found by raising `multiplicative_group_generator()` to the power `(p - 1) / n`.
+    /// As the generator has order `p - 1`, the result has order exactly `n`. For powers of two it
+    /// need not coincide with `TwoAdicField::two_adic_generator`.
+    fn primitive_root_of_unity(n: u64) -> Option<Self> {
+        let group_order = Self::order() - BigUint::one();
+        let n_big = BigUint::from(n);
+        if n == 0 || !(&group_order % &n_big).is_zero() {
+            return None;
+        }
+
+        let cofactor = &group_order / &n_big;
+        let generator = Self::multiplicative_group_generator();
+        let root = cofactor
+            .iter_u64_digits()
+            .rev()
+            .fold(Self::ONE, |acc, digit| {
+                acc.exp_power_of_2(64) * generator.exp_u64(digit)
+            });
+
+        Some(root)
+    }
 }
 
 /// A prime field of order less than `2^64`.
@@ -373,6 +411,16 @@ pub trait PrimeField64: PrimeField {
         // A simple default which is optimal for some fields.
         self.as_canonical_u64()
     }
+
+    /// Sum a slice of field elements, reducing only once at the end.
+    ///
+    /// The default accumulates canonical representatives in a `u128`, which cannot overflow for
+    /// any slice that fits in memory. Fields with a cheaper accumulator, e.g. a `u64` for 31-bit
+    /// fields, override this.
+    fn sum_slice(xs: &[Self]) -> Self {
+        let sum: u128 = xs.iter().map(|x| x.as_canonical_u64() as u128).sum();
+        Self::from_canonical_u64((sum % Self::ORDER_U64 as u128) as u64)
+    }
 }
 
 /// A prime field of order less than `2^32`.
@@ -391,6 +439,21 @@ pub trait PrimeField32: PrimeField64 {
         // A simple default which is optimal for some fields.
         self.as_canonical_u32()
     }
+
+    /// Convert a slice of field elements to their canonical representatives.
+    ///
+    /// Fields whose internal representation is not canonical may override this with a vectorized
+    /// conversion.
+    fn to_canonical_u32_vec(xs: &[Self]) -> Vec<u32> {
+        xs.iter().map(Self::as_canonical_u32).collect()
+    }
+
+    /// Convert a slice of canonical representatives to field elements.
+    ///
+    /// As with `from_canonical_u32`, the behavior is undefined if any input is not canonical.
+    fn from_canonical_u32_slice(xs: &[u32]) -> Vec<Self> {
+        xs.iter().copied().map(Self::from_canonical_u32).collect()
+    }
 }
 
 /// A commutative algebra over an extension field.
@@ -507,6 +570,27 @@ pub trait ExtensionField<Base: Field>: Field + FieldExtensionAlgebra<Base> {
             current,
         }
     }
+
+    /// Pack `Base::Packing::WIDTH` extension elements into a single `ExtensionPacking`, whose `i`-th
+    /// coefficient holds the `i`-th coefficients of `exts` in its lanes.
+    ///
+    /// Panics if `exts.len()` is not `Base::Packing::WIDTH`.
+    fn pack_ext_slice(exts: &[Self]) -> Self::ExtensionPacking {
+        assert_eq!(exts.len(), Base::Packing::WIDTH);
+        Self::ExtensionPacking::from_base_fn(|i| {
+            Base::Packing::from_fn(|j| exts[j].as_base_slice()[i])
+        })
+    }
+
+    /// The inverse of `pack_ext_slice`: the `Base::Packing::WIDTH` extension elements held in the
+    /// lanes of `packed`.
+    fn unpack_ext_packing(packed: &Self::ExtensionPacking) -> Vec<Self> {
+        let coeffs =
+            <Self::ExtensionPacking as FieldExtensionAlgebra<Base::Packing>>::as_base_slice(packed);
+        (0..Base::Packing::WIDTH)
+            .map(|j| Self::from_base_fn(|i| coeffs[i].as_slice()[j]))
+            .collect()
+    }
 }
 
 impl<F: Field> ExtensionField<F> for F {
@@ -549,6 +633,18 @@ pub trait TwoAdicField: Field {
     /// Assumes `bits <= TWO_ADICITY`, otherwise the result is undefined.
     #[must_use]
     fn two_adic_generator(bits: usize) -> Self;
+
+    /// Returns an iterator over the coset `shift * H`, where `H` is the multiplicative subgroup of
+    /// order `2^log_n`, in the order `shift, shift * g, shift * g^2, ...` with
+    /// `g = two_adic_generator(log_n)`.
+    fn coset(shift: Self, log_n: usize) -> CosetIter<Self> {
+        CosetIter::new(shift, log_n)
+    }
+
+    /// Returns an iterator over the multiplicative subgroup of order `2^log_n`, starting at one.
+    fn subgroup(log_n: usize) -> CosetIter<Self> {
+        Self::coset(Self::ONE, log_n)
+    }
 }
 
 /// An iterator which returns the powers of a base element `b` shifted by current `c`: `c, c * b, c * b^2, ...`.
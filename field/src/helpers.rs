@@ -6,9 +6,10 @@ use core::ops::Mul;
 
 use num_bigint::BigUint;
 use p3_maybe_rayon::prelude::{IntoParallelRefMutIterator, ParallelIterator};
+use rand::Rng;
 
 use crate::field::Field;
-use crate::{FieldAlgebra, PackedValue, PrimeField, PrimeField32, TwoAdicField};
+use crate::{FieldAlgebra, PackedValue, PrimeField, PrimeField32, PrimeField64, TwoAdicField};
 
 /// Computes `Z_H(x)`, where `Z_H` is the zerofier of a multiplicative subgroup of order `2^log_n`.
 pub fn two_adic_subgroup_zerofier<F: TwoAdicField>(log_n: usize, x: F) -> F {
@@ -54,10 +55,13 @@ pub fn scale_vec<F: Field>(s: F, vec: Vec<F>) -> Vec<F> {
 }
 
 pub fn scale_slice_in_place<F: Field>(s: F, slice: &mut [F]) {
-    let (packed, sfx) = F::Packing::pack_slice_with_suffix_mut(slice);
+    let (prefix, packed, suffix) = F::Packing::pack_slice_prefix_suffix_mut(slice);
     let packed_s: F::Packing = s.into();
     packed.par_iter_mut().for_each(|x| *x *= packed_s);
-    sfx.iter_mut().for_each(|x| *x *= s);
+    prefix
+        .iter_mut()
+        .chain(suffix.iter_mut())
+        .for_each(|x| *x *= s);
 }
 
 /// `x += y * s`, where `s` is a scalar.
@@ -140,9 +144,23 @@ pub const fn field_to_array<FA: FieldAlgebra, const D: usize>(x: FA) -> [FA; D]
     unsafe { HackyWorkAround::transpose(arr).assume_init() }
 }
 
-/// Naive polynomial multiplication.
+/// Above this many coefficients (in the shorter operand), polynomial multiplication switches from
+/// the grade school algorithm to Karatsuba.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Polynomial multiplication.
+///
+/// Despite the name, inputs whose shorter operand has more than 32 coefficients are multiplied
+/// using Karatsuba's algorithm. The product of an empty polynomial with anything is empty.
 pub fn naive_poly_mul<FA: FieldAlgebra>(a: &[FA], b: &[FA]) -> Vec<FA> {
-    // Grade school algorithm
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    karatsuba_poly_mul(a, b)
+}
+
+/// Grade school polynomial multiplication. Both inputs must be non-empty.
+fn schoolbook_poly_mul<FA: FieldAlgebra>(a: &[FA], b: &[FA]) -> Vec<FA> {
     let mut product = vec![FA::ZERO; a.len() + b.len() - 1];
     for (i, c1) in a.iter().enumerate() {
         for (j, c2) in b.iter().enumerate() {
@@ -152,6 +170,58 @@ pub fn naive_poly_mul<FA: FieldAlgebra>(a: &[FA], b: &[FA]) -> Vec<FA> {
     product
 }
 
+/// Karatsuba polynomial multiplication, falling back to `schoolbook_poly_mul` for small inputs.
+/// Both inputs must be non-empty.
+fn karatsuba_poly_mul<FA: FieldAlgebra>(a: &[FA], b: &[FA]) -> Vec<FA> {
+    if a.len().min(b.len()) <= KARATSUBA_THRESHOLD {
+        return schoolbook_poly_mul(a, b);
+    }
+
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let m = long.len() / 2;
+    let mut product = vec![FA::ZERO; a.len() + b.len() - 1];
+    let (long_lo, long_hi) = long.split_at(m);
+
+    if short.len() <= m {
+        // Too lopsided to split both operands, so just split the longer one.
+        add_poly_assign(&mut product, &karatsuba_poly_mul(long_lo, short));
+        add_poly_assign(&mut product[m..], &karatsuba_poly_mul(long_hi, short));
+        return product;
+    }
+
+    let (short_lo, short_hi) = short.split_at(m);
+    let lo = karatsuba_poly_mul(long_lo, short_lo);
+    let hi = karatsuba_poly_mul(long_hi, short_hi);
+
+    let mut mid = karatsuba_poly_mul(&add_polys(long_lo, long_hi), &add_polys(short_lo, short_hi));
+    for (c, x) in mid.iter_mut().zip(&lo) {
+        *c -= x.clone();
+    }
+    for (c, x) in mid.iter_mut().zip(&hi) {
+        *c -= x.clone();
+    }
+
+    add_poly_assign(&mut product, &lo);
+    add_poly_assign(&mut product[m..], &mid);
+    add_poly_assign(&mut product[2 * m..], &hi);
+    product
+}
+
+/// Add two polynomials of possibly different lengths.
+fn add_polys<FA: FieldAlgebra>(a: &[FA], b: &[FA]) -> Vec<FA> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = long.to_vec();
+    add_poly_assign(&mut sum, short);
+    sum
+}
+
+/// Add `b` into the low coefficients of `a`. Requires `a.len() >= b.len()`.
+fn add_poly_assign<FA: FieldAlgebra>(a: &mut [FA], b: &[FA]) {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x += y.clone();
+    }
+}
+
 /// Expand a product of binomials (x - roots[0])(x - roots[1]).. into polynomial coefficients.
 pub fn binomial_expand<FA: FieldAlgebra>(roots: &[FA]) -> Vec<FA> {
     let mut coeffs = vec![FA::ZERO; roots.len() + 1];
@@ -174,6 +244,29 @@ pub fn eval_poly<FA: FieldAlgebra>(poly: &[FA], x: FA) -> FA {
     acc
 }
 
+/// Evaluate the polynomial with coefficients `poly` (lowest degree first) at `x`, as `eval_poly`
+/// does, but faster for a field with a packing.
+///
+/// The coefficients are processed `F::Packing::WIDTH` at a time: lane `j` of a packed accumulator
+/// runs Horner's method in `x^WIDTH` over the coefficients of index `j mod WIDTH`, and the lanes
+/// are combined with one final scalar Horner pass in `x`. The empty polynomial evaluates to zero.
+pub fn eval_poly_packed<F: Field>(poly: &[F], x: F) -> F {
+    let width = F::Packing::WIDTH;
+    let (packed, suffix) = F::Packing::pack_slice_with_suffix(poly);
+
+    // The leftover high coefficients form the top chunk, padded with zeros.
+    let mut acc = F::Packing::from_fn(|j| suffix.get(j).copied().unwrap_or(F::ZERO));
+    let x_width = x.exp_u64(width as u64);
+    for &chunk in packed.iter().rev() {
+        acc = acc * x_width + chunk;
+    }
+
+    acc.as_slice()
+        .iter()
+        .rev()
+        .fold(F::ZERO, |sum, &lane| sum * x + lane)
+}
+
 /// Given an element x from a 32 bit field F_P compute x/2.
 #[inline]
 pub fn halve_u32<const P: u32>(input: u32) -> u32 {
@@ -244,3 +337,62 @@ where
 {
     li.zip(ri).map(|(l, r)| l * r).sum()
 }
+
+/// Computes the dot product of two slices of field elements.
+///
+/// Unlike `dot_product`, this packs the input into `F::Packing` values, accumulating packed
+/// products over the aligned middle of `a` and the same range of `b`, and only reducing across
+/// lanes once at the end. The unaligned head and tail are handled as scalars.
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+pub fn packed_dot_product<F: Field>(a: &[F], b: &[F]) -> F {
+    assert_eq!(
+        a.len(),
+        b.len(),
+        "dot product of slices of different lengths"
+    );
+    let (a_prefix, a_packed, a_suffix) = F::Packing::pack_slice_prefix_suffix(a);
+    let (b_prefix, b_rest) = b.split_at(a_prefix.len());
+    let (b_packed, b_suffix) = F::Packing::pack_slice_with_suffix(b_rest);
+
+    let packed_sum: F::Packing = a_packed.iter().zip(b_packed).map(|(&x, &y)| x * y).sum();
+    let scalar_sum: F = a_prefix
+        .iter()
+        .zip(b_prefix)
+        .chain(a_suffix.iter().zip(b_suffix))
+        .map(|(&x, &y)| x * y)
+        .sum();
+
+    packed_sum.as_slice().iter().copied().sum::<F>() + scalar_sum
+}
+
+/// Computes the sum of a slice of field elements, using packed additions over the aligned middle of
+/// the slice and scalar additions for the unaligned head and tail.
+pub fn field_sum<F: Field>(xs: &[F]) -> F {
+    let (prefix, packed, suffix) = F::Packing::pack_slice_prefix_suffix(xs);
+    let packed_sum: F::Packing = packed.iter().copied().sum();
+    prefix.iter().copied().sum::<F>()
+        + packed_sum.as_slice().iter().copied().sum::<F>()
+        + suffix.iter().copied().sum::<F>()
+}
+
+/// Sample a uniformly random element of `F` by rejection sampling.
+///
+/// Each attempt draws a random word, keeps only as many of its high bits as are needed to
+/// represent `F::ORDER_U64 - 1`, and retries if the result is not canonical. Reducing modulo the
+/// order instead would bias the output, and truncating first keeps the probability of a retry
+/// below one half.
+pub fn sample_uniform<F: PrimeField64, R: Rng + ?Sized>(rng: &mut R) -> F {
+    let bits = u64::BITS - (F::ORDER_U64 - 1).leading_zeros();
+    loop {
+        let candidate = if bits <= u32::BITS {
+            u64::from(rng.next_u32() >> (u32::BITS - bits))
+        } else {
+            rng.next_u64() >> (u64::BITS - bits)
+        };
+        if let Some(x) = F::from_canonical_checked(candidate) {
+            return x;
+        }
+    }
+}
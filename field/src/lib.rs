@@ -6,15 +6,25 @@ extern crate alloc;
 
 mod array;
 mod batch_inverse;
+#[cfg(feature = "bytemuck")]
+mod cast;
+pub mod codec;
+mod coset;
 mod exponentiation;
 pub mod extension;
 mod field;
 mod helpers;
 mod packed;
+mod same_prime;
+pub mod testing;
 
 pub use array::*;
 pub use batch_inverse::*;
+#[cfg(feature = "bytemuck")]
+pub use cast::*;
+pub use coset::*;
 pub use exponentiation::*;
 pub use field::*;
 pub use helpers::*;
 pub use packed::*;
+pub use same_prime::*;
@@ -50,6 +50,37 @@ pub unsafe trait PackedValue: 'static + Copy + Send + Sync {
         (Self::pack_slice(packed), suffix)
     }
 
+    /// Split a slice into a scalar prefix, a packed middle and a scalar suffix.
+    ///
+    /// Unlike `pack_slice`, this places no requirements on the alignment or length of `buf`. The
+    /// prefix is the shortest head after which the data is aligned for `Self`, and the suffix is
+    /// whatever is left over once the middle has been packed. Both have fewer than `WIDTH`
+    /// elements.
+    fn pack_slice_prefix_suffix(buf: &[Self::Value]) -> (&[Self::Value], &[Self], &[Self::Value]) {
+        let offset = buf.as_ptr().align_offset(align_of::<Self>());
+        if offset > buf.len() {
+            return (buf, &[], &[]);
+        }
+        let (prefix, rest) = buf.split_at(offset);
+        let (packed, suffix) = rest.split_at(rest.len() - rest.len() % Self::WIDTH);
+        let buf_ptr = packed.as_ptr().cast::<Self>();
+        let n = packed.len() / Self::WIDTH;
+        // Safe as `buf_ptr` is aligned for `Self` by the choice of `offset`.
+        let packed = unsafe { slice::from_raw_parts(buf_ptr, n) };
+        (prefix, packed, suffix)
+    }
+
+    /// Iterate over `buf` as an unaligned scalar head, a run of aligned packed values and a scalar
+    /// tail. See `pack_slice_prefix_suffix`.
+    fn aligned_chunks(buf: &[Self::Value]) -> AlignedChunks<'_, Self> {
+        let (prefix, packed, suffix) = Self::pack_slice_prefix_suffix(buf);
+        AlignedChunks {
+            prefix,
+            packed: packed.iter(),
+            suffix,
+        }
+    }
+
     fn pack_slice_mut(buf: &mut [Self::Value]) -> &mut [Self] {
         assert!(align_of::<Self>() <= align_of::<Self::Value>());
         assert!(
@@ -83,6 +114,24 @@ pub unsafe trait PackedValue: 'static + Copy + Send + Sync {
         (Self::pack_slice_mut(packed), suffix)
     }
 
+    /// The mutable analogue of `pack_slice_prefix_suffix`.
+    fn pack_slice_prefix_suffix_mut(
+        buf: &mut [Self::Value],
+    ) -> (&mut [Self::Value], &mut [Self], &mut [Self::Value]) {
+        let offset = buf.as_ptr().align_offset(align_of::<Self>());
+        if offset > buf.len() {
+            return (buf, &mut [], &mut []);
+        }
+        let (prefix, rest) = buf.split_at_mut(offset);
+        let packed_len = rest.len() - rest.len() % Self::WIDTH;
+        let (packed, suffix) = rest.split_at_mut(packed_len);
+        let buf_ptr = packed.as_mut_ptr().cast::<Self>();
+        let n = packed.len() / Self::WIDTH;
+        // Safe as `buf_ptr` is aligned for `Self` by the choice of `offset`.
+        let packed = unsafe { slice::from_raw_parts_mut(buf_ptr, n) };
+        (prefix, packed, suffix)
+    }
+
     fn pack_maybe_uninit_slice_with_suffix_mut(
         buf: &mut [MaybeUninit<Self::Value>],
     ) -> (&mut [MaybeUninit<Self>], &mut [MaybeUninit<Self::Value>]) {
@@ -98,6 +147,52 @@ pub unsafe trait PackedValue: 'static + Copy + Send + Sync {
     }
 }
 
+/// A chunk of a slice, as yielded by `PackedValue::aligned_chunks`.
+#[derive(Copy, Clone)]
+pub enum PackedChunk<'a, P: PackedValue> {
+    /// A run of fewer than `P::WIDTH` scalars from the head or tail of the slice.
+    Scalars(&'a [P::Value]),
+    /// A single aligned packed value.
+    Packed(&'a P),
+}
+
+/// An iterator over a slice in `PackedChunk`s, created by `PackedValue::aligned_chunks`.
+///
+/// Yields the unaligned prefix (if non-empty) as one chunk of scalars, then every packed value, and
+/// finally the suffix (if non-empty) as one chunk of scalars.
+#[derive(Clone)]
+pub struct AlignedChunks<'a, P: PackedValue> {
+    prefix: &'a [P::Value],
+    packed: slice::Iter<'a, P>,
+    suffix: &'a [P::Value],
+}
+
+impl<'a, P: PackedValue> Iterator for AlignedChunks<'a, P> {
+    type Item = PackedChunk<'a, P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.prefix.is_empty() {
+            return Some(PackedChunk::Scalars(core::mem::take(&mut self.prefix)));
+        }
+        if let Some(packed) = self.packed.next() {
+            return Some(PackedChunk::Packed(packed));
+        }
+        if !self.suffix.is_empty() {
+            return Some(PackedChunk::Scalars(core::mem::take(&mut self.suffix)));
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::from(!self.prefix.is_empty())
+            + self.packed.len()
+            + usize::from(!self.suffix.is_empty());
+        (len, Some(len))
+    }
+}
+
+impl<P: PackedValue> ExactSizeIterator for AlignedChunks<'_, P> {}
+
 unsafe impl<T: Packable, const WIDTH: usize> PackedValue for [T; WIDTH] {
     type Value = T;
     const WIDTH: usize = WIDTH;
@@ -183,6 +278,28 @@ pub unsafe trait PackedFieldPow2: PackedField {
     /// `WIDTH` is specified to be a power of 2, `block_len` must also be a power of 2. It cannot be
     /// 0 and it cannot exceed `WIDTH`.
     fn interleave(&self, other: Self, block_len: usize) -> (Self, Self);
+
+    /// Transpose a `WIDTH x WIDTH` block of scalars, stored as `WIDTH` packed rows, in place.
+    ///
+    /// After the call, lane `j` of `block[i]` holds what was lane `i` of `block[j]`. This takes
+    /// `log2(WIDTH)` rounds of `interleave`, starting with blocks of length `WIDTH / 2`: pairing
+    /// rows `i` and `i + block_len` and interleaving them transposes the `2 x 2` matrix of
+    /// `block_len x block_len` sub-blocks they span.
+    ///
+    /// # Panics
+    /// Panics if `block.len() != WIDTH`.
+    fn transpose(block: &mut [Self]) {
+        assert_eq!(block.len(), Self::WIDTH);
+        let mut block_len = Self::WIDTH / 2;
+        while block_len > 0 {
+            for i in (0..Self::WIDTH).filter(|i| i & block_len == 0) {
+                let (x, y) = block[i].interleave(block[i + block_len], block_len);
+                block[i] = x;
+                block[i + block_len] = y;
+            }
+            block_len /= 2;
+        }
+    }
 }
 
 unsafe impl<T: Packable> PackedValue for T {
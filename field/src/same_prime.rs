@@ -0,0 +1,41 @@
+use alloc::vec::Vec;
+
+use crate::PrimeField32;
+
+/// Conversion between two implementations of the same prime field, e.g. a MONTY form and a
+/// canonical form of BabyBear.
+///
+/// This is implemented for every pair of `PrimeField32`s, but a conversion only compiles if the
+/// two orders agree:
+///
+/// ```compile_fail
+/// use p3_baby_bear::BabyBear;
+/// use p3_field::{FieldAlgebra, SamePrimeField};
+/// use p3_koala_bear::KoalaBear;
+///
+/// let _: KoalaBear = BabyBear::ONE.convert();
+/// ```
+pub trait SamePrimeField<Other: PrimeField32>: PrimeField32 {
+    /// Fails to evaluate, and so fails to compile any conversion, if the orders differ.
+    const PRIMES_MATCH: () = assert!(
+        Self::ORDER_U32 == Other::ORDER_U32,
+        "SamePrimeField conversion between fields of different orders"
+    );
+
+    /// Convert a single element, preserving its canonical value.
+    fn convert(self) -> Other {
+        let () = Self::PRIMES_MATCH;
+        Other::from_canonical_u32(self.as_canonical_u32())
+    }
+
+    /// Convert a slice of elements, preserving their canonical values.
+    ///
+    /// This goes through `to_canonical_u32_vec` and `from_canonical_u32_slice`, so picks up any
+    /// vectorized (de)normalization either field provides.
+    fn convert_slice(xs: &[Self]) -> Vec<Other> {
+        let () = Self::PRIMES_MATCH;
+        Other::from_canonical_u32_slice(&Self::to_canonical_u32_vec(xs))
+    }
+}
+
+impl<F: PrimeField32, Other: PrimeField32> SamePrimeField<Other> for F {}
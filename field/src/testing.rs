@@ -0,0 +1,92 @@
+//! Consistency checks for implementations of the field traits.
+//!
+//! Every check panics on failure. They are meant to be called from the unit tests of field
+//! backends; `p3-field-testing` calls them from its `test_field!` and `test_two_adic_field!` macros,
+//! so any field tested with those macros gets them for free.
+
+use crate::{Field, FieldAlgebra, TwoAdicField};
+
+/// Check that the two-adic generators form a chain of square roots ending at one.
+///
+/// That is, `two_adic_generator(0)` is one, each `two_adic_generator(bits)` squares to
+/// `two_adic_generator(bits - 1)`, and `two_adic_generator(TWO_ADICITY)` has order exactly
+/// `2^TWO_ADICITY`. Backends whose generators are not compatible across sizes break any code which
+/// reuses roots of unity between transforms of different lengths.
+pub fn check_two_adic_consistency<F: TwoAdicField>() {
+    assert_eq!(
+        F::two_adic_generator(0),
+        F::ONE,
+        "two_adic_generator(0) must be one"
+    );
+
+    for bits in 1..=F::TWO_ADICITY {
+        assert_eq!(
+            F::two_adic_generator(bits).square(),
+            F::two_adic_generator(bits - 1),
+            "two_adic_generator({bits})^2 != two_adic_generator({})",
+            bits - 1
+        );
+    }
+
+    // An element whose 2^k-th power is one has order exactly 2^k iff its 2^(k-1)-th power is -1.
+    let g = F::two_adic_generator(F::TWO_ADICITY);
+    assert_eq!(g.exp_power_of_2(F::TWO_ADICITY), F::ONE);
+    if F::TWO_ADICITY > 0 {
+        assert_eq!(
+            g.exp_power_of_2(F::TWO_ADICITY - 1),
+            F::NEG_ONE,
+            "two_adic_generator(TWO_ADICITY) does not have order 2^TWO_ADICITY"
+        );
+    }
+}
+
+/// Check the field axioms, and the consistency of the derived operations, on every pair and
+/// consecutive triple of `samples`.
+pub fn check_field_axioms<F: Field>(samples: &[F]) {
+    assert_eq!(F::ZERO + F::ONE, F::ONE);
+    assert_eq!(F::ONE + F::NEG_ONE, F::ZERO);
+    assert_eq!(F::ONE + F::ONE, F::TWO);
+    assert!(F::ZERO.try_inverse().is_none());
+
+    for &x in samples {
+        assert_eq!(x + F::ZERO, x, "zero is not an additive identity");
+        assert_eq!(x * F::ONE, x, "one is not a multiplicative identity");
+        assert_eq!(x * F::ZERO, F::ZERO);
+        assert_eq!(x + (-x), F::ZERO, "negation is not an additive inverse");
+        assert_eq!(x.double(), x + x);
+        assert_eq!(x.square(), x * x);
+        assert_eq!(x.cube(), x * x * x);
+        if !x.is_zero() {
+            assert_eq!(
+                x * x.inverse(),
+                F::ONE,
+                "inverse is not a multiplicative inverse"
+            );
+        }
+
+        for &y in samples {
+            assert_eq!(x + y, y + x, "addition is not commutative");
+            assert_eq!(x * y, y * x, "multiplication is not commutative");
+            assert_eq!(x - y, x + (-y));
+            assert_eq!((x - y) + y, x);
+            if !y.is_zero() {
+                assert_eq!((x / y) * y, x);
+            }
+        }
+    }
+
+    for triple in samples.windows(3) {
+        let [x, y, z] = [triple[0], triple[1], triple[2]];
+        assert_eq!((x + y) + z, x + (y + z), "addition is not associative");
+        assert_eq!(
+            (x * y) * z,
+            x * (y * z),
+            "multiplication is not associative"
+        );
+        assert_eq!(
+            x * (y + z),
+            x * y + x * z,
+            "multiplication does not distribute"
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! The FRI prover on a codeword of `2^20` evaluations, on thread pools of 1, 4 and 8 threads. The
+//! folds of each round and the hashing of their commitments are split across the pool.
+
+use std::marker::PhantomData;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{prover, FriConfig, TwoAdicFriGenericConfig};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_util::reverse_slice_index_bits;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rayon::ThreadPoolBuilder;
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+
+const LOG_HEIGHT: usize = 20;
+
+fn bench_prove_threads(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let mmcs = ChallengeMmcs::new(ValMmcs::new(
+        MyHash::new(perm.clone()),
+        MyCompress::new(perm.clone()),
+    ));
+    let fc = FriConfig {
+        log_blowup: 1,
+        log_final_poly_len: 0,
+        log_folding_factor: 1,
+        folding_schedule: vec![],
+        num_queries: 100,
+        proof_of_work_bits: 16,
+        mmcs,
+    };
+    let g = TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData);
+
+    let evals = RowMajorMatrix::<Val>::rand_nonzero(&mut rng, 1 << (LOG_HEIGHT - 1), 1);
+    let mut lde = Radix2Dit::default()
+        .coset_lde_batch(evals, 1, Val::GENERATOR)
+        .values
+        .into_iter()
+        .map(Challenge::from)
+        .collect::<Vec<_>>();
+    reverse_slice_index_bits(&mut lde);
+    let challenger = Challenger::new(perm);
+
+    let mut group = c.benchmark_group(format!("FRI prover, 2^{LOG_HEIGHT} evaluations"));
+    group.sample_size(10);
+    for num_threads in [1, 4, 8] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("threads", num_threads),
+            &num_threads,
+            |b, _| {
+                b.iter(|| {
+                    pool.install(|| {
+                        prover::prove(
+                            &g,
+                            &fc,
+                            vec![lde.clone()],
+                            &mut challenger.clone(),
+                            |index| vec![(LOG_HEIGHT, lde[index])],
+                        )
+                    })
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_prove_threads);
+criterion_main!(benches);
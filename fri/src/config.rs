@@ -6,9 +6,26 @@ use p3_matrix::Matrix;
 
 #[derive(Debug)]
 pub struct FriConfig<M> {
+    /// Must be at least 1, as with no blowup every codeword is low degree.
     pub log_blowup: usize,
+    /// The commit phase stops folding once the codeword has `2^(log_blowup + log_final_poly_len)`
+    /// evaluations, and sends the `2^log_final_poly_len` coefficients of the polynomial they make
+    /// up, which every query is checked against. A larger final polynomial takes fewer rounds,
+    /// and so fewer commitments and openings; soundness is unchanged, as the rate of the final
+    /// codeword is still `2^-log_blowup`. Unless it is 0, every input must be longer than the final
+    /// codeword.
     // TODO: This parameter and FRI early stopping are not yet implemented in `CirclePcs`.
     pub log_final_poly_len: usize,
+    /// Each commit phase round folds the codeword by up to `2^log_folding_factor`, e.g. 1 to halve
+    /// it each round. A round folds by less when a larger factor would skip past the final
+    /// polynomial or the height of another input.
+    // TODO: Only a factor of 2 is implemented in `CirclePcs`.
+    pub log_folding_factor: usize,
+    /// The log of the arity of each commit phase round in turn, e.g. `[3, 3, 1]` to fold by 8
+    /// twice and then by 2, in place of `log_folding_factor`. It must fold the largest input down
+    /// to the final polynomial without folding past the height of any other input. Empty to fold
+    /// by `log_folding_factor` each round.
+    pub folding_schedule: Vec<usize>,
     pub num_queries: usize,
     pub proof_of_work_bits: usize,
     pub mmcs: M,
@@ -23,6 +40,70 @@ impl<M> FriConfig<M> {
         1 << self.log_final_poly_len
     }
 
+    /// The log of the largest arity a commit phase round can fold by.
+    pub fn max_log_arity(&self) -> usize {
+        self.folding_schedule
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(self.log_folding_factor)
+    }
+
+    /// Whether commit phase rounds folding by `2^log_arities[i]` in turn are allowed: if there is
+    /// a folding schedule, whether they follow it, and otherwise whether each folds by at least 2
+    /// and at most `2^log_folding_factor`.
+    pub fn allows_log_arities(&self, log_arities: &[usize]) -> bool {
+        if self.folding_schedule.is_empty() {
+            log_arities
+                .iter()
+                .all(|&log_arity| 0 < log_arity && log_arity <= self.log_folding_factor)
+        } else {
+            log_arities == self.folding_schedule
+        }
+    }
+
+    /// The log of the arity of each commit phase round, for inputs with the given log heights,
+    /// largest first. This is the schedule the prover folds by, and so the only one the verifier
+    /// accepts: each round folds by the next arity of the folding schedule, or else by
+    /// `2^log_folding_factor`, or by less if that would skip past the final height or the height
+    /// of the next input.
+    ///
+    /// Returns `None` if the folding schedule does not fold the largest input down to the final
+    /// height without folding past another input, or a round would not fold at all.
+    pub fn log_arities(&self, log_heights: &[usize]) -> Option<Vec<usize>> {
+        let log_final_height = self.log_blowup + self.log_final_poly_len;
+        let Some((&log_max_height, rest)) = log_heights.split_first() else {
+            return Some(Vec::new());
+        };
+        let mut next_log_heights = rest.iter().copied().peekable();
+        let mut log_height = log_max_height;
+        let mut log_arities = Vec::new();
+        while log_height > log_final_height {
+            // Inputs of this height have been added in, either at the start or after the round
+            // which folded down to it.
+            while next_log_heights.next_if(|&h| h >= log_height).is_some() {}
+            let log_next_height = next_log_heights
+                .peek()
+                .map_or(log_final_height, |&h| h.max(log_final_height));
+            let log_arity = if self.folding_schedule.is_empty() {
+                self.log_folding_factor.min(log_height - log_next_height)
+            } else {
+                let &log_arity = self.folding_schedule.get(log_arities.len())?;
+                if log_height - log_next_height < log_arity {
+                    return None;
+                }
+                log_arity
+            };
+            if log_arity == 0 {
+                return None;
+            }
+            log_arities.push(log_arity);
+            log_height -= log_arity;
+        }
+        (self.folding_schedule.is_empty() || log_arities.len() == self.folding_schedule.len())
+            .then_some(log_arities)
+    }
+
     /// Returns the soundness bits of this FRI instance based on the
     /// [ethSTARK](https://eprint.iacr.org/2021/582) conjecture.
     ///
@@ -44,8 +125,8 @@ pub trait FriGenericConfig<F: Field> {
     fn extra_query_index_bits(&self) -> usize;
 
     /// Fold a row, returning a single column.
-    /// Right now the input row will always be 2 columns wide,
-    /// but we may support higher folding arity in the future.
+    /// The row holds the `2^k` evaluations which fold into the one at `index` of a codeword of
+    /// height `2^log_height`, where `2^k` is the folding arity of the round.
     fn fold_row(
         &self,
         index: usize,
@@ -64,6 +145,8 @@ pub fn create_test_fri_config<Mmcs>(mmcs: Mmcs) -> FriConfig<Mmcs> {
     FriConfig {
         log_blowup: 1,
         log_final_poly_len: 0,
+        log_folding_factor: 1,
+        folding_schedule: Vec::new(),
         num_queries: 2,
         proof_of_work_bits: 1,
         mmcs,
@@ -76,6 +159,8 @@ pub fn create_benchmark_fri_config<Mmcs>(mmcs: Mmcs) -> FriConfig<Mmcs> {
     FriConfig {
         log_blowup: 1,
         log_final_poly_len: 0,
+        log_folding_factor: 1,
+        folding_schedule: Vec::new(),
         num_queries: 100,
         proof_of_work_bits: 16,
         mmcs,
@@ -1,13 +1,17 @@
 use alloc::vec::Vec;
 
 use itertools::Itertools;
-use p3_field::TwoAdicField;
+use p3_field::{PackedValue, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
-use p3_util::{log2_strict_usize, reverse_slice_index_bits};
+use p3_util::{log2_strict_usize, reverse_bits_len, reverse_slice_index_bits};
 use tracing::instrument;
 
+/// The number of folded evaluations computed by each parallel task. Each task computes the powers
+/// of `g_inv` it needs from a table shared by all of them, at the cost of one exponentiation.
+const LOG_FOLD_CHUNK_LEN: usize = 12;
+
 /// Fold a polynomial
 /// ```ignore
 /// p(x) = p_even(x^2) + x p_odd(x^2)
@@ -19,6 +23,17 @@ use tracing::instrument;
 /// Expects input to be bit-reversed evaluations.
 #[instrument(skip_all, level = "debug")]
 pub fn fold_even_odd<F: TwoAdicField>(poly: Vec<F>, beta: F) -> Vec<F> {
+    fold_pairs(beta, RowMajorMatrix::new(poly, 2))
+}
+
+/// Fold each row of `m`, which must be 2 columns wide and hold the bit-reversed evaluations of a
+/// polynomial two at a time, as in `fold_even_odd`.
+///
+/// The folded codeword is computed in parallel chunks, and the result does not depend on the number
+/// of threads. Within a chunk, a field with a packing folds `F::Packing::WIDTH` evaluations at a
+/// time. An extension field, such as the challenge field FRI folds over, is its own packing, so it
+/// is folded one evaluation at a time instead, with a single extension multiplication each.
+pub(crate) fn fold_pairs<F: TwoAdicField, M: Matrix<F>>(beta: F, m: M) -> Vec<F> {
     // We use the fact that
     //     p_e(x^2) = (p(x) + p(-x)) / 2
     //     p_o(x^2) = (p(x) - p(-x)) / (2 x)
@@ -27,29 +42,60 @@ pub fn fold_even_odd<F: TwoAdicField>(poly: Vec<F>, beta: F) -> Vec<F> {
     //     p_o(g^(2i)) = (p(g^i) - p(g^(n/2 + i))) / (2 g^i)
     // so
     //     result(g^(2i)) = p_e(g^(2i)) + beta p_o(g^(2i))
-    //                    = (1/2 + beta/2 g_inv^i) p(g^i)
-    //                    + (1/2 - beta/2 g_inv^i) p(g^(n/2 + i))
-    let m = RowMajorMatrix::new(poly, 2);
-    let g_inv = F::two_adic_generator(log2_strict_usize(m.height()) + 1).inverse();
-    let one_half = F::TWO.inverse();
+    //                    = (p(g^i) + p(g^(n/2 + i))) / 2
+    //                    + beta/2 g_inv^i (p(g^i) - p(g^(n/2 + i)))
+    let log_height = log2_strict_usize(m.height());
+    let g_inv = F::two_adic_generator(log_height + 1).inverse();
+    let one_half = F::ONE.halve();
     let half_beta = beta * one_half;
 
-    // TODO: vectorize this (after we have packed extension fields)
-
-    // beta/2 times successive powers of g_inv
-    let mut powers = g_inv
-        .shifted_powers(half_beta)
-        .take(m.height())
+    // The `i`'th row needs `beta/2 g_inv^rev(i)`, where `rev` reverses `log_height` bits. In the
+    // `c`'th chunk of `2^k` rows, `rev(c 2^k + j) = rev(j) 2^(log_height - k) + rev(c)`, so every
+    // chunk needs the same bit-reversed powers of `g_inv^(2^(log_height - k))`, scaled by
+    // `beta/2 g_inv^rev(c)`.
+    let log_chunk_len = log_height.min(LOG_FOLD_CHUNK_LEN);
+    let log_num_chunks = log_height - log_chunk_len;
+    let mut chunk_powers = g_inv
+        .exp_power_of_2(log_num_chunks)
+        .powers()
+        .take(1 << log_chunk_len)
         .collect_vec();
-    reverse_slice_index_bits(&mut powers);
-
-    m.par_rows()
-        .zip(powers)
-        .map(|(mut row, power)| {
-            let (r0, r1) = row.next_tuple().unwrap();
-            (one_half + power) * r0 + (one_half - power) * r1
-        })
-        .collect()
+    reverse_slice_index_bits(&mut chunk_powers);
+    // Rows are only packed when that widens the arithmetic; the rest are folded one at a time.
+    let num_packed_rows = if F::Packing::WIDTH > 1 {
+        chunk_powers.len() / F::Packing::WIDTH * F::Packing::WIDTH
+    } else {
+        0
+    };
+    let (packed_chunk_powers, chunk_powers_suffix) = chunk_powers.split_at(num_packed_rows);
+    let packed_chunk_powers = F::Packing::pack_slice(packed_chunk_powers);
+    let packed_one_half = F::Packing::from(one_half);
+
+    let mut folded = F::zero_vec(m.height());
+    folded
+        .par_chunks_mut(1 << log_chunk_len)
+        .enumerate()
+        .for_each(|(c, chunk)| {
+            let scale = half_beta * g_inv.exp_u64(reverse_bits_len(c, log_num_chunks) as u64);
+            let packed_scale = F::Packing::from(scale);
+            let first_row = c << log_chunk_len;
+            let (packed, suffix) = chunk.split_at_mut(num_packed_rows);
+            let packed = F::Packing::pack_slice_mut(packed);
+            for (p, (out, &powers)) in packed.iter_mut().zip(packed_chunk_powers).enumerate() {
+                let r = first_row + p * F::Packing::WIDTH;
+                let lo = F::Packing::from_fn(|l| m.get(r + l, 0));
+                let hi = F::Packing::from_fn(|l| m.get(r + l, 1));
+                let power = powers * packed_scale;
+                *out = (lo + hi) * packed_one_half + (lo - hi) * power;
+            }
+            let first_suffix_row = first_row + num_packed_rows;
+            for (i, (out, &power)) in suffix.iter_mut().zip(chunk_powers_suffix).enumerate() {
+                let (lo, hi) = m.row(first_suffix_row + i).next_tuple().unwrap();
+                // `halve` is cheaper than a multiplication by 1/2 in an extension field.
+                *out = (lo + hi).halve() + (lo - hi) * (power * scale);
+            }
+        });
+    folded
 }
 
 #[cfg(test)]
@@ -57,6 +103,8 @@ mod tests {
     use itertools::izip;
     use p3_baby_bear::BabyBear;
     use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+    use p3_field::extension::BinomialExtensionField;
+    use rand::distributions::{Distribution, Standard};
     use rand::{thread_rng, Rng};
 
     use super::*;
@@ -93,4 +141,75 @@ mod tests {
 
         assert_eq!(expected, folded);
     }
+
+    /// Fold one row at a time, with the powers of `g_inv` computed in order.
+    fn sequential_fold<F: TwoAdicField>(poly: &[F], beta: F) -> Vec<F> {
+        let height = poly.len() / 2;
+        let g_inv = F::two_adic_generator(log2_strict_usize(height) + 1).inverse();
+        let one_half = F::ONE.halve();
+        let mut powers = g_inv
+            .shifted_powers(beta * one_half)
+            .take(height)
+            .collect_vec();
+        reverse_slice_index_bits(&mut powers);
+        poly.chunks_exact(2)
+            .zip(powers)
+            .map(|(pair, power)| (one_half + power) * pair[0] + (one_half - power) * pair[1])
+            .collect()
+    }
+
+    fn check_fold_matches_sequential_fold<F: TwoAdicField>()
+    where
+        Standard: Distribution<F>,
+    {
+        let mut rng = thread_rng();
+        // Codewords smaller than a packed value, than a chunk, and of several chunks.
+        for log_n in [
+            1,
+            2,
+            3,
+            5,
+            LOG_FOLD_CHUNK_LEN + 1,
+            LOG_FOLD_CHUNK_LEN + 3,
+            20,
+        ] {
+            let poly: Vec<F> = (0..1 << log_n).map(|_| rng.gen()).collect();
+            let beta: F = rng.gen();
+            assert_eq!(
+                fold_even_odd(poly.clone(), beta),
+                sequential_fold(&poly, beta),
+                "codeword of 2^{log_n} evaluations"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fold_even_odd_matches_sequential_fold() {
+        // The base field is packed, the extension is not.
+        check_fold_matches_sequential_fold::<BabyBear>();
+        check_fold_matches_sequential_fold::<BinomialExtensionField<BabyBear, 4>>();
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn fold_does_not_depend_on_thread_count() {
+        type EF = BinomialExtensionField<BabyBear, 4>;
+
+        let mut rng = thread_rng();
+        let base_poly: Vec<BabyBear> = (0..1 << 20).map(|_| rng.gen()).collect();
+        let ext_poly: Vec<EF> = (0..1 << 20).map(|_| rng.gen()).collect();
+        let (base_beta, ext_beta): (BabyBear, EF) = (rng.gen(), rng.gen());
+        let expected_base = sequential_fold(&base_poly, base_beta);
+        let expected_ext = sequential_fold(&ext_poly, ext_beta);
+        for num_threads in [1, 4] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            let folded_base = pool.install(|| fold_even_odd(base_poly.clone(), base_beta));
+            assert_eq!(folded_base, expected_base, "{num_threads} threads");
+            let folded_ext = pool.install(|| fold_even_odd(ext_poly.clone(), ext_beta));
+            assert_eq!(folded_ext, expected_ext, "{num_threads} threads");
+        }
+    }
 }
@@ -14,10 +14,21 @@ use rand::Rng;
 use tracing::instrument;
 
 use crate::verifier::FriError;
-use crate::{BatchOpening, FriConfig, FriProof, TwoAdicFriPcs};
+use crate::{BatchOpening, FriConfig, FriProof, InputOpeningError, TwoAdicFriPcs};
 
 /// A hiding FRI PCS. Both MMCSs must also be hiding; this is not enforced at compile time so it's
 /// the user's responsibility to configure.
+///
+/// Each committed matrix is extended with `num_random_codewords` columns of random values on its
+/// domain, i.e. the evaluations of random polynomials of degree below the domain size, and so
+/// below the rate bound of FRI. FRI is run on a random linear combination of every column, so
+/// each codeword it commits to and opens is blinded by them. Their evaluations at the opening
+/// points are sent in the proof, and the verifier adds them back as the extra columns of each
+/// matrix before checking the openings just as `TwoAdicFriPcs` would.
+///
+/// This does not blind the committed polynomials themselves: the claimed evaluations, and the
+/// rows opened at each query, are still those of the caller's matrices. Hiding those is up to
+/// the caller, e.g. by randomizing a trace before committing to it.
 #[derive(Debug)]
 pub struct HidingFriPcs<Val, Dft, InputMmcs, FriMmcs, R> {
     inner: TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>,
@@ -64,7 +75,7 @@ where
         OpenedValues<Challenge>,
         FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>,
     );
-    type Error = FriError<FriMmcs::Error, InputMmcs::Error>;
+    type Error = FriError<FriMmcs::Error, InputOpeningError<InputMmcs::Error>>;
 
     fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain {
         <TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> as Pcs<Challenge, Challenger>>::natural_domain_for_degree(
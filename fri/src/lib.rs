@@ -9,6 +9,7 @@ mod fold_even_odd;
 mod hiding_pcs;
 mod proof;
 pub mod prover;
+mod serialization;
 mod two_adic_pcs;
 pub mod verifier;
 
@@ -16,4 +17,5 @@ pub use config::*;
 pub use fold_even_odd::*;
 pub use hiding_pcs::*;
 pub use proof::*;
+pub use serialization::*;
 pub use two_adic_pcs::*;
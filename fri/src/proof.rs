@@ -1,8 +1,10 @@
+use alloc::vec;
 use alloc::vec::Vec;
 
 use p3_commit::Mmcs;
 use p3_field::Field;
-use serde::{Deserialize, Serialize};
+use serde::ser::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound(
@@ -11,6 +13,8 @@ use serde::{Deserialize, Serialize};
 ))]
 pub struct FriProof<F: Field, M: Mmcs<F>, Witness, InputProof> {
     pub commit_phase_commits: Vec<M::Commitment>,
+    /// One for each distinct query index, in the order they were first sampled. The verifier also
+    /// accepts one for each sampled index, repeats included, as older provers made them.
     pub query_proofs: Vec<QueryProof<F, M, InputProof>>,
     pub final_poly: Vec<F>,
     pub pow_witness: Witness,
@@ -28,13 +32,52 @@ pub struct QueryProof<F: Field, M: Mmcs<F>, InputProof> {
     pub commit_phase_openings: Vec<CommitPhaseProofStep<F, M>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(bound = "")]
+/// The opening of a commit phase codeword at a query.
+///
+/// Its serde encoding is the one from before folding arities were configurable, when every round
+/// had a single sibling, so only openings of rounds of arity 2 can be serialized this way; proofs
+/// with larger arities are encoded with `FriProof::to_bytes`, which records the number of siblings.
+#[derive(Debug, Clone)]
 pub struct CommitPhaseProofStep<F: Field, M: Mmcs<F>> {
-    /// The opening of the commit phase codeword at the sibling location.
-    // This may change to Vec<FC::Challenge> if the library is generalized to support other FRI
-    // folding arities besides 2, meaning that there can be multiple siblings.
-    pub sibling_value: F,
+    /// The openings of the commit phase codeword at the other locations which fold together with
+    /// the queried one, in order. A round of arity `2^k` has `2^k - 1` of them.
+    pub sibling_values: Vec<F>,
 
     pub opening_proof: M::Proof,
 }
+
+/// The serde encoding of a `CommitPhaseProofStep`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "CommitPhaseProofStep")]
+struct BinaryStep<F, Proof> {
+    sibling_value: F,
+    opening_proof: Proof,
+}
+
+impl<F: Field, M: Mmcs<F>> Serialize for CommitPhaseProofStep<F, M> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let [sibling_value] = self.sibling_values.as_slice() else {
+            return Err(S::Error::custom(
+                "only openings of rounds of arity 2 have a serde encoding; use FriProof::to_bytes",
+            ));
+        };
+        BinaryStep {
+            sibling_value,
+            opening_proof: &self.opening_proof,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, F: Field, M: Mmcs<F>> Deserialize<'de> for CommitPhaseProofStep<F, M> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let BinaryStep {
+            sibling_value,
+            opening_proof,
+        } = BinaryStep::<F, M::Proof>::deserialize(deserializer)?;
+        Ok(Self {
+            sibling_values: vec![sibling_value],
+            opening_proof,
+        })
+    }
+}
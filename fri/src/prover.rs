@@ -8,6 +8,7 @@ use p3_commit::Mmcs;
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
 use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
 use p3_util::{log2_strict_usize, reverse_slice_index_bits};
 use tracing::{debug_span, info_span, instrument};
 
@@ -29,6 +30,24 @@ where
     G: FriGenericConfig<Challenge>,
 {
     assert!(!inputs.is_empty());
+    if config.folding_schedule.is_empty() {
+        assert!(
+            config.log_folding_factor > 0,
+            "FRI must fold by at least 2 each round"
+        );
+    } else {
+        assert!(
+            config
+                .folding_schedule
+                .iter()
+                .all(|&log_arity| log_arity > 0),
+            "FRI must fold by at least 2 each round"
+        );
+    }
+    assert!(
+        config.log_blowup > 0,
+        "FRI needs a blowup of at least 2, or any final codeword is low degree"
+    );
     assert!(
         inputs
             .iter()
@@ -39,17 +58,36 @@ where
 
     let log_max_height = log2_strict_usize(inputs[0].len());
     let log_min_height = log2_strict_usize(inputs.last().unwrap().len());
+    if !config.folding_schedule.is_empty() {
+        assert_eq!(
+            config.folding_schedule.iter().sum::<usize>(),
+            log_max_height - config.log_blowup - config.log_final_poly_len,
+            "the folding schedule must fold the largest input down to the final polynomial"
+        );
+    }
     if config.log_final_poly_len > 0 {
-        assert!(log_min_height > config.log_final_poly_len + config.log_blowup);
+        assert!(
+            log_min_height > config.log_final_poly_len + config.log_blowup,
+            "every input must be longer than the final codeword"
+        );
     }
+    let log_heights = inputs
+        .iter()
+        .map(|input| log2_strict_usize(input.len()))
+        .collect_vec();
+    let log_arities = config
+        .log_arities(&log_heights)
+        .expect("the folding schedule folds past the height of an input");
 
-    let commit_phase_result = commit_phase(g, config, inputs, challenger);
+    let commit_phase_result = commit_phase(g, config, &log_arities, inputs, challenger);
 
     let pow_witness = challenger.grind(config.proof_of_work_bits);
 
     let query_proofs = info_span!("query phase").in_scope(|| {
+        // A repeated index would be opened to the same values, so each index is opened once.
         iter::repeat_with(|| challenger.sample_bits(log_max_height + g.extra_query_index_bits()))
             .take(config.num_queries)
+            .unique()
             .map(|index| QueryProof {
                 input_proof: open_input(index),
                 commit_phase_openings: answer_query(
@@ -79,6 +117,7 @@ struct CommitPhaseResult<F: Field, M: Mmcs<F>> {
 fn commit_phase<G, Val, Challenge, M, Challenger>(
     g: &G,
     config: &FriConfig<M>,
+    log_arities: &[usize],
     inputs: Vec<Vec<Challenge>>,
     challenger: &mut Challenger,
 ) -> CommitPhaseResult<Challenge, M>
@@ -94,8 +133,10 @@ where
     let mut commits = vec![];
     let mut data = vec![];
 
-    while folded.len() > config.blowup() * config.final_poly_len() {
-        let leaves = RowMajorMatrix::new(folded, 2);
+    // The arities never skip past the final height, or the height at which the next input is
+    // added in; see `FriConfig::log_arities`.
+    for &log_arity in log_arities {
+        let leaves = RowMajorMatrix::new(folded, 1 << log_arity);
         let (commit, prover_data) = config.mmcs.commit_matrix(leaves);
         challenger.observe(commit.clone());
 
@@ -122,17 +163,20 @@ where
     reverse_slice_index_bits(&mut folded);
     // TODO: For better performance, we could run the IDFT on only the first half
     //       (or less, depending on `log_blowup`) of `final_poly`.
-    let final_poly = debug_span!("idft final poly").in_scope(|| Radix2Dit::default().idft(folded));
+    let mut final_poly =
+        debug_span!("idft final poly").in_scope(|| Radix2Dit::default().idft(folded));
 
     // The evaluation domain is "blown-up" relative to the polynomial degree of `final_poly`,
-    // so all coefficients after the first final_poly_len should be zero.
+    // so all coefficients after the first final_poly_len should be zero. Only the others are
+    // sent; the verifier checks the final codeword against the polynomial they make up.
     debug_assert!(
         final_poly
             .iter()
-            .skip(1 << config.log_final_poly_len)
+            .skip(config.final_poly_len())
             .all(|x| x.is_zero()),
         "All coefficients beyond final_poly_len must be zero"
     );
+    final_poly.truncate(config.final_poly_len());
 
     // Observe all coefficients of the final polynomial.
     for &x in &final_poly {
@@ -155,22 +199,23 @@ where
     F: Field,
     M: Mmcs<F>,
 {
+    let mut index_i = index;
     commit_phase_commits
         .iter()
-        .enumerate()
-        .map(|(i, commit)| {
-            let index_i = index >> i;
-            let index_i_sibling = index_i ^ 1;
-            let index_pair = index_i >> 1;
+        .map(|commit| {
+            let arity = config.mmcs.get_matrices(commit)[0].width();
+            let index_in_row = index_i % arity;
+            let index_row = index_i / arity;
 
-            let (mut opened_rows, opening_proof) = config.mmcs.open_batch(index_pair, commit);
+            let (mut opened_rows, opening_proof) = config.mmcs.open_batch(index_row, commit);
             assert_eq!(opened_rows.len(), 1);
-            let opened_row = opened_rows.pop().unwrap();
-            assert_eq!(opened_row.len(), 2, "Committed data should be in pairs");
-            let sibling_value = opened_row[index_i_sibling % 2];
+            let mut sibling_values = opened_rows.pop().unwrap();
+            assert_eq!(sibling_values.len(), arity);
+            sibling_values.remove(index_in_row);
+            index_i = index_row;
 
             CommitPhaseProofStep {
-                sibling_value,
+                sibling_values,
                 opening_proof,
             }
         })
@@ -0,0 +1,279 @@
+//! A compact, versioned byte encoding of FRI proofs.
+//!
+//! An encoded proof starts with a header holding the version of the format, an identifier of the
+//! field, the digest width of the commit phase MMCS and the log of the arity of each commit phase
+//! round. A proof from another version or made over another field or MMCS is refused from its
+//! header, before the rest is decoded. The proof itself follows, encoded with postcard, and is
+//! checked to have the shape the `FriConfig` asks for before it is returned, so that the verifier
+//! only ever sees well-formed proofs.
+//!
+//! The arities give the dimensions of each round's codeword, and so the size of its commitment,
+//! which the commitment's encoding need not record: a Merkle cap is just its digests, whose number
+//! depends on the cap height of the MMCS and the height of the codeword. The commitments are
+//! decoded with `Mmcs::deserialize_commitment`.
+//!
+//! Unlike the serde encoding of `FriProof`, which is kept from before folding arities were
+//! configurable and so only covers rounds of arity 2 and Merkle caps of height 0, this encodes
+//! proofs of any arity and cap height.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+use p3_commit::Mmcs;
+use p3_field::Field;
+use p3_matrix::Dimensions;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{CommitPhaseProofStep, FriConfig, FriProof, QueryProof};
+
+/// The version of the proof format, bumped whenever it changes.
+pub const FRI_PROOF_FORMAT_VERSION: u32 = 2;
+
+/// The reasons `FriProof::from_bytes` can refuse an encoded proof.
+#[derive(Debug)]
+pub enum ProofDecodingError {
+    /// The bytes could not be decoded, or had bytes left over after the proof.
+    Malformed,
+    /// The proof was encoded in another version of the format.
+    UnsupportedVersion(u32),
+    /// The proof was made over another field.
+    FieldMismatch,
+    /// The proof was made with an MMCS whose digests have another width.
+    DigestWidthMismatch { expected: usize, found: usize },
+    /// The proof does not have the number of queries, rounds, siblings or final polynomial
+    /// coefficients the config allows, or its rounds do not have the arities of its header.
+    InvalidShape,
+}
+
+impl Display for ProofDecodingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "the proof is malformed"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported proof format version {version}")
+            }
+            Self::FieldMismatch => write!(f, "the proof was made over another field"),
+            Self::DigestWidthMismatch { expected, found } => write!(
+                f,
+                "the proof has digests of {found} elements, but {expected} were expected"
+            ),
+            Self::InvalidShape => write!(f, "the proof does not have the shape of the config"),
+        }
+    }
+}
+
+/// Identifies the field in the header: the little-endian bytes of its order. This tells apart
+/// e.g. BabyBear from KoalaBear, and a field from its extensions.
+fn field_id<F: Field>() -> Vec<u8> {
+    F::order().to_bytes_le()
+}
+
+/// A `FriProof` as `to_bytes` encodes it, after the header, in which each commit phase opening
+/// records how many sibling values it has. `from_bytes` decodes the commitments ahead of the rest.
+#[derive(Serialize, Deserialize)]
+struct EncodedProof<Commitments, QueryProofs, FinalPoly, Witness> {
+    commit_phase_commits: Commitments,
+    query_proofs: QueryProofs,
+    final_poly: FinalPoly,
+    pow_witness: Witness,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncodedQueryProof<InputProof, Openings> {
+    input_proof: InputProof,
+    commit_phase_openings: Openings,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncodedOpening<SiblingValues, OpeningProof> {
+    sibling_values: SiblingValues,
+    opening_proof: OpeningProof,
+}
+
+/// Decode a value from the start of `bytes`, returning it with the bytes after it.
+fn take<T: DeserializeOwned>(bytes: &[u8]) -> Result<(T, &[u8]), ProofDecodingError> {
+    postcard::take_from_bytes(bytes).map_err(|_| ProofDecodingError::Malformed)
+}
+
+/// Decode the commit phase commitments from the start of `bytes`, one for each of the rounds of
+/// log arities `log_arities`, returning them with the bytes after them.
+///
+/// The round of log arity `k` commits to the codeword it folds into as a matrix of width `2^k`: the
+/// last folds into the final codeword, and each round before it into one `2^k` times taller, where
+/// `k` is the log arity of the round after it.
+fn take_commitments<'a, F: Field, M: Mmcs<F>>(
+    config: &FriConfig<M>,
+    log_arities: &[usize],
+    bytes: &'a [u8],
+) -> Result<(Vec<M::Commitment>, &'a [u8]), ProofDecodingError> {
+    let (num_commitments, mut rest) = take::<usize>(bytes)?;
+    let log_max_height = log_arities
+        .iter()
+        .try_fold(
+            config.log_blowup + config.log_final_poly_len,
+            |log_height, &log_arity| log_height.checked_add(log_arity),
+        )
+        .filter(|&log_max_height| log_max_height < usize::BITS as usize);
+    let Some(mut log_height) = log_max_height else {
+        return Err(ProofDecodingError::InvalidShape);
+    };
+    if num_commitments != log_arities.len() || !config.allows_log_arities(log_arities) {
+        return Err(ProofDecodingError::InvalidShape);
+    }
+    let mut commitments = Vec::with_capacity(num_commitments);
+    for &log_arity in log_arities {
+        log_height -= log_arity;
+        let dims = [Dimensions {
+            width: 1 << log_arity,
+            height: 1 << log_height,
+        }];
+        let mut deserializer = postcard::Deserializer::from_bytes(rest);
+        let commitment = config
+            .mmcs
+            .deserialize_commitment(&dims, &mut deserializer)
+            .map_err(|_| ProofDecodingError::Malformed)?;
+        rest = deserializer
+            .finalize()
+            .map_err(|_| ProofDecodingError::Malformed)?;
+        commitments.push(commitment);
+    }
+    Ok((commitments, rest))
+}
+
+impl<F: Field, M: Mmcs<F>, Witness, InputProof> FriProof<F, M, Witness, InputProof> {
+    /// Encode the proof, with a header for `from_bytes` to check.
+    ///
+    /// For example, the proof that a BabyBear polynomial of degree `2^11` is low degree, with a
+    /// blowup of 2, 10 queries, challenges in the quartic extension, an arity of 2 and Poseidon2
+    /// digests of 8 elements, takes about 29 kB.
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        Witness: Serialize,
+        InputProof: Serialize,
+    {
+        // Every query opens each round with as many siblings, as `from_bytes` checks.
+        let log_arities = self.query_proofs.first().map_or(vec![], |query_proof| {
+            query_proof
+                .commit_phase_openings
+                .iter()
+                .map(|step| (step.sibling_values.len() + 1).ilog2() as usize)
+                .collect()
+        });
+        let header = (
+            FRI_PROOF_FORMAT_VERSION,
+            field_id::<F>(),
+            M::DIGEST_WIDTH,
+            log_arities,
+        );
+        let query_proofs = self
+            .query_proofs
+            .iter()
+            .map(|query_proof| EncodedQueryProof {
+                input_proof: &query_proof.input_proof,
+                commit_phase_openings: query_proof
+                    .commit_phase_openings
+                    .iter()
+                    .map(|step| EncodedOpening {
+                        sibling_values: &step.sibling_values,
+                        opening_proof: &step.opening_proof,
+                    })
+                    .collect_vec(),
+            })
+            .collect_vec();
+        let proof = EncodedProof {
+            commit_phase_commits: &self.commit_phase_commits,
+            query_proofs,
+            final_poly: &self.final_poly,
+            pow_witness: &self.pow_witness,
+        };
+        postcard::to_allocvec(&(header, proof)).expect("a FRI proof can always be encoded")
+    }
+
+    /// Decode a proof encoded by `to_bytes`, checking that it was made in this version of the
+    /// format, over `F`, with digests of the width of `M`'s, and that it has the shape `config`
+    /// asks for.
+    ///
+    /// Only the shape of the input proofs is not checked, as FRI does not know it; the PCS
+    /// verifier checks it instead.
+    pub fn from_bytes(bytes: &[u8], config: &FriConfig<M>) -> Result<Self, ProofDecodingError>
+    where
+        Witness: DeserializeOwned,
+        InputProof: DeserializeOwned,
+    {
+        let (version, rest) = take::<u32>(bytes)?;
+        if version != FRI_PROOF_FORMAT_VERSION {
+            return Err(ProofDecodingError::UnsupportedVersion(version));
+        }
+        let ((id, digest_width, log_arities), rest) = take::<(Vec<u8>, usize, Vec<usize>)>(rest)?;
+        if id != field_id::<F>() {
+            return Err(ProofDecodingError::FieldMismatch);
+        }
+        if digest_width != M::DIGEST_WIDTH {
+            return Err(ProofDecodingError::DigestWidthMismatch {
+                expected: M::DIGEST_WIDTH,
+                found: digest_width,
+            });
+        }
+        let (commit_phase_commits, rest) = take_commitments(config, &log_arities, rest)?;
+        type Rest<F, M, Witness, InputProof> = (
+            Vec<EncodedQueryProof<InputProof, Vec<EncodedOpening<Vec<F>, <M as Mmcs<F>>::Proof>>>>,
+            Vec<F>,
+            Witness,
+        );
+        let ((query_proofs, final_poly, pow_witness), rest) =
+            take::<Rest<F, M, Witness, InputProof>>(rest)?;
+        if !rest.is_empty() {
+            return Err(ProofDecodingError::Malformed);
+        }
+        let query_proofs = query_proofs
+            .into_iter()
+            .map(|query_proof| QueryProof {
+                input_proof: query_proof.input_proof,
+                commit_phase_openings: query_proof
+                    .commit_phase_openings
+                    .into_iter()
+                    .map(|opening| CommitPhaseProofStep {
+                        sibling_values: opening.sibling_values,
+                        opening_proof: opening.opening_proof,
+                    })
+                    .collect(),
+            })
+            .collect();
+        let proof = Self {
+            commit_phase_commits,
+            query_proofs,
+            final_poly,
+            pow_witness,
+        };
+        if !proof.has_shape_of(config, &log_arities) {
+            return Err(ProofDecodingError::InvalidShape);
+        }
+        Ok(proof)
+    }
+
+    /// Whether every query has an opening for each of the rounds of log arities `log_arities`, of
+    /// which `take_commitments` has checked there is a commitment for each and that `config`
+    /// allows, with the `2^k - 1` siblings of a round of log arity `k`, and whether there are as
+    /// many queries and final polynomial coefficients as `config` asks for. As repeated query
+    /// indices are opened once, there can be fewer queries than `num_queries`, but not none.
+    fn has_shape_of(&self, config: &FriConfig<M>, log_arities: &[usize]) -> bool {
+        if self.final_poly.len() != config.final_poly_len()
+            || self.query_proofs.len() > config.num_queries
+        {
+            return false;
+        }
+        if self.query_proofs.is_empty() {
+            return config.num_queries == 0;
+        }
+        self.query_proofs.iter().all(|query_proof| {
+            query_proof
+                .commit_phase_openings
+                .iter()
+                .map(|step| step.sibling_values.len())
+                .eq(log_arities.iter().map(|&log_arity| (1 << log_arity) - 1))
+        })
+    }
+}
@@ -3,14 +3,15 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::marker::PhantomData;
+use core::ops::Range;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
 use p3_commit::{Mmcs, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{
-    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, dot_product, ExtensionField,
-    Field, TwoAdicField,
+    batch_multiplicative_inverse, cyclic_subgroup_known_order, dot_product, ExtensionField, Field,
+    TwoAdicField,
 };
 use p3_interpolation::interpolate_coset;
 use p3_matrix::bitrev::{BitReversableMatrix, BitReversalPerm};
@@ -22,6 +23,7 @@ use p3_util::{log2_strict_usize, reverse_bits_len, reverse_slice_index_bits, Vec
 use serde::{Deserialize, Serialize};
 use tracing::{info_span, instrument};
 
+use crate::fold_even_odd::fold_pairs;
 use crate::verifier::{self, FriError};
 use crate::{prover, FriConfig, FriGenericConfig, FriProof};
 
@@ -42,6 +44,11 @@ impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
             _phantom: PhantomData,
         }
     }
+
+    /// The FRI parameters, e.g. to decode a proof with `FriProof::from_bytes`.
+    pub const fn fri_config(&self) -> &FriConfig<FriMmcs> {
+        &self.fri
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -56,7 +63,17 @@ pub struct TwoAdicFriGenericConfig<InputProof, InputError>(
 );
 
 pub type TwoAdicFriGenericConfigForMmcs<F, M> =
-    TwoAdicFriGenericConfig<Vec<BatchOpening<F, M>>, <M as Mmcs<F>>::Error>;
+    TwoAdicFriGenericConfig<Vec<BatchOpening<F, M>>, InputOpeningError<<M as Mmcs<F>>::Error>>;
+
+/// The reasons the input openings of a query can fail to verify.
+#[derive(Debug)]
+pub enum InputOpeningError<InputMmcsError> {
+    /// The query opens a different number of batches than were committed.
+    InvalidOpeningShape,
+    /// The opening of the batch committed in round `batch` failed to verify, with the error of
+    /// the input MMCS.
+    BatchMmcsError { batch: usize, error: InputMmcsError },
+}
 
 impl<F: TwoAdicField, InputProof, InputError: Debug> FriGenericConfig<F>
     for TwoAdicFriGenericConfig<InputProof, InputError>
@@ -75,94 +92,98 @@ impl<F: TwoAdicField, InputProof, InputError: Debug> FriGenericConfig<F>
         beta: F,
         evals: impl Iterator<Item = F>,
     ) -> F {
-        let arity = 2;
-        let log_arity = 1;
-        let (e0, e1) = evals
-            .collect_tuple()
-            .expect("TwoAdicFriFolder only supports arity=2");
-        // If performance critical, make this API stateful to avoid this
-        // This is a bit more math than is necessary, but leaving it here
-        // in case we want higher arity in the future
-        let subgroup_start = F::two_adic_generator(log_height + log_arity)
-            .exp_u64(reverse_bits_len(index, log_height) as u64);
-        let mut xs = F::two_adic_generator(log_arity)
-            .shifted_powers(subgroup_start)
-            .take(arity)
-            .collect_vec();
-        reverse_slice_index_bits(&mut xs);
-        assert_eq!(log_arity, 1, "can only interpolate two points for now");
-        // interpolate and evaluate at beta
-        e0 + (beta - xs[0]) * (e1 - e0) / (xs[1] - xs[0])
+        // Folding by `2^k` is the same as folding by 2 `k` times, with challenges `beta`,
+        // `beta^2`, ..., `beta^(2^(k-1))`. The row holds the evaluations in bit-reversed order, so
+        // each halving folds adjacent pairs, the `i`'th of which is at `index * 2^(k-1) + i` in a
+        // codeword of height `2^(log_height + k - 1)`. Each pair is folded into the first half of
+        // the buffer, behind the pairs still to be read.
+        let mut evals = evals.collect_vec();
+        let mut beta = beta;
+        let mut len = evals.len();
+        while len > 1 {
+            let log_half_width = log2_strict_usize(len) - 1;
+            for i in 0..len / 2 {
+                evals[i] = fold_pair(
+                    (index << log_half_width) + i,
+                    log_height + log_half_width,
+                    beta,
+                    evals[2 * i],
+                    evals[2 * i + 1],
+                );
+            }
+            len /= 2;
+            beta = beta.square();
+        }
+        evals[0]
     }
 
     fn fold_matrix<M: Matrix<F>>(&self, beta: F, m: M) -> Vec<F> {
-        // We use the fact that
-        //     p_e(x^2) = (p(x) + p(-x)) / 2
-        //     p_o(x^2) = (p(x) - p(-x)) / (2 x)
-        // that is,
-        //     p_e(g^(2i)) = (p(g^i) + p(g^(n/2 + i))) / 2
-        //     p_o(g^(2i)) = (p(g^i) - p(g^(n/2 + i))) / (2 g^i)
-        // so
-        //     result(g^(2i)) = p_e(g^(2i)) + beta p_o(g^(2i))
-        //                    = (1/2 + beta/2 g_inv^i) p(g^i)
-        //                    + (1/2 - beta/2 g_inv^i) p(g^(n/2 + i))
-        let g_inv = F::two_adic_generator(log2_strict_usize(m.height()) + 1).inverse();
-        let one_half = F::ONE.halve();
-        let half_beta = beta * one_half;
-
-        // TODO: vectorize this (after we have packed extension fields)
-
-        // beta/2 times successive powers of g_inv
-        let mut powers = g_inv
-            .shifted_powers(half_beta)
-            .take(m.height())
-            .collect_vec();
-        reverse_slice_index_bits(&mut powers);
-
-        m.par_rows()
-            .zip(powers)
-            .map(|(mut row, power)| {
-                let (lo, hi) = row.next_tuple().unwrap();
-                (one_half + power) * lo + (one_half - power) * hi
-            })
-            .collect()
+        // As in `fold_row`, fold by 2 as many times as needed. The rows of the matrix are
+        // consecutive chunks of the bit-reversed codeword, so it can be split into pairs.
+        let log_arity = log2_strict_usize(m.width());
+        let mut folded = if log_arity == 1 {
+            fold_pairs(beta, m)
+        } else {
+            let values = m.to_row_major_matrix().values;
+            fold_pairs(beta, RowMajorMatrix::new(values, 2))
+        };
+        let mut beta = beta;
+        for _ in 1..log_arity {
+            beta = beta.square();
+            folded = fold_pairs(beta, RowMajorMatrix::new(folded, 2));
+        }
+        folded
     }
 }
 
-impl<Val, Dft, InputMmcs, FriMmcs, Challenge, Challenger> Pcs<Challenge, Challenger>
-    for TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
+/// Fold the evaluations at `2 * index` and `2 * index + 1` of a bit-reversed codeword of height
+/// `2^(log_height + 1)`.
+fn fold_pair<F: TwoAdicField>(index: usize, log_height: usize, beta: F, e0: F, e1: F) -> F {
+    // If performance critical, make this API stateful to avoid this
+    let subgroup_start =
+        F::two_adic_generator(log_height + 1).exp_u64(reverse_bits_len(index, log_height) as u64);
+    let mut xs = F::two_adic_generator(1)
+        .shifted_powers(subgroup_start)
+        .take(2)
+        .collect_vec();
+    reverse_slice_index_bits(&mut xs);
+    // interpolate and evaluate at beta
+    e0 + (beta - xs[0]) * (e1 - e0) / (xs[1] - xs[0])
+}
+
+impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
 where
     Val: TwoAdicField,
     Dft: TwoAdicSubgroupDft<Val>,
     InputMmcs: Mmcs<Val>,
-    FriMmcs: Mmcs<Challenge>,
-    Challenge: TwoAdicField + ExtensionField<Val>,
-    Challenger:
-        FieldChallenger<Val> + CanObserve<FriMmcs::Commitment> + GrindingChallenger<Witness = Val>,
 {
-    type Domain = TwoAdicMultiplicativeCoset<Val>;
-    type Commitment = InputMmcs::Commitment;
-    type ProverData = InputMmcs::ProverData<RowMajorMatrix<Val>>;
-    type Proof = FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>;
-    type Error = FriError<FriMmcs::Error, InputMmcs::Error>;
-
-    fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain {
-        let log_n = log2_strict_usize(degree);
-        TwoAdicMultiplicativeCoset {
-            log_n,
-            shift: Val::ONE,
-        }
-    }
-
-    fn commit(
+    /// Like `Pcs::commit`, but committing to the LDE of each matrix over the coset of the given
+    /// shift, rather than of `Val::GENERATOR`, e.g. to open a matrix committed elsewhere with
+    /// another convention alongside ones committed here. A matrix given `None` is committed over
+    /// the default coset, as by `commit`. The shifts must be given again to `open_with_shifts`
+    /// and `verify_with_shifts`, and only matrices of the default shift can be read back with
+    /// `get_evaluations_on_domain`.
+    ///
+    /// Each shift must be nonzero, and the points the matrix is opened at must lie outside its
+    /// coset.
+    pub fn commit_with_shifts(
         &self,
-        evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
-    ) -> (Self::Commitment, Self::ProverData) {
+        evaluations: Vec<(
+            TwoAdicMultiplicativeCoset<Val>,
+            RowMajorMatrix<Val>,
+            Option<Val>,
+        )>,
+    ) -> (
+        InputMmcs::Commitment,
+        InputMmcs::ProverData<RowMajorMatrix<Val>>,
+    ) {
         let ldes: Vec<_> = evaluations
             .into_iter()
-            .map(|(domain, evals)| {
+            .map(|(domain, evals, shift)| {
                 assert_eq!(domain.size(), evals.height());
-                let shift = Val::GENERATOR / domain.shift;
+                let shift = shift.unwrap_or(Val::GENERATOR);
+                assert!(!shift.is_zero(), "the shift of a coset must be nonzero");
+                let shift = shift / domain.shift;
                 // Commit to the bit-reversed LDE.
                 self.dft
                     .coset_lde_batch(evals, self.fri.log_blowup, shift)
@@ -174,32 +195,34 @@ where
         self.mmcs.commit(ldes)
     }
 
-    fn get_evaluations_on_domain<'a>(
-        &self,
-        prover_data: &'a Self::ProverData,
-        idx: usize,
-        domain: Self::Domain,
-    ) -> impl Matrix<Val> + 'a {
-        // todo: handle extrapolation for LDEs we don't have
-        assert_eq!(domain.shift, Val::GENERATOR);
-        let lde = self.mmcs.get_matrices(prover_data)[idx];
-        assert!(lde.height() >= domain.size());
-        lde.split_rows(domain.size()).0.bit_reverse_rows()
-    }
-
-    fn open(
+    /// Like `Pcs::open`, for matrices committed with `commit_with_shifts`: each matrix is given
+    /// with the shift it was committed with, or `None` if it was committed with the default one.
+    #[allow(clippy::type_complexity)]
+    pub fn open_with_shifts<Challenge, Challenger>(
         &self,
         // For each round,
         rounds: Vec<(
-            &Self::ProverData,
+            &InputMmcs::ProverData<RowMajorMatrix<Val>>,
             // for each matrix,
-            Vec<
+            Vec<(
+                // the shift of its LDE,
+                Option<Val>,
                 // points to open
                 Vec<Challenge>,
-            >,
+            )>,
         )>,
         challenger: &mut Challenger,
-    ) -> (OpenedValues<Challenge>, Self::Proof) {
+    ) -> (
+        OpenedValues<Challenge>,
+        FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>,
+    )
+    where
+        FriMmcs: Mmcs<Challenge>,
+        Challenge: TwoAdicField + ExtensionField<Val>,
+        Challenger: FieldChallenger<Val>
+            + CanObserve<FriMmcs::Commitment>
+            + GrindingChallenger<Witness = Val>,
+    {
         /*
 
         A quick rundown of the optimizations in this function:
@@ -215,106 +238,147 @@ where
             = a^0 ( a^0 x0 + a^1 x1 ) + a^2 ( a^0 x2 + a^1 x3 ) + ...
             (see `alpha_pows`, `alpha_pow_offset`, `num_reduced`)
 
-        - For each unique point z, we precompute 1/(X-z) for the largest subgroup opened at this point.
-        Since we compute it in bit-reversed order, smaller subgroups can simply truncate the vector.
+        - The points a matrix is opened at, usually zeta and zeta * g for a STARK, share a single
+        quotient. For the set S of points, with vanishing polynomial V(X) = prod_{z in S} (z - X),
+        and L_i the polynomial of degree below |S| taking the values y_z[i] = p_i(z) on S, we use
+            (L_i(X) - p_i(X)) / V(X)
+        rather than one quotient (p_i(X) - y_z[i]) / (X - z) for each point. With a single point,
+        these are the same.
+            (see `quotient_groups`)
+
+        - For each shift s and set of points S, we precompute 1/V(X) for the largest coset sH opened
+        at S. Since we compute it in bit-reversed order, smaller cosets can simply truncate the
+        vector.
             (see `inv_denoms`)
 
-        - Then, for each matrix (with columns p_i) and opening point z, we want:
+        - Then, for each matrix (with columns p_i) and set of opening points S, we want:
             for each row (corresponding to subgroup element X):
-                reduced[X] += alpha_offset * sum_i [ alpha^i * inv_denom[X] * (p_i[X] - y[i]) ]
+                reduced[X] += alpha_offset * sum_i [ alpha^i * inv_denom[X] * (L_i(X) - p_i[X]) ]
 
             We can factor out inv_denom, and expand what's left:
-                reduced[X] += alpha_offset * inv_denom[X] * sum_i [ alpha^i * p_i[X] - alpha^i * y[i] ]
+                reduced[X] += alpha_offset * inv_denom[X] * sum_i [ alpha^i * L_i(X) - alpha^i * p_i[X] ]
 
             And separate the sum:
-                reduced[X] += alpha_offset * inv_denom[X] * [ sum_i [ alpha^i * p_i[X] ] - sum_i [ alpha^i * y[i] ] ]
+                reduced[X] += alpha_offset * inv_denom[X] * [ L(X) - sum_i [ alpha^i * p_i[X] ] ]
 
-            And now the last sum doesn't depend on X, so we can precompute that for the matrix, too.
-            So the hot loop (that depends on both X and i) is just:
+            where L = sum_i alpha^i L_i is the polynomial taking the values sum_i alpha^i y_z[i] on
+            S, so we can precompute its coefficients for the matrix, and evaluate it at X with |S| - 1
+            extension-by-base multiplications. So the hot loop (that depends on both X and i) is
+            just:
                 sum_i [ alpha^i * p_i[X] ]
 
-            with alpha^i an extension, p_i[X] a base
+            with alpha^i an extension, p_i[X] a base, done once for all the points of S.
 
         */
 
         // Batch combination challenge
         let alpha: Challenge = challenger.sample_ext_element();
 
+        // For each round, each matrix with the shift of its LDE and the points to open it at.
         let mats_and_points = rounds
             .iter()
-            .map(|(data, points)| {
-                (
-                    self.mmcs
-                        .get_matrices(data)
-                        .into_iter()
-                        .map(|m| m.as_view())
-                        .collect_vec(),
-                    points,
-                )
+            .map(|(data, mats)| {
+                izip!(self.mmcs.get_matrices(data), mats)
+                    .map(|(m, (shift, points))| {
+                        (
+                            m.as_view(),
+                            shift.unwrap_or(Val::GENERATOR),
+                            points.as_slice(),
+                        )
+                    })
+                    .collect_vec()
             })
             .collect_vec();
-        let mats = mats_and_points
-            .iter()
-            .flat_map(|(mats, _)| mats)
-            .collect_vec();
 
-        let global_max_height = mats.iter().map(|m| m.height()).max().unwrap();
+        let global_max_height = mats_and_points
+            .iter()
+            .flatten()
+            .map(|(m, _, _)| m.height())
+            .max()
+            .unwrap();
         let log_global_max_height = log2_strict_usize(global_max_height);
 
-        // For each unique opening point z, we will find the largest degree bound
-        // for that point, and precompute 1/(z - X) for the largest subgroup (in bitrev order).
-        let inv_denoms = compute_inverse_denominators(&mats_and_points, Val::GENERATOR);
+        // The largest subgroup we will use, in bitrev order. Each matrix is evaluated over its
+        // shift times a prefix of it.
+        let mut subgroup = cyclic_subgroup_known_order(
+            Val::two_adic_generator(log_global_max_height),
+            global_max_height,
+        )
+        .collect_vec();
+        reverse_slice_index_bits(&mut subgroup);
+
+        // For each shift and set of points which share a quotient, we will find the largest
+        // degree bound for it, and precompute 1/V(X) for the largest coset (in bitrev order).
+        let inv_denoms =
+            compute_inverse_denominators(&mats_and_points, &subgroup, self.fri.log_blowup);
 
         let mut all_opened_values: OpenedValues<Challenge> = vec![];
 
         let mut reduced_openings: [_; 32] = core::array::from_fn(|_| None);
         let mut num_reduced = [0; 32];
 
-        for (mats, points) in mats_and_points {
+        for mats in mats_and_points {
             let opened_values_for_round = all_opened_values.pushed_mut(vec![]);
-            for (mat, points_for_mat) in izip!(mats, points) {
+            for (mat, shift, points_for_mat) in mats {
                 let log_height = log2_strict_usize(mat.height());
                 let reduced_opening_for_log_height = reduced_openings[log_height]
                     .get_or_insert_with(|| vec![Challenge::ZERO; mat.height()]);
                 debug_assert_eq!(reduced_opening_for_log_height.len(), mat.height());
 
-                let opened_values_for_mat = opened_values_for_round.pushed_mut(vec![]);
-                for &point in points_for_mat {
-                    let _guard =
-                        info_span!("reduce matrix quotient", dims = %mat.dimensions()).entered();
-
-                    // Use Barycentric interpolation to evaluate the matrix at the given point.
-                    let ys = info_span!("compute opened values with Lagrange interpolation")
-                        .in_scope(|| {
-                            let h = mat.height() >> self.fri.log_blowup;
-                            let (low_coset, _) = mat.split_rows(h);
-                            let mut inv_denoms = inv_denoms.get(&point).unwrap()[..h].to_vec();
+                let _guard =
+                    info_span!("reduce matrix quotient", dims = %mat.dimensions()).entered();
+
+                // Use Barycentric interpolation to evaluate the matrix at the given points.
+                let opened_values_for_mat = info_span!(
+                    "compute opened values with Lagrange interpolation"
+                )
+                .in_scope(|| {
+                    let h = mat.height() >> self.fri.log_blowup;
+                    let (low_coset, _) = mat.split_rows(h);
+                    let low_coset = BitReversalPerm::new_view(low_coset);
+                    points_for_mat
+                        .iter()
+                        .map(|&point| {
+                            let mut inv_denoms =
+                                inv_denoms.get(&(shift, vec![point])).unwrap()[..h].to_vec();
                             reverse_slice_index_bits(&mut inv_denoms);
-                            interpolate_coset(
-                                &BitReversalPerm::new_view(low_coset),
-                                Val::GENERATOR,
-                                point,
-                                Some(&inv_denoms),
-                            )
-                        });
+                            interpolate_coset(&low_coset, shift, point, Some(&inv_denoms))
+                        })
+                        .collect_vec()
+                });
 
+                for group in quotient_groups(points_for_mat) {
                     let alpha_pow_offset = alpha.exp_u64(num_reduced[log_height] as u64);
-                    let reduced_ys: Challenge = dot_product(alpha.powers(), ys.iter().copied());
+                    let reduced_ys = opened_values_for_mat[group.clone()]
+                        .iter()
+                        .map(|ys| dot_product(alpha.powers(), ys.iter().copied()))
+                        .collect_vec();
+                    let interpolant =
+                        interpolating_coeffs(&points_for_mat[group.clone()], &reduced_ys);
+                    let inv_denoms = inv_denoms
+                        .get(&(shift, points_for_mat[group].to_vec()))
+                        .unwrap();
 
                     info_span!("reduce rows").in_scope(|| {
                         mat.dot_ext_powers(alpha)
                             .zip(reduced_opening_for_log_height.par_iter_mut())
-                            // This might be longer, but zip will truncate to smaller subgroup
+                            // These might be longer, but zip will truncate to smaller subgroup
                             // (which is ok because it's bitrev)
-                            .zip(inv_denoms.get(&point).unwrap().par_iter())
-                            .for_each(|((reduced_row, ro), &inv_denom)| {
-                                *ro += alpha_pow_offset * (reduced_ys - reduced_row) * inv_denom
+                            .zip(inv_denoms.par_iter())
+                            .zip(subgroup.par_iter())
+                            .for_each(|(((reduced_row, ro), &inv_denom), &x)| {
+                                let x = shift * x;
+                                let reduced_y = interpolant
+                                    .iter()
+                                    .rev()
+                                    .fold(Challenge::ZERO, |acc, &c| acc * x + c);
+                                *ro += alpha_pow_offset * (reduced_y - reduced_row) * inv_denom
                             });
                     });
 
                     num_reduced[log_height] += mat.width();
-                    opened_values_for_mat.push(ys);
                 }
+                opened_values_for_round.push(opened_values_for_mat);
             }
         }
 
@@ -342,15 +406,20 @@ where
         (all_opened_values, fri_proof)
     }
 
-    fn verify(
+    /// Like `Pcs::verify`, for matrices committed with `commit_with_shifts`: each matrix is given
+    /// with the shift it was committed with, or `None` if it was committed with the default one.
+    #[allow(clippy::type_complexity)]
+    pub fn verify_with_shifts<Challenge, Challenger>(
         &self,
         // For each round:
         rounds: Vec<(
-            Self::Commitment,
+            InputMmcs::Commitment,
             // for each matrix:
             Vec<(
                 // its domain,
-                Self::Domain,
+                TwoAdicMultiplicativeCoset<Val>,
+                // the shift of its LDE,
+                Option<Val>,
                 // for each point:
                 Vec<(
                     // the point,
@@ -360,28 +429,60 @@ where
                 )>,
             )>,
         )>,
-        proof: &Self::Proof,
+        proof: &FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>,
         challenger: &mut Challenger,
-    ) -> Result<(), Self::Error> {
+    ) -> Result<(), FriError<FriMmcs::Error, InputOpeningError<InputMmcs::Error>>>
+    where
+        FriMmcs: Mmcs<Challenge>,
+        Challenge: TwoAdicField + ExtensionField<Val>,
+        Challenger: FieldChallenger<Val>
+            + CanObserve<FriMmcs::Commitment>
+            + GrindingChallenger<Witness = Val>,
+    {
         // Batch combination challenge
         let alpha: Challenge = challenger.sample_ext_element();
 
-        let log_global_max_height =
-            proof.commit_phase_commits.len() + self.fri.log_blowup + self.fri.log_final_poly_len;
+        // FRI has one input for each height of LDE, and folds them by arities which follow from
+        // those heights, so take them from the domains rather than from the proof.
+        let log_input_heights = rounds
+            .iter()
+            .flat_map(|(_, mats)| mats)
+            .map(|(domain, _, _)| log2_strict_usize(domain.size()) + self.fri.log_blowup)
+            .sorted_unstable_by(|l, r| r.cmp(l))
+            .dedup()
+            .collect_vec();
+        let log_global_max_height = log_input_heights
+            .first()
+            .copied()
+            .unwrap_or(self.fri.log_blowup + self.fri.log_final_poly_len);
 
         let g: TwoAdicFriGenericConfigForMmcs<Val, InputMmcs> =
             TwoAdicFriGenericConfig(PhantomData);
 
-        verifier::verify(&g, &self.fri, proof, challenger, |index, input_proof| {
+        let challenges = verifier::verify_shape_and_sample_challenges(
+            &g,
+            &self.fri,
+            &log_input_heights,
+            proof,
+            challenger,
+        )?;
+
+        verifier::verify_challenges(&g, &self.fri, proof, &challenges, |index, input_proof| {
             // TODO: separate this out into functions
 
             // log_height -> (alpha_pow, reduced_opening)
             let mut reduced_openings = BTreeMap::<usize, (Challenge, Challenge)>::new();
 
-            for (batch_opening, (batch_commit, mats)) in izip!(input_proof, &rounds) {
+            if input_proof.len() != rounds.len() {
+                return Err(InputOpeningError::InvalidOpeningShape);
+            }
+            for (batch, (batch_opening, (batch_commit, mats))) in
+                izip!(input_proof, &rounds).enumerate()
+            {
+                let batch_mmcs_error = |error| InputOpeningError::BatchMmcsError { batch, error };
                 let batch_heights = mats
                     .iter()
-                    .map(|(domain, _)| domain.size() << self.fri.log_blowup)
+                    .map(|(domain, _, _)| domain.size() << self.fri.log_blowup)
                     .collect_vec();
                 let batch_dims = batch_heights
                     .iter()
@@ -394,25 +495,29 @@ where
                     let bits_reduced = log_global_max_height - log_batch_max_height;
                     let reduced_index = index >> bits_reduced;
 
-                    self.mmcs.verify_batch(
-                        batch_commit,
-                        &batch_dims,
-                        reduced_index,
-                        &batch_opening.opened_values,
-                        &batch_opening.opening_proof,
-                    )?;
+                    self.mmcs
+                        .verify_batch(
+                            batch_commit,
+                            &batch_dims,
+                            reduced_index,
+                            &batch_opening.opened_values,
+                            &batch_opening.opening_proof,
+                        )
+                        .map_err(batch_mmcs_error)?;
                 } else {
                     // Empty batch?
-                    self.mmcs.verify_batch(
-                        batch_commit,
-                        &[],
-                        0,
-                        &batch_opening.opened_values,
-                        &batch_opening.opening_proof,
-                    )?;
+                    self.mmcs
+                        .verify_batch(
+                            batch_commit,
+                            &[],
+                            0,
+                            &batch_opening.opened_values,
+                            &batch_opening.opening_proof,
+                        )
+                        .map_err(batch_mmcs_error)?;
                 }
 
-                for (mat_opening, (mat_domain, mat_points_and_values)) in
+                for (mat_opening, (mat_domain, mat_shift, mat_points_and_values)) in
                     izip!(&batch_opening.opened_values, mats)
                 {
                     let log_height = log2_strict_usize(mat_domain.size()) + self.fri.log_blowup;
@@ -422,16 +527,31 @@ where
 
                     // todo: this can be nicer with domain methods?
 
-                    let x = Val::GENERATOR
+                    let x = mat_shift.unwrap_or(Val::GENERATOR)
                         * Val::two_adic_generator(log_height).exp_u64(rev_reduced_index as u64);
 
                     let (alpha_pow, ro) = reduced_openings
                         .entry(log_height)
                         .or_insert((Challenge::ONE, Challenge::ZERO));
 
-                    for (z, ps_at_z) in mat_points_and_values {
-                        for (&p_at_x, &p_at_z) in izip!(mat_opening, ps_at_z) {
-                            let quotient = (-p_at_z + p_at_x) / (-*z + x);
+                    if mat_points_and_values
+                        .iter()
+                        .any(|(_, ps_at_z)| ps_at_z.len() != mat_opening.len())
+                    {
+                        return Err(InputOpeningError::InvalidOpeningShape);
+                    }
+                    let points = mat_points_and_values.iter().map(|&(z, _)| z).collect_vec();
+                    for group in quotient_groups(&points) {
+                        let vanishing: Challenge =
+                            points[group.clone()].iter().map(|&z| z - x).product();
+                        let vanishing_inv = vanishing.inverse();
+                        let basis = lagrange_basis_at(&points[group.clone()], x);
+                        let ps_at_zs = &mat_points_and_values[group];
+                        for (i, &p_at_x) in mat_opening.iter().enumerate() {
+                            let interpolant_at_x: Challenge = izip!(ps_at_zs, &basis)
+                                .map(|((_, ps_at_z), &l)| l * ps_at_z[i])
+                                .sum();
+                            let quotient = (interpolant_at_x - p_at_x) * vanishing_inv;
                             *ro += *alpha_pow * quotient;
                             *alpha_pow *= alpha;
                         }
@@ -458,47 +578,201 @@ where
     }
 }
 
-#[instrument(skip_all)]
-fn compute_inverse_denominators<F: TwoAdicField, EF: ExtensionField<F>, M: Matrix<F>>(
-    mats_and_points: &[(Vec<M>, &Vec<Vec<EF>>)],
-    coset_shift: F,
-) -> LinearMap<EF, Vec<EF>> {
-    let mut max_log_height_for_point: LinearMap<EF, usize> = LinearMap::new();
-    for (mats, points) in mats_and_points {
-        for (mat, points_for_mat) in izip!(mats, *points) {
-            let log_height = log2_strict_usize(mat.height());
-            for &z in points_for_mat {
-                if let Some(lh) = max_log_height_for_point.get_mut(&z) {
-                    *lh = core::cmp::max(*lh, log_height);
-                } else {
-                    max_log_height_for_point.insert(z, log_height);
+impl<Val, Dft, InputMmcs, FriMmcs, Challenge, Challenger> Pcs<Challenge, Challenger>
+    for TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
+where
+    Val: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<Val>,
+    InputMmcs: Mmcs<Val>,
+    FriMmcs: Mmcs<Challenge>,
+    Challenge: TwoAdicField + ExtensionField<Val>,
+    Challenger:
+        FieldChallenger<Val> + CanObserve<FriMmcs::Commitment> + GrindingChallenger<Witness = Val>,
+{
+    type Domain = TwoAdicMultiplicativeCoset<Val>;
+    type Commitment = InputMmcs::Commitment;
+    type ProverData = InputMmcs::ProverData<RowMajorMatrix<Val>>;
+    type Proof = FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>;
+    type Error = FriError<FriMmcs::Error, InputOpeningError<InputMmcs::Error>>;
+
+    fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain {
+        let log_n = log2_strict_usize(degree);
+        TwoAdicMultiplicativeCoset {
+            log_n,
+            shift: Val::ONE,
+        }
+    }
+
+    fn commit(
+        &self,
+        evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        let evaluations = evaluations
+            .into_iter()
+            .map(|(domain, evals)| (domain, evals, None))
+            .collect();
+        self.commit_with_shifts(evaluations)
+    }
+
+    fn get_evaluations_on_domain<'a>(
+        &self,
+        prover_data: &'a Self::ProverData,
+        idx: usize,
+        domain: Self::Domain,
+    ) -> impl Matrix<Val> + 'a {
+        // todo: handle extrapolation for LDEs we don't have
+        assert_eq!(domain.shift, Val::GENERATOR);
+        let lde = self.mmcs.get_matrices(prover_data)[idx];
+        assert!(lde.height() >= domain.size());
+        lde.split_rows(domain.size()).0.bit_reverse_rows()
+    }
+
+    fn open(
+        &self,
+        // For each round,
+        rounds: Vec<(
+            &Self::ProverData,
+            // for each matrix,
+            Vec<
+                // points to open
+                Vec<Challenge>,
+            >,
+        )>,
+        challenger: &mut Challenger,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        let rounds = rounds
+            .into_iter()
+            .map(|(data, points)| (data, points.into_iter().map(|ps| (None, ps)).collect()))
+            .collect();
+        self.open_with_shifts(rounds, challenger)
+    }
+
+    fn verify(
+        &self,
+        // For each round:
+        rounds: Vec<(
+            Self::Commitment,
+            // for each matrix:
+            Vec<(
+                // its domain,
+                Self::Domain,
+                // for each point:
+                Vec<(
+                    // the point,
+                    Challenge,
+                    // values at the point
+                    Vec<Challenge>,
+                )>,
+            )>,
+        )>,
+        proof: &Self::Proof,
+        challenger: &mut Challenger,
+    ) -> Result<(), Self::Error> {
+        let rounds = rounds
+            .into_iter()
+            .map(|(commit, mats)| {
+                let mats = mats
+                    .into_iter()
+                    .map(|(domain, points)| (domain, None, points))
+                    .collect();
+                (commit, mats)
+            })
+            .collect();
+        self.verify_with_shifts(rounds, proof, challenger)
+    }
+}
+
+/// The ranges of `points` which share a quotient when opening a matrix at them: all of them, as
+/// when a STARK opens a trace at `zeta` and `zeta * g`, unless some are equal, e.g. as `g` is 1 for
+/// a trace of height 1. Then each point gets a quotient of its own.
+fn quotient_groups<EF: PartialEq>(points: &[EF]) -> Vec<Range<usize>> {
+    let distinct = points.iter().tuple_combinations().all(|(a, b)| a != b);
+    if distinct && points.len() > 1 {
+        vec![0..points.len()]
+    } else {
+        (0..points.len()).map(|i| i..i + 1).collect()
+    }
+}
+
+/// The coefficients, lowest degree first, of the polynomial of degree below `points.len()` which
+/// takes the values `ys` at the distinct `points`.
+fn interpolating_coeffs<EF: Field>(points: &[EF], ys: &[EF]) -> Vec<EF> {
+    let mut coeffs = vec![EF::ZERO; points.len()];
+    for (j, (&z_j, &y_j)) in izip!(points, ys).enumerate() {
+        // The numerator prod_{m != j} (X - z_m) of the j-th Lagrange basis polynomial, and its
+        // value at z_j.
+        let mut basis = vec![EF::ONE];
+        let mut basis_at_z_j = EF::ONE;
+        for (m, &z_m) in points.iter().enumerate() {
+            if m != j {
+                basis.insert(0, EF::ZERO);
+                for t in 0..basis.len() - 1 {
+                    let next = basis[t + 1];
+                    basis[t] -= z_m * next;
                 }
+                basis_at_z_j *= z_j - z_m;
             }
         }
+        let scale = y_j * basis_at_z_j.inverse();
+        for (c, b) in coeffs.iter_mut().zip(basis) {
+            *c += scale * b;
+        }
     }
+    coeffs
+}
 
-    // Compute the largest subgroup we will use, in bitrev order.
-    let max_log_height = *max_log_height_for_point.values().max().unwrap();
-    let mut subgroup = cyclic_subgroup_coset_known_order(
-        F::two_adic_generator(max_log_height),
-        coset_shift,
-        1 << max_log_height,
-    )
-    .collect_vec();
-    reverse_slice_index_bits(&mut subgroup);
+/// The Lagrange basis polynomials for the distinct `points`, evaluated at `x`.
+fn lagrange_basis_at<F: Field, EF: ExtensionField<F>>(points: &[EF], x: F) -> Vec<EF> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(j, &z_j)| {
+            let (numerator, denominator) = points
+                .iter()
+                .enumerate()
+                .filter(|&(m, _)| m != j)
+                .fold((EF::ONE, EF::ONE), |(n, d), (_, &z_m)| {
+                    (n * (-z_m + x), d * (z_j - z_m))
+                });
+            numerator * denominator.inverse()
+        })
+        .collect()
+}
 
-    max_log_height_for_point
+/// For each shift `s` and set of points sharing a quotient, `1 / prod_z (z - X)` for `X` in the
+/// largest coset it is needed over, which is `s` times a prefix of `subgroup` (which is in bitrev
+/// order). Each single point is also needed over the low coset of each matrix opened at it, to
+/// interpolate the matrix.
+#[instrument(skip_all)]
+fn compute_inverse_denominators<F: TwoAdicField, EF: ExtensionField<F>, M: Matrix<F>>(
+    mats_and_points: &[Vec<(M, F, &[EF])>],
+    subgroup: &[F],
+    log_blowup: usize,
+) -> LinearMap<(F, Vec<EF>), Vec<EF>> {
+    let mut max_log_height_for_points: LinearMap<(F, Vec<EF>), usize> = LinearMap::new();
+    let mut require = |shift: F, points: &[EF], log_height: usize| {
+        let lh =
+            max_log_height_for_points.get_or_insert_with((shift, points.to_vec()), || log_height);
+        *lh = core::cmp::max(*lh, log_height);
+    };
+    for (mat, shift, points_for_mat) in mats_and_points.iter().flatten() {
+        let log_height = log2_strict_usize(mat.height());
+        for group in quotient_groups(points_for_mat) {
+            require(*shift, &points_for_mat[group], log_height);
+        }
+        for z in *points_for_mat {
+            require(*shift, core::slice::from_ref(z), log_height - log_blowup);
+        }
+    }
+
+    max_log_height_for_points
         .into_iter()
-        .map(|(z, log_height)| {
-            (
-                z,
-                batch_multiplicative_inverse(
-                    &subgroup[..(1 << log_height)]
-                        .iter()
-                        .map(|&x| z - x)
-                        .collect_vec(),
-                ),
-            )
+        .map(|((shift, points), log_height)| {
+            let denominators = subgroup[..(1 << log_height)]
+                .iter()
+                .map(|&x| points.iter().map(|&z| z - shift * x).product())
+                .collect_vec();
+            ((shift, points), batch_multiplicative_inverse(&denominators))
         })
         .collect()
 }
@@ -1,27 +1,72 @@
-use alloc::vec;
 use alloc::vec::Vec;
+use core::{iter, slice};
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
 use p3_commit::Mmcs;
-use p3_field::{ExtensionField, Field, TwoAdicField};
+use p3_field::{eval_poly_packed, ExtensionField, Field, TwoAdicField};
 use p3_matrix::Dimensions;
 use p3_util::reverse_bits_len;
 
-use crate::{CommitPhaseProofStep, FriConfig, FriGenericConfig, FriProof};
+use crate::{FriConfig, FriGenericConfig, FriProof};
 
+/// The reasons FRI verification can fail.
+///
+/// Errors found while checking a query say which one: `query` is the position of its opening in
+/// the proof, and `index` is the query index sampled for it.
 #[derive(Debug)]
 pub enum FriError<CommitMmcsErr, InputError> {
     InvalidProofShape,
-    CommitPhaseMmcsError(CommitMmcsErr),
-    InputError(InputError),
-    FinalPolyMismatch,
+    /// An opening of the commit phase commitment of `round` failed to verify, with the error of
+    /// its MMCS, e.g. a `MerkleTreeError`.
+    ///
+    /// Each folded evaluation is checked by opening it in the codeword of the next round, so this
+    /// is also how a fold which disagrees with the commitments is reported, at the round after it.
+    CommitPhaseMmcsError {
+        query: usize,
+        index: usize,
+        round: usize,
+        error: CommitMmcsErr,
+    },
+    /// An opening of the input failed to verify, with the error of the input's PCS.
+    InputError {
+        query: usize,
+        index: usize,
+        error: InputError,
+    },
+    /// The final polynomial disagrees with the evaluation folded from the openings. This is how a
+    /// fold in the last round which disagrees with the final polynomial is reported.
+    FinalPolyMismatch {
+        query: usize,
+        index: usize,
+    },
     InvalidPowWitness,
 }
 
+/// What the verifier draws from the transcript of a proof before checking its queries, with the
+/// shape of the proof it was checked to have.
+#[derive(Debug)]
+pub struct FriChallenges<F> {
+    /// The index sampled for each query opened in the proof, in the order they are opened.
+    pub query_indices: Vec<usize>,
+    /// The folding challenge of each commit phase round.
+    pub betas: Vec<F>,
+    /// The log of the arity each commit phase round folds by.
+    pub log_arities: Vec<usize>,
+    /// The log of the height of the largest input, which the query indices index into.
+    pub log_max_height: usize,
+}
+
+/// Verify a FRI proof, sampling its challenges with `verify_shape_and_sample_challenges` and
+/// checking its queries with `verify_challenges`.
+///
+/// `log_input_heights` are the log heights of the inputs, largest first. `open_input` checks the
+/// input proof of a query, returning the reduced openings of the input at its index, sorted by
+/// height descending.
 pub fn verify<G, Val, Challenge, M, Challenger>(
     g: &G,
     config: &FriConfig<M>,
+    log_input_heights: &[usize],
     proof: &FriProof<Challenge, M, Challenger::Witness, G::InputProof>,
     challenger: &mut Challenger,
     open_input: impl Fn(usize, &G::InputProof) -> Result<Vec<(usize, Challenge)>, G::InputError>,
@@ -33,6 +78,86 @@ where
     Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
+    let challenges =
+        verify_shape_and_sample_challenges(g, config, log_input_heights, proof, challenger)?;
+    verify_challenges(g, config, proof, &challenges, open_input)
+}
+
+/// Check every query of `proof` against the challenges `verify_shape_and_sample_challenges`
+/// returned for it. This is the second half of `verify`, for callers which check the challenges
+/// against their input before the queries.
+///
+/// The queries are checked one at a time with buffers reused across queries. Besides the proof,
+/// only the challenges, of a word or so per query and round, and one opened row are held at once.
+///
+/// `open_input` checks the input proof of a query, returning the reduced openings of the input at
+/// its index, sorted by height descending.
+pub fn verify_challenges<G, Challenge, M, Witness>(
+    g: &G,
+    config: &FriConfig<M>,
+    proof: &FriProof<Challenge, M, Witness, G::InputProof>,
+    challenges: &FriChallenges<Challenge>,
+    open_input: impl Fn(usize, &G::InputProof) -> Result<Vec<(usize, Challenge)>, G::InputError>,
+) -> Result<(), FriError<M::Error, G::InputError>>
+where
+    Challenge: TwoAdicField,
+    M: Mmcs<Challenge>,
+    G: FriGenericConfig<Challenge>,
+{
+    let mut scratch = Vec::with_capacity(1 << config.max_log_arity());
+    for (query, (&index, qp)) in izip!(&challenges.query_indices, &proof.query_proofs).enumerate() {
+        let ro = open_input(index, &qp.input_proof).map_err(|error| FriError::InputError {
+            query,
+            index,
+            error,
+        })?;
+
+        debug_assert!(
+            ro.iter().tuple_windows().all(|((l, _), (r, _))| l > r),
+            "reduced openings sorted by height descending"
+        );
+
+        verify_query(g, config, proof, challenges, query, ro, &mut scratch)?;
+    }
+
+    Ok(())
+}
+
+/// Replay the transcript of `proof` up to its queries, checking its shape and proof of work, and
+/// return the challenges its queries are checked against with `verify_query`.
+///
+/// The shape is that of a proof for inputs with the given log heights, largest first: the rounds
+/// must fold by the arities `FriConfig::log_arities` gives for them, as the prover's do.
+pub fn verify_shape_and_sample_challenges<G, Val, Challenge, M, Challenger>(
+    g: &G,
+    config: &FriConfig<M>,
+    log_input_heights: &[usize],
+    proof: &FriProof<Challenge, M, Challenger::Witness, G::InputProof>,
+    challenger: &mut Challenger,
+) -> Result<FriChallenges<Challenge>, FriError<M::Error, G::InputError>>
+where
+    Val: Field,
+    Challenge: ExtensionField<Val> + TwoAdicField,
+    M: Mmcs<Challenge>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
+    G: FriGenericConfig<Challenge>,
+{
+    // The arities follow from the config and the heights alone. Every query must open each round
+    // with the siblings of a round of its arity.
+    let log_arities = config
+        .log_arities(log_input_heights)
+        .ok_or(FriError::InvalidProofShape)?;
+    if proof.commit_phase_commits.len() != log_arities.len()
+        || proof.query_proofs.iter().any(|qp| {
+            !qp.commit_phase_openings
+                .iter()
+                .map(|opening| opening.sibling_values.len() + 1)
+                .eq(log_arities.iter().map(|&log_arity| 1 << log_arity))
+        })
+    {
+        return Err(FriError::InvalidProofShape);
+    }
+
     let betas: Vec<Challenge> = proof
         .commit_phase_commits
         .iter()
@@ -42,106 +167,123 @@ where
         })
         .collect();
 
+    // The final polynomial must have fewer coefficients than the final codeword, or it would not
+    // show the codeword to be low degree.
+    assert!(
+        config.log_blowup > 0,
+        "FRI needs a blowup of at least 2, or any final codeword is low degree"
+    );
+    if proof.final_poly.len() != config.final_poly_len() {
+        return Err(FriError::InvalidProofShape);
+    }
+
     // Observe all coefficients of the final polynomial.
     proof
         .final_poly
         .iter()
         .for_each(|x| challenger.observe_ext_element(*x));
 
-    if proof.query_proofs.len() != config.num_queries {
-        return Err(FriError::InvalidProofShape);
-    }
-
     // Check PoW.
     if !challenger.check_witness(config.proof_of_work_bits, proof.pow_witness) {
         return Err(FriError::InvalidPowWitness);
     }
 
-    let log_max_height =
-        proof.commit_phase_commits.len() + config.log_blowup + config.log_final_poly_len;
-
-    for qp in &proof.query_proofs {
-        let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
-        let ro = open_input(index, &qp.input_proof).map_err(FriError::InputError)?;
-
-        debug_assert!(
-            ro.iter().tuple_windows().all(|((l, _), (r, _))| l > r),
-            "reduced openings sorted by height descending"
-        );
-
-        let folded_eval = verify_query(
-            g,
-            config,
-            index >> g.extra_query_index_bits(),
-            izip!(
-                &betas,
-                &proof.commit_phase_commits,
-                &qp.commit_phase_openings
-            ),
-            ro,
-            log_max_height,
-        )?;
-
-        let final_poly_index = index >> (proof.commit_phase_commits.len());
-
-        let mut eval = Challenge::ZERO;
-
-        // We open the final polynomial at index `final_poly_index`, which corresponds to evaluating
-        // the polynomial at x^k, where x is the 2-adic generator of order `max_height` and k is
-        // `reverse_bits_len(final_poly_index, log_max_height)`.
-        let x = Challenge::two_adic_generator(log_max_height)
-            .exp_u64(reverse_bits_len(final_poly_index, log_max_height) as u64);
-        let mut x_pow = Challenge::ONE;
-
-        // Evaluate the final polynomial at x.
-        for coeff in &proof.final_poly {
-            eval += *coeff * x_pow;
-            x_pow *= x;
-        }
+    let log_max_height = log_input_heights
+        .first()
+        .copied()
+        .unwrap_or(config.log_blowup + config.log_final_poly_len);
 
-        if eval != folded_eval {
-            return Err(FriError::FinalPolyMismatch);
-        }
-    }
+    let indices =
+        iter::repeat_with(|| challenger.sample_bits(log_max_height + g.extra_query_index_bits()))
+            .take(config.num_queries)
+            .collect_vec();
+    // The prover opens each distinct index once, in the order they were first sampled. Proofs
+    // which open every sampled index, repeats included, are accepted too. There are few queries,
+    // so repeats are found by search rather than with a set.
+    let first_occurrences = (0..indices.len()).filter(|&i| !indices[..i].contains(&indices[i]));
+    let query_indices = if proof.query_proofs.len() == first_occurrences.clone().count() {
+        first_occurrences.map(|i| indices[i]).collect()
+    } else if proof.query_proofs.len() == config.num_queries {
+        indices
+    } else {
+        return Err(FriError::InvalidProofShape);
+    };
 
-    Ok(())
+    Ok(FriChallenges {
+        query_indices,
+        betas,
+        log_arities,
+        log_max_height,
+    })
 }
 
-type CommitStep<'a, F, M> = (
-    &'a F,
-    &'a <M as Mmcs<F>>::Commitment,
-    &'a CommitPhaseProofStep<F, M>,
-);
-
-fn verify_query<'a, G, F, M>(
+/// Check the query at position `query` in `proof`, against the challenges
+/// `verify_shape_and_sample_challenges` returned for it and the reduced openings of the input at
+/// its index, sorted by height descending.
+///
+/// `scratch` holds the row opened in each round. It is cleared before use, so one buffer, with
+/// room for the largest arity, can be reused for every query without allocating.
+pub fn verify_query<G, Challenge, M, Witness>(
     g: &G,
     config: &FriConfig<M>,
-    mut index: usize,
-    steps: impl Iterator<Item = CommitStep<'a, F, M>>,
-    reduced_openings: Vec<(usize, F)>,
-    log_max_height: usize,
-) -> Result<F, FriError<M::Error, G::InputError>>
+    proof: &FriProof<Challenge, M, Witness, G::InputProof>,
+    challenges: &FriChallenges<Challenge>,
+    query: usize,
+    reduced_openings: impl IntoIterator<Item = (usize, Challenge)>,
+    scratch: &mut Vec<Challenge>,
+) -> Result<(), FriError<M::Error, G::InputError>>
 where
-    F: Field,
-    M: Mmcs<F> + 'a,
-    G: FriGenericConfig<F>,
+    Challenge: TwoAdicField,
+    M: Mmcs<Challenge>,
+    G: FriGenericConfig<Challenge>,
 {
-    let mut folded_eval = F::ZERO;
+    let (Some(&sampled_index), Some(qp)) = (
+        challenges.query_indices.get(query),
+        proof.query_proofs.get(query),
+    ) else {
+        return Err(FriError::InvalidProofShape);
+    };
+    if qp.commit_phase_openings.len() != challenges.log_arities.len() {
+        return Err(FriError::InvalidProofShape);
+    }
+
+    let mut index = sampled_index >> g.extra_query_index_bits();
+    let mut folded_eval = Challenge::ZERO;
     let mut ro_iter = reduced_openings.into_iter().peekable();
+    let mut log_height = challenges.log_max_height;
 
-    for (log_folded_height, (&beta, comm, opening)) in izip!((0..log_max_height).rev(), steps) {
-        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_folded_height + 1) {
+    for (round, (&log_arity, &beta, comm, opening)) in izip!(
+        &challenges.log_arities,
+        &challenges.betas,
+        &proof.commit_phase_commits,
+        &qp.commit_phase_openings
+    )
+    .enumerate()
+    {
+        let log_folded_height = log_height - log_arity;
+        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_height) {
             folded_eval += ro;
         }
+        // Each input must be added in at the start of a round, not folded past.
+        if ro_iter
+            .peek()
+            .is_some_and(|(lh, _)| *lh > log_folded_height)
+        {
+            return Err(FriError::InvalidProofShape);
+        }
+        if opening.sibling_values.len() != (1 << log_arity) - 1 {
+            return Err(FriError::InvalidProofShape);
+        }
 
-        let index_sibling = index ^ 1;
-        let index_pair = index >> 1;
+        let index_in_row = index % (1 << log_arity);
+        let index_row = index >> log_arity;
 
-        let mut evals = vec![folded_eval; 2];
-        evals[index_sibling % 2] = opening.sibling_value;
+        scratch.clear();
+        scratch.extend_from_slice(&opening.sibling_values);
+        scratch.insert(index_in_row, folded_eval);
 
         let dims = &[Dimensions {
-            width: 2,
+            width: 1 << log_arity,
             height: 1 << log_folded_height,
         }];
         config
@@ -149,15 +291,25 @@ where
             .verify_batch(
                 comm,
                 dims,
-                index_pair,
-                &[evals.clone()],
+                index_row,
+                slice::from_ref(scratch),
                 &opening.opening_proof,
             )
-            .map_err(FriError::CommitPhaseMmcsError)?;
+            .map_err(|error| FriError::CommitPhaseMmcsError {
+                query,
+                index: sampled_index,
+                round,
+                error,
+            })?;
 
-        index = index_pair;
+        index = index_row;
+        log_height = log_folded_height;
 
-        folded_eval = g.fold_row(index, log_folded_height, beta, evals.into_iter());
+        folded_eval = g.fold_row(index, log_folded_height, beta, scratch.iter().copied());
+    }
+    // As in the prover, an input of the final height is added in after the last round.
+    if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_height) {
+        folded_eval += ro;
     }
 
     debug_assert!(
@@ -165,10 +317,30 @@ where
         "index was {}",
         index,
     );
-    debug_assert!(
-        ro_iter.next().is_none(),
-        "verifier reduced_openings were not in descending order?"
-    );
+    // Every input must have been added in by now. Any left over is either out of order or
+    // shorter than the final codeword.
+    if ro_iter.next().is_some() {
+        return Err(FriError::InvalidProofShape);
+    }
+
+    let log_folded_bits: usize = challenges.log_arities.iter().sum();
+    let final_poly_index = sampled_index >> log_folded_bits;
+
+    // We open the final polynomial at index `final_poly_index`, which corresponds to evaluating
+    // the polynomial at x^k, where x is the 2-adic generator of order `max_height` and k is
+    // `reverse_bits_len(final_poly_index, log_max_height)`.
+    let log_max_height = challenges.log_max_height;
+    let x = Challenge::two_adic_generator(log_max_height)
+        .exp_u64(reverse_bits_len(final_poly_index, log_max_height) as u64);
 
-    Ok(folded_eval)
+    let eval = eval_poly_packed(&proof.final_poly, x);
+
+    if eval != folded_eval {
+        return Err(FriError::FinalPolyMismatch {
+            query,
+            index: sampled_index,
+        });
+    }
+
+    Ok(())
 }
@@ -1,13 +1,17 @@
 use core::cmp::Reverse;
 use std::marker::PhantomData;
 
+use itertools::Itertools;
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-use p3_challenger::{CanSampleBits, DuplexChallenger, FieldChallenger};
-use p3_commit::ExtensionMmcs;
+use p3_challenger::{
+    CanObserve, CanSampleBits, DuplexChallenger, FieldChallenger, GrindingChallenger,
+};
+use p3_commit::{ExtensionMmcs, Mmcs};
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{Field, FieldAlgebra};
-use p3_fri::{prover, verifier, FriConfig, TwoAdicFriGenericConfig};
+use p3_fri::verifier::FriError;
+use p3_fri::{prover, verifier, FriConfig, FriProof, ProofDecodingError, TwoAdicFriGenericConfig};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::util::reverse_matrix_index_bits;
 use p3_matrix::Matrix;
@@ -16,6 +20,7 @@ use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
 use p3_util::log2_strict_usize;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha20Rng;
+use serde::Serialize;
 
 type Val = BabyBear;
 type Challenge = BinomialExtensionField<Val, 4>;
@@ -28,8 +33,14 @@ type ValMmcs =
 type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
 type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
 type MyFriConfig = FriConfig<ChallengeMmcs>;
+type MyFriProof = FriProof<Challenge, ChallengeMmcs, Val, Vec<(usize, Challenge)>>;
+type MyFriError = FriError<<ChallengeMmcs as Mmcs<Challenge>>::Error, ()>;
 
-fn get_ldt_for_testing<R: Rng>(rng: &mut R, log_final_poly_len: usize) -> (Perm, MyFriConfig) {
+fn get_ldt_for_testing<R: Rng>(
+    rng: &mut R,
+    log_final_poly_len: usize,
+    log_folding_factor: usize,
+) -> (Perm, MyFriConfig) {
     let perm = Perm::new_from_rng_128(rng);
     let hash = MyHash::new(perm.clone());
     let compress = MyCompress::new(perm.clone());
@@ -37,6 +48,8 @@ fn get_ldt_for_testing<R: Rng>(rng: &mut R, log_final_poly_len: usize) -> (Perm,
     let fri_config = FriConfig {
         log_blowup: 1,
         log_final_poly_len,
+        log_folding_factor,
+        folding_schedule: vec![],
         num_queries: 10,
         proof_of_work_bits: 8,
         mmcs,
@@ -44,13 +57,20 @@ fn get_ldt_for_testing<R: Rng>(rng: &mut R, log_final_poly_len: usize) -> (Perm,
     (perm, fri_config)
 }
 
-fn do_test_fri_ldt<R: Rng>(rng: &mut R, log_final_poly_len: usize) {
-    let (perm, fc) = get_ldt_for_testing(rng, log_final_poly_len);
+/// Prove that the LDEs of random polynomials of the given degrees are low degree, returning the
+/// proof along with a sample of the prover's challenger after FRI.
+fn prove_ldt<R: Rng>(
+    rng: &mut R,
+    perm: &Perm,
+    fc: &MyFriConfig,
+    deg_bits: impl IntoIterator<Item = usize>,
+) -> (MyFriProof, usize) {
     let dft = Radix2Dit::default();
 
     let shift = Val::GENERATOR;
 
-    let ldes: Vec<RowMajorMatrix<Val>> = (5..10)
+    let ldes: Vec<RowMajorMatrix<Val>> = deg_bits
+        .into_iter()
         .map(|deg_bits| {
             let evals = RowMajorMatrix::<Val>::rand_nonzero(rng, 1 << deg_bits, 16);
             let mut lde = dft.coset_lde_batch(evals, 1, shift);
@@ -59,7 +79,7 @@ fn do_test_fri_ldt<R: Rng>(rng: &mut R, log_final_poly_len: usize) {
         })
         .collect();
 
-    let (proof, p_sample) = {
+    {
         // Prover world
         let mut chal = Challenger::new(perm.clone());
         let alpha: Challenge = chal.sample_ext_element();
@@ -91,7 +111,7 @@ fn do_test_fri_ldt<R: Rng>(rng: &mut R, log_final_poly_len: usize) {
 
         let proof = prover::prove(
             &TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData),
-            &fc,
+            fc,
             input.clone(),
             &mut chal,
             |idx| {
@@ -107,22 +127,93 @@ fn do_test_fri_ldt<R: Rng>(rng: &mut R, log_final_poly_len: usize) {
         );
 
         (proof, chal.sample_bits(8))
-    };
+    }
+}
+
+/// The log heights of the inputs of a proof made by `prove_ldt`. Its input proofs are the reduced
+/// openings themselves, one for each input, so these are the heights its first query opens.
+fn log_input_heights(proof: &MyFriProof) -> Vec<usize> {
+    proof.query_proofs.first().map_or(vec![], |qp| {
+        qp.input_proof
+            .iter()
+            .map(|&(log_height, _)| log_height)
+            .collect()
+    })
+}
 
-    let mut v_challenger = Challenger::new(perm);
+/// Verify a proof made by `prove_ldt`, returning a sample of the verifier's challenger after FRI.
+fn verify_ldt(perm: &Perm, fc: &MyFriConfig, proof: &MyFriProof) -> Result<usize, MyFriError> {
+    let mut v_challenger = Challenger::new(perm.clone());
     let _alpha: Challenge = v_challenger.sample_ext_element();
     verifier::verify(
         &TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData),
-        &fc,
-        &proof,
+        fc,
+        &log_input_heights(proof),
+        proof,
         &mut v_challenger,
         |_index, proof| Ok(proof.clone()),
-    )
-    .unwrap();
+    )?;
+    Ok(v_challenger.sample_bits(8))
+}
+
+/// Verify a proof made by `prove_ldt` as `verify_ldt` does, but through the query by query API.
+fn verify_ldt_query_by_query(
+    perm: &Perm,
+    fc: &MyFriConfig,
+    proof: &MyFriProof,
+) -> Result<usize, MyFriError> {
+    let g = TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData);
+    let mut v_challenger = Challenger::new(perm.clone());
+    let _alpha: Challenge = v_challenger.sample_ext_element();
+    let challenges = verifier::verify_shape_and_sample_challenges(
+        &g,
+        fc,
+        &log_input_heights(proof),
+        proof,
+        &mut v_challenger,
+    )?;
+    let mut scratch = vec![];
+    for (query, qp) in proof.query_proofs.iter().enumerate() {
+        let ro = qp.input_proof.iter().copied();
+        verifier::verify_query(&g, fc, proof, &challenges, query, ro, &mut scratch)?;
+    }
+    Ok(v_challenger.sample_bits(8))
+}
+
+/// The query indices the verifier samples for `proof`, found by replaying its transcript.
+fn sampled_indices(
+    perm: &Perm,
+    fc: &MyFriConfig,
+    proof: &MyFriProof,
+    log_max_height: usize,
+) -> Vec<usize> {
+    let mut challenger = Challenger::new(perm.clone());
+    let _alpha: Challenge = challenger.sample_ext_element();
+    for commit in &proof.commit_phase_commits {
+        challenger.observe(commit.clone());
+        let _beta: Challenge = challenger.sample_ext_element();
+    }
+    for &x in &proof.final_poly {
+        challenger.observe_ext_element(x);
+    }
+    assert!(challenger.check_witness(fc.proof_of_work_bits, proof.pow_witness));
+    (0..fc.num_queries)
+        .map(|_| challenger.sample_bits(log_max_height))
+        .collect()
+}
+
+fn do_test_fri_ldt<R: Rng>(
+    rng: &mut R,
+    log_final_poly_len: usize,
+    log_folding_factor: usize,
+    deg_bits: impl IntoIterator<Item = usize>,
+) {
+    let (perm, fc) = get_ldt_for_testing(rng, log_final_poly_len, log_folding_factor);
+    let (proof, p_sample) = prove_ldt(rng, &perm, &fc, deg_bits);
+    let v_sample = verify_ldt(&perm, &fc, &proof).unwrap();
 
     assert_eq!(
-        p_sample,
-        v_challenger.sample_bits(8),
+        p_sample, v_sample,
         "prover and verifier transcript have same state after FRI"
     );
 }
@@ -132,7 +223,7 @@ fn test_fri_ldt() {
     // FRI is kind of flaky depending on indexing luck
     for i in 0..4 {
         let mut rng = ChaCha20Rng::seed_from_u64(i as u64);
-        do_test_fri_ldt(&mut rng, i + 1);
+        do_test_fri_ldt(&mut rng, i + 1, 1, 5..10);
     }
 }
 
@@ -143,6 +234,603 @@ fn test_fri_ldt_should_panic() {
     // FRI is kind of flaky depending on indexing luck
     for i in 0..4 {
         let mut rng = ChaCha20Rng::seed_from_u64(i);
-        do_test_fri_ldt(&mut rng, 5);
+        do_test_fri_ldt(&mut rng, 5, 1, 5..10);
+    }
+}
+
+#[test]
+fn test_fri_ldt_higher_arity() {
+    for log_folding_factor in 1..=3 {
+        let mut rng = ChaCha20Rng::seed_from_u64(log_folding_factor as u64);
+        // A single input is folded by the full arity in every round but the last.
+        do_test_fri_ldt(&mut rng, 0, log_folding_factor, [11]);
+        do_test_fri_ldt(&mut rng, 2, log_folding_factor, [11]);
+        // Inputs of nearby heights force some rounds to fold by less.
+        do_test_fri_ldt(&mut rng, 0, log_folding_factor, [4, 5, 9, 11]);
+        do_test_fri_ldt(&mut rng, 1, log_folding_factor, 5..10);
+    }
+}
+
+#[test]
+fn test_fri_ldt_rejects_tampered_proof_at_arity_4() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 0, 2);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [5, 9, 11]);
+    assert!(verify_ldt(&perm, &fc, &proof).is_ok());
+    let first_opening = &proof.query_proofs[0].commit_phase_openings[0];
+    assert_eq!(first_opening.sibling_values.len(), 3);
+
+    let mut tampered = proof.clone();
+    tampered.query_proofs[0].commit_phase_openings[1].sibling_values[2] += Challenge::ONE;
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::CommitPhaseMmcsError { .. })
+    ));
+
+    let mut tampered = proof.clone();
+    tampered.query_proofs[1].commit_phase_openings[0]
+        .sibling_values
+        .pop();
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::InvalidProofShape)
+    ));
+
+    let mut tampered = proof.clone();
+    tampered.final_poly[0] += Challenge::ONE;
+    assert!(verify_ldt(&perm, &fc, &tampered).is_err());
+
+    // The verifier does not accept rounds folding by more than its config allows.
+    let (_, binary_fc) = get_ldt_for_testing(&mut ChaCha20Rng::seed_from_u64(0), 0, 1);
+    assert!(matches!(
+        verify_ldt(&perm, &binary_fc, &proof),
+        Err(MyFriError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_fri_proof_size_shrinks_with_arity() {
+    let proof_sizes = (1..=3)
+        .map(|log_folding_factor| {
+            let mut rng = ChaCha20Rng::seed_from_u64(0);
+            let (perm, fc) = get_ldt_for_testing(&mut rng, 0, log_folding_factor);
+            let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [11]);
+            proof.to_bytes().len()
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        proof_sizes.windows(2).all(|w| w[0] > w[1]),
+        "proof sizes {proof_sizes:?}"
+    );
+}
+
+#[test]
+fn test_fri_proof_size_shrinks_with_final_poly_len() {
+    let proof_sizes = [0, 2, 4]
+        .map(|log_final_poly_len| {
+            let mut rng = ChaCha20Rng::seed_from_u64(0);
+            let (perm, fc) = get_ldt_for_testing(&mut rng, log_final_poly_len, 1);
+            let (proof, p_sample) = prove_ldt(&mut rng, &perm, &fc, [11]);
+            assert_eq!(proof.final_poly.len(), 1 << log_final_poly_len);
+            assert_eq!(
+                proof.commit_phase_commits.len(),
+                11 - log_final_poly_len,
+                "one round fewer for each doubling of the final polynomial"
+            );
+            assert_eq!(verify_ldt(&perm, &fc, &proof).unwrap(), p_sample);
+            proof.to_bytes().len()
+        })
+        .to_vec();
+    assert!(
+        proof_sizes.windows(2).all(|w| w[0] > w[1]),
+        "proof sizes {proof_sizes:?}"
+    );
+}
+
+#[test]
+fn test_fri_ldt_rejects_tampered_final_poly() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 2, 1);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [5, 9, 11]);
+    assert!(verify_ldt(&perm, &fc, &proof).is_ok());
+
+    // Changing a coefficient changes the transcript after it, so it is usually caught by the proof
+    // of work; `test_fri_errors_locate_the_failure` checks the final polynomial check on its own.
+    for i in 0..proof.final_poly.len() {
+        let mut tampered = proof.clone();
+        tampered.final_poly[i] += Challenge::ONE;
+        assert!(verify_ldt(&perm, &fc, &tampered).is_err());
+    }
+
+    // A final polynomial of higher degree would not show the final codeword to be low degree,
+    // even if its high coefficients are zero.
+    let mut tampered = proof.clone();
+    tampered.final_poly.push(Challenge::ZERO);
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::InvalidProofShape)
+    ));
+    let mut tampered = proof.clone();
+    tampered.final_poly.pop();
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_fri_ldt_folding_schedules() {
+    // The LDEs of the second set of inputs have heights 2^12, 2^10, 2^6 and 2^5, and a schedule
+    // must stop at each of them.
+    for (deg_bits, schedule) in [
+        (vec![11], vec![3, 3, 3, 2]),
+        (vec![11], vec![1, 2, 3, 2, 3]),
+        (vec![11], vec![3, 3, 1, 1, 1, 1, 1]),
+        (vec![4, 5, 9, 11], vec![2, 3, 1, 1, 3, 1]),
+    ] {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let (perm, mut fc) = get_ldt_for_testing(&mut rng, 0, 1);
+        fc.folding_schedule = schedule.clone();
+        let (proof, p_sample) = prove_ldt(&mut rng, &perm, &fc, deg_bits);
+        let log_arities = proof.query_proofs[0]
+            .commit_phase_openings
+            .iter()
+            .map(|step| (step.sibling_values.len() + 1).ilog2() as usize)
+            .collect_vec();
+        assert_eq!(log_arities, schedule);
+        assert_eq!(verify_ldt(&perm, &fc, &proof).unwrap(), p_sample);
+        assert!(MyFriProof::from_bytes(&proof.to_bytes(), &fc).is_ok());
+    }
+}
+
+#[test]
+fn test_fri_uniform_schedule_matches_folding_factor() {
+    // Folding a degree 2^11 input by up to 4 each round folds by 2 in the last round.
+    let proof_bytes = |folding_schedule: Vec<usize>| {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let (perm, mut fc) = get_ldt_for_testing(&mut rng, 0, 2);
+        fc.folding_schedule = folding_schedule;
+        prove_ldt(&mut rng, &perm, &fc, [11]).0.to_bytes()
+    };
+    assert_eq!(proof_bytes(vec![2, 2, 2, 2, 2, 1]), proof_bytes(vec![]));
+}
+
+#[test]
+fn test_fri_ldt_rejects_other_folding_schedules() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, mut fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    fc.folding_schedule = vec![3, 3, 3, 2];
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [11]);
+    assert!(verify_ldt(&perm, &fc, &proof).is_ok());
+
+    // The same total arity in another order, and folding by 2 each round.
+    for folding_schedule in [vec![3, 3, 2, 3], vec![]] {
+        let mut other_fc = config_with_mmcs(&fc, fc.mmcs.clone());
+        other_fc.folding_schedule = folding_schedule;
+        assert!(matches!(
+            verify_ldt(&perm, &other_fc, &proof),
+            Err(MyFriError::InvalidProofShape)
+        ));
+        assert!(matches!(
+            MyFriProof::from_bytes(&proof.to_bytes(), &other_fc),
+            Err(ProofDecodingError::InvalidShape)
+        ));
+    }
+}
+
+#[test]
+fn test_fri_ldt_rejects_arities_the_prover_did_not_have_to_use() {
+    // Folding by up to 4 each round, a degree 2^11 input folds by 4 five times and then by 2. A
+    // proof folding by 2 first instead has only legal arities, but is not the one for this input.
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 0, 2);
+    assert_eq!(fc.log_arities(&[12]), Some(vec![2, 2, 2, 2, 2, 1]));
+    let mut reordered_fc = config_with_mmcs(&fc, fc.mmcs.clone());
+    reordered_fc.folding_schedule = vec![1, 2, 2, 2, 2, 2];
+    let (proof, _) = prove_ldt(&mut rng, &perm, &reordered_fc, [11]);
+    assert!(verify_ldt(&perm, &reordered_fc, &proof).is_ok());
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &proof),
+        Err(MyFriError::InvalidProofShape)
+    ));
+}
+
+#[test]
+#[should_panic(expected = "the folding schedule must fold the largest input down")]
+fn test_fri_ldt_refuses_schedule_of_the_wrong_total() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, mut fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    fc.folding_schedule = vec![3, 3, 3];
+    prove_ldt(&mut rng, &perm, &fc, [11]);
+}
+
+#[test]
+#[should_panic(expected = "the folding schedule folds past the height of an input")]
+fn test_fri_ldt_refuses_schedule_folding_past_an_input() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, mut fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    fc.folding_schedule = vec![3, 3, 3, 2];
+    prove_ldt(&mut rng, &perm, &fc, [9, 11]);
+}
+
+#[test]
+fn test_fri_ldt_opens_repeated_indices_once() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, mut fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    // The LDE has 16 rows, so 20 queries must repeat some indices.
+    fc.num_queries = 20;
+    let (proof, p_sample) = prove_ldt(&mut rng, &perm, &fc, [3]);
+    let indices = sampled_indices(&perm, &fc, &proof, 4);
+    let distinct = indices.iter().copied().unique().collect_vec();
+    assert!(distinct.len() < indices.len());
+    assert_eq!(proof.query_proofs.len(), distinct.len());
+    assert_eq!(verify_ldt(&perm, &fc, &proof).unwrap(), p_sample);
+
+    // A proof opening every sampled index, repeats included, also verifies.
+    let opening_of = |index| distinct.iter().position(|&d| d == index).unwrap();
+    let full = MyFriProof {
+        query_proofs: indices
+            .iter()
+            .map(|&index| proof.query_proofs[opening_of(index)].clone())
+            .collect(),
+        ..proof.clone()
+    };
+    assert_eq!(verify_ldt(&perm, &fc, &full).unwrap(), p_sample);
+
+    let mut missing = proof.clone();
+    missing.query_proofs.pop();
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &missing),
+        Err(MyFriError::InvalidProofShape)
+    ));
+
+    // Tampering with an opening used for several queries is caught, as is tampering with a
+    // repeat in a proof which opens every sampled index.
+    let repeated = *indices.iter().duplicates().next().unwrap();
+    let mut tampered = proof.clone();
+    tampered.query_proofs[opening_of(repeated)].commit_phase_openings[0].sibling_values[0] +=
+        Challenge::ONE;
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::CommitPhaseMmcsError { .. })
+    ));
+
+    let second_occurrence = indices
+        .iter()
+        .positions(|&index| index == repeated)
+        .nth(1)
+        .unwrap();
+    let mut tampered = full.clone();
+    tampered.query_proofs[second_occurrence].commit_phase_openings[0].sibling_values[0] +=
+        Challenge::ONE;
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::CommitPhaseMmcsError { .. })
+    ));
+}
+
+#[test]
+fn test_fri_errors_locate_the_failure() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [5, 7]);
+    assert!(verify_ldt(&perm, &fc, &proof).is_ok());
+
+    let mut tampered = proof.clone();
+    tampered.query_proofs[3].commit_phase_openings[2].sibling_values[0] += Challenge::ONE;
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::CommitPhaseMmcsError {
+            query: 3,
+            round: 2,
+            ..
+        })
+    ));
+
+    // A wrong reduced opening is folded into the first round's codeword.
+    let mut tampered = proof.clone();
+    tampered.query_proofs[1].input_proof[0].1 += Challenge::ONE;
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::CommitPhaseMmcsError {
+            query: 1,
+            round: 0,
+            ..
+        })
+    ));
+
+    let mut tampered = proof.clone();
+    tampered.pow_witness += Val::ONE;
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::InvalidPowWitness)
+    ));
+
+    // Changing the final polynomial changes every challenge after it, so without proof of work
+    // and with no commit phase rounds, the final polynomial check is the only one left to fail.
+    let (perm, mut fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    fc.proof_of_work_bits = 0;
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [0]);
+    assert!(proof.commit_phase_commits.is_empty());
+    assert!(verify_ldt(&perm, &fc, &proof).is_ok());
+    let mut tampered = proof.clone();
+    tampered.final_poly[0] += Challenge::ONE;
+    assert!(matches!(
+        verify_ldt(&perm, &fc, &tampered),
+        Err(MyFriError::FinalPolyMismatch { query: 0, .. })
+    ));
+}
+
+#[test]
+fn test_fri_query_by_query_verification_matches_verify() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 1, 2);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [5, 9, 11]);
+
+    let mut corpus = vec![proof.clone()];
+    let mut tampered = proof.clone();
+    tampered.query_proofs[1].commit_phase_openings[2].sibling_values[0] += Challenge::ONE;
+    corpus.push(tampered);
+    let mut tampered = proof.clone();
+    tampered.query_proofs[4].input_proof[1].1 += Challenge::ONE;
+    corpus.push(tampered);
+    let mut tampered = proof.clone();
+    tampered.query_proofs[3].commit_phase_openings[1]
+        .sibling_values
+        .pop();
+    corpus.push(tampered);
+    let mut tampered = proof.clone();
+    tampered.query_proofs.pop();
+    corpus.push(tampered);
+    let mut tampered = proof.clone();
+    tampered.final_poly[1] += Challenge::ONE;
+    corpus.push(tampered);
+    let mut tampered = proof.clone();
+    tampered.pow_witness += Val::ONE;
+    corpus.push(tampered);
+
+    for (i, proof) in corpus.iter().enumerate() {
+        let expected = format!("{:?}", verify_ldt(&perm, &fc, proof));
+        let found = format!("{:?}", verify_ldt_query_by_query(&perm, &fc, proof));
+        assert_eq!(found, expected, "proof {i}");
+        assert_eq!(i == 0, expected.starts_with("Ok"));
+    }
+}
+
+/// `fc` with its MMCS replaced by `mmcs`.
+fn config_with_mmcs<M>(fc: &MyFriConfig, mmcs: M) -> FriConfig<M> {
+    FriConfig {
+        log_blowup: fc.log_blowup,
+        log_final_poly_len: fc.log_final_poly_len,
+        log_folding_factor: fc.log_folding_factor,
+        folding_schedule: fc.folding_schedule.clone(),
+        num_queries: fc.num_queries,
+        proof_of_work_bits: fc.proof_of_work_bits,
+        mmcs,
+    }
+}
+
+#[test]
+fn test_fri_proof_round_trips_through_bytes() {
+    for log_folding_factor in 1..=3 {
+        let mut rng = ChaCha20Rng::seed_from_u64(log_folding_factor as u64);
+        let (perm, fc) = get_ldt_for_testing(&mut rng, 1, log_folding_factor);
+        let (proof, p_sample) = prove_ldt(&mut rng, &perm, &fc, [4, 5, 9, 11]);
+        let bytes = proof.to_bytes();
+        let decoded = MyFriProof::from_bytes(&bytes, &fc).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(verify_ldt(&perm, &fc, &decoded).unwrap(), p_sample);
+    }
+}
+
+/// A `FriProof` as it was defined before folding arities were configurable, when each commit phase
+/// opening had a single sibling value.
+#[derive(Serialize)]
+#[serde(rename = "FriProof")]
+struct BaselineFriProof<'a> {
+    commit_phase_commits: &'a [<ChallengeMmcs as Mmcs<Challenge>>::Commitment],
+    query_proofs: Vec<BaselineQueryProof<'a>>,
+    final_poly: &'a [Challenge],
+    pow_witness: Val,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "QueryProof")]
+struct BaselineQueryProof<'a> {
+    input_proof: &'a [(usize, Challenge)],
+    commit_phase_openings: Vec<BaselineCommitPhaseProofStep<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "CommitPhaseProofStep")]
+struct BaselineCommitPhaseProofStep<'a> {
+    sibling_value: Challenge,
+    opening_proof: &'a <ChallengeMmcs as Mmcs<Challenge>>::Proof,
+}
+
+impl<'a> BaselineFriProof<'a> {
+    fn new(proof: &'a MyFriProof) -> Self {
+        Self {
+            commit_phase_commits: &proof.commit_phase_commits,
+            query_proofs: proof
+                .query_proofs
+                .iter()
+                .map(|qp| BaselineQueryProof {
+                    input_proof: &qp.input_proof,
+                    commit_phase_openings: qp
+                        .commit_phase_openings
+                        .iter()
+                        .map(|step| BaselineCommitPhaseProofStep {
+                            sibling_value: step.sibling_values[0],
+                            opening_proof: &step.opening_proof,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            final_poly: &proof.final_poly,
+            pow_witness: proof.pow_witness,
+        }
     }
 }
+
+#[test]
+fn test_fri_proof_serde_encoding_is_unchanged_at_arity_2() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 1, 1);
+    let (proof, p_sample) = prove_ldt(&mut rng, &perm, &fc, [4, 5, 9, 11]);
+
+    // Proofs serialized before folding arities were configurable still decode and verify.
+    let baseline_bytes = postcard::to_allocvec(&BaselineFriProof::new(&proof)).unwrap();
+    let decoded: MyFriProof = postcard::from_bytes(&baseline_bytes).unwrap();
+    assert_eq!(verify_ldt(&perm, &fc, &decoded).unwrap(), p_sample);
+    assert_eq!(postcard::to_allocvec(&proof).unwrap(), baseline_bytes);
+
+    // Openings of rounds of larger arities have no such encoding.
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 1, 2);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [11]);
+    assert!(postcard::to_allocvec(&proof).is_err());
+    assert!(MyFriProof::from_bytes(&proof.to_bytes(), &fc).is_ok());
+}
+
+#[test]
+fn test_fri_proof_with_merkle_caps_round_trips_through_bytes() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 0, 2);
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress).with_cap_height(3));
+    let fc = config_with_mmcs(&fc, mmcs);
+    let (proof, p_sample) = prove_ldt(&mut rng, &perm, &fc, [4, 5, 9, 11]);
+
+    // The last round folds into a codeword of height 2, too short for a cap of height 3.
+    let cap_sizes = proof
+        .commit_phase_commits
+        .iter()
+        .map(|commit| commit.roots().len())
+        .collect_vec();
+    assert_eq!(cap_sizes.first(), Some(&8));
+    assert_eq!(cap_sizes.last(), Some(&2));
+
+    let bytes = proof.to_bytes();
+    let decoded = MyFriProof::from_bytes(&bytes, &fc).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert_eq!(verify_ldt(&perm, &fc, &decoded).unwrap(), p_sample);
+}
+
+#[test]
+fn test_fri_proof_bytes_are_refused_by_other_versions_and_configs() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [5, 7]);
+    let bytes = proof.to_bytes();
+
+    // The version comes first, as a single byte while it is below 128.
+    let mut other_version = bytes.clone();
+    other_version[0] = 1;
+    assert!(matches!(
+        MyFriProof::from_bytes(&other_version, &fc),
+        Err(ProofDecodingError::UnsupportedVersion(1))
+    ));
+
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let base_config = config_with_mmcs(&fc, ValMmcs::new(hash, compress));
+    assert!(matches!(
+        FriProof::<Val, ValMmcs, Val, Vec<(usize, Challenge)>>::from_bytes(&bytes, &base_config),
+        Err(ProofDecodingError::FieldMismatch)
+    ));
+
+    type ShortHash = PaddingFreeSponge<Perm, 16, 8, 4>;
+    type ShortCompress = TruncatedPermutation<Perm, 2, 4, 16>;
+    type ShortValMmcs = MerkleTreeMmcs<
+        <Val as Field>::Packing,
+        <Val as Field>::Packing,
+        ShortHash,
+        ShortCompress,
+        4,
+    >;
+    type ShortChallengeMmcs = ExtensionMmcs<Val, Challenge, ShortValMmcs>;
+    let short_mmcs = ShortChallengeMmcs::new(ShortValMmcs::new(
+        ShortHash::new(perm.clone()),
+        ShortCompress::new(perm.clone()),
+    ));
+    let short_config = config_with_mmcs(&fc, short_mmcs);
+    assert!(matches!(
+        FriProof::<Challenge, ShortChallengeMmcs, Val, Vec<(usize, Challenge)>>::from_bytes(
+            &bytes,
+            &short_config
+        ),
+        Err(ProofDecodingError::DigestWidthMismatch {
+            expected: 4,
+            found: 8
+        })
+    ));
+
+    assert!(matches!(
+        MyFriProof::from_bytes(&bytes[..bytes.len() - 1], &fc),
+        Err(ProofDecodingError::Malformed)
+    ));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        MyFriProof::from_bytes(&trailing, &fc),
+        Err(ProofDecodingError::Malformed)
+    ));
+}
+
+#[test]
+fn test_fri_proof_bytes_are_checked_against_the_config() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 1, 2);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [5, 7]);
+    assert!(MyFriProof::from_bytes(&proof.to_bytes(), &fc).is_ok());
+
+    let refused = |proof: &MyFriProof, fc: &MyFriConfig| {
+        matches!(
+            MyFriProof::from_bytes(&proof.to_bytes(), fc),
+            Err(ProofDecodingError::InvalidShape)
+        )
+    };
+
+    let mut tampered = proof.clone();
+    tampered.final_poly.pop();
+    assert!(refused(&tampered, &fc));
+
+    let mut tampered = proof.clone();
+    tampered.query_proofs[1].commit_phase_openings.pop();
+    assert!(refused(&tampered, &fc));
+
+    // Each query must fold by the same arity in each round, and by a power of two.
+    let mut tampered = proof.clone();
+    tampered.query_proofs[2].commit_phase_openings[0]
+        .sibling_values
+        .truncate(1);
+    assert!(refused(&tampered, &fc));
+    let mut tampered = proof.clone();
+    for query_proof in &mut tampered.query_proofs {
+        query_proof.commit_phase_openings[0]
+            .sibling_values
+            .truncate(2);
+    }
+    assert!(refused(&tampered, &fc));
+
+    let mut tampered = proof.clone();
+    tampered.query_proofs.clear();
+    assert!(refused(&tampered, &fc));
+
+    let mut fewer_queries = fc;
+    fewer_queries.num_queries = proof.query_proofs.len() - 1;
+    assert!(refused(&proof, &fewer_queries));
+    let (_, binary_fc) = get_ldt_for_testing(&mut ChaCha20Rng::seed_from_u64(0), 1, 1);
+    assert!(refused(&proof, &binary_fc));
+}
+
+#[test]
+fn test_fri_proof_byte_size() {
+    // The reference proof documented on `FriProof::to_bytes`.
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, fc) = get_ldt_for_testing(&mut rng, 0, 1);
+    let (proof, _) = prove_ldt(&mut rng, &perm, &fc, [11]);
+    let size = proof.to_bytes().len();
+    assert!((25_000..30_000).contains(&size), "proof size {size}");
+}
@@ -4,8 +4,11 @@ use p3_challenger::{CanObserve, DuplexChallenger, FieldChallenger};
 use p3_commit::{ExtensionMmcs, Pcs, PolynomialSpace};
 use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
-use p3_field::{ExtensionField, Field};
-use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_field::{ExtensionField, Field, FieldAlgebra};
+use p3_fri::verifier::FriError;
+use p3_fri::{FriConfig, FriProof, InputOpeningError, TwoAdicFriPcs};
+use p3_interpolation::interpolate_subgroup;
+use p3_koala_bear::{default_koalabear_poseidon2_16, KoalaBear, Poseidon2KoalaBear};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_merkle_tree::MerkleTreeMmcs;
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
@@ -17,9 +20,51 @@ fn seeded_rng() -> impl Rng {
     ChaCha20Rng::seed_from_u64(0)
 }
 
+/// The points each matrix is opened at, given the random point `zeta`.
+#[derive(Clone, Copy)]
+enum OpeningPoints {
+    Zeta,
+    /// `zeta` and the next point of the domain, as for a STARK trace.
+    ZetaAndNext,
+    ZetaTwice,
+}
+
+impl OpeningPoints {
+    fn points<D: PolynomialSpace, Challenge: ExtensionField<D::Val>>(
+        self,
+        domain: &D,
+        zeta: Challenge,
+    ) -> Vec<Challenge> {
+        match self {
+            Self::Zeta => vec![zeta],
+            Self::ZetaAndNext => vec![zeta, domain.next_point(zeta).unwrap()],
+            Self::ZetaTwice => vec![zeta, zeta],
+        }
+    }
+}
+
 fn do_test_fri_pcs<Val, Challenge, Challenger, P>(
+    pcs_and_challenger: &(P, Challenger),
+    log_degrees_by_round: &[&[usize]],
+) where
+    P: Pcs<Challenge, Challenger>,
+    P::Domain: PolynomialSpace<Val = Val>,
+    Val: Field,
+    Standard: Distribution<Val>,
+    Challenge: ExtensionField<Val>,
+    Challenger: Clone + CanObserve<P::Commitment> + FieldChallenger<Val>,
+{
+    do_test_fri_pcs_at::<Val, Challenge, Challenger, P>(
+        pcs_and_challenger,
+        log_degrees_by_round,
+        OpeningPoints::Zeta,
+    );
+}
+
+fn do_test_fri_pcs_at<Val, Challenge, Challenger, P>(
     (pcs, challenger): &(P, Challenger),
     log_degrees_by_round: &[&[usize]],
+    opening_points: OpeningPoints,
 ) where
     P: Pcs<Challenge, Challenger>,
     P::Domain: PolynomialSpace<Val = Val>,
@@ -61,9 +106,14 @@ fn do_test_fri_pcs<Val, Challenge, Challenger, P>(
 
     let zeta: Challenge = p_challenger.sample_ext_element();
 
-    let points_by_round = log_degrees_by_round
+    let points_by_round = domains_and_polys_by_round
         .iter()
-        .map(|log_degrees| vec![vec![zeta]; log_degrees.len()])
+        .map(|domains_and_polys| {
+            domains_and_polys
+                .iter()
+                .map(|(domain, _)| opening_points.points(domain, zeta))
+                .collect_vec()
+        })
         .collect_vec();
     let data_and_points = data_by_round.iter().zip(points_by_round).collect();
     let (opening_by_round, proof) = pcs.open(data_and_points, &mut p_challenger);
@@ -84,7 +134,10 @@ fn do_test_fri_pcs<Val, Challenge, Challenger, P>(
         let claims = domains_and_polys
             .iter()
             .zip(openings)
-            .map(|((domain, _), mat_openings)| (*domain, vec![(zeta, mat_openings[0].clone())]))
+            .map(|((domain, _), mat_openings)| {
+                let points = opening_points.points(domain, zeta);
+                (*domain, points.into_iter().zip(mat_openings).collect_vec())
+            })
             .collect_vec();
         (commit, claims)
     })
@@ -147,6 +200,17 @@ macro_rules! make_tests_for_pcs {
             $crate::do_test_fri_pcs(&p, &[&[3, 3], &[2, 2]]);
             $crate::do_test_fri_pcs(&p, &[&[2], &[3, 3]]);
         }
+
+        #[test]
+        fn rotated_points() {
+            let p = $p;
+            let at_zeta_and_next = $crate::OpeningPoints::ZetaAndNext;
+            $crate::do_test_fri_pcs_at(&p, &[&[5]], at_zeta_and_next);
+            $crate::do_test_fri_pcs_at(&p, &[&[3, 4, 4, 6], &[5]], at_zeta_and_next);
+            $crate::do_test_fri_pcs_at(&p, &[&[4, 2], &[4, 2]], at_zeta_and_next);
+            // Repeated points each get a quotient of their own.
+            $crate::do_test_fri_pcs_at(&p, &[&[3, 5]], $crate::OpeningPoints::ZetaTwice);
+        }
     };
 }
 
@@ -168,7 +232,15 @@ mod babybear_fri_pcs {
     type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
     type MyPcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
 
-    fn get_pcs(log_blowup: usize) -> (MyPcs, Challenger) {
+    fn get_pcs(log_blowup: usize, log_folding_factor: usize) -> (MyPcs, Challenger) {
+        get_pcs_with_pow(log_blowup, log_folding_factor, 8)
+    }
+
+    fn get_pcs_with_pow(
+        log_blowup: usize,
+        log_folding_factor: usize,
+        proof_of_work_bits: usize,
+    ) -> (MyPcs, Challenger) {
         let perm = Perm::new_from_rng_128(&mut seeded_rng());
         let hash = MyHash::new(perm.clone());
         let compress = MyCompress::new(perm.clone());
@@ -179,8 +251,10 @@ mod babybear_fri_pcs {
         let fri_config = FriConfig {
             log_blowup,
             log_final_poly_len: 0,
+            log_folding_factor,
+            folding_schedule: vec![],
             num_queries: 10,
-            proof_of_work_bits: 8,
+            proof_of_work_bits,
             mmcs: challenge_mmcs,
         };
 
@@ -189,10 +263,395 @@ mod babybear_fri_pcs {
     }
 
     mod blowup_1 {
-        make_tests_for_pcs!(super::get_pcs(1));
+        make_tests_for_pcs!(super::get_pcs(1, 1));
     }
     mod blowup_2 {
-        make_tests_for_pcs!(super::get_pcs(2));
+        make_tests_for_pcs!(super::get_pcs(2, 1));
+    }
+    mod arity_4 {
+        make_tests_for_pcs!(super::get_pcs(1, 2));
+    }
+    mod arity_8 {
+        make_tests_for_pcs!(super::get_pcs(2, 3));
+    }
+
+    type MyProof = <MyPcs as Pcs<Challenge, Challenger>>::Proof;
+    type MyError = <MyPcs as Pcs<Challenge, Challenger>>::Error;
+    type Claims = Vec<(
+        <MyPcs as Pcs<Challenge, Challenger>>::Commitment,
+        Vec<(
+            <MyPcs as Pcs<Challenge, Challenger>>::Domain,
+            Vec<(Challenge, Vec<Challenge>)>,
+        )>,
+    )>;
+
+    /// Commit to a matrix of degree 16 and one of degree 64, and open both at a random point.
+    fn prove_two_openings(pcs: &MyPcs, challenger: &Challenger) -> (Claims, MyProof) {
+        let mut rng = seeded_rng();
+        let (commits, data): (Vec<_>, Vec<_>) = [4, 6]
+            .into_iter()
+            .map(|log_degree| {
+                let domain = <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(
+                    pcs,
+                    1 << log_degree,
+                );
+                let evals = RowMajorMatrix::<Val>::rand(&mut rng, 1 << log_degree, 3);
+                let (commit, data) =
+                    <MyPcs as Pcs<Challenge, Challenger>>::commit(pcs, vec![(domain, evals)]);
+                ((commit, domain), data)
+            })
+            .unzip();
+
+        let mut p_challenger = challenger.clone();
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let data_and_points = data.iter().map(|data| (data, vec![vec![zeta]])).collect();
+        let (openings, proof) = pcs.open(data_and_points, &mut p_challenger);
+        let claims = izip!(commits, openings)
+            .map(|((commit, domain), mut opening)| {
+                (commit, vec![(domain, vec![(zeta, opening[0].remove(0))])])
+            })
+            .collect_vec();
+        (claims, proof)
+    }
+
+    fn verify_openings(
+        pcs: &MyPcs,
+        challenger: &Challenger,
+        claims: &Claims,
+        proof: &MyProof,
+    ) -> Result<(), MyError> {
+        let mut v_challenger = challenger.clone();
+        let _zeta: Challenge = v_challenger.sample_ext_element();
+        pcs.verify(claims.clone(), proof, &mut v_challenger)
+    }
+
+    #[test]
+    fn tampered_opened_value_names_its_query_and_batch() {
+        let (pcs, challenger) = get_pcs(1, 1);
+        let (claims, proof) = prove_two_openings(&pcs, &challenger);
+        let verify = |proof| verify_openings(&pcs, &challenger, &claims, proof);
+        assert!(verify(&proof).is_ok());
+
+        let mut tampered = proof.clone();
+        tampered.query_proofs[2].input_proof[1].opened_values[0][1] += Val::ONE;
+        assert!(matches!(
+            verify(&tampered),
+            Err(FriError::InputError {
+                query: 2,
+                error: InputOpeningError::BatchMmcsError { batch: 1, .. },
+                ..
+            })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.query_proofs[0].input_proof.pop();
+        assert!(matches!(
+            verify(&tampered),
+            Err(FriError::InputError {
+                query: 0,
+                error: InputOpeningError::InvalidOpeningShape,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn proof_folding_from_another_height_is_rejected() {
+        // Without proof of work, a proof with its last round dropped passes the FRI shape checks,
+        // but folds from half the height of the largest LDE.
+        let (pcs, challenger) = get_pcs_with_pow(1, 1, 0);
+        let (claims, proof) = prove_two_openings(&pcs, &challenger);
+        assert!(verify_openings(&pcs, &challenger, &claims, &proof).is_ok());
+
+        let mut short = proof;
+        short.commit_phase_commits.pop();
+        for qp in &mut short.query_proofs {
+            qp.commit_phase_openings.pop();
+        }
+        assert!(matches!(
+            verify_openings(&pcs, &challenger, &claims, &short),
+            Err(FriError::InvalidProofShape)
+        ));
+    }
+
+    #[test]
+    fn rotated_openings_match_naive_evaluation() {
+        let (pcs, challenger) = get_pcs(1, 1);
+        let domain = <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, 1 << 5);
+        let evals = RowMajorMatrix::<Val>::rand(&mut seeded_rng(), 1 << 5, 7);
+        let (commit, data) =
+            <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, vec![(domain, evals.clone())]);
+
+        let mut p_challenger = challenger.clone();
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let points = vec![zeta, domain.next_point(zeta).unwrap()];
+        let (mut openings, proof) =
+            pcs.open(vec![(&data, vec![points.clone()])], &mut p_challenger);
+        let opened = openings[0].remove(0);
+        for (&point, values) in izip!(&points, &opened) {
+            assert_eq!(*values, interpolate_subgroup(&evals, point));
+        }
+
+        let verify = |claims: Claims| {
+            let mut v_challenger = challenger.clone();
+            let _zeta: Challenge = v_challenger.sample_ext_element();
+            pcs.verify(claims, &proof, &mut v_challenger)
+        };
+        let claims = vec![(commit, vec![(domain, izip!(points, opened).collect_vec())])];
+        assert!(verify(claims.clone()).is_ok());
+        let mut wrong = claims;
+        wrong[0].1[0].1[1].1[3] += Challenge::ONE;
+        assert!(verify(wrong).is_err());
+    }
+
+    #[test]
+    fn matrices_committed_over_different_cosets() {
+        let (pcs, challenger) = get_pcs(1, 1);
+        let mut rng = seeded_rng();
+        let shifts = [None, Some(Val::GENERATOR.square())];
+        let (domains, evals): (Vec<_>, Vec<_>) = [4, 6]
+            .into_iter()
+            .map(|log_degree| {
+                let domain = <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(
+                    &pcs,
+                    1 << log_degree,
+                );
+                (
+                    domain,
+                    RowMajorMatrix::<Val>::rand(&mut rng, 1 << log_degree, 3),
+                )
+            })
+            .unzip();
+        let (commit, data) =
+            pcs.commit_with_shifts(izip!(domains.clone(), evals.clone(), shifts).collect_vec());
+
+        let mut p_challenger = challenger.clone();
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let points = shifts.map(|shift| (shift, vec![zeta])).to_vec();
+        let (mut openings, proof) = pcs.open_with_shifts(vec![(&data, points)], &mut p_challenger);
+        let opened = openings.remove(0);
+        for (evals, values) in izip!(&evals, &opened) {
+            assert_eq!(values[0], interpolate_subgroup(evals, zeta));
+        }
+
+        let verify = |shifts: [Option<Val>; 2]| {
+            let mats = izip!(domains.clone(), shifts, opened.clone())
+                .map(|(domain, shift, mut values)| (domain, shift, vec![(zeta, values.remove(0))]))
+                .collect_vec();
+            let mut v_challenger = challenger.clone();
+            let _zeta: Challenge = v_challenger.sample_ext_element();
+            pcs.verify_with_shifts(vec![(commit.clone(), mats)], &proof, &mut v_challenger)
+        };
+        assert!(verify(shifts).is_ok());
+        // The verifier must be given the shift each matrix was committed with.
+        assert!(verify([None, None]).is_err());
+        assert!(verify([shifts[1], shifts[1]]).is_err());
+    }
+
+    #[test]
+    fn proof_round_trips_through_bytes() {
+        for log_folding_factor in [1, 2] {
+            let (pcs, challenger) = get_pcs(1, log_folding_factor);
+            let (claims, proof) = prove_two_openings(&pcs, &challenger);
+            let bytes = proof.to_bytes();
+            let decoded: MyProof = FriProof::from_bytes(&bytes, pcs.fri_config()).unwrap();
+            assert_eq!(decoded.to_bytes(), bytes);
+            verify_openings(&pcs, &challenger, &claims, &decoded).unwrap();
+        }
+    }
+}
+
+mod babybear_hiding_fri_pcs {
+    use p3_fri::HidingFriPcs;
+    use p3_merkle_tree::MerkleTreeHidingMmcs;
+
+    use super::*;
+
+    type Val = BabyBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+
+    type ValMmcs = MerkleTreeHidingMmcs<
+        <Val as Field>::Packing,
+        <Val as Field>::Packing,
+        MyHash,
+        MyCompress,
+        ChaCha20Rng,
+        8,
+        4,
+    >;
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+
+    type Dft = Radix2DitParallel<Val>;
+    type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+    type MyPcs = HidingFriPcs<Val, Dft, ValMmcs, ChallengeMmcs, ChaCha20Rng>;
+
+    /// A PCS which draws its salts and random codewords from generators seeded by `seed`.
+    fn get_pcs(seed: u64) -> (MyPcs, Challenger) {
+        let perm = Perm::new_from_rng_128(&mut seeded_rng());
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm.clone());
+        let rng = |stream| ChaCha20Rng::seed_from_u64(3 * seed + stream);
+
+        let val_mmcs = ValMmcs::new(hash.clone(), compress.clone(), rng(0));
+        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress, rng(1)));
+
+        let fri_config = FriConfig {
+            log_blowup: 1,
+            log_final_poly_len: 0,
+            log_folding_factor: 1,
+            folding_schedule: vec![],
+            num_queries: 10,
+            proof_of_work_bits: 8,
+            mmcs: challenge_mmcs,
+        };
+
+        let pcs = MyPcs::new(Dft::default(), val_mmcs, fri_config, 4, rng(2));
+        (pcs, Challenger::new(perm))
+    }
+
+    mod blowup_1 {
+        make_tests_for_pcs!(super::get_pcs(0));
+    }
+
+    #[test]
+    fn blinding_changes_every_opened_value_but_the_claims() {
+        let evals = RowMajorMatrix::<Val>::rand(&mut seeded_rng(), 1 << 5, 3);
+        let prove = |seed| {
+            let (pcs, challenger) = get_pcs(seed);
+            let domain =
+                <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, 1 << 5);
+            let (commit, data) =
+                <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, vec![(domain, evals.clone())]);
+
+            let mut p_challenger = challenger.clone();
+            let zeta: Challenge = p_challenger.sample_ext_element();
+            let (mut openings, proof) =
+                pcs.open(vec![(&data, vec![vec![zeta]])], &mut p_challenger);
+            let claim = openings[0][0].remove(0);
+
+            let mut v_challenger = challenger;
+            let _zeta: Challenge = v_challenger.sample_ext_element();
+            let claims = vec![(commit.clone(), vec![(domain, vec![(zeta, claim.clone())])])];
+            pcs.verify(claims, &proof, &mut v_challenger).unwrap();
+            (commit, claim, proof)
+        };
+
+        let (commit_0, claim_0, (random_values_0, fri_proof_0)) = prove(0);
+        let (commit_1, claim_1, (random_values_1, fri_proof_1)) = prove(1);
+        // The claims are the evaluations of the same polynomials at the same point, but
+        // everything FRI sees is blinded with other random codewords.
+        assert_eq!(claim_0, claim_1);
+        assert_ne!(commit_0, commit_1);
+        assert_ne!(random_values_0, random_values_1);
+        assert_ne!(fri_proof_0.final_poly, fri_proof_1.final_poly);
+        let (query_0, query_1) = (&fri_proof_0.query_proofs[0], &fri_proof_1.query_proofs[0]);
+        assert_ne!(
+            query_0.input_proof[0].opened_values[0][3..],
+            query_1.input_proof[0].opened_values[0][3..]
+        );
+        assert_ne!(
+            query_0.commit_phase_openings[0].sibling_values,
+            query_1.commit_phase_openings[0].sibling_values
+        );
+    }
+}
+
+mod koalabear_fri_pcs {
+    use super::*;
+
+    type Val = KoalaBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type Perm = Poseidon2KoalaBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+
+    type ValMmcs =
+        MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+
+    type Dft = Radix2DitParallel<Val>;
+    type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+    type MyPcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+
+    fn get_pcs(log_blowup: usize) -> (MyPcs, Challenger) {
+        let perm = default_koalabear_poseidon2_16();
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm.clone());
+
+        let val_mmcs = ValMmcs::new(hash, compress);
+        let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+        let fri_config = FriConfig {
+            log_blowup,
+            log_final_poly_len: 0,
+            log_folding_factor: 1,
+            folding_schedule: vec![],
+            num_queries: 10,
+            proof_of_work_bits: 8,
+            mmcs: challenge_mmcs,
+        };
+
+        let pcs = MyPcs::new(Dft::default(), val_mmcs, fri_config);
+        (pcs, Challenger::new(perm))
+    }
+
+    mod blowup_1 {
+        make_tests_for_pcs!(super::get_pcs(1));
+    }
+}
+
+mod babybear_keccak_fri_pcs {
+    use p3_keccak::Keccak256Hash;
+    use p3_merkle_tree::ByteDigestMmcs;
+    use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
+
+    use super::*;
+
+    type Val = BabyBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type ByteHash = Keccak256Hash;
+    type FieldHash = SerializingHasher32<ByteHash>;
+    type MyCompress = CompressionFunctionFromHasher<ByteHash, 2, 32>;
+
+    // Keccak digests, presented to the Poseidon2 challenger as 9 BabyBear elements.
+    type KeccakMmcs = MerkleTreeMmcs<Val, u8, FieldHash, MyCompress, 32>;
+    type ValMmcs = ByteDigestMmcs<Val, KeccakMmcs, 32, 9>;
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+
+    type Perm = Poseidon2BabyBear<16>;
+    type Dft = Radix2DitParallel<Val>;
+    type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+    type MyPcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+
+    fn get_pcs(log_blowup: usize) -> (MyPcs, Challenger) {
+        let byte_hash = ByteHash {};
+        let keccak_mmcs = KeccakMmcs::new(FieldHash::new(byte_hash), MyCompress::new(byte_hash));
+        let val_mmcs = ValMmcs::new(keccak_mmcs);
+        let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+        let fri_config = FriConfig {
+            log_blowup,
+            log_final_poly_len: 0,
+            log_folding_factor: 1,
+            folding_schedule: vec![],
+            num_queries: 10,
+            proof_of_work_bits: 8,
+            mmcs: challenge_mmcs,
+        };
+
+        let pcs = MyPcs::new(Dft::default(), val_mmcs, fri_config);
+        let perm = Perm::new_from_rng_128(&mut seeded_rng());
+        (pcs, Challenger::new(perm))
+    }
+
+    mod blowup_1 {
+        make_tests_for_pcs!(super::get_pcs(1));
     }
 }
 
@@ -232,6 +691,8 @@ mod m31_fri_pcs {
         let fri_config = FriConfig {
             log_blowup,
             log_final_poly_len: 0,
+            log_folding_factor: 1,
+            folding_schedule: vec![],
             num_queries: 10,
             proof_of_work_bits: 8,
             mmcs: challenge_mmcs,
@@ -0,0 +1,154 @@
+//! Checks that FRI verification holds one opened row at a time, so that the memory it uses beyond
+//! the proof does not grow with the number of queries, save for their indices.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::{DuplexChallenger, FieldChallenger};
+use p3_commit::ExtensionMmcs;
+use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{prover, verifier, FriConfig, TwoAdicFriGenericConfig};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_util::reverse_slice_index_bits;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+
+/// Counts the allocations made on a thread while it is measuring, and the most bytes allocated
+/// since it started that are live at once. Reallocations change the live bytes, but are not
+/// counted, so that the count does not depend on how buffers grow.
+struct CountingAllocator;
+
+thread_local! {
+    static MEASURING: Cell<bool> = const { Cell::new(false) };
+    static NUM_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+    static PEAK_LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn record(bytes: isize, is_allocation: bool) {
+    let _ = MEASURING.try_with(|measuring| {
+        if measuring.get() {
+            if is_allocation {
+                NUM_ALLOCATIONS.set(NUM_ALLOCATIONS.get() + 1);
+            }
+            let live = LIVE_BYTES.get() + bytes;
+            LIVE_BYTES.set(live);
+            PEAK_LIVE_BYTES.set(PEAK_LIVE_BYTES.get().max(live));
+        }
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size() as isize, true);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(-(layout.size() as isize), false);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size as isize - layout.size() as isize, false);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f`, returning the number of allocations it made and the most bytes it held at once.
+fn measure(f: impl FnOnce()) -> (usize, isize) {
+    NUM_ALLOCATIONS.set(0);
+    LIVE_BYTES.set(0);
+    PEAK_LIVE_BYTES.set(0);
+    MEASURING.set(true);
+    f();
+    MEASURING.set(false);
+    (NUM_ALLOCATIONS.get(), PEAK_LIVE_BYTES.get())
+}
+
+/// Prove and verify that the LDE of a random polynomial of degree `2^10` is low degree with
+/// `num_queries` queries, returning the number of queries in the proof, and the number of
+/// allocations and most bytes held at once while verifying it.
+fn measure_verification(num_queries: usize) -> (usize, usize, isize) {
+    let mut rng = ChaCha20Rng::seed_from_u64(num_queries as u64);
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let mmcs = ChallengeMmcs::new(ValMmcs::new(
+        MyHash::new(perm.clone()),
+        MyCompress::new(perm.clone()),
+    ));
+    let fc = FriConfig {
+        log_blowup: 1,
+        log_final_poly_len: 0,
+        log_folding_factor: 1,
+        folding_schedule: vec![],
+        num_queries,
+        proof_of_work_bits: 8,
+        mmcs,
+    };
+    let g = TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData);
+
+    let evals = RowMajorMatrix::<Val>::rand_nonzero(&mut rng, 1 << 10, 1);
+    let mut lde = Radix2Dit::default()
+        .coset_lde_batch(evals, 1, Val::GENERATOR)
+        .values
+        .into_iter()
+        .map(Challenge::from)
+        .collect::<Vec<_>>();
+    reverse_slice_index_bits(&mut lde);
+
+    let challenger = Challenger::new(perm);
+    let proof = prover::prove(
+        &g,
+        &fc,
+        vec![lde.clone()],
+        &mut challenger.clone(),
+        |index| vec![(11, lde[index])],
+    );
+
+    let mut v_challenger = challenger.clone();
+    let (num_allocations, peak_bytes) = measure(|| {
+        verifier::verify(&g, &fc, &[11], &proof, &mut v_challenger, |_, ro| {
+            Ok(ro.clone())
+        })
+        .unwrap();
+    });
+    (proof.query_proofs.len(), num_allocations, peak_bytes)
+}
+
+#[test]
+fn verification_memory_does_not_grow_with_queries() {
+    let [(n0, count0, peak0), (n1, count1, peak1), (n2, count2, peak2)] =
+        [10, 20, 40].map(measure_verification);
+    assert!(n0 < n1 && n1 < n2);
+
+    // Each query makes the same allocations, in its input opening and in the commit phase MMCS,
+    // and frees them before the next.
+    assert_eq!((count1 - count0) * (n2 - n1), (count2 - count1) * (n1 - n0));
+
+    // What is held at once grows by the indices of the extra queries, of a few words each, and
+    // not with their openings, of about 2 kB each.
+    assert!(
+        peak2 - peak0 <= 32 * (n2 - n0) as isize,
+        "peak bytes {peak0}, {peak1}, {peak2} for {n0}, {n1}, {n2} queries"
+    );
+}
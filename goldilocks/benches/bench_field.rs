@@ -37,6 +37,30 @@ fn bench_field(c: &mut Criterion) {
         )
     });
 }
+
+/// Compare the `u128` multiplication with the 32-bit limb multiplication used on 32-bit targets.
+///
+/// The difference only shows on such targets, e.g. with
+/// `CARGO_TARGET_WASM32_WASIP1_RUNNER=wasmtime cargo bench --target wasm32-wasip1 --bench bench_field`.
+fn bench_mul_paths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Goldilocks mul");
+    group.bench_function("u128", |b| {
+        b.iter_batched(
+            || (rand::random::<F>(), rand::random::<u64>()),
+            |(x, y)| F::from_noncanonical_u128(x.mul_add_unreduced(y, 0)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("without_u128", |b| {
+        b.iter_batched(
+            || (rand::random::<F>(), rand::random::<F>()),
+            |(x, y)| x.mul_without_u128(y),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
 fn bench_packedfield(c: &mut Criterion) {
     let name = type_name::<<F as Field>::Packing>().to_string();
     // Note that each round of throughput has 10 operations
@@ -52,5 +76,10 @@ fn bench_packedfield(c: &mut Criterion) {
     benchmark_mul_throughput::<<F as Field>::Packing, REPS>(c, &name);
 }
 
-criterion_group!(goldilocks_arithmetic, bench_field, bench_packedfield);
+criterion_group!(
+    goldilocks_arithmetic,
+    bench_field,
+    bench_mul_paths,
+    bench_packedfield
+);
 criterion_main!(goldilocks_arithmetic);
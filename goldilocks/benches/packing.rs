@@ -0,0 +1,120 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use p3_dft::{Radix2Dit, Radix2DitParallel, TwoAdicSubgroupDft};
+use p3_field::{Field, PackedValue};
+use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_symmetric::{CryptographicHasher, PaddingFreeSponge, Permutation};
+use p3_util::pretty_name;
+use rand::distributions::{Distribution, Standard};
+use rand::{thread_rng, Rng};
+
+type F = Goldilocks;
+type P = <F as Field>::Packing;
+
+/// Benchmark the DFTs, whose butterflies run over `Goldilocks::Packing`, so that the packing in use
+/// on the target shows in the results. The group names the packing.
+fn bench_dft(c: &mut Criterion) {
+    const BATCH_SIZE: usize = 256;
+    dft::<Radix2Dit<_>, BATCH_SIZE>(c, &[14, 16, 18]);
+    dft::<Radix2DitParallel<_>, BATCH_SIZE>(c, &[14, 16, 18]);
+}
+
+fn dft<Dft: TwoAdicSubgroupDft<F>, const BATCH_SIZE: usize>(
+    c: &mut Criterion,
+    log_sizes: &[usize],
+) {
+    let mut group = c.benchmark_group(format!(
+        "dft/{}/{}/ncols={}",
+        pretty_name::<P>(),
+        pretty_name::<Dft>(),
+        BATCH_SIZE
+    ));
+    group.sample_size(10);
+
+    let mut rng = thread_rng();
+    for n_log in log_sizes {
+        let n = 1 << n_log;
+        let messages = RowMajorMatrix::<F>::rand(&mut rng, n, BATCH_SIZE);
+        let dft = Dft::default();
+        group.throughput(Throughput::Elements((n * BATCH_SIZE) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &dft, |b, dft| {
+            b.iter(|| dft.dft_batch(messages.clone()));
+        });
+    }
+}
+
+/// Compare the Poseidon2 permutation of `P::WIDTH` states, one at a time over `Goldilocks` and all
+/// at once over `Goldilocks::Packing`.
+fn bench_poseidon2(c: &mut Criterion) {
+    let mut rng = thread_rng();
+    poseidon2::<8>(c, Poseidon2Goldilocks::<8>::new_from_rng_128(&mut rng));
+    poseidon2::<12>(c, Poseidon2Goldilocks::<12>::new_from_rng_128(&mut rng));
+}
+
+fn poseidon2<const WIDTH: usize>(
+    c: &mut Criterion,
+    poseidon2: impl Permutation<[F; WIDTH]> + Permutation<[P; WIDTH]>,
+) where
+    Standard: Distribution<[F; WIDTH]>,
+{
+    let mut rng = thread_rng();
+    let mut group = c.benchmark_group(format!("poseidon2::<Goldilocks, {WIDTH}>"));
+    group.throughput(Throughput::Elements(P::WIDTH as u64));
+
+    let states: Vec<[F; WIDTH]> = (0..P::WIDTH).map(|_| rng.gen()).collect();
+    group.bench_function(pretty_name::<F>(), |b| {
+        b.iter(|| {
+            let mut states = states.clone();
+            states
+                .iter_mut()
+                .for_each(|state| poseidon2.permute_mut(state));
+            states
+        })
+    });
+
+    let packed_state: [P; WIDTH] = core::array::from_fn(|i| P::from_fn(|lane| states[lane][i]));
+    group.bench_function(pretty_name::<P>(), |b| {
+        b.iter(|| {
+            let mut state = packed_state;
+            poseidon2.permute_mut(&mut state);
+            state
+        })
+    });
+    group.finish();
+}
+
+/// Compare the throughput of hashing `P::WIDTH` rows with a Poseidon2 sponge, one row at a time
+/// over `Goldilocks` and all rows at once over `Goldilocks::Packing`, as Merkle trees hash their
+/// leaves.
+fn bench_hash(c: &mut Criterion) {
+    const ROW_LEN: usize = 64;
+
+    let mut rng = thread_rng();
+    let hash =
+        PaddingFreeSponge::<_, 8, 4, 4>::new(Poseidon2Goldilocks::<8>::new_from_rng_128(&mut rng));
+
+    let mut group = c.benchmark_group(format!("hash/poseidon2::<Goldilocks, 8>/row_len={ROW_LEN}"));
+    group.throughput(Throughput::Bytes(
+        (P::WIDTH * ROW_LEN * core::mem::size_of::<F>()) as u64,
+    ));
+
+    let rows: Vec<[F; ROW_LEN]> = (0..P::WIDTH).map(|_| rng.gen()).collect();
+    group.bench_function(pretty_name::<F>(), |b| {
+        b.iter(|| {
+            rows.iter()
+                .map(|row| hash.hash_slice(row.as_slice()))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let packed_rows: Vec<P> = (0..ROW_LEN)
+        .map(|i| P::from_fn(|lane| rows[lane][i]))
+        .collect();
+    group.bench_function(pretty_name::<P>(), |b| {
+        b.iter(|| hash.hash_slice(packed_rows.as_slice()))
+    });
+    group.finish();
+}
+
+criterion_group!(goldilocks_packing, bench_dft, bench_poseidon2, bench_hash);
+criterion_main!(goldilocks_packing);
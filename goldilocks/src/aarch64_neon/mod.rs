@@ -0,0 +1,2 @@
+mod packing;
+pub use packing::*;
@@ -0,0 +1,453 @@
+use alloc::vec::Vec;
+use core::arch::aarch64::*;
+use core::fmt;
+use core::fmt::{Debug, Formatter};
+use core::iter::{Product, Sum};
+use core::mem::transmute;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use p3_field::{Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue, PrimeField64};
+use p3_util::convert_vec;
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+
+use crate::Goldilocks;
+
+const WIDTH: usize = 2;
+
+/// NEON Goldilocks Field
+///
+/// As with the AVX2 packing, we wrap `[Goldilocks; 2]` rather than `uint64x2_t` so that
+/// `PackedGoldilocksNeon` has the same alignment as `Goldilocks`, and convert to and from
+/// `uint64x2_t` with the `new` and `get` methods.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct PackedGoldilocksNeon(pub [Goldilocks; WIDTH]);
+
+// Safety: `PackedGoldilocksNeon` is `repr(transparent)` over an array of `Goldilocks`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for PackedGoldilocksNeon {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for PackedGoldilocksNeon {}
+
+impl PackedGoldilocksNeon {
+    #[inline]
+    fn new(x: uint64x2_t) -> Self {
+        unsafe { transmute(x) }
+    }
+    #[inline]
+    fn get(&self) -> uint64x2_t {
+        unsafe { transmute(*self) }
+    }
+}
+
+impl Add<Self> for PackedGoldilocksNeon {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self::new(unsafe { add(self.get(), rhs.get()) })
+    }
+}
+impl Add<Goldilocks> for PackedGoldilocksNeon {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Goldilocks) -> Self {
+        self + Self::from(rhs)
+    }
+}
+impl Add<PackedGoldilocksNeon> for Goldilocks {
+    type Output = PackedGoldilocksNeon;
+    #[inline]
+    fn add(self, rhs: Self::Output) -> Self::Output {
+        Self::Output::from(self) + rhs
+    }
+}
+impl AddAssign<Self> for PackedGoldilocksNeon {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl AddAssign<Goldilocks> for PackedGoldilocksNeon {
+    #[inline]
+    fn add_assign(&mut self, rhs: Goldilocks) {
+        *self = *self + rhs;
+    }
+}
+
+impl Debug for PackedGoldilocksNeon {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({:?})", self.get())
+    }
+}
+
+impl Default for PackedGoldilocksNeon {
+    #[inline]
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Div<Goldilocks> for PackedGoldilocksNeon {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn div(self, rhs: Goldilocks) -> Self {
+        self * rhs.inverse()
+    }
+}
+impl DivAssign<Goldilocks> for PackedGoldilocksNeon {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    #[inline]
+    fn div_assign(&mut self, rhs: Goldilocks) {
+        *self *= rhs.inverse();
+    }
+}
+
+impl From<Goldilocks> for PackedGoldilocksNeon {
+    fn from(x: Goldilocks) -> Self {
+        Self([x; WIDTH])
+    }
+}
+
+impl Mul<Self> for PackedGoldilocksNeon {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(unsafe { mul(self.get(), rhs.get()) })
+    }
+}
+impl Mul<Goldilocks> for PackedGoldilocksNeon {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Goldilocks) -> Self {
+        self * Self::from(rhs)
+    }
+}
+impl Mul<PackedGoldilocksNeon> for Goldilocks {
+    type Output = PackedGoldilocksNeon;
+    #[inline]
+    fn mul(self, rhs: PackedGoldilocksNeon) -> Self::Output {
+        Self::Output::from(self) * rhs
+    }
+}
+impl MulAssign<Self> for PackedGoldilocksNeon {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+impl MulAssign<Goldilocks> for PackedGoldilocksNeon {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Goldilocks) {
+        *self = *self * rhs;
+    }
+}
+
+impl Neg for PackedGoldilocksNeon {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self::new(unsafe { neg(self.get()) })
+    }
+}
+
+impl Product for PackedGoldilocksNeon {
+    #[inline]
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|x, y| x * y).unwrap_or(Self::ONE)
+    }
+}
+
+impl FieldAlgebra for PackedGoldilocksNeon {
+    type F = Goldilocks;
+
+    const ZERO: Self = Self([Goldilocks::ZERO; WIDTH]);
+    const ONE: Self = Self([Goldilocks::ONE; WIDTH]);
+    const TWO: Self = Self([Goldilocks::TWO; WIDTH]);
+    const NEG_ONE: Self = Self([Goldilocks::NEG_ONE; WIDTH]);
+
+    #[inline]
+    fn from_f(f: Self::F) -> Self {
+        f.into()
+    }
+
+    #[inline]
+    fn from_bool(b: bool) -> Self {
+        Goldilocks::from_bool(b).into()
+    }
+    #[inline]
+    fn from_canonical_u8(n: u8) -> Self {
+        Goldilocks::from_canonical_u8(n).into()
+    }
+    #[inline]
+    fn from_canonical_u16(n: u16) -> Self {
+        Goldilocks::from_canonical_u16(n).into()
+    }
+    #[inline]
+    fn from_canonical_u32(n: u32) -> Self {
+        Goldilocks::from_canonical_u32(n).into()
+    }
+    #[inline]
+    fn from_canonical_u64(n: u64) -> Self {
+        Goldilocks::from_canonical_u64(n).into()
+    }
+    #[inline]
+    fn from_canonical_usize(n: usize) -> Self {
+        Goldilocks::from_canonical_usize(n).into()
+    }
+
+    #[inline]
+    fn from_wrapped_u32(n: u32) -> Self {
+        Goldilocks::from_wrapped_u32(n).into()
+    }
+    #[inline]
+    fn from_wrapped_u64(n: u64) -> Self {
+        Goldilocks::from_wrapped_u64(n).into()
+    }
+
+    #[inline]
+    fn square(&self) -> Self {
+        Self::new(unsafe { square(self.get()) })
+    }
+
+    #[inline]
+    fn zero_vec(len: usize) -> Vec<Self> {
+        // SAFETY: this is a repr(transparent) wrapper around an array.
+        unsafe { convert_vec(Self::F::zero_vec(len * WIDTH)) }
+    }
+}
+
+unsafe impl PackedValue for PackedGoldilocksNeon {
+    type Value = Goldilocks;
+
+    const WIDTH: usize = WIDTH;
+
+    #[inline]
+    fn from_slice(slice: &[Goldilocks]) -> &Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe { &*slice.as_ptr().cast() }
+    }
+    #[inline]
+    fn from_slice_mut(slice: &mut [Goldilocks]) -> &mut Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe { &mut *slice.as_mut_ptr().cast() }
+    }
+    #[inline]
+    fn as_slice(&self) -> &[Goldilocks] {
+        &self.0[..]
+    }
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Goldilocks] {
+        &mut self.0[..]
+    }
+
+    /// Similar to `core:array::from_fn`.
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> Goldilocks>(f: F) -> Self {
+        let vals_arr: [_; WIDTH] = core::array::from_fn(f);
+        Self(vals_arr)
+    }
+}
+
+unsafe impl PackedField for PackedGoldilocksNeon {
+    type Scalar = Goldilocks;
+}
+
+unsafe impl PackedFieldPow2 for PackedGoldilocksNeon {
+    #[inline]
+    fn interleave(&self, other: Self, block_len: usize) -> (Self, Self) {
+        let (v0, v1) = (self.get(), other.get());
+        let (res0, res1) = match block_len {
+            1 => unsafe { interleave1(v0, v1) },
+            2 => (v0, v1),
+            _ => panic!("unsupported block_len"),
+        };
+        (Self::new(res0), Self::new(res1))
+    }
+}
+
+impl Sub<Self> for PackedGoldilocksNeon {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(unsafe { sub(self.get(), rhs.get()) })
+    }
+}
+impl Sub<Goldilocks> for PackedGoldilocksNeon {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Goldilocks) -> Self {
+        self - Self::from(rhs)
+    }
+}
+impl Sub<PackedGoldilocksNeon> for Goldilocks {
+    type Output = PackedGoldilocksNeon;
+    #[inline]
+    fn sub(self, rhs: PackedGoldilocksNeon) -> Self::Output {
+        Self::Output::from(self) - rhs
+    }
+}
+impl SubAssign<Self> for PackedGoldilocksNeon {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+impl SubAssign<Goldilocks> for PackedGoldilocksNeon {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Goldilocks) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for PackedGoldilocksNeon {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|x, y| x + y).unwrap_or(Self::ZERO)
+    }
+}
+
+impl Distribution<PackedGoldilocksNeon> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PackedGoldilocksNeon {
+        PackedGoldilocksNeon(rng.gen())
+    }
+}
+
+// Unlike AVX2, NEON has unsigned 64-bit comparisons, so none of the shifting tricks used by the
+// x86 backends are needed here. Comparison instructions return all ones for true and zero for
+// false, so `mask & EPSILON` is `EPSILON` where the comparison held and zero elsewhere. This is
+// the `wrapback_amt` AVX2 gets from `mask >> 32`: both add or subtract `2^64 - FIELD_ORDER`
+// exactly where the 64-bit result wrapped.
+//
+// NEON has no 64-bit multiplication either. We build the 128-bit product from four 32 x 32 -> 64
+// bit products using `vmull_u32` and `vmlal_u32`, then reduce it with the same method as the scalar
+// `reduce128`.
+
+const FIELD_ORDER: uint64x2_t = unsafe { transmute([Goldilocks::ORDER_U64; WIDTH]) };
+const EPSILON: uint64x2_t = unsafe { transmute([Goldilocks::ORDER_U64.wrapping_neg(); WIDTH]) };
+
+/// Convert to canonical representation.
+#[inline]
+unsafe fn canonicalize(x: uint64x2_t) -> uint64x2_t {
+    // All ones if x >= FIELD_ORDER, in which case a single subtraction suffices as x < 2^64.
+    let mask = vcgeq_u64(x, FIELD_ORDER);
+    vsubq_u64(x, vandq_u64(mask, FIELD_ORDER))
+}
+
+/// Addition u64 + u64 -> u64. Assumes that x + y < 2^64 + FIELD_ORDER.
+#[inline]
+unsafe fn add_no_double_overflow_64_64(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    let res_wrapped = vaddq_u64(x, y);
+    let mask = vcgtq_u64(y, res_wrapped); // All ones if overflowed else 0.
+    vaddq_u64(res_wrapped, vandq_u64(mask, EPSILON))
+}
+
+#[inline]
+unsafe fn add(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    add_no_double_overflow_64_64(x, canonicalize(y))
+}
+
+#[inline]
+unsafe fn sub(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    let y = canonicalize(y);
+    let mask = vcgtq_u64(y, x); // All ones if sub will underflow (y > x) else 0.
+    let res_wrapped = vsubq_u64(x, y);
+    // On underflow res_wrapped >= 2^64 - FIELD_ORDER + 1 > EPSILON, so this cannot underflow.
+    vsubq_u64(res_wrapped, vandq_u64(mask, EPSILON))
+}
+
+#[inline]
+unsafe fn neg(y: uint64x2_t) -> uint64x2_t {
+    vsubq_u64(FIELD_ORDER, canonicalize(y))
+}
+
+/// Full 64-bit by 64-bit multiplication, returning the high and low words of the product.
+#[inline]
+unsafe fn mul64_64(x: uint64x2_t, y: uint64x2_t) -> (uint64x2_t, uint64x2_t) {
+    let x_lo = vmovn_u64(x);
+    let x_hi = vshrn_n_u64::<32>(x);
+    let y_lo = vmovn_u64(y);
+    let y_hi = vshrn_n_u64::<32>(y);
+
+    // Bignum addition of the four partial products. None of these can overflow, as
+    // (2^32 - 1)^2 + 2 (2^32 - 1) = 2^64 - 1.
+    let mul_ll = vmull_u32(x_lo, y_lo);
+    let t0 = vmlal_u32(vshrq_n_u64::<32>(mul_ll), x_hi, y_lo);
+    let t1 = vmlal_u32(vandq_u64(t0, EPSILON), x_lo, y_hi);
+    let t2 = vaddq_u64(vshrq_n_u64::<32>(t0), vshrq_n_u64::<32>(t1));
+    let res_hi = vmlal_u32(t2, x_hi, y_hi);
+
+    // The low 32 bits of mul_ll with the low 32 bits of t1 in the high position.
+    let res_lo = vsliq_n_u64::<32>(mul_ll, t1);
+
+    (res_hi, res_lo)
+}
+
+#[inline]
+unsafe fn reduce128(x: (uint64x2_t, uint64x2_t)) -> uint64x2_t {
+    let (hi0, lo0) = x;
+    let hi_hi0 = vshrq_n_u64::<32>(hi0);
+
+    // lo0 - hi_hi0, subtracting EPSILON again on borrow. This cannot underflow.
+    let borrow = vcgtq_u64(hi_hi0, lo0);
+    let t0 = vsubq_u64(vsubq_u64(lo0, hi_hi0), vandq_u64(borrow, EPSILON));
+
+    // hi_lo0 * EPSILON, where EPSILON = 2^32 - 1 fits in 32 bits.
+    let t1 = vmull_u32(vmovn_u64(hi0), vmovn_u64(EPSILON));
+
+    // t1 <= (2^32 - 1)^2, so t0 + t1 < 2^64 + FIELD_ORDER.
+    add_no_double_overflow_64_64(t0, t1)
+}
+
+/// Multiply two integers modulo FIELD_ORDER.
+#[inline]
+unsafe fn mul(x: uint64x2_t, y: uint64x2_t) -> uint64x2_t {
+    reduce128(mul64_64(x, y))
+}
+
+/// Square an integer modulo FIELD_ORDER.
+#[inline]
+unsafe fn square(x: uint64x2_t) -> uint64x2_t {
+    mul(x, x)
+}
+
+#[inline]
+unsafe fn interleave1(x: uint64x2_t, y: uint64x2_t) -> (uint64x2_t, uint64x2_t) {
+    (vtrn1q_u64(x, y), vtrn2q_u64(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::FieldAlgebra;
+    use p3_field_testing::{
+        test_packed_edge_operands, test_packed_field, test_packed_sampled_operands, EDGE_VALS_U64,
+    };
+    use rand::Rng;
+
+    use super::{Goldilocks, PackedGoldilocksNeon, WIDTH};
+
+    const SPECIAL_VALS: [Goldilocks; WIDTH] =
+        Goldilocks::new_array([0xFFFF_FFFF_0000_0000, 0xFFFF_FFFF_FFFF_FFFF]);
+
+    const EDGE_VALS: [Goldilocks; 12] = Goldilocks::new_array(EDGE_VALS_U64);
+
+    test_packed_field!(
+        crate::PackedGoldilocksNeon,
+        crate::PackedGoldilocksNeon::ZERO,
+        crate::PackedGoldilocksNeon(super::SPECIAL_VALS)
+    );
+
+    #[test]
+    fn test_edge_operands() {
+        test_packed_edge_operands::<PackedGoldilocksNeon>(&EDGE_VALS);
+    }
+
+    #[test]
+    fn test_random_u64_operands() {
+        let mut rng = rand::thread_rng();
+        test_packed_sampled_operands::<PackedGoldilocksNeon>(|| Goldilocks::new(rng.gen()));
+    }
+}
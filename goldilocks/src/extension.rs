@@ -1,5 +1,5 @@
 use p3_field::extension::{BinomiallyExtendable, HasTwoAdicBinomialExtension};
-use p3_field::{FieldAlgebra, TwoAdicField};
+use p3_field::{field_to_array, FieldAlgebra, TwoAdicField};
 
 use crate::Goldilocks;
 
@@ -31,11 +31,58 @@ impl HasTwoAdicBinomialExtension<2> for Goldilocks {
     }
 }
 
+impl BinomiallyExtendable<3> for Goldilocks {
+    // Verifiable in Sage with
+    // `R.<x> = GF(p)[]; assert (x^3 - 2).is_irreducible()`.
+    const W: Self = Self::new(2);
+
+    // DTH_ROOT = W^((p - 1)/3).
+    const DTH_ROOT: Self = Self::new(4294967295);
+
+    // Verifiable in Sage with
+    // `F.extension(x^3 - 2, 'u')`, checking that `u + 5` has order `p^3 - 1`.
+    const EXT_GENERATOR: [Self; 3] = [Self::new(5), Self::ONE, Self::ZERO];
+}
+
+impl HasTwoAdicBinomialExtension<3> for Goldilocks {
+    // `p^3 - 1 = (p - 1)(p^2 + p + 1)` and `p^2 + p + 1` is odd, so the cubic extension has
+    // the same two-adicity as the base field.
+    const EXT_TWO_ADICITY: usize = 32;
+
+    fn ext_two_adic_generator(bits: usize) -> [Self; 3] {
+        field_to_array(Self::two_adic_generator(bits))
+    }
+}
+
+impl BinomiallyExtendable<5> for Goldilocks {
+    // `5 | p - 1`, so `x^5 - W` is irreducible iff `W` is not a fifth power. `2` is a fifth power
+    // in Goldilocks, so we use `3`. Verifiable in Sage with
+    // `R.<x> = GF(p)[]; assert (x^5 - 3).is_irreducible()`.
+    const W: Self = Self::new(3);
+
+    // DTH_ROOT = W^((p - 1)/5).
+    const DTH_ROOT: Self = Self::new(1041288259238279555);
+
+    // Verifiable in Sage with
+    // `F.extension(x^5 - 3, 'u')`, checking that `u + 2` has order `p^5 - 1`.
+    const EXT_GENERATOR: [Self; 5] = [Self::TWO, Self::ONE, Self::ZERO, Self::ZERO, Self::ZERO];
+}
+
+impl HasTwoAdicBinomialExtension<5> for Goldilocks {
+    // `p^5 - 1 = (p - 1)(p^4 + p^3 + p^2 + p + 1)` and the second factor is odd, so the quintic
+    // extension has the same two-adicity as the base field.
+    const EXT_TWO_ADICITY: usize = 32;
+
+    fn ext_two_adic_generator(bits: usize) -> [Self; 5] {
+        field_to_array(Self::two_adic_generator(bits))
+    }
+}
+
 #[cfg(test)]
 mod test_quadratic_extension {
 
     use p3_field::extension::BinomialExtensionField;
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
 
     use crate::Goldilocks;
 
@@ -45,4 +92,76 @@ mod test_quadratic_extension {
     test_field!(super::EF);
 
     test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
+}
+
+#[cfg(test)]
+mod test_cubic_extension {
+    use p3_field::extension::{BinomialExtensionField, HasFrobenius};
+    use p3_field::{FieldAlgebra, PrimeField64, TwoAdicField};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
+    use rand::random;
+
+    use crate::Goldilocks;
+
+    type F = Goldilocks;
+    type EF = BinomialExtensionField<F, 3>;
+
+    test_field!(super::EF);
+
+    test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
+
+    #[test]
+    fn frobenius_has_order_three() {
+        for _ in 0..100 {
+            let x: EF = random();
+            assert_eq!(x.repeated_frobenius(3), x);
+            assert_eq!(x.frobenius().frobenius().frobenius(), x);
+            assert_eq!(x.frobenius(), x.exp_u64(F::ORDER_U64));
+        }
+    }
+
+    #[test]
+    fn two_adic_generator_order() {
+        let g = EF::two_adic_generator(EF::TWO_ADICITY);
+        assert_eq!(g.exp_power_of_2(EF::TWO_ADICITY), EF::ONE);
+        assert_ne!(g.exp_power_of_2(EF::TWO_ADICITY - 1), EF::ONE);
+    }
+}
+
+#[cfg(test)]
+mod test_quintic_extension {
+    use p3_field::extension::{BinomialExtensionField, HasFrobenius};
+    use p3_field::{Field, FieldAlgebra, PrimeField64};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
+    use rand::random;
+
+    use crate::Goldilocks;
+
+    type F = Goldilocks;
+    type EF = BinomialExtensionField<F, 5>;
+
+    test_field!(super::EF);
+
+    test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
+
+    #[test]
+    fn frobenius_has_order_five() {
+        for _ in 0..100 {
+            let x: EF = random();
+            assert_eq!(x.repeated_frobenius(5), x);
+            assert_ne!(x.repeated_frobenius(1), x);
+            assert_eq!(x.frobenius(), x.exp_u64(F::ORDER_U64));
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        for _ in 0..100 {
+            let x: EF = random();
+            assert_eq!(x * x.inverse(), EF::ONE);
+        }
+    }
 }
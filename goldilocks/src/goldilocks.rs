@@ -9,27 +9,43 @@ use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use num_bigint::BigUint;
 use p3_field::{
-    exp_10540996611094048183, exp_u64_by_squaring, halve_u64, Field, FieldAlgebra, Packable,
-    PrimeField, PrimeField64, TwoAdicField,
+    exp_10540996611094048183, exp_u64_by_squaring, halve_u64, sample_uniform, Field, FieldAlgebra,
+    Packable, PrimeField, PrimeField64, TwoAdicField,
 };
 use p3_util::{assume, branch_hint};
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// The Goldilocks prime
 const P: u64 = 0xFFFF_FFFF_0000_0001;
 
 /// The prime field known as Goldilocks, defined as `F_p` where `p = 2^64 - 2^32 + 1`.
-///
-/// Note that the safety of deriving `Serialize` and `Deserialize` relies on the fact that the internal value can be any u64.
-#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Default)]
 #[repr(transparent)] // Packed field implementations rely on this!
 pub struct Goldilocks {
     /// Not necessarily canonical.
     pub(crate) value: u64,
 }
 
+// Safety: `Goldilocks` is `repr(transparent)` over a `u64`, and every `u64` is a valid (if not
+// necessarily canonical) representation.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Goldilocks {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Goldilocks {}
+
+#[cfg(feature = "bytemuck")]
+impl p3_field::FieldWord for Goldilocks {
+    type Word = u64;
+
+    #[inline]
+    fn is_canonical_word(word: u64) -> bool {
+        word < P
+    }
+}
+
 impl Goldilocks {
     pub(crate) const fn new(value: u64) -> Self {
         Self { value }
@@ -55,6 +71,57 @@ impl Goldilocks {
 
     /// Two's complement of `ORDER`, i.e. `2^64 - ORDER = 2^32 - 1`.
     const NEG_ORDER: u64 = Self::ORDER_U64.wrapping_neg();
+
+    /// Reduce a 96-bit integer `n_lo + 2^64 n_hi`.
+    ///
+    /// As `2^64 = 2^32 - 1 mod p`, this needs a single 32 x 32 bit multiplication and one
+    /// addition, which is cheaper than `from_noncanonical_u128`.
+    #[inline]
+    pub fn from_noncanonical_u96((n_lo, n_hi): (u64, u32)) -> Self {
+        let t = u64::from(n_hi) * Self::NEG_ORDER;
+        // SAFETY: `add_no_canonicalize_trashing_input` needs `n_lo + t < 2^64 + ORDER`. Here
+        // `n_lo <= 2^64 - 1` and `t <= (2^32 - 1)^2 = 2^64 - 2^33 + 1`, so the sum is at most
+        // `2^65 - 2^33`, which is less than `2^64 + ORDER = 2^65 - 2^32 + 1`.
+        Self::new(unsafe { add_no_canonicalize_trashing_input(n_lo, t) })
+    }
+
+    /// Reduce an arbitrary 128-bit integer.
+    #[inline]
+    pub fn from_noncanonical_u128(n: u128) -> Self {
+        reduce128(n)
+    }
+
+    /// Compute `acc + self * rhs` as an integer, without reducing.
+    ///
+    /// This lets callers accumulate a sum of products and reduce it once with
+    /// `from_noncanonical_u128`. The caller must ensure the sum doesn't overflow; as the
+    /// internal value of `self` is less than `2^64`, summing `N` products whose `rhs` are all
+    /// less than `2^k` is safe when `N <= 2^(64 - k)`.
+    #[inline]
+    #[must_use]
+    pub const fn mul_add_unreduced(self, rhs: u64, acc: u128) -> u128 {
+        acc + (self.value as u128) * (rhs as u128)
+    }
+
+    /// Multiply without forming a `u128` intermediate.
+    ///
+    /// The operands are split into 32-bit halves and the four partial products are accumulated
+    /// into the two 64-bit halves of the full product, which is then reduced as in
+    /// `from_noncanonical_u128`. On 32-bit targets, where `u128` multiplication is a slow libcall,
+    /// this is what `Mul` uses; elsewhere it is only exposed for testing and benchmarking.
+    #[inline]
+    #[must_use]
+    pub fn mul_without_u128(self, rhs: Self) -> Self {
+        let (x_lo, x_hi) = (self.value & 0xFFFF_FFFF, self.value >> 32);
+        let (y_lo, y_hi) = (rhs.value & 0xFFFF_FFFF, rhs.value >> 32);
+
+        // Each partial product is less than 2^64, but the sum of the cross terms may not be.
+        let (mid, mid_carry) = (x_lo * y_hi).overflowing_add(x_hi * y_lo);
+        let (lo, lo_carry) = (x_lo * y_lo).overflowing_add(mid << 32);
+        // The full product is less than 2^128, so this sum cannot overflow.
+        let hi = x_hi * y_hi + (mid >> 32) + ((mid_carry as u64) << 32) + lo_carry as u64;
+        reduce_halves(lo, hi)
+    }
 }
 
 impl PartialEq for Goldilocks {
@@ -99,13 +166,20 @@ impl Debug for Goldilocks {
 
 impl Distribution<Goldilocks> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Goldilocks {
-        loop {
-            let next_u64 = rng.next_u64();
-            let is_canonical = next_u64 < Goldilocks::ORDER_U64;
-            if is_canonical {
-                return Goldilocks::new(next_u64);
-            }
-        }
+        sample_uniform(rng)
+    }
+}
+
+impl Serialize for Goldilocks {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.as_canonical_u64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Goldilocks {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let val = u64::deserialize(d)?;
+        Self::from_canonical_checked(val).ok_or_else(|| D::Error::custom("Value is out of range"))
     }
 }
 
@@ -167,7 +241,8 @@ impl FieldAlgebra for Goldilocks {
 }
 
 impl Field for Goldilocks {
-    // TODO: Add cfg-guarded Packing for NEON
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    type Packing = crate::PackedGoldilocksNeon;
 
     #[cfg(all(
         target_arch = "x86_64",
@@ -183,6 +258,7 @@ impl Field for Goldilocks {
     ))]
     type Packing = crate::PackedGoldilocksAVX512;
     #[cfg(not(any(
+        all(target_arch = "aarch64", target_feature = "neon"),
         all(
             target_arch = "x86_64",
             target_feature = "avx2",
@@ -271,6 +347,19 @@ impl PrimeField for Goldilocks {
     fn as_canonical_biguint(&self) -> BigUint {
         <Self as PrimeField64>::as_canonical_u64(self).into()
     }
+
+    #[inline]
+    fn from_canonical_checked(n: u64) -> Option<Self> {
+        if n < P {
+            Some(Self::new(n))
+        } else {
+            None
+        }
+    }
+
+    fn from_canonical_biguint_checked(n: &BigUint) -> Option<Self> {
+        u64::try_from(n).ok().and_then(Self::from_canonical_checked)
+    }
 }
 
 impl PrimeField64 for Goldilocks {
@@ -285,6 +374,12 @@ impl PrimeField64 for Goldilocks {
         }
         c
     }
+
+    #[inline]
+    fn sum_slice(xs: &[Self]) -> Self {
+        // Our Sum implementation already accumulates non-canonical values into a u128.
+        xs.iter().copied().sum()
+    }
 }
 
 impl TwoAdicField for Goldilocks {
@@ -380,9 +475,16 @@ impl Mul for Goldilocks {
     type Output = Self;
 
     #[inline]
+    #[cfg(not(target_pointer_width = "32"))]
     fn mul(self, rhs: Self) -> Self {
         reduce128(u128::from(self.value) * u128::from(rhs.value))
     }
+
+    #[inline]
+    #[cfg(target_pointer_width = "32")]
+    fn mul(self, rhs: Self) -> Self {
+        self.mul_without_u128(rhs)
+    }
 }
 
 impl MulAssign for Goldilocks {
@@ -418,6 +520,13 @@ fn exp_acc<const N: usize>(base: Goldilocks, tail: Goldilocks) -> Goldilocks {
 #[inline]
 pub(crate) fn reduce128(x: u128) -> Goldilocks {
     let (x_lo, x_hi) = split(x); // This is a no-op
+    reduce_halves(x_lo, x_hi)
+}
+
+/// Reduces the 128-bit integer `x_lo + 2^64 x_hi` to a 64-bit value, which might not be in
+/// canonical form.
+#[inline(always)]
+fn reduce_halves(x_lo: u64, x_hi: u64) -> Goldilocks {
     let x_hi_hi = x_hi >> 32;
     let x_hi_lo = x_hi & Goldilocks::NEG_ORDER;
 
@@ -427,6 +536,9 @@ pub(crate) fn reduce128(x: u128) -> Goldilocks {
         t0 -= Goldilocks::NEG_ORDER; // Cannot underflow.
     }
     let t1 = x_hi_lo * Goldilocks::NEG_ORDER;
+    // SAFETY: `add_no_canonicalize_trashing_input` needs `t0 + t1 < 2^64 + ORDER`. Any `u128`
+    // input is allowed: `t0 <= 2^64 - 1`, and `x_hi_lo < 2^32` gives `t1 <= (2^32 - 1)^2`, so the
+    // sum is at most `2^65 - 2^33`, below `2^64 + ORDER = 2^65 - 2^32 + 1`.
     let t2 = unsafe { add_no_canonicalize_trashing_input(t0, t1) };
     Goldilocks::new(t2)
 }
@@ -477,9 +589,12 @@ unsafe fn add_no_canonicalize_trashing_input(x: u64, y: u64) -> u64 {
     res_wrapped + Goldilocks::NEG_ORDER * u64::from(carry)
 }
 
+#[cfg(all(test, feature = "bytemuck"))]
+p3_field_testing::test_field_bytemuck!(crate::Goldilocks, u64, 0xFFFF_FFFF_0000_0001);
+
 #[cfg(test)]
 mod tests {
-    use p3_field_testing::{test_field, test_field_dft, test_two_adic_field};
+    use p3_field_testing::{test_field, test_field_dft, test_prime_field_64, test_two_adic_field};
 
     use super::*;
 
@@ -567,11 +682,123 @@ mod tests {
         assert_eq!(f_2.exp_u64(10540996611094048183).exp_const_u64::<7>(), f_2);
     }
 
+    /// Reference reduction of `n` via big integers.
+    fn reduce_biguint(n: u128) -> F {
+        let r: BigUint = BigUint::from(n) % F::ORDER_U64;
+        F::from_canonical_u64(r.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_from_noncanonical_u128() {
+        let p = F::ORDER_U64 as u128;
+        let boundary = [
+            0,
+            1,
+            p - 1,
+            p,
+            p + 1,
+            u64::MAX as u128,
+            1 << 64,
+            p << 64,
+            (p << 64) - 1,
+            (p << 64) + 1,
+            (p << 64) + p,
+            p * p,
+            p * p - 1,
+            u128::MAX - p,
+            u128::MAX - 1,
+            u128::MAX,
+        ];
+        let mut rng = rand::thread_rng();
+        let random = (0..1000).map(|_| rng.gen::<u128>());
+        for n in boundary.into_iter().chain(random) {
+            assert_eq!(F::from_noncanonical_u128(n), reduce_biguint(n), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_from_noncanonical_u96() {
+        let boundary = [
+            (0, 0),
+            (1, 0),
+            (F::ORDER_U64, 0),
+            (u64::MAX, 0),
+            (0, 1),
+            (F::ORDER_U64, 1),
+            (0, u32::MAX),
+            (F::ORDER_U64 - 1, u32::MAX),
+            (F::ORDER_U64, u32::MAX),
+            (u64::MAX, u32::MAX),
+        ];
+        let mut rng = rand::thread_rng();
+        let random = (0..1000).map(|_| (rng.gen::<u64>(), rng.gen::<u32>()));
+        for (lo, hi) in boundary.into_iter().chain(random) {
+            let n = u128::from(lo) + (u128::from(hi) << 64);
+            assert_eq!(
+                F::from_noncanonical_u96((lo, hi)),
+                reduce_biguint(n),
+                "n = {n}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mul_add_unreduced() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let xs: [F; 16] = core::array::from_fn(|_| F::new(rng.gen()));
+            let ys: [u64; 16] = core::array::from_fn(|_| rng.gen::<u64>() >> 4);
+            let acc = xs
+                .iter()
+                .zip(ys)
+                .fold(0, |acc, (&x, y)| x.mul_add_unreduced(y, acc));
+            let expected: F = xs.iter().zip(ys).map(|(&x, y)| x * F::new(y)).sum();
+            assert_eq!(F::from_noncanonical_u128(acc), expected);
+        }
+    }
+
+    #[test]
+    fn test_mul_without_u128() {
+        // Values either side of the limb boundary, of p, and of 2^64, including non-canonical ones.
+        let p = F::ORDER_U64;
+        let structured = [
+            0,
+            1,
+            2,
+            (1 << 32) - 1,
+            1 << 32,
+            (1 << 32) + 1,
+            (1 << 63) - 1,
+            1 << 63,
+            p - 1,
+            p,
+            p + 1,
+            u64::MAX - (1 << 32),
+            u64::MAX - 1,
+            u64::MAX,
+        ];
+        let mut rng = rand::thread_rng();
+        let random: Vec<u64> = (0..100).map(|_| rng.gen()).collect();
+        let samples: Vec<u64> = structured.into_iter().chain(random).collect();
+
+        for &x in &samples {
+            for &y in &samples {
+                let expected = reduce128(u128::from(x) * u128::from(y));
+                let product = F::new(x).mul_without_u128(F::new(y));
+                assert_eq!(product.value, expected.value, "x = {x}, y = {y}");
+                assert_eq!(product, reduce_biguint(u128::from(x) * u128::from(y)));
+                assert_eq!(F::new(x) * F::new(y), expected);
+            }
+        }
+    }
+
     test_field!(crate::Goldilocks);
+    test_prime_field_64!(crate::Goldilocks);
     test_two_adic_field!(crate::Goldilocks);
 
     test_field_dft!(radix2dit, crate::Goldilocks, p3_dft::Radix2Dit<_>);
     test_field_dft!(bowers, crate::Goldilocks, p3_dft::Radix2Bowers);
+    test_field_dft!(split_radix, crate::Goldilocks, p3_dft::SplitRadixDit<_>);
     test_field_dft!(
         parallel,
         crate::Goldilocks,
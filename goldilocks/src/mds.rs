@@ -12,7 +12,7 @@ use p3_mds::util::{apply_circulant, apply_circulant_fft, first_row_to_first_col}
 use p3_mds::MdsPermutation;
 use p3_symmetric::Permutation;
 
-use crate::{reduce128, Goldilocks};
+use crate::Goldilocks;
 
 #[derive(Clone, Debug, Default)]
 pub struct MdsMatrixGoldilocks;
@@ -55,7 +55,9 @@ impl Convolve<Goldilocks, i128, i64, i128> for SmallConvolveGoldilocks {
     #[inline(always)]
     fn reduce(z: i128) -> Goldilocks {
         debug_assert!(z >= 0);
-        reduce128(z as u128)
+        // As 0 <= z < 2^123, the cast is exact, and
+        // `from_noncanonical_u128` reduces any `u128`.
+        Goldilocks::from_noncanonical_u128(z as u128)
     }
 }
 
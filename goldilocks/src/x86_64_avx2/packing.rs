@@ -26,6 +26,12 @@ const WIDTH: usize = 4;
 #[repr(transparent)]
 pub struct PackedGoldilocksAVX2(pub [Goldilocks; WIDTH]);
 
+// Safety: `PackedGoldilocksAVX2` is `repr(transparent)` over an array of `Goldilocks`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for PackedGoldilocksAVX2 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for PackedGoldilocksAVX2 {}
+
 impl PackedGoldilocksAVX2 {
     #[inline]
     fn new(x: __m256i) -> Self {
@@ -574,7 +580,7 @@ unsafe fn interleave2(x: __m256i, y: __m256i) -> (__m256i, __m256i) {
 
 #[cfg(test)]
 mod tests {
-    use p3_field_testing::test_packed_field;
+    use p3_field_testing::{test_packed_edge_operands, test_packed_field, EDGE_VALS_U64};
 
     use super::{Goldilocks, WIDTH};
 
@@ -585,9 +591,16 @@ mod tests {
         0xFFFF_FFFF_0000_0001,
     ]);
 
+    const EDGE_VALS: [Goldilocks; 12] = Goldilocks::new_array(EDGE_VALS_U64);
+
     test_packed_field!(
         crate::PackedGoldilocksAVX2,
         crate::PackedGoldilocksAVX2::ZERO,
         crate::PackedGoldilocksAVX2(super::SPECIAL_VALS)
     );
+
+    #[test]
+    fn test_edge_operands() {
+        test_packed_edge_operands::<crate::PackedGoldilocksAVX2>(&EDGE_VALS);
+    }
 }
@@ -25,6 +25,12 @@ const WIDTH: usize = 8;
 #[repr(transparent)]
 pub struct PackedGoldilocksAVX512(pub [Goldilocks; WIDTH]);
 
+// Safety: `PackedGoldilocksAVX512` is `repr(transparent)` over an array of `Goldilocks`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for PackedGoldilocksAVX512 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for PackedGoldilocksAVX512 {}
+
 impl PackedGoldilocksAVX512 {
     #[inline]
     fn new(x: __m512i) -> Self {
@@ -471,7 +477,7 @@ unsafe fn interleave4(x: __m512i, y: __m512i) -> (__m512i, __m512i) {
 
 #[cfg(test)]
 mod tests {
-    use p3_field_testing::test_packed_field;
+    use p3_field_testing::{test_packed_edge_operands, test_packed_field, EDGE_VALS_U64};
 
     use super::{Goldilocks, WIDTH};
 
@@ -486,9 +492,16 @@ mod tests {
         0x0FFF_FFFF_F000_0000,
     ]);
 
+    const EDGE_VALS: [Goldilocks; 12] = Goldilocks::new_array(EDGE_VALS_U64);
+
     test_packed_field!(
         crate::PackedGoldilocksAVX512,
         crate::PackedGoldilocksAVX512::ZERO,
         crate::PackedGoldilocksAVX512(super::SPECIAL_VALS)
     );
+
+    #[test]
+    fn test_edge_operands() {
+        test_packed_edge_operands::<crate::PackedGoldilocksAVX512>(&EDGE_VALS);
+    }
 }
@@ -4,7 +4,7 @@ mod test_quartic_extension {
 
     use p3_field::extension::BinomialExtensionField;
     use p3_field::{FieldAlgebra, FieldExtensionAlgebra};
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
 
     use crate::KoalaBear;
 
@@ -13,6 +13,7 @@ mod test_quartic_extension {
 
     test_field!(super::EF);
     test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
 
     #[test]
     fn display() {
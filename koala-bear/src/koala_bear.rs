@@ -103,7 +103,7 @@ impl BinomialExtensionData<4> for KoalaBearParameters {
 #[cfg(test)]
 mod tests {
     use p3_field::{PrimeField32, PrimeField64, TwoAdicField};
-    use p3_field_testing::{test_field, test_field_dft, test_two_adic_field};
+    use p3_field_testing::{test_field, test_field_dft, test_prime_field_32, test_two_adic_field};
 
     use super::*;
 
@@ -203,9 +203,14 @@ mod tests {
         let m2_serialized = serde_json::to_string(&m2).unwrap();
         let m2_deserialized: F = serde_json::from_str(&m2_serialized).unwrap();
         assert_eq!(m2, m2_deserialized);
+
+        // Elements are serialized as their canonical values, and non-canonical values are rejected.
+        assert_eq!(serde_json::to_string(&f_p_minus_1).unwrap(), "2130706432");
+        assert!(serde_json::from_str::<F>("2130706433").is_err());
     }
 
     test_field!(crate::KoalaBear);
+    test_prime_field_32!(crate::KoalaBear);
     test_two_adic_field!(crate::KoalaBear);
 
     test_field_dft!(radix2dit, crate::KoalaBear, p3_dft::Radix2Dit<_>);
@@ -220,4 +225,14 @@ mod tests {
         crate::KoalaBear,
         p3_monty_31::dft::RecursiveDft<_>
     );
+    test_field_dft!(
+        recur_bowers,
+        crate::KoalaBear,
+        p3_monty_31::dft::RecursiveBowers<_>
+    );
+    test_field_dft!(
+        dispatcher,
+        crate::KoalaBear,
+        p3_monty_31::dft::DftDispatcher<_>
+    );
 }
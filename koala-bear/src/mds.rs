@@ -0,0 +1,382 @@
+//! MDS matrices over the KoalaBear field, and permutations defined by them.
+//!
+//! NB: Not all sizes have fast implementations of their permutations.
+//! Supported sizes: 8, 12, 16, 24, 32, 64.
+//!
+//! Sizes 8 and 12 reuse the small circulant matrices used for BabyBear. Being MDS depends on the
+//! prime, so the tests check every square minor for KoalaBear, by Gaussian elimination.
+//!
+//! Sizes 16, 32 and 64 are MDS by construction: with `w = 3^((p - 1)/n)` a primitive `n`-th root
+//! of unity, the Cauchy matrix `1/(w^i - 3 w^j)` equals the circulant matrix with first column
+//! `c_k = 1/(w^k - 3)` times the diagonal matrix `diag(w^{-j})`. Every minor of a Cauchy matrix is
+//! non-zero, and scaling columns does not change that. The tests check that each first column is
+//! this one.
+//!
+//! There is no circulant matrix of size 24 built in the same way, as `3` does not divide `p - 1`,
+//! so there is no subgroup of order 24. Size 24 is instead the Cauchy matrix `1/(x_i - y_j)` with
+//! `x_i = i` and `y_j = -1 - j`, i.e. the Hilbert matrix `1/(i + j + 1)`, which is MDS as no
+//! `i + j + 1 <= 47` is a multiple of `p`. It is multiplied by densely.
+
+use p3_field::FieldAlgebra;
+use p3_mds::util::first_row_to_first_col;
+use p3_mds::MdsPermutation;
+use p3_monty_31::{MDSUtils, MdsMatrixMontyField31};
+use p3_symmetric::Permutation;
+
+use crate::KoalaBear;
+
+#[derive(Clone, Debug, Default)]
+pub struct MDSKoalaBearData;
+
+impl MDSUtils for MDSKoalaBearData {
+    const MATRIX_CIRC_MDS_8_COL: [i64; 8] = first_row_to_first_col(&[7, 1, 3, 8, 8, 3, 4, 9]);
+    const MATRIX_CIRC_MDS_12_COL: [i64; 12] =
+        first_row_to_first_col(&[1, 1, 2, 1, 8, 9, 10, 7, 5, 9, 4, 10]);
+    const MATRIX_CIRC_MDS_16_COL: [i64; 16] = first_row_to_first_col(&[
+        0x3F800000, 0x4F99EF64, 0x3B8C5D5B, 0x3332E1F0, 0x32B34CCD, 0x7E0FA758, 0x406A6A9F,
+        0x00767534, 0x1FC00000, 0x38F410E3, 0x6573B561, 0x2624D594, 0x65B3199A, 0x2A30E4D4,
+        0x03CDB499, 0x0B8254A5,
+    ]);
+    const MATRIX_CIRC_MDS_32_COL: [i64; 32] = first_row_to_first_col(&[
+        0x3F800000, 0x7BDA19CC, 0x4F99EF64, 0x1B4D7137, 0x3B8C5D5B, 0x277378B4, 0x3332E1F0,
+        0x2BBE4727, 0x32B34CCD, 0x1A8CCC46, 0x7E0FA758, 0x6414E966, 0x406A6A9F, 0x5BA46FC5,
+        0x00767534, 0x4AAB673C, 0x1FC00000, 0x026FCABD, 0x38F410E3, 0x540559B0, 0x6573B561,
+        0x1FBCC6EC, 0x2624D594, 0x46C9374F, 0x65B3199A, 0x182217C7, 0x2A30E4D4, 0x14905F55,
+        0x03CDB499, 0x2D9BDFC4, 0x0B8254A5, 0x4B5F774D,
+    ]);
+    const MATRIX_CIRC_MDS_64_COL: [i64; 64] = first_row_to_first_col(&[
+        0x3F800000, 0x22B778E0, 0x7BDA19CC, 0x57884B53, 0x4F99EF64, 0x6E027BC0, 0x1B4D7137,
+        0x30725E67, 0x3B8C5D5B, 0x6C4E9E95, 0x277378B4, 0x4A76BF56, 0x3332E1F0, 0x56D4032A,
+        0x2BBE4727, 0x0B3A6E97, 0x32B34CCD, 0x689F01F7, 0x1A8CCC46, 0x6EA8C647, 0x7E0FA758,
+        0x1A0ECB90, 0x6414E966, 0x263DD5E1, 0x406A6A9F, 0x5F7485DF, 0x5BA46FC5, 0x6A832EA7,
+        0x00767534, 0x1804C817, 0x4AAB673C, 0x378D17A6, 0x1FC00000, 0x22B36261, 0x026FCABD,
+        0x6ACB8DC6, 0x38F410E3, 0x01F3B31A, 0x540559B0, 0x63877746, 0x6573B561, 0x585E7E2D,
+        0x1FBCC6EC, 0x29FEA67D, 0x2624D594, 0x654EA2AD, 0x46C9374F, 0x2601C05E, 0x65B3199A,
+        0x6FAFB76D, 0x182217C7, 0x56DAD6F1, 0x2A30E4D4, 0x2BAE8988, 0x14905F55, 0x73347964,
+        0x03CDB499, 0x75C8D13C, 0x2D9BDFC4, 0x371989E4, 0x0B8254A5, 0x3711FD69, 0x4B5F774D,
+        0x1CDF5438,
+    ]);
+}
+
+/// `1/k` for `k = 1, ..., 47`: row `i` of the size 24 matrix is entries `i` to `i + 23`.
+const INVERSES_TO_47: [KoalaBear; 47] = KoalaBear::new_array([
+    1, 1065353217, 710235478, 1598029825, 1278423860, 355117739, 913159900, 1864368129, 946980637,
+    639211930, 1162203509, 1242912086, 1147303464, 456579950, 1846612242, 1997537281, 1880035088,
+    1538843535, 1457851770, 319605965, 1014622111, 1646454971, 1389591152, 621456043, 255684772,
+    573651732, 1025895690, 228289975, 587781085, 923306121, 824789587, 2064121857, 1807872125,
+    940017544, 182631980, 1834774984, 978973226, 728925885, 382434488, 1225156199, 1507085038,
+    1572664272, 247756562, 1888580702, 615537414, 694795576, 317339256,
+]);
+
+#[derive(Clone, Debug, Default)]
+pub struct MdsMatrixKoalaBear {
+    /// The circulant matrices of every size but 24.
+    circulant: MdsMatrixMontyField31<MDSKoalaBearData>,
+}
+
+impl Permutation<[KoalaBear; 8]> for MdsMatrixKoalaBear {
+    fn permute(&self, input: [KoalaBear; 8]) -> [KoalaBear; 8] {
+        self.circulant.permute(input)
+    }
+
+    fn permute_mut(&self, input: &mut [KoalaBear; 8]) {
+        self.circulant.permute_mut(input);
+    }
+}
+impl MdsPermutation<KoalaBear, 8> for MdsMatrixKoalaBear {}
+
+impl Permutation<[KoalaBear; 12]> for MdsMatrixKoalaBear {
+    fn permute(&self, input: [KoalaBear; 12]) -> [KoalaBear; 12] {
+        self.circulant.permute(input)
+    }
+
+    fn permute_mut(&self, input: &mut [KoalaBear; 12]) {
+        self.circulant.permute_mut(input);
+    }
+}
+impl MdsPermutation<KoalaBear, 12> for MdsMatrixKoalaBear {}
+
+impl Permutation<[KoalaBear; 16]> for MdsMatrixKoalaBear {
+    fn permute(&self, input: [KoalaBear; 16]) -> [KoalaBear; 16] {
+        self.circulant.permute(input)
+    }
+
+    fn permute_mut(&self, input: &mut [KoalaBear; 16]) {
+        self.circulant.permute_mut(input);
+    }
+}
+impl MdsPermutation<KoalaBear, 16> for MdsMatrixKoalaBear {}
+
+impl Permutation<[KoalaBear; 24]> for MdsMatrixKoalaBear {
+    fn permute(&self, input: [KoalaBear; 24]) -> [KoalaBear; 24] {
+        core::array::from_fn(|i| {
+            KoalaBear::dot_product(&input, INVERSES_TO_47[i..i + 24].try_into().unwrap())
+        })
+    }
+
+    fn permute_mut(&self, input: &mut [KoalaBear; 24]) {
+        *input = self.permute(*input);
+    }
+}
+impl MdsPermutation<KoalaBear, 24> for MdsMatrixKoalaBear {}
+
+impl Permutation<[KoalaBear; 32]> for MdsMatrixKoalaBear {
+    fn permute(&self, input: [KoalaBear; 32]) -> [KoalaBear; 32] {
+        self.circulant.permute(input)
+    }
+
+    fn permute_mut(&self, input: &mut [KoalaBear; 32]) {
+        self.circulant.permute_mut(input);
+    }
+}
+impl MdsPermutation<KoalaBear, 32> for MdsMatrixKoalaBear {}
+
+impl Permutation<[KoalaBear; 64]> for MdsMatrixKoalaBear {
+    fn permute(&self, input: [KoalaBear; 64]) -> [KoalaBear; 64] {
+        self.circulant.permute(input)
+    }
+
+    fn permute_mut(&self, input: &mut [KoalaBear; 64]) {
+        self.circulant.permute_mut(input);
+    }
+}
+impl MdsPermutation<KoalaBear, 64> for MdsMatrixKoalaBear {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_field::{Field, FieldAlgebra, PrimeField64};
+    use p3_monty_31::MDSUtils;
+    use p3_poseidon::Poseidon;
+    use p3_symmetric::Permutation;
+    use rand::SeedableRng;
+    use rand_xoshiro::Xoroshiro128Plus;
+
+    use super::{MDSKoalaBearData, MdsMatrixKoalaBear, INVERSES_TO_47};
+    use crate::KoalaBear;
+
+    type F = KoalaBear;
+
+    /// Check that every square minor of the `N x N` circulant matrix with first column `col` is
+    /// non-zero, by Gaussian elimination on each one.
+    fn assert_circulant_mds<const N: usize>(col: [i64; N]) {
+        let col = col.map(|c| F::from_canonical_u64(c as u64));
+        let entry = |i: usize, j: usize| col[(N + i - j) % N];
+        for rows in 1..(1u32 << N) {
+            for cols in (1..(1u32 << N)).filter(|c| c.count_ones() == rows.count_ones()) {
+                let rs = (0..N).filter(|&i| rows >> i & 1 == 1);
+                let cs: Vec<_> = (0..N).filter(|&j| cols >> j & 1 == 1).collect();
+                let mut m: Vec<Vec<F>> = rs
+                    .map(|i| cs.iter().map(|&j| entry(i, j)).collect())
+                    .collect();
+                let k = m.len();
+                for c in 0..k {
+                    let pivot = (c..k).find(|&r| !m[r][c].is_zero()).unwrap_or_else(|| {
+                        panic!("singular minor: rows {rows:#x}, cols {cols:#x}")
+                    });
+                    m.swap(c, pivot);
+                    let inv = m[c][c].inverse();
+                    for r in c + 1..k {
+                        let factor = m[r][c] * inv;
+                        for j in c..k {
+                            let sub = factor * m[c][j];
+                            m[r][j] -= sub;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check that the circulant matrix with first column `col` is the Cauchy-derived matrix
+    /// described in the module documentation.
+    fn assert_circulant_cauchy<const N: usize>(col: [i64; N]) {
+        let w = F::GENERATOR.exp_u64((F::ORDER_U64 - 1) / N as u64);
+        assert_eq!(w.exp_u64(N as u64 / 2), F::NEG_ONE);
+        for (k, c) in col.into_iter().enumerate() {
+            let c = F::from_canonical_u64(c as u64);
+            assert_eq!(c * (w.exp_u64(k as u64) - F::GENERATOR), F::ONE);
+        }
+    }
+
+    #[test]
+    fn mds_8_and_12_are_mds() {
+        assert_circulant_mds(MDSKoalaBearData::MATRIX_CIRC_MDS_8_COL);
+        assert_circulant_mds(MDSKoalaBearData::MATRIX_CIRC_MDS_12_COL);
+    }
+
+    #[test]
+    fn mds_16_32_and_64_are_cauchy() {
+        assert_circulant_cauchy(MDSKoalaBearData::MATRIX_CIRC_MDS_16_COL);
+        assert_circulant_cauchy(MDSKoalaBearData::MATRIX_CIRC_MDS_32_COL);
+        assert_circulant_cauchy(MDSKoalaBearData::MATRIX_CIRC_MDS_64_COL);
+    }
+
+    #[test]
+    fn mds_24_is_cauchy() {
+        for (k, inverse) in INVERSES_TO_47.into_iter().enumerate() {
+            assert_eq!(inverse * F::from_canonical_usize(k + 1), F::ONE);
+        }
+    }
+
+    #[test]
+    fn koalabear8() {
+        let input: [F; 8] = [
+            149982203, 704414938, 1392344226, 1491294829, 197688581, 899311491, 1056162890,
+            764723777,
+        ]
+        .map(F::from_canonical_u64);
+
+        let output = MdsMatrixKoalaBear::default().permute(input);
+
+        let expected: [F; 8] = [
+            1287693848, 1581880264, 486390426, 1086355928, 1912946752, 702917380, 387610364,
+            1767054953,
+        ]
+        .map(F::from_canonical_u64);
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn koalabear12() {
+        let input: [F; 12] = [
+            449808672, 1084903689, 171434081, 1641662408, 655935650, 1228113479, 1508774146,
+            1421170583, 1274387589, 403072570, 1601967553, 1752628859,
+        ]
+        .map(F::from_canonical_u64);
+
+        let output = MdsMatrixKoalaBear::default().permute(input);
+
+        let expected: [F; 12] = [
+            2084003272, 837427297, 244118183, 1420842050, 843704766, 1343092527, 395183054,
+            1151377652, 1730206919, 763784160, 52485228, 1663415488,
+        ]
+        .map(F::from_canonical_u64);
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn koalabear16() {
+        let input: [F; 16] = [
+            39886939, 2011156828, 27642459, 208322461, 2080282783, 836237753, 763905965,
+            1984915582, 565126060, 1782274063, 2038996442, 1721684758, 544756088, 815114208,
+            2065774539, 1228994480,
+        ]
+        .map(F::from_canonical_u64);
+
+        let output = MdsMatrixKoalaBear::default().permute(input);
+
+        let expected: [F; 16] = [
+            135476893, 1566036145, 1484479275, 1429389810, 1053620149, 910631500, 1503594036,
+            1126523064, 1031919767, 894957504, 2090902491, 1779420115, 1396974452, 1102021554,
+            13836683, 1844007940,
+        ]
+        .map(F::from_canonical_u64);
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn koalabear24() {
+        let input: [F; 24] = [
+            157305976, 146102703, 119566464, 1916278631, 369634796, 1744289962, 1025654776,
+            1860108409, 1958689809, 1608147253, 1289864134, 523711080, 694878937, 1880620166,
+            1918531964, 1862400354, 377703889, 124205164, 914984196, 660014323, 852066105,
+            359295307, 601626532, 174109107,
+        ]
+        .map(F::from_canonical_u64);
+
+        let output = MdsMatrixKoalaBear::default().permute(input);
+
+        let expected: [F; 24] = [
+            1377024784, 831271916, 227927541, 1133234677, 1195916586, 169640197, 480288154,
+            1889257151, 1610071874, 2038139708, 1552953463, 552652429, 1912419540, 1373274770,
+            920032032, 320317785, 270345186, 757900382, 1267227066, 612903986, 1358888033,
+            669587159, 1912736125, 503356127,
+        ]
+        .map(F::from_canonical_u64);
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn koalabear32() {
+        let input: [F; 32] = [
+            491926639, 1319383600, 1898187379, 180331270, 1723031861, 1400175095, 397994274,
+            49971757, 306272827, 1831912802, 468535188, 1544568889, 1114243593, 1410646647,
+            1032561517, 343087019, 1817069394, 1629401981, 1037947417, 1451211528, 1719077331,
+            1008116199, 734732114, 1925215432, 1767196516, 317664581, 1162076651, 714677,
+            782036973, 428695191, 166795905, 251324149,
+        ]
+        .map(F::from_canonical_u64);
+
+        let output = MdsMatrixKoalaBear::default().permute(input);
+
+        let expected: [F; 32] = [
+            1538302579, 1344571379, 944545889, 1875637410, 2086819095, 984844310, 116139186,
+            965312425, 1236763214, 719256597, 1739135232, 431041961, 1970617820, 825074372,
+            1726311352, 1198397467, 2088024730, 157886485, 113428783, 1081611952, 257734456,
+            349215119, 1502312500, 1187383551, 61167119, 1280291807, 37425689, 1288727358,
+            608615174, 1673365703, 1157492891, 1735772407,
+        ]
+        .map(F::from_canonical_u64);
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn koalabear64() {
+        let input: [F; 64] = [
+            1103635030, 783239254, 2122012652, 1204190581, 1366222545, 1944129258, 1481028382,
+            1490120284, 927634381, 1304512256, 527518981, 871185958, 1388426043, 651902213,
+            164257100, 579720568, 1072663191, 495166891, 1948505502, 455511753, 1491155752,
+            1540891214, 78341207, 592039221, 1411255569, 1510245508, 501808767, 480067908,
+            1327703114, 1757388607, 84722148, 2116982772, 1456644483, 395103279, 2099510668,
+            1579956393, 1124496440, 344620540, 564154863, 505995716, 1783534630, 1452085293,
+            478537061, 497166395, 590069616, 941298206, 121360795, 1710918950, 58295855,
+            1966656716, 823903742, 46772869, 2008800701, 1933724967, 547645206, 2084431637,
+            404710466, 690580977, 1142837322, 1086691669, 720145094, 266900184, 2102775952,
+            887192108,
+        ]
+        .map(F::from_canonical_u64);
+
+        let output = MdsMatrixKoalaBear::default().permute(input);
+
+        let expected: [F; 64] = [
+            1797315835, 32848463, 1887579402, 389801239, 666087865, 236625301, 1240728365,
+            1825728269, 807799435, 925050672, 1475418634, 1678196213, 1502456727, 37295902,
+            134415973, 1368292717, 1598617344, 1580879027, 512247644, 978069482, 1687392347,
+            997694675, 564105345, 1924412606, 410691282, 1510248772, 512022369, 1631335888,
+            1909305181, 1310448088, 1238072892, 1976922864, 1909765007, 610715503, 2102512741,
+            1534836699, 1318400318, 916735993, 1766391421, 1378356669, 338024968, 626615010,
+            381833389, 787620351, 743533390, 680763270, 1957405518, 1568185739, 601465190,
+            612622383, 623577852, 1934060054, 523281895, 35572232, 2034846305, 424013256, 12895010,
+            2096295289, 1717788501, 92874799, 972844251, 464426830, 2098196824, 615123086,
+        ]
+        .map(F::from_canonical_u64);
+
+        assert_eq!(output, expected);
+    }
+
+    /// A classic Poseidon instance over KoalaBear, with `x^3` as the S-box (as `3` does not divide
+    /// `p - 1`) and round constants drawn from `Xoroshiro128Plus::seed_from_u64(1)`.
+    #[test]
+    fn poseidon_width_16() {
+        let mds = MdsMatrixKoalaBear::default();
+        let mut rng = Xoroshiro128Plus::seed_from_u64(1);
+        let poseidon = Poseidon::<F, MdsMatrixKoalaBear, 16, 3>::new_from_rng(4, 20, mds, &mut rng);
+
+        let input: [F; 16] = core::array::from_fn(F::from_canonical_usize);
+        let expected: [F; 16] = [
+            1975020868, 122861461, 685194780, 2062849191, 1324944019, 248261427, 1191342180,
+            1511827970, 1278511795, 358461548, 315327270, 879902760, 285058477, 1019908000,
+            1421737799, 344873772,
+        ]
+        .map(F::from_canonical_u64);
+
+        assert_eq!(poseidon.permute(input), expected);
+    }
+}
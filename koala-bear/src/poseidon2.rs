@@ -20,7 +20,7 @@ use p3_monty_31::{
     GenericPoseidon2LinearLayersMonty31, InternalLayerBaseParameters, InternalLayerParameters,
     MontyField31, Poseidon2ExternalLayerMonty31, Poseidon2InternalLayerMonty31,
 };
-use p3_poseidon2::Poseidon2;
+use p3_poseidon2::{ExternalLayerConstants, Poseidon2};
 
 use crate::{KoalaBear, KoalaBearParameters};
 
@@ -258,6 +258,162 @@ impl InternalLayerBaseParameters<KoalaBearParameters, 24> for KoalaBearInternalL
 impl InternalLayerParameters<KoalaBearParameters, 16> for KoalaBearInternalLayerParameters {}
 impl InternalLayerParameters<KoalaBearParameters, 24> for KoalaBearInternalLayerParameters {}
 
+/// Create a width 16 Poseidon2 permutation on KoalaBear with fixed round constants.
+///
+/// These are the constants `Poseidon2KoalaBear::<16>::new_from_rng_128` samples from
+/// `Xoroshiro128Plus::seed_from_u64(1)`, as described on `Poseidon2::new_from_rng`.
+pub fn default_koalabear_poseidon2_16() -> Poseidon2KoalaBear<16> {
+    Poseidon2::new(
+        ExternalLayerConstants::new(
+            KOALABEAR_RC16_EXTERNAL_INITIAL.to_vec(),
+            KOALABEAR_RC16_EXTERNAL_FINAL.to_vec(),
+        ),
+        KOALABEAR_RC16_INTERNAL.to_vec(),
+    )
+}
+
+/// Create a width 24 Poseidon2 permutation on KoalaBear with fixed round constants, sampled as
+/// for [`default_koalabear_poseidon2_16`].
+pub fn default_koalabear_poseidon2_24() -> Poseidon2KoalaBear<24> {
+    Poseidon2::new(
+        ExternalLayerConstants::new(
+            KOALABEAR_RC24_EXTERNAL_INITIAL.to_vec(),
+            KOALABEAR_RC24_EXTERNAL_FINAL.to_vec(),
+        ),
+        KOALABEAR_RC24_INTERNAL.to_vec(),
+    )
+}
+
+/// Initial external round constants for the width 16 Poseidon2 permutation on KoalaBear.
+///
+/// See [`default_koalabear_poseidon2_16`] for how these were generated.
+pub const KOALABEAR_RC16_EXTERNAL_INITIAL: [[KoalaBear; 16]; 4] = [
+    KoalaBear::new_array([
+        0x08f08bfb, 0x29fc84da, 0x52fd7ca2, 0x58e35a6d, 0x0bc87d05, 0x359a6783, 0x3ef3c44a,
+        0x2d94c241, 0x1acf8920, 0x40aa5109, 0x0a37e061, 0x61d9c7c8, 0x2718c8a2, 0x49338647,
+        0x59ee1102, 0x54b55797,
+    ]),
+    KoalaBear::new_array([
+        0x4bf59c85, 0x1806663a, 0x5f7c15c1, 0x6876fe7b, 0x0260a05b, 0x77dfd15c, 0x01a5ca5b,
+        0x0c6abf9d, 0x7bfe989f, 0x31d7f9b9, 0x2d8847ad, 0x764f687e, 0x21af23ac, 0x6a3b580f,
+        0x79889dda, 0x669ed316,
+    ]),
+    KoalaBear::new_array([
+        0x20785178, 0x3095a7e0, 0x7b2137cb, 0x4940f7b0, 0x1d52346f, 0x4ea43230, 0x71240a73,
+        0x0abfa306, 0x66b36135, 0x5374f9f7, 0x17b8e922, 0x02fa822d, 0x12415a3b, 0x6d30c562,
+        0x1bed4794, 0x5c104039,
+    ]),
+    KoalaBear::new_array([
+        0x426a0209, 0x5414c277, 0x3d8ba36d, 0x147317ab, 0x6c4e4752, 0x611eb37d, 0x3dddd219,
+        0x567fbb08, 0x667709d3, 0x3c16a1e7, 0x2bcb1f52, 0x72c074c8, 0x69554764, 0x12ef2d45,
+        0x4543e1eb, 0x000ae7b5,
+    ]),
+];
+
+/// Terminal external round constants for the width 16 Poseidon2 permutation on KoalaBear.
+///
+/// See [`default_koalabear_poseidon2_16`] for how these were generated.
+pub const KOALABEAR_RC16_EXTERNAL_FINAL: [[KoalaBear; 16]; 4] = [
+    KoalaBear::new_array([
+        0x2e9cefed, 0x198d5e97, 0x09f11a81, 0x0efae6f5, 0x41c82256, 0x2eaf4856, 0x7e7b57ec,
+        0x47c67d75, 0x516ee6d1, 0x73e10eea, 0x5846b31e, 0x58d16e5c, 0x374a93cd, 0x4dc14700,
+        0x1f714d05, 0x33ed3e26,
+    ]),
+    KoalaBear::new_array([
+        0x52c1b33b, 0x26db3d05, 0x09ca5d4c, 0x228dd578, 0x3fef8a97, 0x1d83a5ab, 0x7423d59e,
+        0x1b268ec9, 0x58e13b28, 0x5bd8224e, 0x04ab6457, 0x2349cd35, 0x541e0d11, 0x5a048484,
+        0x1de8fe7f, 0x1c9d4144,
+    ]),
+    KoalaBear::new_array([
+        0x4f23244a, 0x68bf9f3f, 0x050cc1e4, 0x7e2e97f4, 0x56d2a183, 0x178ccc2f, 0x7d23fd8c,
+        0x5e2c38a9, 0x43067438, 0x148a7dfc, 0x21a051ef, 0x1e28e1c4, 0x6a4e9426, 0x568d102d,
+        0x1c85e565, 0x1da2283b,
+    ]),
+    KoalaBear::new_array([
+        0x232bbf70, 0x381b121e, 0x073bd19b, 0x65fa8d26, 0x0379862f, 0x7538cccc, 0x311bc5fe,
+        0x02c9b285, 0x77bbddbd, 0x73424d27, 0x20a46716, 0x7c3de715, 0x181f6442, 0x29296df1,
+        0x441e504a, 0x40c59955,
+    ]),
+];
+
+/// Internal round constants for the width 16 Poseidon2 permutation on KoalaBear.
+///
+/// See [`default_koalabear_poseidon2_16`] for how these were generated.
+pub const KOALABEAR_RC16_INTERNAL: [KoalaBear; 20] = KoalaBear::new_array([
+    0x2aec8ac6, 0x0fe892d8, 0x7d55d090, 0x34e17a2c, 0x692287a3, 0x1c9f14a8, 0x1351d071, 0x5ba96ec8,
+    0x72b44e21, 0x231d148f, 0x298e8602, 0x7477dd63, 0x12bbaadb, 0x4fd95f10, 0x4c1cfbd3, 0x7e157163,
+    0x49e69d18, 0x0ef57a66, 0x3f0e09b8, 0x09a4f750,
+]);
+
+/// Initial external round constants for the width 24 Poseidon2 permutation on KoalaBear.
+///
+/// See [`default_koalabear_poseidon2_24`] for how these were generated.
+pub const KOALABEAR_RC24_EXTERNAL_INITIAL: [[KoalaBear; 24]; 4] = [
+    KoalaBear::new_array([
+        0x08f08bfb, 0x29fc84da, 0x52fd7ca2, 0x58e35a6d, 0x0bc87d05, 0x359a6783, 0x3ef3c44a,
+        0x2d94c241, 0x1acf8920, 0x40aa5109, 0x0a37e061, 0x61d9c7c8, 0x2718c8a2, 0x49338647,
+        0x59ee1102, 0x54b55797, 0x4bf59c85, 0x1806663a, 0x5f7c15c1, 0x6876fe7b, 0x0260a05b,
+        0x77dfd15c, 0x01a5ca5b, 0x0c6abf9d,
+    ]),
+    KoalaBear::new_array([
+        0x7bfe989f, 0x31d7f9b9, 0x2d8847ad, 0x764f687e, 0x21af23ac, 0x6a3b580f, 0x79889dda,
+        0x669ed316, 0x20785178, 0x3095a7e0, 0x7b2137cb, 0x4940f7b0, 0x1d52346f, 0x4ea43230,
+        0x71240a73, 0x0abfa306, 0x66b36135, 0x5374f9f7, 0x17b8e922, 0x02fa822d, 0x12415a3b,
+        0x6d30c562, 0x1bed4794, 0x5c104039,
+    ]),
+    KoalaBear::new_array([
+        0x426a0209, 0x5414c277, 0x3d8ba36d, 0x147317ab, 0x6c4e4752, 0x611eb37d, 0x3dddd219,
+        0x567fbb08, 0x667709d3, 0x3c16a1e7, 0x2bcb1f52, 0x72c074c8, 0x69554764, 0x12ef2d45,
+        0x4543e1eb, 0x000ae7b5, 0x2e9cefed, 0x198d5e97, 0x09f11a81, 0x0efae6f5, 0x41c82256,
+        0x2eaf4856, 0x7e7b57ec, 0x47c67d75,
+    ]),
+    KoalaBear::new_array([
+        0x516ee6d1, 0x73e10eea, 0x5846b31e, 0x58d16e5c, 0x374a93cd, 0x4dc14700, 0x1f714d05,
+        0x33ed3e26, 0x52c1b33b, 0x26db3d05, 0x09ca5d4c, 0x228dd578, 0x3fef8a97, 0x1d83a5ab,
+        0x7423d59e, 0x1b268ec9, 0x58e13b28, 0x5bd8224e, 0x04ab6457, 0x2349cd35, 0x541e0d11,
+        0x5a048484, 0x1de8fe7f, 0x1c9d4144,
+    ]),
+];
+
+/// Terminal external round constants for the width 24 Poseidon2 permutation on KoalaBear.
+///
+/// See [`default_koalabear_poseidon2_24`] for how these were generated.
+pub const KOALABEAR_RC24_EXTERNAL_FINAL: [[KoalaBear; 24]; 4] = [
+    KoalaBear::new_array([
+        0x4f23244a, 0x68bf9f3f, 0x050cc1e4, 0x7e2e97f4, 0x56d2a183, 0x178ccc2f, 0x7d23fd8c,
+        0x5e2c38a9, 0x43067438, 0x148a7dfc, 0x21a051ef, 0x1e28e1c4, 0x6a4e9426, 0x568d102d,
+        0x1c85e565, 0x1da2283b, 0x232bbf70, 0x381b121e, 0x073bd19b, 0x65fa8d26, 0x0379862f,
+        0x7538cccc, 0x311bc5fe, 0x02c9b285,
+    ]),
+    KoalaBear::new_array([
+        0x77bbddbd, 0x73424d27, 0x20a46716, 0x7c3de715, 0x181f6442, 0x29296df1, 0x441e504a,
+        0x40c59955, 0x2aec8ac6, 0x0fe892d8, 0x7d55d090, 0x34e17a2c, 0x692287a3, 0x1c9f14a8,
+        0x1351d071, 0x5ba96ec8, 0x72b44e21, 0x231d148f, 0x298e8602, 0x7477dd63, 0x12bbaadb,
+        0x4fd95f10, 0x4c1cfbd3, 0x7e157163,
+    ]),
+    KoalaBear::new_array([
+        0x49e69d18, 0x0ef57a66, 0x3f0e09b8, 0x09a4f750, 0x6ddad610, 0x1fa35169, 0x461b8667,
+        0x33d8e771, 0x2321d3db, 0x3b31f48e, 0x0b2924d7, 0x4a5c9928, 0x40884d1b, 0x40734b9f,
+        0x7eb667a3, 0x688da5c7, 0x54080af9, 0x0248287a, 0x272aeeb4, 0x26d276ce, 0x049a507a,
+        0x2dfe2059, 0x495e9f8c, 0x63baaeb4,
+    ]),
+    KoalaBear::new_array([
+        0x759e0854, 0x517fc6fa, 0x09176fae, 0x74e05384, 0x73808126, 0x6a3e5400, 0x21825f52,
+        0x2f845fcf, 0x043d9a06, 0x73d82455, 0x18a28b2f, 0x1b09da42, 0x52b76670, 0x0c5cf462,
+        0x1c6bcd5f, 0x318a2150, 0x6060d3b7, 0x4330c500, 0x3383d93f, 0x5ebd0e37, 0x14811352,
+        0x3336260a, 0x472f4733, 0x033514c2,
+    ]),
+];
+
+/// Internal round constants for the width 24 Poseidon2 permutation on KoalaBear.
+///
+/// See [`default_koalabear_poseidon2_24`] for how these were generated.
+pub const KOALABEAR_RC24_INTERNAL: [KoalaBear; 23] = KoalaBear::new_array([
+    0x57ef708f, 0x5fa90922, 0x05120b57, 0x5613a9ff, 0x54558143, 0x26f55c2d, 0x467db662, 0x52c0e184,
+    0x73fdd05e, 0x0ffc2746, 0x28e5c606, 0x79d1930e, 0x2b781c03, 0x45b33b7a, 0x5e6a2440, 0x68e235d5,
+    0x339eca2b, 0x0adf4b40, 0x5ece7708, 0x70c41b3f, 0x75348d7c, 0x2872287a, 0x62ab8f61,
+]);
+
 #[cfg(test)]
 mod tests {
     use p3_field::FieldAlgebra;
@@ -278,7 +434,7 @@ mod tests {
     /// vector([KB.random_element() for t in range(16)]).
     #[test]
     fn test_poseidon2_width_16_random() {
-        let mut input: [F; 16] = [
+        let input: [F; 16] = [
             894848333, 1437655012, 1200606629, 1690012884, 71131202, 1749206695, 1717947831,
             120589055, 19776022, 42382981, 1831865506, 724844064, 171220207, 1299207443, 227047920,
             1783754913,
@@ -294,9 +450,10 @@ mod tests {
 
         let mut rng = Xoroshiro128Plus::seed_from_u64(1);
         let perm = Poseidon2KoalaBear::new_from_rng_128(&mut rng);
+        assert_eq!(perm.permute(input), expected);
 
-        perm.permute_mut(&mut input);
-        assert_eq!(input, expected);
+        // The default constants were sampled the same way, so give the same output.
+        assert_eq!(default_koalabear_poseidon2_16().permute(input), expected);
     }
 
     /// Test on a roughly random input.
@@ -305,7 +462,7 @@ mod tests {
     /// vector([KB.random_element() for t in range(24)]).
     #[test]
     fn test_poseidon2_width_24_random() {
-        let mut input: [F; 24] = [
+        let input: [F; 24] = [
             886409618, 1327899896, 1902407911, 591953491, 648428576, 1844789031, 1198336108,
             355597330, 1799586834, 59617783, 790334801, 1968791836, 559272107, 31054313,
             1042221543, 474748436, 135686258, 263665994, 1962340735, 1741539604, 2026927696,
@@ -323,9 +480,9 @@ mod tests {
 
         let mut rng = Xoroshiro128Plus::seed_from_u64(1);
         let perm = Poseidon2KoalaBear::new_from_rng_128(&mut rng);
+        assert_eq!(perm.permute(input), expected);
 
-        perm.permute_mut(&mut input);
-        assert_eq!(input, expected);
+        assert_eq!(default_koalabear_poseidon2_24().permute(input), expected);
     }
 
     /// Test the generic internal layer against the optimized internal layer
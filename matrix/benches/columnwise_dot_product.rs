@@ -29,5 +29,29 @@ fn columnwise_dot_product(c: &mut Criterion) {
         });
 }
 
-criterion_group!(benches, columnwise_dot_product);
+fn mul_vec(c: &mut Criterion) {
+    let mut rng = ChaChaRng::seed_from_u64(0);
+
+    type F = BabyBear;
+    type EF = BinomialExtensionField<F, 4>;
+    let log_rows = 18;
+    let width = 100;
+
+    c.benchmark_group("babybear")
+        .sample_size(10)
+        .bench_function("mul_vec", |b| {
+            b.iter_batched(
+                || {
+                    (
+                        RowMajorMatrix::<F>::rand_nonzero(&mut rng, 1 << log_rows, width),
+                        RowMajorMatrix::<EF>::rand_nonzero(&mut rng, width, 1).values,
+                    )
+                },
+                |(m, v)| m.mul_vec(&v),
+                BatchSize::PerIteration,
+            );
+        });
+}
+
+criterion_group!(benches, columnwise_dot_product, mul_vec);
 criterion_main!(benches);
@@ -1,4 +1,5 @@
 use criterion::{criterion_group, criterion_main, BenchmarkGroup, Criterion, Throughput};
+use p3_baby_bear::BabyBear;
 use p3_matrix::dense::RowMajorMatrix;
 use rand::thread_rng;
 
@@ -37,5 +38,27 @@ fn transpose_benchmark(c: &mut Criterion) {
     inner(&mut g, &LARGE_DIMS);
 }
 
-criterion_group!(benches, transpose_benchmark);
+/// The shape of a trace going into a DFT batch: 2^20 rows of 64 BabyBear columns.
+fn transpose_baby_bear_trace(c: &mut Criterion) {
+    let (nrows, ncols) = (1 << 20, 64);
+    let mut rng = thread_rng();
+    let trace = RowMajorMatrix::<BabyBear>::rand(&mut rng, nrows, ncols);
+    let mut transposed = RowMajorMatrix::default(nrows, ncols);
+
+    let mut g = c.benchmark_group("transpose_baby_bear");
+    g.sample_size(10);
+    g.throughput(Throughput::Bytes(
+        (nrows * ncols * core::mem::size_of::<BabyBear>()) as u64,
+    ));
+    g.bench_function("2^20 x 64", |b| {
+        b.iter(|| trace.transpose_into(&mut transposed))
+    });
+
+    let mut square = RowMajorMatrix::<BabyBear>::rand(&mut rng, 1 << 12, 1 << 12);
+    g.bench_function("2^12 x 2^12 in place", |b| {
+        b.iter(|| square.transpose_in_place())
+    });
+}
+
+criterion_group!(benches, transpose_benchmark, transpose_baby_bear_trace);
 criterion_main!(benches);
@@ -13,20 +13,25 @@ pub trait BitReversableMatrix<T: Send + Sync>: Matrix<T> {
     fn bit_reverse_rows(self) -> Self::BitRev;
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct BitReversalPerm {
+    height: usize,
     log_height: usize,
 }
 
 impl BitReversalPerm {
-    /// Assumes the inner matrix height is a power of two; panics otherwise.
+    /// Assumes the inner matrix height is zero or a power of two; panics otherwise.
     pub fn new_view<T: Send + Sync, Inner: Matrix<T>>(
         inner: Inner,
     ) -> BitReversedMatrixView<Inner> {
+        let height = inner.height();
+        let log_height = if height == 0 {
+            0
+        } else {
+            log2_strict_usize(height)
+        };
         RowIndexMappedView {
-            index_map: Self {
-                log_height: log2_strict_usize(inner.height()),
-            },
+            index_map: Self { height, log_height },
             inner,
         }
     }
@@ -34,7 +39,7 @@ impl BitReversalPerm {
 
 impl RowIndexMap for BitReversalPerm {
     fn height(&self) -> usize {
-        1 << self.log_height
+        self.height
     }
     fn map_row_index(&self, r: usize) -> usize {
         reverse_bits_len(r, self.log_height)
@@ -51,6 +56,16 @@ impl RowIndexMap for BitReversalPerm {
     }
 }
 
+/// A lazy view of `Inner` with its rows in bit-reversed order: row `r` of the view is row
+/// `reverse_bits_len(r, log_height)` of `Inner`, and nothing is copied until the view is
+/// explicitly materialized with `to_row_major_matrix`.
+///
+/// Row accesses, including `row_slice` and the horizontally packed rows, go straight to the
+/// corresponding row of `Inner`. Vertically packed rows fall back to the default, which gathers
+/// `P::WIDTH` rows through `row_slice`, as consecutive rows of the view are not contiguous in
+/// `Inner`.
+///
+/// Bit-reversing the rows of a view of a `DenseMatrix` again returns the inner matrix.
 pub type BitReversedMatrixView<Inner> = RowIndexMappedView<BitReversalPerm, Inner>;
 
 impl<T: Clone + Send + Sync, S: DenseStorage<T>> BitReversableMatrix<T>
@@ -68,3 +83,74 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> BitReversableMatrix<T> for Dens
         BitReversalPerm::new_view(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::PackedValue;
+    use rand::thread_rng;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn rows_are_bit_reversed() {
+        let mut rng = thread_rng();
+        for log_h in 0..8 {
+            let h = 1 << log_h;
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
+            let view = mat.clone().bit_reverse_rows();
+            assert_eq!(view.height(), h);
+            assert_eq!(view.width(), 3);
+            for r in 0..h {
+                let expected = mat.row_slice(reverse_bits_len(r, log_h)).to_vec();
+                assert_eq!(view.row_slice(r).to_vec(), expected);
+                assert_eq!(view.row(r).collect::<Vec<_>>(), expected);
+                assert_eq!(view.get(r, 1), expected[1]);
+            }
+
+            // Materializing permutes the rows the same way.
+            let mut expected = mat.clone();
+            reverse_matrix_index_bits(&mut expected);
+            assert_eq!(view.to_row_major_matrix(), expected);
+        }
+    }
+
+    #[test]
+    fn double_bit_reversal_is_identity() {
+        let mut rng = thread_rng();
+        for log_h in 0..8 {
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, 1 << log_h, 5);
+            assert_eq!(mat.clone().bit_reverse_rows().bit_reverse_rows(), mat);
+
+            // A view of a view maps each row back to itself.
+            let nested = BitReversalPerm::new_view(BitReversalPerm::new_view(mat.clone()));
+            for r in 0..mat.height() {
+                assert_eq!(nested.row_slice(r).to_vec(), mat.row_slice(r).to_vec());
+            }
+            assert_eq!(nested.to_row_major_matrix(), mat);
+        }
+    }
+
+    #[test]
+    fn vertically_packed_rows_follow_the_permutation() {
+        type P = <F as p3_field::Field>::Packing;
+        let mut rng = thread_rng();
+        let h = 4 * P::WIDTH;
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
+        let view = mat.clone().bit_reverse_rows();
+        let materialized = view.clone().to_row_major_matrix();
+        for r in (0..h).step_by(P::WIDTH) {
+            let unpack = |packed: Vec<P>| -> Vec<F> {
+                packed.iter().flat_map(|p| p.as_slice().to_vec()).collect()
+            };
+            assert_eq!(
+                unpack(view.vertically_packed_row(r).collect()),
+                unpack(materialized.vertically_packed_row(r).collect())
+            );
+        }
+    }
+}
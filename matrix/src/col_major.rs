@@ -0,0 +1,200 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::iter::{Cloned, Skip, StepBy};
+use core::slice;
+
+use crate::dense::{DenseMatrix, DenseStorage, RowMajorMatrix};
+use crate::util::transpose_blocked;
+use crate::Matrix;
+
+/// A dense matrix stored in column-major form: column `c` is `values[c * height..(c + 1) * height]`.
+///
+/// Columns are contiguous, but rows are not, so `row` steps through the values with a stride of
+/// `height`, and `row_slice` has to collect each row into a new buffer. Code which reads many rows
+/// should convert the matrix once with `to_row_major_matrix`, which uses the blocked transpose.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColMajorMatrix<T> {
+    pub values: Vec<T>,
+    pub height: usize,
+}
+
+impl<T: Clone + Send + Sync> ColMajorMatrix<T> {
+    #[must_use]
+    pub fn new(values: Vec<T>, height: usize) -> Self {
+        debug_assert!(height == 0 || values.len() % height == 0);
+        Self { values, height }
+    }
+
+    /// Build a matrix from its columns.
+    ///
+    /// Panics if the columns do not all have the same length.
+    #[must_use]
+    pub fn from_cols(cols: Vec<Vec<T>>) -> Self {
+        let height = cols.first().map_or(0, Vec::len);
+        assert!(
+            cols.iter().all(|col| col.len() == height),
+            "columns must all have the same length"
+        );
+        Self::new(cols.concat(), height)
+    }
+
+    /// Split the matrix into its columns.
+    pub fn into_cols(self) -> Vec<Vec<T>> {
+        if self.height == 0 {
+            return vec![];
+        }
+        self.values
+            .chunks_exact(self.height)
+            .map(|col| col.to_vec())
+            .collect()
+    }
+
+    pub fn col(&self, c: usize) -> &[T] {
+        &self.values[c * self.height..(c + 1) * self.height]
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = &[T]> {
+        self.values.chunks_exact(self.height.max(1))
+    }
+
+    /// The transpose of this matrix, which is a row-major matrix with the same values, so this
+    /// is free.
+    pub fn transpose(self) -> RowMajorMatrix<T> {
+        RowMajorMatrix::new(self.values, self.height)
+    }
+}
+
+impl<T: Copy + Default + Send + Sync> ColMajorMatrix<T> {
+    /// Copy a row-major matrix into column-major form, using the blocked transpose.
+    pub fn from_row_major<S: DenseStorage<T>>(mat: &DenseMatrix<T, S>) -> Self {
+        let (width, height) = (mat.width(), mat.height());
+        let mut values = vec![T::default(); width * height];
+        transpose_blocked(mat.values.borrow(), &mut values, width, height);
+        Self::new(values, height)
+    }
+}
+
+impl<T: Copy + Default + Send + Sync> Matrix<T> for ColMajorMatrix<T> {
+    #[inline]
+    fn width(&self) -> usize {
+        if self.height == 0 {
+            0
+        } else {
+            self.values.len() / self.height
+        }
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    fn get(&self, r: usize, c: usize) -> T {
+        self.values[c * self.height + r]
+    }
+
+    type Row<'a>
+        = Cloned<StepBy<Skip<slice::Iter<'a, T>>>>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        debug_assert!(r < self.height);
+        self.values.iter().skip(r).step_by(self.height).cloned()
+    }
+
+    fn to_row_major_matrix(self) -> RowMajorMatrix<T>
+    where
+        Self: Sized,
+        T: Clone,
+    {
+        let (width, height) = (self.width(), self.height);
+        let mut values = vec![T::default(); width * height];
+        // The values are the row-major transpose, with `width` rows of length `height`.
+        transpose_blocked(&self.values, &mut values, height, width);
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_symmetric::{CryptographicHasher, PaddingFreeSponge};
+    use rand::thread_rng;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn matches_row_major() {
+        let mut rng = thread_rng();
+        for (height, width) in [(1, 1), (7, 3), (100, 37), (64, 64)] {
+            let row_major = RowMajorMatrix::<F>::rand(&mut rng, height, width);
+            let col_major = ColMajorMatrix::from_row_major(&row_major);
+            assert_eq!(col_major.dimensions(), row_major.dimensions());
+            for r in 0..row_major.height() {
+                assert!(col_major.row(r).eq(row_major.row(r)));
+                assert_eq!(*col_major.row_slice(r), *row_major.row_slice(r));
+                for c in 0..row_major.width() {
+                    assert_eq!(col_major.get(r, c), row_major.get(r, c));
+                }
+            }
+            for (c, col) in col_major.cols().enumerate() {
+                assert!(col
+                    .iter()
+                    .eq(row_major.values.iter().skip(c).step_by(width)));
+            }
+            assert_eq!(col_major.to_row_major_matrix(), row_major);
+        }
+    }
+
+    #[test]
+    fn columns_round_trip() {
+        let cols = vec![vec![1u32, 2, 3], vec![4, 5, 6]];
+        let mat = ColMajorMatrix::from_cols(cols.clone());
+        assert_eq!(mat.values, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(mat.col(1), [4, 5, 6]);
+        assert_eq!(mat.clone().into_cols(), cols);
+        // The values of a column-major matrix are those of its row-major transpose.
+        assert_eq!(
+            mat.clone().transpose(),
+            RowMajorMatrix::new(vec![1, 2, 3, 4, 5, 6], 3)
+        );
+        assert_eq!(
+            mat.to_row_major_matrix(),
+            RowMajorMatrix::new(vec![1, 4, 2, 5, 3, 6], 2)
+        );
+
+        let empty = ColMajorMatrix::<u32>::from_cols(vec![]);
+        assert_eq!(
+            empty.dimensions(),
+            RowMajorMatrix::<u32>::new(vec![], 0).dimensions()
+        );
+        assert!(empty.into_cols().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn from_cols_rejects_ragged_columns() {
+        ColMajorMatrix::from_cols(vec![vec![1u32, 2], vec![3]]);
+    }
+
+    #[test]
+    fn hashing_rows_matches_row_major() {
+        type Perm = Poseidon2BabyBear<16>;
+        let hash =
+            PaddingFreeSponge::<Perm, 16, 8, 8>::new(Perm::new_from_rng_128(&mut thread_rng()));
+        let row_major = RowMajorMatrix::<F>::rand(&mut thread_rng(), 200, 13);
+        let col_major = ColMajorMatrix::from_row_major(&row_major);
+        for r in 0..200 {
+            assert_eq!(
+                hash.hash_iter(col_major.row(r)),
+                hash.hash_iter(row_major.row(r))
+            );
+        }
+    }
+}
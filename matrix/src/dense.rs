@@ -1,22 +1,31 @@
 use alloc::borrow::Cow;
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::borrow::{Borrow, BorrowMut};
 use core::marker::PhantomData;
+use core::mem::align_of;
 use core::ops::Deref;
 use core::{iter, slice};
 
-use p3_field::{scale_slice_in_place, ExtensionField, Field, PackedValue};
+use p3_field::{scale_slice_in_place, ExtensionField, Field, PackedField, PackedValue};
 use p3_maybe_rayon::prelude::*;
+use p3_util::convert_vec;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tracing::instrument;
 
+use crate::util::{transpose_blocked, transpose_square_in_place};
 use crate::Matrix;
 
 /// A dense matrix stored in row-major form.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// It is serialized as its width, its height and its values, each value with its own codec (so
+/// field elements are checked to be canonical when deserialized). Deserialization fails if the
+/// number of values does not match the dimensions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct DenseMatrix<T, V = Vec<T>> {
     pub values: V,
     pub width: usize,
@@ -27,7 +36,13 @@ pub type RowMajorMatrix<T> = DenseMatrix<T, Vec<T>>;
 pub type RowMajorMatrixView<'a, T> = DenseMatrix<T, &'a [T]>;
 pub type RowMajorMatrixViewMut<'a, T> = DenseMatrix<T, &'a mut [T]>;
 pub type RowMajorMatrixCow<'a, T> = DenseMatrix<T, Cow<'a, [T]>>;
+/// A dense matrix whose values are reference counted, so clones share them rather than copying.
+pub type RowMajorMatrixArc<T> = DenseMatrix<T, Arc<[T]>>;
 
+/// A contiguous buffer of values which can back a `DenseMatrix`.
+///
+/// Besides the implementations here, `p3_dft::MmapBuffer` keeps the values in a
+/// memory-mapped file.
 pub trait DenseStorage<T>: Borrow<[T]> + Send + Sync {
     fn to_vec(self) -> Vec<T>;
 }
@@ -52,6 +67,58 @@ impl<T: Clone + Send + Sync> DenseStorage<T> for Cow<'_, [T]> {
         self.into_owned()
     }
 }
+impl<T: Clone + Send + Sync> DenseStorage<T> for Arc<[T]> {
+    fn to_vec(self) -> Vec<T> {
+        <[T]>::to_vec(&self)
+    }
+}
+
+/// The serialized form of a `DenseMatrix`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "DenseMatrix")]
+struct SerdeDenseMatrix<V> {
+    width: usize,
+    height: usize,
+    values: V,
+}
+
+impl<T: Clone + Send + Sync + Serialize, V: DenseStorage<T>> Serialize for DenseMatrix<T, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerdeDenseMatrix {
+            width: self.width,
+            height: self.height(),
+            values: self.values.borrow(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T, V> Deserialize<'de> for DenseMatrix<T, V>
+where
+    T: Clone + Send + Sync,
+    V: DenseStorage<T> + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SerdeDenseMatrix {
+            width,
+            height,
+            values,
+        } = SerdeDenseMatrix::<V>::deserialize(deserializer)?;
+        let len = values.borrow().len();
+        // A matrix with no columns has no rows, as in `Matrix::height`.
+        let consistent = if width == 0 {
+            height == 0 && len == 0
+        } else {
+            width.checked_mul(height) == Some(len)
+        };
+        if !consistent {
+            return Err(D::Error::custom(format_args!(
+                "{len} values do not fill a matrix of width {width} and height {height}"
+            )));
+        }
+        Ok(Self::new(values, width))
+    }
+}
 
 impl<T: Clone + Send + Sync + Default> DenseMatrix<T> {
     /// Create a new dense matrix of the given dimensions, backed by a `Vec`, and filled with
@@ -204,6 +271,18 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> DenseMatrix<T, S> {
         )
     }
 
+    /// The number of values in `chunk_rows` rows. For a matrix with no columns, which has no
+    /// values, any nonzero chunk length will do.
+    fn chunk_len(&self, chunk_rows: usize) -> usize {
+        assert!(chunk_rows > 0, "chunks must have at least one row");
+        self.width.max(1) * chunk_rows
+    }
+
+    /// Split the matrix into views of `chunk_rows` rows each, in parallel. The last chunk is
+    /// shorter if `chunk_rows` does not divide the height; a matrix shorter than `chunk_rows` is a
+    /// single chunk.
+    ///
+    /// Panics if `chunk_rows` is zero.
     pub fn par_row_chunks(
         &self,
         chunk_rows: usize,
@@ -213,10 +292,11 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> DenseMatrix<T, S> {
     {
         self.values
             .borrow()
-            .par_chunks(self.width * chunk_rows)
+            .par_chunks(self.chunk_len(chunk_rows))
             .map(|slice| RowMajorMatrixView::new(slice, self.width))
     }
 
+    /// As `par_row_chunks`, but leaving out the last chunk if it has fewer than `chunk_rows` rows.
     pub fn par_row_chunks_exact(
         &self,
         chunk_rows: usize,
@@ -226,10 +306,12 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> DenseMatrix<T, S> {
     {
         self.values
             .borrow()
-            .par_chunks_exact(self.width * chunk_rows)
+            .par_chunks_exact(self.chunk_len(chunk_rows))
             .map(|slice| RowMajorMatrixView::new(slice, self.width))
     }
 
+    /// Split the matrix into mutable views of `chunk_rows` rows each, in parallel, with the same
+    /// chunks as `par_row_chunks`.
     pub fn par_row_chunks_mut(
         &mut self,
         chunk_rows: usize,
@@ -238,9 +320,10 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> DenseMatrix<T, S> {
         T: Send,
         S: BorrowMut<[T]>,
     {
+        let chunk_len = self.chunk_len(chunk_rows);
         self.values
             .borrow_mut()
-            .par_chunks_mut(self.width * chunk_rows)
+            .par_chunks_mut(chunk_len)
             .map(|slice| RowMajorMatrixViewMut::new(slice, self.width))
     }
 
@@ -252,9 +335,10 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> DenseMatrix<T, S> {
         T: Send,
         S: BorrowMut<[T]>,
     {
+        let chunk_len = self.chunk_len(chunk_rows);
         self.values
             .borrow_mut()
-            .chunks_exact_mut(self.width * chunk_rows)
+            .chunks_exact_mut(chunk_len)
             .map(|slice| RowMajorMatrixViewMut::new(slice, self.width))
     }
 
@@ -266,9 +350,10 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> DenseMatrix<T, S> {
         T: Send,
         S: BorrowMut<[T]>,
     {
+        let chunk_len = self.chunk_len(chunk_rows);
         self.values
             .borrow_mut()
-            .par_chunks_exact_mut(self.width * chunk_rows)
+            .par_chunks_exact_mut(chunk_len)
             .map(|slice| RowMajorMatrixViewMut::new(slice, self.width))
     }
 
@@ -374,6 +459,7 @@ impl<T: Clone + Send + Sync, S: DenseStorage<T>> Matrix<T> for DenseMatrix<T, S>
         Self: Sized,
         T: Clone,
     {
+        // For `Vec` storage this is a move, not a copy.
         RowMajorMatrix::new(self.values.to_vec(), self.width)
     }
 
@@ -416,6 +502,12 @@ impl<T: Clone + Default + Send + Sync> DenseMatrix<T, Vec<T>> {
         RowMajorMatrixCow::new(Cow::Owned(self.values), self.width)
     }
 
+    /// Move the values into reference-counted storage, so the matrix can be cheaply cloned and
+    /// shared between threads.
+    pub fn into_arc(self) -> RowMajorMatrixArc<T> {
+        RowMajorMatrixArc::new(self.values.into(), self.width)
+    }
+
     pub fn rand<R: Rng>(rng: &mut R, rows: usize, cols: usize) -> Self
     where
         Standard: Distribution<T>,
@@ -447,14 +539,80 @@ impl<T: Copy + Default + Send + Sync> DenseMatrix<T, Vec<T>> {
     pub fn transpose(&self) -> Self {
         let nelts = self.height() * self.width();
         let mut values = vec![T::default(); nelts];
-        transpose::transpose(&self.values, &mut values, self.width(), self.height());
+        transpose_blocked(&self.values, &mut values, self.width(), self.height());
         Self::new(values, self.height())
     }
 
+    /// Write the transpose of `self` into `other`, which must already have the transposed
+    /// dimensions.
     pub fn transpose_into(&self, other: &mut Self) {
         assert_eq!(self.height(), other.width());
         assert_eq!(other.height(), self.width());
-        transpose::transpose(&self.values, &mut other.values, self.width(), self.height());
+        transpose_blocked(&self.values, &mut other.values, self.width(), self.height());
+    }
+
+    /// Transpose a square matrix in place, without allocating.
+    ///
+    /// Panics if the matrix is not square.
+    pub fn transpose_in_place(&mut self) {
+        let n = self.width();
+        assert_eq!(
+            self.height(),
+            n,
+            "only a square matrix can be transposed in place"
+        );
+        transpose_square_in_place(&mut self.values, n);
+    }
+}
+
+impl<F: Field> DenseMatrix<F, Vec<F>> {
+    /// Reinterpret the matrix as a matrix of packed values, each holding `F::Packing::WIDTH`
+    /// adjacent entries of a row, or return it unchanged if its width is not a multiple of
+    /// `F::Packing::WIDTH`.
+    ///
+    /// The buffer is handed over without copying when the allocator can free it as packed values,
+    /// i.e. when they have the alignment of `F` (as all the packings in this repository do) and
+    /// the capacity is a whole number of them. Otherwise, the values are copied into a new buffer.
+    pub fn pack_columns(self) -> Result<RowMajorMatrix<F::Packing>, Self> {
+        let packing_width = F::Packing::WIDTH;
+        if self.width % packing_width != 0 {
+            return Err(self);
+        }
+        let width = self.width / packing_width;
+        let values = if align_of::<F::Packing>() == align_of::<F>()
+            && self.values.capacity() % packing_width == 0
+        {
+            // SAFETY: `F::Packing` is laid out as `[F; WIDTH]` and has the same alignment as `F`,
+            // and the length (a multiple of the width) and the capacity are multiples of WIDTH.
+            unsafe { convert_vec(self.values) }
+        } else {
+            self.values
+                .chunks_exact(packing_width)
+                .map(|chunk| F::Packing::from_fn(|i| chunk[i]))
+                .collect()
+        };
+        Ok(RowMajorMatrix::new(values, width))
+    }
+}
+
+impl<P: PackedField> DenseMatrix<P, Vec<P>> {
+    /// The inverse of `pack_columns`: reinterpret a matrix of packed values as a matrix of their
+    /// scalars, each packed value covering `P::WIDTH` adjacent columns of its row.
+    ///
+    /// As with `pack_columns`, the buffer is only copied if `P` is more strictly aligned than its
+    /// scalars.
+    pub fn unpack_columns(self) -> RowMajorMatrix<P::Scalar> {
+        let width = self.width * P::WIDTH;
+        let values = if align_of::<P>() == align_of::<P::Scalar>() {
+            // SAFETY: `P` is laid out as `[P::Scalar; WIDTH]` and has the same alignment.
+            unsafe { convert_vec(self.values) }
+        } else {
+            self.values
+                .iter()
+                .flat_map(|p| p.as_slice().iter().copied())
+                .collect()
+        };
+        RowMajorMatrix::new(values, width)
     }
 }
 
@@ -466,7 +624,15 @@ impl<'a, T: Clone + Default + Send + Sync> DenseMatrix<T, &'a [T]> {
 
 #[cfg(test)]
 mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+
+    use p3_baby_bear::BabyBear;
+    use rand::thread_rng;
+
     use super::*;
+    use crate::bitrev::BitReversableMatrix;
+    use crate::util::reverse_matrix_index_bits;
 
     #[test]
     fn test_transpose_square_matrix() {
@@ -562,4 +728,326 @@ mod tests {
             }
         }
     }
+
+    fn naive_transpose<T: Copy + Default + Send + Sync>(
+        matrix: &RowMajorMatrix<T>,
+    ) -> RowMajorMatrix<T> {
+        let (height, width) = (matrix.height(), matrix.width());
+        let mut values = vec![T::default(); height * width];
+        for r in 0..height {
+            for c in 0..width {
+                values[c * height + r] = matrix.values[r * width + c];
+            }
+        }
+        RowMajorMatrix::new(values, height)
+    }
+
+    #[test]
+    fn test_transpose_matches_naive() {
+        // Thin matrices, exact multiples of the tile and micro-kernel sizes, and ragged edges.
+        let dims = [
+            (1, 1),
+            (1, 37),
+            (37, 1),
+            (8, 8),
+            (7, 9),
+            (32, 64),
+            (33, 31),
+            (100, 3),
+            (3, 100),
+            (65, 130),
+            // Tall enough to be split into several segments of rows.
+            (2048, 40),
+            (4100, 37),
+        ];
+        for (height, width) in dims {
+            let values = (0..(height * width) as u32).collect();
+            let matrix = RowMajorMatrix::new(values, width);
+            let expected = naive_transpose(&matrix);
+            assert_eq!(matrix.transpose(), expected, "{height} x {width}");
+
+            let mut transposed = RowMajorMatrix::default(height, width);
+            matrix.transpose_into(&mut transposed);
+            assert_eq!(transposed, expected, "{height} x {width}");
+        }
+    }
+
+    #[test]
+    fn test_transpose_in_place_matches_naive() {
+        for n in [0, 1, 2, 7, 8, 9, 31, 32, 33, 64, 100] {
+            let values = (0..(n * n) as u64).collect();
+            let mut matrix = RowMajorMatrix::new(values, n);
+            let expected = naive_transpose(&matrix);
+            matrix.transpose_in_place();
+            assert_eq!(matrix, expected, "{n} x {n}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "only a square matrix")]
+    fn test_transpose_in_place_rejects_non_square() {
+        RowMajorMatrix::new(vec![0u32; 6], 3).transpose_in_place();
+    }
+
+    #[test]
+    fn test_par_row_chunks() {
+        let matrix = RowMajorMatrix::new((0..30).collect::<Vec<u32>>(), 3);
+        let heights = |chunk_rows| {
+            matrix
+                .par_row_chunks(chunk_rows)
+                .map(|chunk| chunk.height())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(heights(1), vec![1; 10]);
+        assert_eq!(heights(5), vec![5, 5]);
+        assert_eq!(heights(4), vec![4, 4, 2]);
+        assert_eq!(heights(10), vec![10]);
+        assert_eq!(heights(64), vec![10]);
+
+        // The chunks cover the rows in order.
+        let values: Vec<u32> = matrix
+            .par_row_chunks(4)
+            .flat_map_iter(|chunk| chunk.values.to_vec())
+            .collect();
+        assert_eq!(values, matrix.values);
+
+        let exact: Vec<_> = matrix
+            .par_row_chunks_exact(4)
+            .map(|chunk| chunk.height())
+            .collect();
+        assert_eq!(exact, vec![4, 4]);
+
+        let empty = RowMajorMatrix::<u32>::new(vec![], 0);
+        assert_eq!(empty.par_row_chunks(4).count(), 0);
+    }
+
+    #[test]
+    fn test_par_row_chunks_mut() {
+        let mut matrix = RowMajorMatrix::new(vec![0u32; 21], 3);
+        matrix
+            .par_row_chunks_mut(3)
+            .enumerate()
+            .for_each(|(i, mut chunk)| {
+                for (j, row) in chunk.rows_mut().enumerate() {
+                    row.fill((3 * i + j) as u32);
+                }
+            });
+        // Each row was written through exactly one chunk, including the partial final one.
+        let expected = (0..7).flat_map(|r| [r; 3]).collect::<Vec<u32>>();
+        assert_eq!(matrix.values, expected);
+
+        let mut empty = RowMajorMatrix::<u32>::new(vec![], 0);
+        assert_eq!(empty.par_row_chunks_mut(2).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one row")]
+    fn test_par_row_chunks_rejects_empty_chunks() {
+        let matrix = RowMajorMatrix::new(vec![0u32; 6], 3);
+        let _ = matrix.par_row_chunks(0).count();
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        type F = BabyBear;
+        let mut rng = thread_rng();
+
+        let large = RowMajorMatrix::<F>::rand(&mut rng, 1 << 12, 37);
+        let bytes = postcard::to_allocvec(&large).unwrap();
+        assert_eq!(
+            postcard::from_bytes::<RowMajorMatrix<F>>(&bytes).unwrap(),
+            large
+        );
+
+        let config = bincode::config::standard();
+        let bytes = bincode::serde::encode_to_vec(&large, config).unwrap();
+        let (decoded, len) =
+            bincode::serde::decode_from_slice::<RowMajorMatrix<F>, _>(&bytes, config).unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(decoded, large);
+
+        let small = RowMajorMatrix::<F>::rand(&mut rng, 3, 2);
+        let json = serde_json::to_string(&small).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RowMajorMatrix<F>>(&json).unwrap(),
+            small
+        );
+
+        // Views serialize the same way as the matrices they borrow from.
+        assert_eq!(serde_json::to_string(&small.as_view()).unwrap(), json);
+
+        let empty = RowMajorMatrix::<F>::new(vec![], 0);
+        let json = serde_json::to_string(&empty).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RowMajorMatrix<F>>(&json).unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn test_serde_rejects_inconsistent_dimensions() {
+        let json =
+            serde_json::to_string(&RowMajorMatrix::new(vec![1u32, 2, 3, 4, 5, 6], 3)).unwrap();
+        assert_eq!(json, r#"{"width":3,"height":2,"values":[1,2,3,4,5,6]}"#);
+        assert!(serde_json::from_str::<RowMajorMatrix<u32>>(&json).is_ok());
+
+        for corrupted in [
+            r#"{"width":3,"height":2,"values":[1,2,3,4,5]}"#,
+            r#"{"width":3,"height":3,"values":[1,2,3,4,5,6]}"#,
+            r#"{"width":0,"height":2,"values":[]}"#,
+            r#"{"width":2,"height":9223372036854775808,"values":[1,2]}"#,
+        ] {
+            let err = serde_json::from_str::<RowMajorMatrix<u32>>(corrupted).unwrap_err();
+            assert!(err.to_string().contains("do not fill a matrix"), "{err}");
+        }
+
+        // Elements are still checked by their own codec.
+        let out_of_range = format!(r#"{{"width":1,"height":1,"values":[{}]}}"#, u32::MAX);
+        assert!(serde_json::from_str::<RowMajorMatrix<BabyBear>>(&out_of_range).is_err());
+    }
+
+    /// Check that `mat` reads the same as the `Vec`-backed `expected`, through every accessor.
+    fn check_backend<S: DenseStorage<BabyBear> + Clone>(
+        mat: &DenseMatrix<BabyBear, S>,
+        expected: &RowMajorMatrix<BabyBear>,
+    ) {
+        type P = <BabyBear as Field>::Packing;
+        assert_eq!(mat.dimensions(), expected.dimensions());
+        assert!(mat.row_slices().eq(expected.row_slices()));
+        for r in 0..expected.height() {
+            assert!(mat.row(r).eq(expected.row(r)));
+            assert_eq!(*mat.row_slice(r), *expected.row_slice(r));
+            let (packed, sfx) = mat.horizontally_packed_row::<P>(r);
+            let (expected_packed, expected_sfx) = expected.horizontally_packed_row::<P>(r);
+            assert!(packed
+                .flat_map(|p| p.as_slice().to_vec())
+                .eq(expected_packed.flat_map(|p| p.as_slice().to_vec())));
+            assert!(sfx.eq(expected_sfx));
+            assert!(mat
+                .vertically_packed_row::<P>(r)
+                .flat_map(|p| p.as_slice().to_vec())
+                .eq(expected
+                    .vertically_packed_row::<P>(r)
+                    .flat_map(|p| p.as_slice().to_vec())));
+        }
+        assert_eq!(mat.clone().to_row_major_matrix(), *expected);
+    }
+
+    #[test]
+    fn test_borrowed_and_shared_storage() {
+        let mat = RowMajorMatrix::<BabyBear>::rand(&mut thread_rng(), 37, 11);
+        check_backend(&mat.as_view(), &mat);
+        check_backend(&mat.clone().as_cow(), &mat);
+        check_backend(&mat.clone().into_arc(), &mat);
+    }
+
+    #[test]
+    fn test_shared_storage_concurrent_reads() {
+        extern crate std;
+
+        let expected = RowMajorMatrix::<BabyBear>::rand(&mut thread_rng(), 1 << 10, 8);
+        let shared = expected.clone().into_arc();
+        let copy = shared.clone();
+        // Clones share the values rather than copying them.
+        assert!(Arc::ptr_eq(&shared.values, &copy.values));
+
+        std::thread::scope(|s| {
+            s.spawn(|| check_backend(&shared, &expected));
+            s.spawn(|| check_backend(&copy, &expected));
+        });
+    }
+
+    #[test]
+    fn test_to_row_major_matrix_moves_owned_values() {
+        // Generic code can't tell whether it was given a dense matrix.
+        fn materialize<M: Matrix<BabyBear>>(mat: M) -> RowMajorMatrix<BabyBear> {
+            mat.to_row_major_matrix()
+        }
+
+        let mat = RowMajorMatrix::<BabyBear>::rand(&mut thread_rng(), 1 << 10, 8);
+        let expected = mat.clone();
+        let ptr = mat.values.as_ptr();
+        let dense = materialize(mat);
+        assert_eq!(dense.values.as_ptr(), ptr);
+        assert_eq!(dense, expected);
+
+        // Bit reversing the rows of an owned matrix permutes them in its own allocation.
+        let reversed = materialize(dense.bit_reverse_rows());
+        assert_eq!(reversed.values.as_ptr(), ptr);
+        let mut expected_reversed = expected.clone();
+        reverse_matrix_index_bits(&mut expected_reversed);
+        assert_eq!(reversed, expected_reversed);
+
+        // Borrowed views are copied.
+        let view = expected.as_view().submatrix(3..10, 2..5);
+        let copy = materialize(view);
+        assert_eq!(copy.dimensions().height, 7);
+        for r in 0..7 {
+            assert_eq!(*copy.row_slice(r), expected.row_slice(r + 3)[2..5]);
+        }
+        assert!(materialize(expected.as_view()).values.as_ptr() != expected.values.as_ptr());
+    }
+
+    #[test]
+    fn test_pack_columns_round_trip() {
+        type P = <BabyBear as Field>::Packing;
+        let (height, width) = (33, 3 * P::WIDTH);
+        let mut values = Vec::with_capacity(height * width);
+        values.extend(RowMajorMatrix::<BabyBear>::rand(&mut thread_rng(), height, width).values);
+        let mat = RowMajorMatrix::new(values, width);
+        let ptr = mat.values.as_ptr();
+
+        let packed = mat.clone().pack_columns().unwrap();
+        assert_eq!(packed.dimensions().width, 3);
+        assert_eq!(packed.dimensions().height, height);
+        // Each packed value covers adjacent columns of one row.
+        for r in 0..height {
+            for c in 0..3 {
+                assert_eq!(
+                    packed.get(r, c).as_slice(),
+                    &mat.row_slice(r)[c * P::WIDTH..(c + 1) * P::WIDTH]
+                );
+            }
+        }
+
+        let moved = mat.clone();
+        let moved_ptr = moved.values.as_ptr();
+        let unpacked = moved.pack_columns().unwrap().unpack_columns();
+        assert_eq!(unpacked.values.as_ptr(), moved_ptr);
+        assert_eq!(unpacked, mat);
+        assert_eq!(
+            mat.pack_columns()
+                .unwrap()
+                .values
+                .as_ptr()
+                .cast::<BabyBear>(),
+            ptr
+        );
+    }
+
+    #[test]
+    fn test_pack_columns_rejects_and_copies() {
+        type P = <BabyBear as Field>::Packing;
+        let mut rng = thread_rng();
+        if P::WIDTH > 1 {
+            // A width which isn't a multiple of the packing width is given back unchanged.
+            let mat = RowMajorMatrix::<BabyBear>::rand(&mut rng, 8, P::WIDTH + 1);
+            assert_eq!(mat.clone().pack_columns().unwrap_err(), mat);
+        }
+
+        // A buffer whose capacity isn't a whole number of packed values can't be handed over, so
+        // it is copied.
+        let (height, width) = (16, 2 * P::WIDTH);
+        let expected = RowMajorMatrix::<BabyBear>::rand(&mut rng, height, width);
+        let mut values = Vec::with_capacity(height * width + 1);
+        values.extend_from_slice(&expected.values);
+        let packed = RowMajorMatrix::new(values, width).pack_columns().unwrap();
+        assert_eq!(packed.unpack_columns(), expected);
+
+        let empty = RowMajorMatrix::<BabyBear>::new(Vec::new(), width);
+        assert_eq!(
+            empty.clone().pack_columns().unwrap().unpack_columns(),
+            empty
+        );
+    }
 }
@@ -41,7 +41,7 @@ where
     #[inline(always)]
     fn get(&self, r: usize, c: usize) -> T {
         debug_assert!(c < self.truncated_width);
-        self.inner.get(c, r)
+        self.inner.get(r, c)
     }
 
     type Row<'a>
@@ -54,3 +54,24 @@ where
         self.inner.row(r).take(self.truncated_width)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::dense::RowMajorMatrix;
+
+    #[test]
+    fn get_matches_row() {
+        let mat = RowMajorMatrix::new((0..4 * 5).collect::<Vec<u32>>(), 5);
+        let truncated = HorizontallyTruncated::new(mat, 3);
+        for r in 0..4 {
+            let row: Vec<_> = truncated.row(r).collect();
+            assert_eq!(row.len(), 3);
+            for (c, &x) in row.iter().enumerate() {
+                assert_eq!(truncated.get(r, c), x);
+            }
+        }
+    }
+}
@@ -6,27 +6,33 @@ extern crate alloc;
 
 use alloc::vec::Vec;
 use core::fmt::{Debug, Display, Formatter};
-use core::ops::Deref;
+use core::ops::{Deref, Range};
 
 use itertools::{izip, Itertools};
 use p3_field::{
     dot_product, ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra, PackedValue,
 };
 use p3_maybe_rayon::prelude::*;
+use padded::PaddedView;
 use strided::{VerticallyStridedMatrixView, VerticallyStridedRowIndexMap};
+use submatrix::SubMatrixView;
 use tracing::instrument;
 
 use crate::dense::RowMajorMatrix;
 
 pub mod bitrev;
+pub mod col_major;
 pub mod dense;
 pub mod extension;
 pub mod horizontally_truncated;
 pub mod mul;
+pub mod padded;
+pub mod permuted;
 pub mod row_index_mapped;
 pub mod sparse;
 pub mod stack;
 pub mod strided;
+pub mod submatrix;
 pub mod util;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -89,6 +95,11 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
         self.row(self.height() - 1)
     }
 
+    /// Copy the matrix into a `RowMajorMatrix`.
+    ///
+    /// This consumes `self` so that implementations which already own their values in row-major
+    /// order can move them rather than copy them: for a `RowMajorMatrix` this is free, and views
+    /// such as `BitReversedMatrixView` reuse the allocation of an owned inner matrix.
     fn to_row_major_matrix(self) -> RowMajorMatrix<T>
     where
         Self: Sized,
@@ -164,14 +175,22 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
     /// Pack together a collection of adjacent rows from the matrix.
     ///
     /// Returns an iterator whose i'th element is packing of the i'th element of the
-    /// rows r through r + P::WIDTH - 1. If we exceed the height of the matrix,
-    /// wrap around and include initial rows.
+    /// rows r through r + P::WIDTH - 1.
+    ///
+    /// Row indices are taken modulo the height, i.e. the matrix is treated as cyclic: when the
+    /// height is not a multiple of `P::WIDTH`, the last packed row is completed with rows from
+    /// the top of the matrix rather than with zeros. This is what the quotient computation needs
+    /// for the rows following the last one, and implementations must not change it. Callers who
+    /// want zeros instead should pack a `padded_view`.
+    ///
+    /// Panics if the matrix has height 0.
     #[inline]
     fn vertically_packed_row<P>(&self, r: usize) -> impl Iterator<Item = P>
     where
         T: Copy,
         P: PackedValue<Value = T>,
     {
+        assert!(self.height() > 0, "cannot pack the rows of an empty matrix");
         let rows = (0..(P::WIDTH))
             .map(|c| self.row_slice((r + c) % self.height()))
             .collect_vec();
@@ -183,14 +202,21 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
     /// Returns a vector corresponding to 2 packed rows. The i'th element of the first
     /// row contains the packing of the i'th element of the rows r through r + P::WIDTH - 1.
     /// The i'th element of the second row contains the packing of the i'th element of the
-    /// rows r + step through r + step + P::WIDTH - 1. If at some point we exceed the
-    /// height of the matrix, wrap around and include initial rows.
+    /// rows r + step through r + step + P::WIDTH - 1. This is the (current, next) pair used in
+    /// constraint evaluation.
+    ///
+    /// Row indices wrap around exactly as in `vertically_packed_row`, so the first half of the
+    /// result equals `vertically_packed_row(r)` and the second `vertically_packed_row(r + step)`.
+    ///
+    /// Panics if the matrix has height 0.
     #[inline]
     fn vertically_packed_row_pair<P>(&self, r: usize, step: usize) -> Vec<P>
     where
         T: Copy,
         P: PackedValue<Value = T>,
     {
+        assert!(self.height() > 0, "cannot pack the rows of an empty matrix");
+
         // Whilst it would appear that this can be replaced by two calls to vertically_packed_row
         // tests seem to indicate that combining them in the same function is slightly faster.
         // It's probably allowing the compiler to make some optimizations on the fly.
@@ -216,6 +242,38 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
         VerticallyStridedRowIndexMap::new_view(self, stride, offset)
     }
 
+    /// A view of the rows in `row_range` and the columns in `col_range`, without copying.
+    ///
+    /// To keep using the matrix afterwards, take the view of a borrowed view, such as
+    /// `mat.as_view().submatrix(..)`.
+    fn submatrix(self, row_range: Range<usize>, col_range: Range<usize>) -> SubMatrixView<T, Self>
+    where
+        Self: Sized,
+    {
+        SubMatrixView::new(self, row_range, col_range)
+    }
+
+    /// A view of the columns in `col_range`, of every row, without copying.
+    fn columns(self, col_range: Range<usize>) -> SubMatrixView<T, Self>
+    where
+        Self: Sized,
+    {
+        let height = self.height();
+        SubMatrixView::new(self, 0..height, col_range)
+    }
+
+    /// A view of the matrix extended to `new_height` rows, the new rows being zeros (i.e.
+    /// `T::default()`), without allocating them.
+    ///
+    /// Panics if `new_height` is less than the height of the matrix.
+    fn padded_view(self, new_height: usize) -> PaddedView<T, Self>
+    where
+        Self: Sized,
+        T: Clone + Default,
+    {
+        PaddedView::new(self, new_height)
+    }
+
     /// Compute Mᵀv, aka premultiply this matrix by the given vector,
     /// aka scale each row by the corresponding entry in `v` and take the sum across rows.
     /// `v` can be a vector of extension elements.
@@ -255,6 +313,48 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
             .collect()
     }
 
+    /// Compute Mv, aka the dot product of each row with `v`, i.e. the linear combination of the
+    /// columns with coefficients `v`. `v` can be a vector of extension elements.
+    ///
+    /// Rows are processed in parallel, each as a packed dot product with `v` transposed into
+    /// packed extension elements. For Mᵀv, see `columnwise_dot_product`.
+    ///
+    /// Panics if the length of `v` is not the width of the matrix.
+    #[instrument(level = "debug", skip_all, fields(dims = %self.dimensions()))]
+    fn mul_vec<EF>(&self, v: &[EF]) -> Vec<EF>
+    where
+        T: Field,
+        EF: ExtensionField<T>,
+    {
+        assert_eq!(
+            v.len(),
+            self.width(),
+            "vector length does not match the matrix width"
+        );
+        // Zero padding `v` to whole packings cancels any padding of the rows.
+        let v_packed = v
+            .chunks(T::Packing::WIDTH)
+            .map(|chunk| {
+                EF::ExtensionPacking::from_base_fn(|i| {
+                    T::Packing::from_fn(|j| chunk.get(j).map_or(T::ZERO, |x| x.as_base_slice()[i]))
+                })
+            })
+            .collect_vec();
+        self.par_padded_horizontally_packed_rows::<T::Packing>()
+            .map(|row_packed| {
+                let packed_sum_of_packed: EF::ExtensionPacking =
+                    dot_product(v_packed.iter().copied(), row_packed);
+                EF::from_base_fn(|i| {
+                    packed_sum_of_packed.as_base_slice()[i]
+                        .as_slice()
+                        .iter()
+                        .copied()
+                        .sum()
+                })
+            })
+            .collect()
+    }
+
     /// Multiply this matrix by the vector of powers of `base`, which is an extension element.
     fn dot_ext_powers<EF>(&self, base: EF) -> impl IndexedParallelIterator<Item = EF>
     where
@@ -293,6 +393,7 @@ mod tests {
     use rand::thread_rng;
 
     use super::*;
+    use crate::bitrev::BitReversableMatrix;
 
     #[test]
     fn test_columnwise_dot_product() {
@@ -312,6 +413,110 @@ mod tests {
         assert_eq!(m.columnwise_dot_product(&v), expected);
     }
 
+    #[test]
+    fn test_mul_vec() {
+        type F = BabyBear;
+        type EF = BinomialExtensionField<BabyBear, 4>;
+        let mut rng = thread_rng();
+
+        for (height, width) in [
+            (1 << 8, 37),
+            (1 << 6, 1 << 4),
+            (5, 1),
+            (0, 7),
+            (9, 0),
+            (0, 0),
+        ] {
+            let m = RowMajorMatrix::<F>::rand(&mut rng, height, width);
+            let v = RowMajorMatrix::<EF>::rand(&mut rng, width, 1).values;
+
+            let expected = (0..height)
+                .map(|r| (0..width).map(|c| v[c] * m.get(r, c)).sum())
+                .collect::<Vec<EF>>();
+
+            let result = m.mul_vec(&v);
+            // A matrix of width 0 has no rows to multiply.
+            assert_eq!(result.len(), m.height());
+            assert_eq!(result, expected[..m.height()]);
+            // Views take the generic path through `padded_horizontally_packed_row`.
+            assert_eq!(m.as_view().columns(0..width).mul_vec(&v), result);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the matrix width")]
+    fn test_mul_vec_rejects_wrong_length() {
+        type F = BabyBear;
+        let m = RowMajorMatrix::<F>::rand(&mut thread_rng(), 4, 3);
+        m.mul_vec(&[F::ONE; 4]);
+    }
+
+    /// The rows `r, r + 1, ...` of `mat` taken modulo its height, packed `N` at a time.
+    fn expected_packed_rows<const N: usize>(mat: &RowMajorMatrix<u32>, r: usize) -> Vec<[u32; N]> {
+        (0..mat.width())
+            .map(|c| core::array::from_fn(|i| mat.get((r + i) % mat.height(), c)))
+            .collect()
+    }
+
+    #[test]
+    fn test_vertically_packed_row_wraps_around() {
+        // Height 6 with packing width 4: the second block holds rows 4, 5, 0 and 1.
+        let mat = RowMajorMatrix::new((0..18).collect(), 3);
+        let packed: Vec<[u32; 4]> = mat.vertically_packed_row(4).collect();
+        assert_eq!(packed, vec![[12, 15, 0, 3], [13, 16, 1, 4], [14, 17, 2, 5]]);
+
+        for r in 0..mat.height() {
+            assert_eq!(
+                mat.vertically_packed_row::<[u32; 4]>(r).collect::<Vec<_>>(),
+                expected_packed_rows::<4>(&mat, r)
+            );
+            for step in [1, 2, 5] {
+                let mut expected = expected_packed_rows::<4>(&mat, r);
+                expected.extend(expected_packed_rows::<4>(&mat, r + step));
+                assert_eq!(
+                    mat.vertically_packed_row_pair::<[u32; 4]>(r, step),
+                    expected
+                );
+            }
+        }
+
+        // A matrix shorter than the packing width wraps more than once.
+        let short = RowMajorMatrix::new(vec![1, 2, 3], 1);
+        let packed: Vec<[u32; 8]> = short.vertically_packed_row(2).collect();
+        assert_eq!(packed, vec![[3, 1, 2, 3, 1, 2, 3, 1]]);
+    }
+
+    #[test]
+    fn test_vertically_packed_row_wraps_in_views() {
+        // The views use the same semantics, wrapping around the rows of the view.
+        let mat = RowMajorMatrix::new((0..32).collect::<Vec<u32>>(), 4);
+        let bit_reversed = mat.clone().bit_reverse_rows();
+        let materialized = bit_reversed.clone().to_row_major_matrix();
+        let top = mat.as_view().submatrix(0..6, 1..3);
+        let top_materialized = top.clone().to_row_major_matrix();
+        for r in 0..8 {
+            assert_eq!(
+                bit_reversed
+                    .vertically_packed_row::<[u32; 3]>(r)
+                    .collect::<Vec<_>>(),
+                expected_packed_rows::<3>(&materialized, r)
+            );
+        }
+        for r in 0..6 {
+            assert_eq!(
+                top.vertically_packed_row::<[u32; 4]>(r).collect::<Vec<_>>(),
+                expected_packed_rows::<4>(&top_materialized, r)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "empty matrix")]
+    fn test_vertically_packed_row_rejects_empty_matrix() {
+        let mat = RowMajorMatrix::<u32>::new(vec![], 3);
+        let _ = mat.vertically_packed_row::<[u32; 4]>(0).count();
+    }
+
     // Mock implementation for testing purposes
     struct MockMatrix {
         data: Vec<Vec<u32>>,
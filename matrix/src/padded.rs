@@ -0,0 +1,202 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::Cloned;
+use core::ops::Deref;
+use core::slice;
+
+use crate::stack::EitherRow;
+use crate::Matrix;
+
+/// A view of `Inner` extended with rows of zeros (`T::default()`) to a greater height.
+///
+/// The padding rows are not allocated: they all read from a single cached zero row, which is
+/// also what `row_slice` returns for them. Packing rows vertically across the boundary mixes
+/// real and padding rows as if the matrix had been padded eagerly.
+#[derive(Clone, Debug)]
+pub struct PaddedView<T, Inner> {
+    inner: Inner,
+    height: usize,
+    zero_row: Vec<T>,
+}
+
+impl<T: Clone + Default + Send + Sync, Inner: Matrix<T>> PaddedView<T, Inner> {
+    /// Panics if `new_height` is less than the height of `inner`.
+    pub fn new(inner: Inner, new_height: usize) -> Self {
+        assert!(
+            new_height >= inner.height(),
+            "cannot pad a matrix of height {} to height {new_height}",
+            inner.height()
+        );
+        let zero_row = vec![T::default(); inner.width()];
+        Self {
+            inner,
+            height: new_height,
+            zero_row,
+        }
+    }
+
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<T: Clone + Default + Send + Sync, Inner: Matrix<T>> Matrix<T> for PaddedView<T, Inner> {
+    #[inline]
+    fn width(&self) -> usize {
+        self.zero_row.len()
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    fn get(&self, r: usize, c: usize) -> T {
+        debug_assert!(r < self.height());
+        if r < self.inner.height() {
+            self.inner.get(r, c)
+        } else {
+            self.zero_row[c].clone()
+        }
+    }
+
+    type Row<'a>
+        = EitherRow<Inner::Row<'a>, Cloned<slice::Iter<'a, T>>>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        debug_assert!(r < self.height());
+        if r < self.inner.height() {
+            EitherRow::Left(self.inner.row(r))
+        } else {
+            EitherRow::Right(self.zero_row.iter().cloned())
+        }
+    }
+
+    #[inline]
+    fn row_slice(&self, r: usize) -> impl Deref<Target = [T]> {
+        debug_assert!(r < self.height());
+        if r < self.inner.height() {
+            EitherRow::Left(self.inner.row_slice(r))
+        } else {
+            EitherRow::Right(self.zero_row.as_slice())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_field::{Field, FieldAlgebra, PackedValue};
+    use p3_symmetric::{CryptographicHasher, PaddingFreeSponge};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::dense::RowMajorMatrix;
+
+    type F = BabyBear;
+
+    fn eagerly_padded(mat: &RowMajorMatrix<F>, new_height: usize) -> RowMajorMatrix<F> {
+        let mut padded = mat.clone();
+        padded.pad_to_height(new_height, F::ZERO);
+        padded
+    }
+
+    fn check_view(mat: &RowMajorMatrix<F>, new_height: usize) {
+        let view = mat.as_view().padded_view(new_height);
+        let expected = eagerly_padded(mat, new_height);
+        assert_eq!(view.dimensions(), expected.dimensions());
+        for r in 0..new_height {
+            assert_eq!(*view.row_slice(r), *expected.row_slice(r));
+            assert!(view.row(r).eq(expected.row(r)));
+            for c in 0..mat.width() {
+                assert_eq!(view.get(r, c), expected.get(r, c));
+            }
+        }
+        assert_eq!(view.to_row_major_matrix(), expected);
+    }
+
+    #[test]
+    fn padded_view_matches_eager_padding() {
+        let mut rng = thread_rng();
+        for height in [1, 5, 100, 1000] {
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, height, 7);
+            check_view(&mat, height.next_power_of_two());
+            check_view(&mat, 3 * height);
+        }
+    }
+
+    #[test]
+    fn padding_edge_cases() {
+        // Padding an empty matrix gives rows of zeros.
+        let empty = RowMajorMatrix::<F>::new(Vec::new(), 4);
+        check_view(&empty, 8);
+        check_view(&empty, 0);
+
+        // Padding to the current height adds no rows.
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 16, 3);
+        check_view(&mat, 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot pad")]
+    fn padded_view_rejects_shrinking() {
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 16, 3);
+        mat.as_view().padded_view(15);
+    }
+
+    #[test]
+    fn vertical_packing_across_the_boundary() {
+        type P = <F as Field>::Packing;
+        // The real rows end partway through a packing block, and the last blocks wrap around to
+        // the first rows.
+        let height = 4 * P::WIDTH + P::WIDTH / 2 + 1;
+        let new_height = 6 * P::WIDTH + 3;
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), height, 5);
+        let view = mat.as_view().padded_view(new_height);
+        let expected = eagerly_padded(&mat, new_height);
+        for r in 0..new_height {
+            let unpack = |row: Vec<P>| {
+                row.iter()
+                    .flat_map(|p| p.as_slice().to_vec())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                unpack(view.vertically_packed_row::<P>(r).collect()),
+                unpack(expected.vertically_packed_row::<P>(r).collect()),
+                "r = {r}"
+            );
+            assert_eq!(
+                unpack(view.vertically_packed_row_pair::<P>(r, 1)),
+                unpack(expected.vertically_packed_row_pair::<P>(r, 1)),
+                "r = {r}"
+            );
+        }
+    }
+
+    #[test]
+    fn hashing_view_matches_hashing_copy() {
+        type Perm = Poseidon2BabyBear<16>;
+        let hash =
+            PaddingFreeSponge::<Perm, 16, 8, 8>::new(Perm::new_from_rng_128(&mut thread_rng()));
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 300, 12);
+        let view = mat.as_view().padded_view(512);
+        let copy = eagerly_padded(&mat, 512);
+        for r in [0, 299, 300, 511] {
+            assert_eq!(
+                hash.hash_iter(view.row(r)),
+                hash.hash_iter(copy.row(r)),
+                "r = {r}"
+            );
+        }
+        assert_eq!(
+            hash.hash_iter(view.rows().flatten()),
+            hash.hash_iter(copy.values.iter().copied())
+        );
+    }
+}
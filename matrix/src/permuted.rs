@@ -0,0 +1,201 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::row_index_mapped::{RowIndexMap, RowIndexMappedView};
+use crate::Matrix;
+
+/// A permutation of row indices, stored as the image of each index: row `r` of a permuted view is
+/// row `perm[r]` of the inner matrix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowPermutation {
+    perm: Vec<usize>,
+}
+
+impl RowPermutation {
+    /// Panics unless `perm` is a bijection on `0..perm.len()`.
+    pub fn new(perm: Vec<usize>) -> Self {
+        let mut seen = vec![false; perm.len()];
+        for &i in &perm {
+            assert!(
+                i < perm.len(),
+                "row index {i} is out of range for a permutation of {} rows",
+                perm.len()
+            );
+            assert!(!seen[i], "row index {i} appears twice in the permutation");
+            seen[i] = true;
+        }
+        Self { perm }
+    }
+
+    /// The identity permutation on `0..height`.
+    pub fn identity(height: usize) -> Self {
+        Self {
+            perm: (0..height).collect(),
+        }
+    }
+
+    /// The permutation which undoes this one.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        let mut inv = vec![0; self.perm.len()];
+        for (r, &i) in self.perm.iter().enumerate() {
+            inv[i] = r;
+        }
+        Self { perm: inv }
+    }
+
+    /// The permutation which applies this one and then `outer`, i.e. maps `r` to
+    /// `self.perm[outer.perm[r]]`.
+    ///
+    /// Panics if the permutations have different lengths.
+    #[must_use]
+    pub fn then(&self, outer: &Self) -> Self {
+        assert_eq!(
+            self.perm.len(),
+            outer.perm.len(),
+            "composed permutations must have the same length"
+        );
+        Self {
+            perm: outer.perm.iter().map(|&i| self.perm[i]).collect(),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Panics if the length of the permutation is not the height of `inner`.
+    pub fn new_view<T: Send + Sync, Inner: Matrix<T>>(
+        self,
+        inner: Inner,
+    ) -> RowPermutedView<Inner> {
+        assert_eq!(
+            self.perm.len(),
+            inner.height(),
+            "a row permutation must have one index per row"
+        );
+        RowIndexMappedView {
+            index_map: self,
+            inner,
+        }
+    }
+}
+
+impl RowIndexMap for RowPermutation {
+    fn height(&self) -> usize {
+        self.perm.len()
+    }
+
+    fn map_row_index(&self, r: usize) -> usize {
+        self.perm[r]
+    }
+}
+
+/// A lazy view of `Inner` with its rows reordered by an arbitrary permutation.
+pub type RowPermutedView<Inner> = RowIndexMappedView<RowPermutation, Inner>;
+
+impl<Inner> RowPermutedView<Inner> {
+    /// Permute the rows of this view again, composing the permutations so that rows are still
+    /// found with a single lookup rather than one per layer of views.
+    ///
+    /// Panics if the length of `perm` is not the height of the view.
+    #[must_use]
+    pub fn permute_rows(self, perm: &RowPermutation) -> Self {
+        Self {
+            index_map: self.index_map.then(perm),
+            inner: self.inner,
+        }
+    }
+
+    /// A view of the inner matrix in its original row order.
+    pub fn unpermuted(self) -> Inner {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use rand::seq::SliceRandom;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::dense::{RowMajorMatrix, RowMajorMatrixView};
+
+    type F = BabyBear;
+
+    fn random_permutation(height: usize) -> RowPermutation {
+        let mut perm: Vec<usize> = (0..height).collect();
+        perm.shuffle(&mut thread_rng());
+        RowPermutation::new(perm)
+    }
+
+    #[test]
+    fn rows_follow_the_permutation() {
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 37, 4);
+        let perm = random_permutation(37);
+        let view = perm.clone().new_view(mat.as_view());
+        assert_eq!(view.dimensions(), mat.dimensions());
+        for r in 0..37 {
+            let expected = mat.row_slice(perm.as_slice()[r]).to_vec();
+            assert_eq!(view.row_slice(r).to_vec(), expected);
+            assert!(view.row(r).eq(expected.iter().copied()));
+            assert_eq!(view.get(r, 2), expected[2]);
+        }
+    }
+
+    #[test]
+    fn inverse_restores_the_original_order() {
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 64, 3);
+        let perm = random_permutation(64);
+        let inverse = perm.inverse();
+        assert_eq!(perm.then(&inverse), RowPermutation::identity(64));
+        assert_eq!(inverse.then(&perm), RowPermutation::identity(64));
+
+        let permuted = perm.new_view(mat.as_view()).to_row_major_matrix();
+        assert_ne!(permuted, mat);
+        assert_eq!(inverse.new_view(permuted).to_row_major_matrix(), mat);
+    }
+
+    #[test]
+    fn composed_views_flatten() {
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 50, 2);
+        let (first, second) = (random_permutation(50), random_permutation(50));
+
+        let nested = second
+            .clone()
+            .new_view(first.clone().new_view(mat.as_view()))
+            .to_row_major_matrix();
+        let flat = first.new_view(mat.as_view()).permute_rows(&second);
+        // The composed view reads the inner matrix directly.
+        let _: &RowMajorMatrixView<'_, F> = &flat.inner;
+        assert_eq!(flat.to_row_major_matrix(), nested);
+    }
+
+    #[test]
+    fn empty_permutation() {
+        let mat = RowMajorMatrix::<F>::new(Vec::new(), 3);
+        let view = RowPermutation::new(Vec::new()).new_view(mat.as_view());
+        assert_eq!(view.height(), 0);
+        assert_eq!(view.to_row_major_matrix(), mat);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn rejects_out_of_range_indices() {
+        RowPermutation::new(vec![0, 3, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "appears twice")]
+    fn rejects_duplicate_indices() {
+        RowPermutation::new(vec![0, 2, 2, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one index per row")]
+    fn rejects_a_permutation_of_the_wrong_height() {
+        let mat = RowMajorMatrix::<F>::new(vec![F::default(); 12], 3);
+        RowPermutation::new(vec![1, 0, 2]).new_view(mat);
+    }
+}
@@ -3,14 +3,21 @@ use core::ops::Deref;
 
 use crate::Matrix;
 
-/// A combination of two matrices, stacked together vertically.
+/// A combination of two matrices, stacked together vertically, without copying either.
+///
+/// Rows are read from whichever matrix they fall in, so `row`, `get` and `row_slice` are as cheap
+/// as those of the children. Pairs nest, so `VerticalPair::new(a, VerticalPair::new(b, c))` stacks
+/// three matrices.
 #[derive(Copy, Clone, Debug)]
 pub struct VerticalPair<First, Second> {
     pub first: First,
     pub second: Second,
 }
 
-/// A combination of two matrices, stacked together horizontally.
+/// A combination of two matrices, stacked together horizontally, without copying either.
+///
+/// A row is the chain of the children's rows; as it is not contiguous in memory, `row_slice`
+/// falls back to collecting it. Pairs nest as `VerticalPair`s do.
 #[derive(Copy, Clone, Debug)]
 pub struct HorizontalPair<First, Second> {
     pub first: First,
@@ -18,25 +25,35 @@ pub struct HorizontalPair<First, Second> {
 }
 
 impl<First, Second> VerticalPair<First, Second> {
+    /// Panics if the matrices have different widths.
     pub fn new<T>(first: First, second: Second) -> Self
     where
         T: Send + Sync,
         First: Matrix<T>,
         Second: Matrix<T>,
     {
-        assert_eq!(first.width(), second.width());
+        assert_eq!(
+            first.width(),
+            second.width(),
+            "vertically stacked matrices must have the same width"
+        );
         Self { first, second }
     }
 }
 
 impl<First, Second> HorizontalPair<First, Second> {
+    /// Panics if the matrices have different heights.
     pub fn new<T>(first: First, second: Second) -> Self
     where
         T: Send + Sync,
         First: Matrix<T>,
         Second: Matrix<T>,
     {
-        assert_eq!(first.height(), second.height());
+        assert_eq!(
+            first.height(),
+            second.height(),
+            "horizontally stacked matrices must have the same height"
+        );
         Self { first, second }
     }
 }
@@ -146,3 +163,82 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::dense::RowMajorMatrix;
+
+    fn check_rows<M: Matrix<u32>>(mat: &M, expected: &RowMajorMatrix<u32>) {
+        assert_eq!(mat.dimensions(), expected.dimensions());
+        for r in 0..expected.height() {
+            let expected_row = expected.row_slice(r).to_vec();
+            assert_eq!(mat.row(r).collect::<Vec<_>>(), expected_row);
+            assert_eq!(mat.row_slice(r).to_vec(), expected_row);
+            for (c, &x) in expected_row.iter().enumerate() {
+                assert_eq!(mat.get(r, c), x);
+            }
+        }
+    }
+
+    #[test]
+    fn vertical_pair_stacks_rows() {
+        let a = RowMajorMatrix::new((0..6).collect(), 2);
+        let b = RowMajorMatrix::new((6..16).collect(), 2);
+        let pair = VerticalPair::new(a.as_view(), b.as_view());
+        let expected = RowMajorMatrix::new((0..16).collect(), 2);
+        check_rows(&pair, &expected);
+        assert_eq!(pair.to_row_major_matrix(), expected);
+
+        // Nesting stacks more than two matrices.
+        let c = RowMajorMatrix::new((16..20).collect(), 2);
+        let triple = VerticalPair::new(a.as_view(), VerticalPair::new(b.as_view(), c.as_view()));
+        check_rows(&triple, &RowMajorMatrix::new((0..20).collect(), 2));
+    }
+
+    #[test]
+    fn vertical_pair_with_empty_children() {
+        let a = RowMajorMatrix::new((0..6).collect(), 3);
+        let empty = RowMajorMatrix::<u32>::new(vec![], 3);
+        check_rows(&VerticalPair::new(empty.as_view(), a.as_view()), &a);
+        check_rows(&VerticalPair::new(a.as_view(), empty.as_view()), &a);
+        assert_eq!(
+            VerticalPair::new(empty.as_view(), empty.as_view()).height(),
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "same width")]
+    fn vertical_pair_rejects_different_widths() {
+        let a = RowMajorMatrix::new(vec![0u32; 6], 3);
+        let b = RowMajorMatrix::new(vec![0u32; 6], 2);
+        VerticalPair::new(a, b);
+    }
+
+    #[test]
+    fn horizontal_pair_joins_rows() {
+        let a = RowMajorMatrix::new(vec![0u32, 1, 4, 5, 8, 9], 2);
+        let b = RowMajorMatrix::new(vec![2u32, 3, 6, 7, 10, 11], 2);
+        let pair = HorizontalPair::new(a.as_view(), b.as_view());
+        let expected = RowMajorMatrix::new((0..12).collect(), 4);
+        check_rows(&pair, &expected);
+        assert_eq!(pair.to_row_major_matrix(), expected);
+
+        let empty = RowMajorMatrix::<u32>::new(vec![], 2);
+        let empty_pair = HorizontalPair::new(empty.as_view(), empty.as_view());
+        assert_eq!(empty_pair.dimensions().height, 0);
+        assert_eq!(empty_pair.width(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "same height")]
+    fn horizontal_pair_rejects_different_heights() {
+        let a = RowMajorMatrix::new(vec![0u32; 6], 3);
+        let b = RowMajorMatrix::new(vec![0u32; 6], 2);
+        HorizontalPair::new(a, b);
+    }
+}
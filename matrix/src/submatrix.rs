@@ -0,0 +1,197 @@
+use core::iter::{Skip, Take};
+use core::marker::PhantomData;
+use core::ops::{Deref, Range};
+
+use crate::Matrix;
+
+/// A view of the rows in `row_range` and the columns in `col_range` of `Inner`.
+///
+/// Nothing is copied: `row_slice` takes the row slice of `Inner` and narrows it to the view's
+/// columns, so it is as cheap as the inner matrix's `row_slice`. For a dense matrix, that is a
+/// contiguous slice of its values.
+#[derive(Clone, Debug)]
+pub struct SubMatrixView<T, Inner> {
+    inner: Inner,
+    row_range: Range<usize>,
+    col_range: Range<usize>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Send + Sync, Inner: Matrix<T>> SubMatrixView<T, Inner> {
+    /// Panics unless both ranges are in bounds and not decreasing.
+    pub fn new(inner: Inner, row_range: Range<usize>, col_range: Range<usize>) -> Self {
+        assert!(
+            row_range.start <= row_range.end && row_range.end <= inner.height(),
+            "row range {row_range:?} is out of bounds for height {}",
+            inner.height()
+        );
+        assert!(
+            col_range.start <= col_range.end && col_range.end <= inner.width(),
+            "column range {col_range:?} is out of bounds for width {}",
+            inner.width()
+        );
+        Self {
+            inner,
+            row_range,
+            col_range,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<T: Send + Sync, Inner: Matrix<T>> Matrix<T> for SubMatrixView<T, Inner> {
+    #[inline]
+    fn width(&self) -> usize {
+        self.col_range.len()
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.row_range.len()
+    }
+
+    #[inline]
+    fn get(&self, r: usize, c: usize) -> T {
+        debug_assert!(r < self.height() && c < self.width());
+        self.inner
+            .get(self.row_range.start + r, self.col_range.start + c)
+    }
+
+    type Row<'a>
+        = Take<Skip<Inner::Row<'a>>>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        debug_assert!(r < self.height());
+        self.inner
+            .row(self.row_range.start + r)
+            .skip(self.col_range.start)
+            .take(self.width())
+    }
+
+    #[inline]
+    fn row_slice(&self, r: usize) -> impl Deref<Target = [T]> {
+        debug_assert!(r < self.height());
+        SubRowSlice {
+            row: self.inner.row_slice(self.row_range.start + r),
+            col_range: self.col_range.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A row slice of the inner matrix, narrowed to the columns of a `SubMatrixView`.
+struct SubRowSlice<T, D> {
+    row: D,
+    col_range: Range<usize>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, D: Deref<Target = [T]>> Deref for SubRowSlice<T, D> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        &self.row[self.col_range.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_symmetric::{CryptographicHasher, PaddingFreeSponge};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::dense::RowMajorMatrix;
+
+    fn naive_submatrix(
+        mat: &RowMajorMatrix<u32>,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> RowMajorMatrix<u32> {
+        let values = rows
+            .flat_map(|r| mat.row_slice(r)[cols.clone()].to_vec())
+            .collect();
+        RowMajorMatrix::new(values, cols.len())
+    }
+
+    fn check_view<M: Matrix<u32>>(view: &M, expected: &RowMajorMatrix<u32>) {
+        assert_eq!(view.dimensions(), expected.dimensions());
+        for r in 0..expected.height() {
+            let expected_row = expected.row_slice(r).to_vec();
+            assert_eq!(view.row_slice(r).to_vec(), expected_row);
+            assert_eq!(view.row(r).collect::<Vec<_>>(), expected_row);
+            for (c, &x) in expected_row.iter().enumerate() {
+                assert_eq!(view.get(r, c), x);
+            }
+        }
+    }
+
+    #[test]
+    fn submatrix_matches_copy() {
+        let mat = RowMajorMatrix::new((0..200 * 10).collect(), 10);
+        for (rows, cols) in [
+            (100..200, 3..7),
+            (0..200, 0..10),
+            (0..1, 9..10),
+            (0..0, 2..5),
+        ] {
+            let view = mat.as_view().submatrix(rows.clone(), cols.clone());
+            let expected = naive_submatrix(&mat, rows, cols);
+            check_view(&view, &expected);
+            assert_eq!(view.to_row_major_matrix(), expected);
+        }
+    }
+
+    #[test]
+    fn columns_keep_every_row() {
+        let mat = RowMajorMatrix::new((0..8 * 6).collect(), 6);
+        let view = mat.as_view().columns(2..5);
+        check_view(&view, &naive_submatrix(&mat, 0..8, 2..5));
+        // Unlike a dense matrix, a view with no columns keeps its height.
+        let empty = mat.as_view().columns(0..0);
+        assert_eq!(empty.width(), 0);
+        assert_eq!(empty.height(), 8);
+        assert!(empty.row_slice(3).is_empty());
+    }
+
+    #[test]
+    fn nested_views_compose() {
+        let mat = RowMajorMatrix::new((0..64 * 16).collect(), 16);
+        let outer = mat.as_view().submatrix(10..50, 2..14);
+        let inner = outer.submatrix(5..20, 3..8);
+        check_view(&inner, &naive_submatrix(&mat, 15..30, 5..10));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn submatrix_rejects_out_of_bounds_columns() {
+        let mat = RowMajorMatrix::new(vec![0u32; 12], 3);
+        mat.as_view().submatrix(0..4, 1..4);
+    }
+
+    #[test]
+    fn hashing_view_matches_hashing_copy() {
+        type F = BabyBear;
+        type Perm = Poseidon2BabyBear<16>;
+        let hash =
+            PaddingFreeSponge::<Perm, 16, 8, 8>::new(Perm::new_from_rng_128(&mut thread_rng()));
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 300, 12);
+        let view = mat.as_view().submatrix(100..200, 3..7);
+        let copy = view.clone().to_row_major_matrix();
+        assert_eq!(
+            hash.hash_iter(view.rows().flatten()),
+            hash.hash_iter(copy.values.iter().copied())
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::borrow::BorrowMut;
 
 use p3_maybe_rayon::prelude::*;
@@ -15,6 +16,9 @@ where
 {
     let w = mat.width();
     let h = mat.height();
+    if h == 0 {
+        return;
+    }
     let log_h = log2_strict_usize(h);
     let values = mat.values.borrow_mut().as_mut_ptr() as usize;
 
@@ -44,3 +48,159 @@ pub(crate) unsafe fn swap_rows_raw<F>(mat: *mut F, w: usize, i: usize, j: usize)
     let row_j = core::slice::from_raw_parts_mut(mat.add(j * w), w);
     row_i.swap_with_slice(row_j);
 }
+
+/// The side of the square tiles which `transpose_square_in_place` works through one at a time,
+/// chosen so that a tile of the source and of the destination fit in L1, and the number of rows
+/// of `dst` in each band of `transpose_blocked`.
+const TRANSPOSE_TILE: usize = 32;
+
+/// The side of the micro-kernel blocks which tiles are split into. A block is loaded into a local
+/// array and stored transposed, which the compiler can keep in registers for 32-bit elements.
+const TRANSPOSE_MICRO: usize = 8;
+
+/// The number of columns of `dst` which `transpose_blocked` writes in one task, so that a tall
+/// matrix, whose `dst` has few rows, is still split into many tasks.
+const TRANSPOSE_SEGMENT: usize = 64 * TRANSPOSE_TILE;
+
+/// Transpose the row-major `height x width` matrix in `src` into `dst`, which is then the
+/// row-major `width x height` transpose.
+///
+/// The matrix is split into `2048 x 32` tiles, each from a band of 32 rows of `dst` and a segment
+/// of 2048 of its columns, so that a tall matrix still gives many tasks, and the tiles are
+/// transposed in parallel. A tile is walked in `8 x 8` blocks, transposed with a register-sized
+/// micro-kernel, so that the writes to each of its 32 rows of `dst` move forward together.
+#[instrument(level = "debug", skip_all, fields(width, height))]
+pub fn transpose_blocked<T: Copy + Send + Sync>(
+    src: &[T],
+    dst: &mut [T],
+    width: usize,
+    height: usize,
+) {
+    assert_eq!(src.len(), width * height);
+    assert_eq!(dst.len(), width * height);
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    // Each band of `dst` holds the transpose of `TRANSPOSE_TILE` columns of `src`.
+    dst.par_chunks_mut(TRANSPOSE_TILE * height)
+        .enumerate()
+        .for_each(|(band, dst_band)| {
+            let col_start = band * TRANSPOSE_TILE;
+            // Segment `s` of the band holds the pieces of its rows which transpose the rows
+            // `s * TRANSPOSE_SEGMENT..` of `src`.
+            let mut segments: Vec<Vec<&mut [T]>> = (0..height.div_ceil(TRANSPOSE_SEGMENT))
+                .map(|_| Vec::with_capacity(TRANSPOSE_TILE))
+                .collect();
+            for dst_row in dst_band.chunks_mut(height) {
+                for (segment, piece) in segments
+                    .iter_mut()
+                    .zip(dst_row.chunks_mut(TRANSPOSE_SEGMENT))
+                {
+                    segment.push(piece);
+                }
+            }
+            segments
+                .into_par_iter()
+                .enumerate()
+                .for_each(|(segment, mut dst_rows)| {
+                    let row_start = segment * TRANSPOSE_SEGMENT;
+                    let src_segment = &src[row_start * width + col_start..];
+                    transpose_segment(src_segment, width, &mut dst_rows);
+                });
+        });
+}
+
+/// Transpose the `dst_rows[0].len() x dst_rows.len()` block at the start of `src`, whose rows are
+/// `width` elements apart, into `dst_rows`.
+fn transpose_segment<T: Copy>(src: &[T], width: usize, dst_rows: &mut [&mut [T]]) {
+    let rows = dst_rows[0].len();
+    let cols = dst_rows.len();
+    for r in (0..rows).step_by(TRANSPOSE_MICRO) {
+        let block_rows = TRANSPOSE_MICRO.min(rows - r);
+        for c in (0..cols).step_by(TRANSPOSE_MICRO) {
+            let block_cols = TRANSPOSE_MICRO.min(cols - c);
+            let src_block = &src[r * width + c..];
+            let dst_block = &mut dst_rows[c..c + block_cols];
+            if block_rows == TRANSPOSE_MICRO && block_cols == TRANSPOSE_MICRO {
+                let block = load_micro_block(src_block, width);
+                for (j, dst_row) in dst_block.iter_mut().enumerate() {
+                    for (i, x) in dst_row[r..r + TRANSPOSE_MICRO].iter_mut().enumerate() {
+                        *x = block[i][j];
+                    }
+                }
+            } else {
+                for i in 0..block_rows {
+                    for (j, dst_row) in dst_block.iter_mut().enumerate() {
+                        dst_row[r + i] = src_block[i * width + j];
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Transpose the row-major `n x n` matrix in `values` in place.
+///
+/// As with `transpose_blocked`, this works through `32 x 32` tiles and `8 x 8` micro-kernel
+/// blocks, swapping each block above the diagonal with its mirror image below it.
+#[instrument(level = "debug", skip_all, fields(n))]
+pub fn transpose_square_in_place<T: Copy>(values: &mut [T], n: usize) {
+    assert_eq!(values.len(), n * n);
+    for tile_row in (0..n).step_by(TRANSPOSE_TILE) {
+        for tile_col in (tile_row..n).step_by(TRANSPOSE_TILE) {
+            let row_end = (tile_row + TRANSPOSE_TILE).min(n);
+            let col_end = (tile_col + TRANSPOSE_TILE).min(n);
+            for r in (tile_row..row_end).step_by(TRANSPOSE_MICRO) {
+                // On the diagonal tile, only visit the blocks on or above the diagonal.
+                let first_col = if tile_row == tile_col { r } else { tile_col };
+                for c in (first_col..col_end).step_by(TRANSPOSE_MICRO) {
+                    let rows = TRANSPOSE_MICRO.min(row_end - r);
+                    let cols = TRANSPOSE_MICRO.min(col_end - c);
+                    if rows == TRANSPOSE_MICRO && cols == TRANSPOSE_MICRO {
+                        let upper = load_micro_block(&values[r * n + c..], n);
+                        let lower = load_micro_block(&values[c * n + r..], n);
+                        store_micro_block_transposed(&mut values[c * n + r..], n, &upper);
+                        store_micro_block_transposed(&mut values[r * n + c..], n, &lower);
+                    } else {
+                        for i in 0..rows {
+                            // On a diagonal block, only swap the elements above the diagonal.
+                            let first_j = if r == c { i + 1 } else { 0 };
+                            for j in first_j..cols {
+                                values.swap((r + i) * n + c + j, (c + j) * n + r + i);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Load the `8 x 8` block at the start of `values`, whose rows are `stride` elements apart.
+#[inline(always)]
+fn load_micro_block<T: Copy>(
+    values: &[T],
+    stride: usize,
+) -> [[T; TRANSPOSE_MICRO]; TRANSPOSE_MICRO] {
+    core::array::from_fn(|i| {
+        let start = i * stride;
+        core::array::from_fn(|j| values[start + j])
+    })
+}
+
+/// Store the transpose of `block` at the start of `values`, whose rows are `stride` elements
+/// apart.
+#[inline(always)]
+fn store_micro_block_transposed<T: Copy>(
+    values: &mut [T],
+    stride: usize,
+    block: &[[T; TRANSPOSE_MICRO]; TRANSPOSE_MICRO],
+) {
+    for j in 0..TRANSPOSE_MICRO {
+        let row = &mut values[j * stride..j * stride + TRANSPOSE_MICRO];
+        for (i, x) in row.iter_mut().enumerate() {
+            *x = block[i][j];
+        }
+    }
+}
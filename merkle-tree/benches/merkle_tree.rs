@@ -44,7 +44,14 @@ fn bench_bb_poseidon2(criterion: &mut Criterion) {
         h.clone(),
         c.clone(),
     );
-    bench_merkle_tree::<<F as Field>::Packing, <F as Field>::Packing, H, C, 8>(criterion, h, c);
+    bench_merkle_tree::<<F as Field>::Packing, <F as Field>::Packing, H, C, 8>(
+        criterion,
+        h.clone(),
+        c.clone(),
+    );
+    bench_merkle_tree_2_20_leaves::<<F as Field>::Packing, <F as Field>::Packing, H, C, 8>(
+        criterion, h, c,
+    );
 }
 
 fn bench_bb_rescue(criterion: &mut Criterion) {
@@ -135,6 +142,45 @@ where
     });
 }
 
+/// A tall, narrow tree, for which most of the time goes on compressing digests rather than
+/// hashing leaves.
+fn bench_merkle_tree_2_20_leaves<P, PW, H, C, const DIGEST_ELEMS: usize>(
+    criterion: &mut Criterion,
+    h: H,
+    c: C,
+) where
+    P: PackedField,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Scalar, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    [PW::Value; DIGEST_ELEMS]: Serialize + DeserializeOwned,
+    Standard: Distribution<P::Scalar>,
+{
+    const ROWS: usize = 1 << 20;
+    const COLS: usize = 8;
+
+    let matrix = RowMajorMatrix::<P::Scalar>::rand(&mut thread_rng(), ROWS, COLS);
+    let params = BenchmarkId::from_parameter(matrix.dimensions());
+    let leaves = vec![matrix];
+
+    let name = format!(
+        "MerkleTree::<{}, {}>::new",
+        type_name::<H>(),
+        type_name::<C>()
+    );
+    let mut group = criterion.benchmark_group(name);
+    group.sample_size(10);
+
+    let mmcs = MerkleTreeMmcs::<P, PW, H, C, DIGEST_ELEMS>::new(h, c);
+    group.bench_with_input(params, &leaves, |b, input| {
+        b.iter(|| mmcs.commit(input.clone()))
+    });
+}
+
 fn bench_mmcs<P, PW, H, C, const DIGEST_ELEMS: usize>(criterion: &mut Criterion, h: H, c: C)
 where
     P: PackedField,
@@ -0,0 +1,334 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::marker::PhantomData;
+
+use p3_commit::Mmcs;
+use p3_field::PrimeField64;
+use p3_matrix::{Dimensions, Matrix};
+use p3_symmetric::{Hash, MerkleCap};
+use serde::de::DeserializeOwned;
+use serde::{Deserializer, Serialize};
+
+/// An MMCS which wraps an MMCS with byte digests, such as a Keccak-256 `MerkleTreeMmcs`, and
+/// exposes its commitments as field elements, so that they can be observed by a challenger over
+/// `F`, such as `DuplexChallenger`.
+///
+/// Each digest of the cap is encoded as `FIELD_ELEMS` limbs by `pack_digest`. Openings and proofs
+/// are those of the inner MMCS, so only the commitment changes. To verify, the limbs are decoded
+/// by `unpack_digest`, which rejects any limbs that `pack_digest` could not have produced, and the
+/// opening is checked against the inner MMCS's commitment.
+///
+/// Generics:
+/// - `F`: the field of the transcript
+/// - `InnerMmcs`: an MMCS with digests of `DIGEST_BYTES` bytes
+#[derive(Clone, Debug)]
+pub struct ByteDigestMmcs<F, InnerMmcs, const DIGEST_BYTES: usize, const FIELD_ELEMS: usize> {
+    inner: InnerMmcs,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField64, InnerMmcs, const DIGEST_BYTES: usize, const FIELD_ELEMS: usize>
+    ByteDigestMmcs<F, InnerMmcs, DIGEST_BYTES, FIELD_ELEMS>
+{
+    /// Panics unless `FIELD_ELEMS` limbs can hold a digest; see `pack_digest`.
+    pub fn new(inner: InnerMmcs) -> Self {
+        check_limb_count::<F, DIGEST_BYTES, FIELD_ELEMS>();
+        Self {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// The number of bits of a digest held by each limb: the largest `b` with `2^b <= F::ORDER_U64`,
+/// so that every `b`-bit value is a canonical field element. This is 30 for BabyBear, KoalaBear
+/// and Mersenne31, and 63 for Goldilocks.
+const fn limb_bits<F: PrimeField64>() -> usize {
+    F::ORDER_U64.ilog2() as usize
+}
+
+fn check_limb_count<F: PrimeField64, const DIGEST_BYTES: usize, const FIELD_ELEMS: usize>() {
+    assert!(
+        FIELD_ELEMS * limb_bits::<F>() >= 8 * DIGEST_BYTES,
+        "{FIELD_ELEMS} limbs of {} bits cannot hold a digest of {DIGEST_BYTES} bytes",
+        limb_bits::<F>()
+    );
+}
+
+/// Encode a byte digest as field elements.
+///
+/// The digest is read as a little-endian integer and split into `FIELD_ELEMS` limbs of
+/// `floor(log2(F::ORDER_U64))` bits each, least significant first, with any limbs past the end of
+/// the digest set to zero. Since every limb is below the field order, distinct digests give
+/// distinct limbs, and `unpack_digest` recovers the digest.
+///
+/// For a 32-byte digest, BabyBear, KoalaBear and Mersenne31 need 9 limbs and Goldilocks needs 5.
+/// Panics if `FIELD_ELEMS` limbs are too few.
+pub fn pack_digest<F: PrimeField64, const DIGEST_BYTES: usize, const FIELD_ELEMS: usize>(
+    digest: &[u8; DIGEST_BYTES],
+) -> [F; FIELD_ELEMS] {
+    check_limb_count::<F, DIGEST_BYTES, FIELD_ELEMS>();
+    let bits = limb_bits::<F>();
+    let mask = (1 << bits) - 1;
+
+    let mut limbs = [F::ZERO; FIELD_ELEMS];
+    let mut limb_index = 0;
+    let mut acc = 0u128;
+    let mut acc_bits = 0;
+    for &byte in digest {
+        acc |= u128::from(byte) << acc_bits;
+        acc_bits += 8;
+        while acc_bits >= bits {
+            limbs[limb_index] = F::from_canonical_u64((acc & mask) as u64);
+            acc >>= bits;
+            acc_bits -= bits;
+            limb_index += 1;
+        }
+    }
+    if acc_bits > 0 {
+        limbs[limb_index] = F::from_canonical_u64(acc as u64);
+    }
+    limbs
+}
+
+/// Decode field elements produced by `pack_digest` back into the digest.
+///
+/// Returns `None` if the limbs are not the image of any digest, i.e. if a limb has more than
+/// `floor(log2(F::ORDER_U64))` bits, or if any bits beyond the end of the digest are set.
+pub fn unpack_digest<F: PrimeField64, const DIGEST_BYTES: usize, const FIELD_ELEMS: usize>(
+    limbs: &[F; FIELD_ELEMS],
+) -> Option<[u8; DIGEST_BYTES]> {
+    check_limb_count::<F, DIGEST_BYTES, FIELD_ELEMS>();
+    let bits = limb_bits::<F>();
+
+    let mut digest = [0u8; DIGEST_BYTES];
+    let mut byte_index = 0;
+    let mut acc = 0u128;
+    let mut acc_bits = 0;
+    for limb in limbs {
+        let limb = limb.as_canonical_u64();
+        if limb >> bits != 0 {
+            return None;
+        }
+        acc |= u128::from(limb) << acc_bits;
+        acc_bits += bits;
+        while acc_bits >= 8 && byte_index < DIGEST_BYTES {
+            digest[byte_index] = acc as u8;
+            acc >>= 8;
+            acc_bits -= 8;
+            byte_index += 1;
+        }
+        if byte_index == DIGEST_BYTES {
+            // Whatever is left is padding, which must be zero.
+            if acc != 0 {
+                return None;
+            }
+            acc_bits = 0;
+        }
+    }
+    Some(digest)
+}
+
+/// An error from verifying an opening of a `ByteDigestMmcs`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ByteDigestMmcsError<InnerError> {
+    /// A digest of the commitment is not the image of any byte digest under `pack_digest`.
+    NonCanonicalDigest,
+    /// The inner MMCS rejected the opening.
+    Inner(InnerError),
+}
+
+impl<InnerError: Display> Display for ByteDigestMmcsError<InnerError> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NonCanonicalDigest => {
+                write!(f, "the commitment does not encode a byte digest")
+            }
+            Self::Inner(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl<F, T, InnerMmcs, const DIGEST_BYTES: usize, const FIELD_ELEMS: usize> Mmcs<T>
+    for ByteDigestMmcs<F, InnerMmcs, DIGEST_BYTES, FIELD_ELEMS>
+where
+    F: PrimeField64,
+    T: Send + Sync,
+    InnerMmcs: Mmcs<T, Commitment = MerkleCap<F, u8, DIGEST_BYTES>>,
+    [F; FIELD_ELEMS]: Serialize + DeserializeOwned,
+{
+    type ProverData<M> = InnerMmcs::ProverData<M>;
+    type Commitment = MerkleCap<F, F, FIELD_ELEMS>;
+    type Proof = InnerMmcs::Proof;
+    type Error = ByteDigestMmcsError<InnerMmcs::Error>;
+
+    const DIGEST_WIDTH: usize = FIELD_ELEMS;
+
+    fn commit<M: Matrix<T>>(&self, inputs: Vec<M>) -> (Self::Commitment, Self::ProverData<M>) {
+        let (byte_cap, prover_data) = self.inner.commit(inputs);
+        let roots = byte_cap
+            .into_iter()
+            .map(|root| Hash::from(pack_digest(root.as_ref())))
+            .collect::<Vec<_>>();
+        (MerkleCap::from(roots), prover_data)
+    }
+
+    fn open_batch<M: Matrix<T>>(
+        &self,
+        index: usize,
+        prover_data: &Self::ProverData<M>,
+    ) -> (Vec<Vec<T>>, Self::Proof) {
+        self.inner.open_batch(index, prover_data)
+    }
+
+    fn get_matrices<'a, M: Matrix<T>>(&self, prover_data: &'a Self::ProverData<M>) -> Vec<&'a M> {
+        self.inner.get_matrices(prover_data)
+    }
+
+    fn commitment_cap_height(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner.commitment_cap_height(dimensions)
+    }
+
+    fn deserialize_commitment<'de, D: Deserializer<'de>>(
+        &self,
+        dimensions: &[Dimensions],
+        deserializer: D,
+    ) -> Result<Self::Commitment, D::Error> {
+        // The cap has a packed digest for each digest of the inner cap.
+        MerkleCap::deserialize_with_height(self.commitment_cap_height(dimensions), deserializer)
+    }
+
+    fn verify_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[Vec<T>],
+        proof: &Self::Proof,
+    ) -> Result<(), Self::Error> {
+        let byte_roots = commit
+            .roots()
+            .iter()
+            .map(|root| unpack_digest(root.as_ref()).map(Hash::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ByteDigestMmcsError::NonCanonicalDigest)?;
+        let byte_cap = MerkleCap::from(byte_roots);
+        self.inner
+            .verify_batch(&byte_cap, dimensions, index, opened_values, proof)
+            .map_err(ByteDigestMmcsError::Inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_challenger::{CanObserve, DuplexChallenger};
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::Goldilocks;
+    use p3_keccak::Keccak256Hash;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{MerkleTreeError, MerkleTreeMmcs};
+
+    type F = BabyBear;
+    type FieldHash = SerializingHasher32<Keccak256Hash>;
+    type MyCompress = CompressionFunctionFromHasher<Keccak256Hash, 2, 32>;
+    type KeccakMmcs = MerkleTreeMmcs<F, u8, FieldHash, MyCompress, 32>;
+    type MyMmcs = ByteDigestMmcs<F, KeccakMmcs, 32, 9>;
+
+    fn keccak_mmcs() -> KeccakMmcs {
+        KeccakMmcs::new(
+            FieldHash::new(Keccak256Hash {}),
+            MyCompress::new(Keccak256Hash {}),
+        )
+    }
+
+    #[test]
+    fn packing_round_trips() {
+        let mut rng = thread_rng();
+        for digest in [[0u8; 32], [0xff; 32], rng.gen()] {
+            let limbs: [F; 9] = pack_digest(&digest);
+            assert_eq!(unpack_digest(&limbs), Some(digest));
+            let limbs: [Goldilocks; 5] = pack_digest(&digest);
+            assert_eq!(unpack_digest(&limbs), Some(digest));
+        }
+        // Extra limbs are zero.
+        let limbs: [F; 10] = pack_digest(&[0xff; 32]);
+        assert_eq!(limbs[9], F::ZERO);
+        assert_eq!(unpack_digest(&limbs), Some([0xff; 32]));
+    }
+
+    #[test]
+    fn unpacking_rejects_non_canonical_limbs() {
+        let limbs: [F; 9] = pack_digest(&[0xff; 32]);
+        // A limb with more than 30 bits.
+        let mut too_wide = limbs;
+        too_wide[0] = F::from_canonical_u32(1 << 30);
+        assert_eq!(unpack_digest::<F, 32, 9>(&too_wide), None);
+        // The last limb holds 256 - 8 * 30 = 16 bits of the digest.
+        let mut past_the_end = limbs;
+        past_the_end[8] = F::from_canonical_u32(1 << 16);
+        assert_eq!(unpack_digest::<F, 32, 9>(&past_the_end), None);
+        let mut extra = [F::ZERO; 10];
+        extra[9] = F::ONE;
+        assert_eq!(unpack_digest::<F, 32, 10>(&extra), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot hold a digest")]
+    fn too_few_limbs() {
+        ByteDigestMmcs::<F, KeccakMmcs, 32, 8>::new(keccak_mmcs());
+    }
+
+    #[test]
+    fn commit_open_verify() {
+        let mut rng = thread_rng();
+        let inner = keccak_mmcs().with_cap_height(1);
+        let mmcs = MyMmcs::new(inner.clone());
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 64, 7),
+            RowMajorMatrix::<F>::rand(&mut rng, 20, 3),
+        ];
+        let dims = mats.iter().map(Matrix::dimensions).collect::<Vec<_>>();
+
+        let (commit, prover_data) = mmcs.commit(mats.clone());
+        let (byte_commit, _) = inner.commit(mats);
+        assert_eq!(commit.roots().len(), 2);
+        for (root, byte_root) in commit.roots().iter().zip(byte_commit.roots()) {
+            assert_eq!(unpack_digest(root.as_ref()), Some(*byte_root.as_ref()));
+        }
+
+        // The commitment can be observed by a challenger over the field.
+        let perm = Poseidon2BabyBear::<16>::new_from_rng_128(&mut rng);
+        let mut challenger = DuplexChallenger::<F, _, 16, 8>::new(perm);
+        challenger.observe(commit.clone());
+
+        for index in [0, 31, 32, 63] {
+            let (opened_values, proof) = mmcs.open_batch(index, &prover_data);
+            mmcs.verify_batch(&commit, &dims, index, &opened_values, &proof)
+                .expect("expected verification to succeed");
+
+            let mut bad_values = opened_values.clone();
+            bad_values[0][0] += F::ONE;
+            assert_eq!(
+                mmcs.verify_batch(&commit, &dims, index, &bad_values, &proof),
+                Err(ByteDigestMmcsError::Inner(MerkleTreeError::RootMismatch))
+            );
+        }
+
+        let mut bad_commit = commit.roots().to_vec();
+        let mut limbs: [F; 9] = bad_commit[0].into();
+        limbs[8] = F::from_canonical_u32(1 << 20);
+        bad_commit[0] = limbs.into();
+        let (opened_values, proof) = mmcs.open_batch(0, &prover_data);
+        assert_eq!(
+            mmcs.verify_batch(&bad_commit.into(), &dims, 0, &opened_values, &proof),
+            Err(ByteDigestMmcsError::NonCanonicalDigest)
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Checkpoints of partially built Merkle trees.
+//!
+//! A checkpoint holds the digest layers a `MerkleTreeBuilder` has built so far, but not the
+//! leaves, which the prover is expected to be able to reproduce. To catch a checkpoint being
+//! resumed against the wrong inputs, it also records the dimensions of the leaves and a
+//! fingerprint of the hasher and compression function. The contents of the leaves are not
+//! checked, just as with `MerkleTree::from_parts`.
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::iter;
+use std::io::{self, Read, Write};
+
+use p3_matrix::Matrix;
+use p3_symmetric::{CryptographicHasher, PseudoCompressionFunction};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::merkle_tree::digest_layer_lens;
+use crate::MerkleTreeBuilder;
+
+/// The version of the checkpoint format, bumped whenever it changes.
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// The number of default leaf values hashed to fingerprint the hasher.
+const FINGERPRINT_PROBE_LEN: usize = 16;
+
+/// Everything in a checkpoint besides the digest layers.
+#[derive(Serialize, Deserialize)]
+struct CheckpointHeader<W, const DIGEST_ELEMS: usize> {
+    version: u32,
+    /// The width and height of each leaf matrix, in order.
+    dimensions: Vec<(usize, usize)>,
+    #[serde(bound(serialize = "[W; DIGEST_ELEMS]: Serialize"))]
+    #[serde(bound(deserialize = "[W; DIGEST_ELEMS]: Deserialize<'de>"))]
+    fingerprint: [W; DIGEST_ELEMS],
+    num_pruned_layers: usize,
+}
+
+/// The reasons `MerkleTreeBuilder::resume_from` can refuse a checkpoint.
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    /// The checkpoint could not be decoded, or its layers do not have the shape of a tree.
+    Malformed,
+    /// The checkpoint was written in another version of the format.
+    UnsupportedVersion(u32),
+    /// The checkpoint was made for leaves of other dimensions.
+    LeavesMismatch,
+    /// The checkpoint was made with another hasher or compression function.
+    HasherMismatch,
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not access the checkpoint: {err}"),
+            Self::Malformed => write!(f, "the checkpoint is malformed"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported checkpoint version {version}")
+            }
+            Self::LeavesMismatch => {
+                write!(f, "the checkpoint was made for leaves of other dimensions")
+            }
+            Self::HasherMismatch => {
+                write!(f, "the checkpoint was made with another hasher")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// A digest which depends on the parameters of `h` and `c`, e.g. their round constants, but not
+/// on any data.
+fn hasher_fingerprint<F, W, H, C, const DIGEST_ELEMS: usize>(h: &H, c: &C) -> [W; DIGEST_ELEMS]
+where
+    F: Clone + Default,
+    H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+    C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    W: Copy,
+{
+    let probe = h.hash_iter(iter::repeat(F::default()).take(FINGERPRINT_PROBE_LEN));
+    c.compress([probe, probe])
+}
+
+fn leaf_dimensions<F, M: Matrix<F>>(leaves: &[M]) -> Vec<(usize, usize)>
+where
+    F: Send + Sync,
+{
+    leaves.iter().map(|m| (m.width(), m.height())).collect()
+}
+
+impl<F, W, M, const DIGEST_ELEMS: usize> MerkleTreeBuilder<F, W, M, DIGEST_ELEMS>
+where
+    F: Clone + Default + Send + Sync,
+    W: Copy + PartialEq,
+    M: Matrix<F>,
+    [W; DIGEST_ELEMS]: Serialize + DeserializeOwned,
+{
+    /// Write the layers built so far to `writer`, along with the metadata `resume_from` checks.
+    ///
+    /// `h` and `c` must be the hasher and compression function the layers were built with.
+    pub fn save_layers<H, C>(&self, h: &H, c: &C, writer: impl Write) -> io::Result<()>
+    where
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    {
+        let header = CheckpointHeader {
+            version: CHECKPOINT_VERSION,
+            dimensions: leaf_dimensions(&self.leaves),
+            fingerprint: hasher_fingerprint::<F, _, _, _, DIGEST_ELEMS>(h, c),
+            num_pruned_layers: self.num_pruned_layers,
+        };
+        postcard::to_io(&(header, &self.digest_layers), writer)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        Ok(())
+    }
+
+    /// Read a checkpoint written by `save_layers`, and continue building the tree over `leaves`
+    /// from its last layer. The digest storage is the one the checkpoint was made with.
+    ///
+    /// Refuses the checkpoint if it was made for leaves of other dimensions, or with another
+    /// hasher or compression function.
+    pub fn resume_from<H, C>(
+        h: &H,
+        c: &C,
+        mut reader: impl Read,
+        leaves: Vec<M>,
+    ) -> Result<Self, CheckpointError>
+    where
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let (header, digest_layers): (CheckpointHeader<W, DIGEST_ELEMS>, Vec<Vec<_>>) =
+            postcard::from_bytes(&bytes).map_err(|_| CheckpointError::Malformed)?;
+        drop(bytes);
+
+        if header.version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::UnsupportedVersion(header.version));
+        }
+        if header.dimensions != leaf_dimensions(&leaves) {
+            return Err(CheckpointError::LeavesMismatch);
+        }
+        if header.fingerprint != hasher_fingerprint::<F, _, _, _, DIGEST_ELEMS>(h, c) {
+            return Err(CheckpointError::HasherMismatch);
+        }
+
+        let mut builder = Self::new(leaves, Default::default());
+        let expected_lens =
+            digest_layer_lens(builder.leaves.iter().map(|m| m.height()).max().unwrap());
+        let num_built_layers = digest_layers.len();
+        // Pruned layers are empty, and are never the last layer built.
+        let well_formed = header.num_pruned_layers < expected_lens.len()
+            && num_built_layers <= expected_lens.len()
+            && digest_layers
+                .iter()
+                .zip(expected_lens)
+                .enumerate()
+                .all(|(i, (layer, len))| {
+                    let pruned = i < header.num_pruned_layers && i + 1 < num_built_layers;
+                    layer.len() == if pruned { 0 } else { len }
+                });
+        if !well_formed {
+            return Err(CheckpointError::Malformed);
+        }
+
+        builder.digest_layers = digest_layers;
+        builder.num_pruned_layers = header.num_pruned_layers;
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use itertools::Itertools;
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_field::Field;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{DigestStorage, MerkleTree};
+
+    type F = BabyBear;
+    type P = <F as Field>::Packing;
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+    type Builder = MerkleTreeBuilder<F, F, RowMajorMatrix<F>, 8>;
+
+    fn leaves() -> Vec<RowMajorMatrix<F>> {
+        let mut rng = thread_rng();
+        [1000, 250, 60, 3]
+            .into_iter()
+            .map(|height| RowMajorMatrix::rand(&mut rng, height, 3))
+            .collect()
+    }
+
+    #[test]
+    fn resumed_build_matches_uninterrupted_build() {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        let (h, c) = (MyHash::new(perm.clone()), MyCompress::new(perm));
+        let leaves = leaves();
+
+        for storage in [DigestStorage::Full, DigestStorage::Pruned { top_layers: 3 }] {
+            let expected =
+                MerkleTree::new_with_storage::<P, P, _, _>(&h, &c, leaves.clone(), storage);
+
+            let mut builder = Builder::new(leaves.clone(), storage);
+            let half = builder.num_layers() / 2;
+            for _ in 0..half {
+                builder.build_layer::<P, P, _, _>(&h, &c);
+            }
+            let mut checkpoint = vec![];
+            builder.save_layers(&h, &c, &mut checkpoint).unwrap();
+            drop(builder);
+
+            let mut resumed =
+                Builder::resume_from(&h, &c, &checkpoint[..], leaves.clone()).unwrap();
+            assert_eq!(resumed.num_built_layers(), half);
+            while !resumed.is_complete() {
+                resumed.build_layer::<P, P, _, _>(&h, &c);
+            }
+            let tree = resumed.finish();
+            assert_eq!(tree.root(), expected.root());
+            assert_eq!(tree.digest_layers, expected.digest_layers);
+        }
+    }
+
+    #[test]
+    fn refuses_mismatched_checkpoints() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let (h, c) = (MyHash::new(perm.clone()), MyCompress::new(perm));
+        let leaves = leaves();
+
+        let mut builder = Builder::new(leaves.clone(), DigestStorage::Full);
+        builder.build_layer::<P, P, _, _>(&h, &c);
+        let mut checkpoint = vec![];
+        builder.save_layers(&h, &c, &mut checkpoint).unwrap();
+        assert!(Builder::resume_from(&h, &c, &checkpoint[..], leaves.clone()).is_ok());
+
+        let shorter = leaves.iter().cloned().map(|mut m| {
+            m.values.truncate(m.values.len() - 3);
+            m
+        });
+        assert!(matches!(
+            Builder::resume_from(&h, &c, &checkpoint[..], shorter.collect_vec()),
+            Err(CheckpointError::LeavesMismatch)
+        ));
+
+        let other_perm = Perm::new_from_rng_128(&mut rng);
+        let other_h = MyHash::new(other_perm.clone());
+        let other_c = MyCompress::new(other_perm);
+        assert!(matches!(
+            Builder::resume_from(&other_h, &other_c, &checkpoint[..], leaves.clone()),
+            Err(CheckpointError::HasherMismatch)
+        ));
+
+        let truncated = &checkpoint[..checkpoint.len() - 1];
+        assert!(matches!(
+            Builder::resume_from(&h, &c, truncated, leaves),
+            Err(CheckpointError::Malformed)
+        ));
+    }
+}
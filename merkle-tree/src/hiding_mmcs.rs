@@ -7,13 +7,13 @@ use p3_field::PackedValue;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::stack::HorizontalPair;
 use p3_matrix::{Dimensions, Matrix};
-use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_symmetric::{CryptographicHasher, MerkleCap, PseudoCompressionFunction};
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{MerkleTree, MerkleTreeError, MerkleTreeMmcs};
+use crate::{DigestStorage, MerkleTree, MerkleTreeError, MerkleTreeMmcs};
 
 /// A vector commitment scheme backed by a `MerkleTree`.
 ///
@@ -21,6 +21,12 @@ use crate::{MerkleTree, MerkleTreeError, MerkleTreeMmcs};
 /// done to turn the Merkle tree into a hiding commitment. See e.g. Section 3 of
 /// [Interactive Oracle Proofs](https://eprint.iacr.org/2016/116).
 ///
+/// The salts of each matrix are drawn from `R` at commit time, kept in the prover data alongside
+/// the matrix, and revealed only for the rows which are opened. Since every leaf hash covers
+/// `SALT_ELEMS` fresh random elements, the commitment and the authentication paths reveal nothing
+/// about the rows which are not opened, beyond their dimensions. Committing to the same matrices
+/// twice gives unrelated commitments.
+///
 /// `SALT_ELEMS` should be set such that the product of `SALT_ELEMS` with the size of the value
 /// (`P::Value`) is at least the target security parameter.
 ///
@@ -51,6 +57,20 @@ impl<P, PW, H, C, R, const DIGEST_ELEMS: usize, const SALT_ELEMS: usize>
             rng: rng.into(),
         }
     }
+
+    /// Commit to the cap of height `cap_height` rather than the root, as with `MerkleTreeMmcs`.
+    #[must_use]
+    pub fn with_cap_height(mut self, cap_height: usize) -> Self {
+        self.inner = self.inner.with_cap_height(cap_height);
+        self
+    }
+
+    /// Keep only some of the digest layers in the prover data, as with `MerkleTreeMmcs`.
+    #[must_use]
+    pub fn with_digest_storage(mut self, digest_storage: DigestStorage) -> Self {
+        self.inner = self.inner.with_digest_storage(digest_storage);
+        self
+    }
 }
 
 impl<P, PW, H, C, R, const DIGEST_ELEMS: usize, const SALT_ELEMS: usize> Mmcs<P::Value>
@@ -72,11 +92,13 @@ where
 {
     type ProverData<M> =
         MerkleTree<P::Value, PW::Value, HorizontalPair<M, RowMajorMatrix<P::Value>>, DIGEST_ELEMS>;
-    type Commitment = Hash<P::Value, PW::Value, DIGEST_ELEMS>;
+    type Commitment = MerkleCap<P::Value, PW::Value, DIGEST_ELEMS>;
     /// The first item is salts; the second is the usual Merkle proof (sibling digests).
     type Proof = (Vec<Vec<P::Value>>, Vec<[PW::Value; DIGEST_ELEMS]>);
     type Error = MerkleTreeError;
 
+    const DIGEST_WIDTH: usize = DIGEST_ELEMS;
+
     fn commit<M: Matrix<P::Value>>(
         &self,
         inputs: Vec<M>,
@@ -104,7 +126,8 @@ where
         let (openings, salts): (Vec<_>, Vec<_>) = salted_openings
             .into_iter()
             .map(|row| {
-                let (a, b) = row.split_at(row.len() - SALT_ELEMS);
+                // A row past the end of a shorter matrix is empty, salt included.
+                let (a, b) = row.split_at(row.len().saturating_sub(SALT_ELEMS));
                 (a.to_vec(), b.to_vec())
             })
             .unzip();
@@ -118,6 +141,18 @@ where
         prover_data.leaves.iter().map(|mat| &mat.first).collect()
     }
 
+    fn commitment_cap_height(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner.commitment_cap_height(dimensions)
+    }
+
+    fn deserialize_commitment<'de, D: Deserializer<'de>>(
+        &self,
+        dimensions: &[Dimensions],
+        deserializer: D,
+    ) -> Result<Self::Commitment, D::Error> {
+        self.inner.deserialize_commitment(dimensions, deserializer)
+    }
+
     fn verify_batch(
         &self,
         commit: &Self::Commitment,
@@ -127,6 +162,13 @@ where
         proof: &Self::Proof,
     ) -> Result<(), Self::Error> {
         let (salts, siblings) = proof;
+        if salts.len() != opened_values.len()
+            || salts.iter().zip(opened_values).any(|(salt, opened)| {
+                salt.len() != SALT_ELEMS && !(salt.is_empty() && opened.is_empty())
+            })
+        {
+            return Err(MerkleTreeError::WrongBatchSize);
+        }
 
         let opened_salted_values = opened_values
             .iter()
@@ -148,8 +190,9 @@ mod tests {
     use p3_commit::Mmcs;
     use p3_field::{Field, FieldAlgebra};
     use p3_matrix::dense::RowMajorMatrix;
-    use p3_matrix::Matrix;
+    use p3_matrix::{Dimensions, Matrix};
     use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+    use p3_util::log2_ceil_usize;
     use rand::prelude::*;
 
     use super::MerkleTreeHidingMmcs;
@@ -208,4 +251,101 @@ mod tests {
         let (opened_values, proof) = mmcs.open_batch(17, &prover_data);
         mmcs.verify_batch(&commit, &dims, 17, &opened_values, &proof)
     }
+
+    fn new_mmcs() -> MyMmcs {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        MyMmcs::new(
+            MyHash::new(perm.clone()),
+            MyCompress::new(perm),
+            thread_rng(),
+        )
+    }
+
+    #[test]
+    fn mixed_heights() -> Result<(), MerkleTreeError> {
+        let mmcs = new_mmcs();
+        let mut rng = thread_rng();
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 100, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 25, 7),
+            RowMajorMatrix::<F>::rand(&mut rng, 3, 1),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats.clone());
+
+        for index in [0, 63, 99] {
+            let (opened_values, proof) = mmcs.open_batch(index, &prover_data);
+            // Only the rows are opened; the salts travel in the proof. Row 99 is past the end of
+            // the last matrix, so neither its row nor its salt is opened.
+            for (((mat, dims), opened), salt) in
+                mats.iter().zip(&dims).zip(&opened_values).zip(&proof.0)
+            {
+                let row = index >> (log2_ceil_usize(100) - log2_ceil_usize(dims.height));
+                if row < dims.height {
+                    assert_eq!(opened, &mat.row_slice(row).to_vec());
+                    assert_eq!(salt.len(), SALT_ELEMS);
+                } else {
+                    assert!(opened.is_empty() && salt.is_empty());
+                }
+            }
+            mmcs.verify_batch(&commit, &dims, index, &opened_values, &proof)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn same_matrices_different_salts() {
+        let mmcs = new_mmcs();
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 16, 4);
+        let (commit_1, prover_data_1) = mmcs.commit(vec![mat.clone()]);
+        let (commit_2, prover_data_2) = mmcs.commit(vec![mat]);
+        assert_ne!(commit_1, commit_2);
+
+        // The openings are the same, but the proofs are not interchangeable.
+        let (opened_1, proof_1) = mmcs.open_batch(5, &prover_data_1);
+        let (opened_2, _) = mmcs.open_batch(5, &prover_data_2);
+        assert_eq!(opened_1, opened_2);
+        let dims = [Dimensions {
+            width: 4,
+            height: 16,
+        }];
+        assert!(mmcs
+            .verify_batch(&commit_1, &dims, 5, &opened_1, &proof_1)
+            .is_ok());
+        assert!(mmcs
+            .verify_batch(&commit_2, &dims, 5, &opened_1, &proof_1)
+            .is_err());
+    }
+
+    #[test]
+    fn tampered_salt_fails() {
+        let mmcs = new_mmcs();
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 32, 2);
+        let dims = [mat.dimensions()];
+        let (commit, prover_data) = mmcs.commit(vec![mat]);
+        let (opened_values, proof) = mmcs.open_batch(9, &prover_data);
+
+        let mut tampered = proof.clone();
+        tampered.0[0][SALT_ELEMS - 1] += F::ONE;
+        assert!(mmcs
+            .verify_batch(&commit, &dims, 9, &opened_values, &tampered)
+            .is_err());
+
+        // Moving an element from the row into the salt keeps the hashed leaf the same, but the salt
+        // no longer has the right length.
+        let mut short_row = opened_values.clone();
+        let last = short_row[0].pop().unwrap();
+        let mut long_salt = proof.clone();
+        long_salt.0[0].insert(0, last);
+        assert!(matches!(
+            mmcs.verify_batch(&commit, &dims, 9, &short_row, &long_salt),
+            Err(MerkleTreeError::WrongBatchSize)
+        ));
+
+        let mut missing = proof;
+        missing.0.clear();
+        assert!(mmcs
+            .verify_batch(&commit, &dims, 9, &opened_values, &missing)
+            .is_err());
+    }
 }
@@ -0,0 +1,264 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_matrix::Dimensions;
+use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+
+/// A Merkle tree over the rows of a single matrix, which can grow one row at a time and have rows
+/// replaced, at a cost of `O(log n)` hashes each.
+///
+/// The tree has the same shape as a `MerkleTree` over the same rows, so it has the same root, and
+/// `open` produces the same openings as `MerkleTreeMmcs::open_batch`. They can be checked with
+/// `MerkleTreeMmcs::verify_batch`, against the root as a cap of height 0.
+///
+/// Generics:
+/// - `F`: a leaf value
+/// - `W`: an element of a digest
+/// - `H`: the leaf hasher
+/// - `C`: the digest compression function
+#[derive(Clone, Debug)]
+pub struct IncrementalMerkleTree<F, W, H, C, const DIGEST_ELEMS: usize> {
+    hash: H,
+    compress: C,
+    leaves: Vec<Vec<F>>,
+    /// The digests of each layer, from the leaves up. Unlike in `MerkleTree`, layers are not
+    /// padded; a missing right child is taken to be the default digest.
+    digest_layers: Vec<Vec<[W; DIGEST_ELEMS]>>,
+}
+
+impl<F, W, H, C, const DIGEST_ELEMS: usize> IncrementalMerkleTree<F, W, H, C, DIGEST_ELEMS>
+where
+    F: Clone,
+    W: Copy + Default,
+    H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+    C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+{
+    pub fn new(hash: H, compress: C) -> Self {
+        Self {
+            hash,
+            compress,
+            leaves: vec![],
+            digest_layers: vec![vec![]],
+        }
+    }
+
+    /// The number of leaves, i.e. the height of the matrix.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// The dimensions of the matrix, to pass to `MerkleTreeMmcs::verify_batch`.
+    pub fn dimensions(&self) -> Dimensions {
+        Dimensions {
+            width: self.leaves.first().map_or(0, Vec::len),
+            height: self.len(),
+        }
+    }
+
+    /// Add `leaf` as the next row. Panics unless it has the same width as the other leaves.
+    pub fn append(&mut self, leaf: Vec<F>) {
+        self.check_width(&leaf);
+        let index = self.len();
+        self.digest_layers[0].push(self.hash.hash_slice(&leaf));
+        self.leaves.push(leaf);
+        self.update_ancestors(index);
+    }
+
+    /// Replace the row at `index` with `leaf`. Panics unless it has the same width as the other
+    /// leaves.
+    pub fn update(&mut self, index: usize, leaf: Vec<F>) {
+        self.check_index(index);
+        self.check_width(&leaf);
+        self.digest_layers[0][index] = self.hash.hash_slice(&leaf);
+        self.leaves[index] = leaf;
+        self.update_ancestors(index);
+    }
+
+    /// Panics if the tree is empty.
+    #[must_use]
+    pub fn root(&self) -> Hash<F, W, DIGEST_ELEMS> {
+        assert!(!self.is_empty(), "an empty tree has no root");
+        self.digest_layers.last().unwrap()[0].into()
+    }
+
+    /// Open the row at `index`, in the same form as `MerkleTreeMmcs::open_batch`: the opened rows
+    /// of each matrix, of which there is one, and the siblings on the path to the root.
+    pub fn open(&self, index: usize) -> (Vec<Vec<F>>, Vec<[W; DIGEST_ELEMS]>) {
+        self.check_index(index);
+        let default_digest = [W::default(); DIGEST_ELEMS];
+        let proof = self.digest_layers[..self.digest_layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(layer, digests)| {
+                digests
+                    .get((index >> layer) ^ 1)
+                    .copied()
+                    .unwrap_or(default_digest)
+            })
+            .collect();
+        (vec![self.leaves[index].clone()], proof)
+    }
+
+    /// Recompute the ancestors of the leaf at `index`, adding a layer whenever the top one has
+    /// more than one node.
+    fn update_ancestors(&mut self, mut index: usize) {
+        let default_digest = [W::default(); DIGEST_ELEMS];
+        let mut layer = 0;
+        while self.digest_layers[layer].len() > 1 {
+            let children = &self.digest_layers[layer];
+            let left = children[index & !1];
+            let right = children.get(index | 1).copied().unwrap_or(default_digest);
+            let parent = self.compress.compress([left, right]);
+
+            index >>= 1;
+            layer += 1;
+            if layer == self.digest_layers.len() {
+                self.digest_layers.push(vec![]);
+            }
+            let parents = &mut self.digest_layers[layer];
+            if index == parents.len() {
+                parents.push(parent);
+            } else {
+                parents[index] = parent;
+            }
+        }
+    }
+
+    fn check_index(&self, index: usize) {
+        assert!(
+            index < self.len(),
+            "index {index} is out of bounds for a tree of {} leaves",
+            self.len()
+        );
+    }
+
+    fn check_width(&self, leaf: &[F]) {
+        if let Some(first) = self.leaves.first() {
+            assert_eq!(
+                leaf.len(),
+                first.len(),
+                "all leaves of the tree must have the same width"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_commit::Mmcs;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_symmetric::{MerkleCap, PaddingFreeSponge, TruncatedPermutation};
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::{MerkleTree, MerkleTreeMmcs};
+
+    type F = BabyBear;
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+    type MyMmcs = MerkleTreeMmcs<F, F, MyHash, MyCompress, 8>;
+
+    const WIDTH: usize = 3;
+
+    fn random_leaf(rng: &mut impl Rng) -> Vec<F> {
+        (0..WIDTH).map(|_| rng.gen()).collect()
+    }
+
+    /// Check the tree against a `MerkleTree` built from scratch over the same leaves.
+    fn check_against_rebuild(
+        tree: &IncrementalMerkleTree<F, F, MyHash, MyCompress, 8>,
+        leaves: &[Vec<F>],
+        mmcs: &MyMmcs,
+    ) {
+        let mat = RowMajorMatrix::new(leaves.concat(), WIDTH);
+        let (commit, prover_data) = mmcs.commit_matrix(mat);
+        assert_eq!(commit, MerkleCap::from(tree.root()));
+
+        let dims = [tree.dimensions()];
+        for index in 0..leaves.len() {
+            let (opened_values, proof) = tree.open(index);
+            let (expected_values, expected_proof) = mmcs.open_batch(index, &prover_data);
+            assert_eq!(opened_values, expected_values);
+            assert_eq!(proof, expected_proof);
+            mmcs.verify_batch(&commit, &dims, index, &opened_values, &proof)
+                .expect("expected verification to succeed");
+        }
+    }
+
+    #[test]
+    fn matches_rebuilt_tree() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let (hash, compress) = (MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mmcs = MyMmcs::new(hash.clone(), compress.clone());
+        let mut tree = IncrementalMerkleTree::new(hash.clone(), compress.clone());
+        let mut leaves = vec![];
+
+        for _ in 0..33 {
+            let leaf = random_leaf(&mut rng);
+            tree.append(leaf.clone());
+            leaves.push(leaf);
+            let expected = MerkleTree::new::<F, F, _, _>(
+                &hash,
+                &compress,
+                vec![RowMajorMatrix::new(leaves.concat(), WIDTH)],
+            );
+            assert_eq!(tree.root(), expected.root());
+        }
+        check_against_rebuild(&tree, &leaves, &mmcs);
+
+        // Interleave updates, including of the last leaf, with more appends.
+        for step in 0..40 {
+            if step % 3 == 0 {
+                let leaf = random_leaf(&mut rng);
+                tree.append(leaf.clone());
+                leaves.push(leaf);
+            } else {
+                let index = if step % 5 == 0 {
+                    leaves.len() - 1
+                } else {
+                    rng.gen_range(0..leaves.len())
+                };
+                let leaf = random_leaf(&mut rng);
+                tree.update(index, leaf.clone());
+                leaves[index] = leaf;
+            }
+            let (commit, _) = mmcs.commit_matrix(RowMajorMatrix::new(leaves.concat(), WIDTH));
+            assert_eq!(commit, MerkleCap::from(tree.root()));
+        }
+        check_against_rebuild(&tree, &leaves, &mmcs);
+    }
+
+    #[test]
+    fn single_leaf() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let (hash, compress) = (MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mut tree = IncrementalMerkleTree::new(hash.clone(), compress.clone());
+        let leaf = random_leaf(&mut rng);
+        tree.append(leaf.clone());
+        assert_eq!(tree.root(), hash.hash_slice(&leaf));
+        assert!(tree.open(0).1.is_empty());
+
+        let mmcs = MyMmcs::new(hash, compress);
+        check_against_rebuild(&tree, &[leaf], &mmcs);
+    }
+
+    #[test]
+    #[should_panic(expected = "same width")]
+    fn rejects_leaves_of_another_width() {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        let mut tree = IncrementalMerkleTree::<F, F, _, _, 8>::new(
+            MyHash::new(perm.clone()),
+            MyCompress::new(perm),
+        );
+        tree.append(vec![F::default(); WIDTH]);
+        tree.append(vec![F::default(); WIDTH + 1]);
+    }
+}
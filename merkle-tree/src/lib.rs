@@ -1,11 +1,25 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod byte_digest_mmcs;
+#[cfg(feature = "std")]
+mod checkpoint;
 mod hiding_mmcs;
+mod incremental;
 mod merkle_tree;
 mod mmcs;
+#[cfg(feature = "persist")]
+mod persist;
 
+pub use byte_digest_mmcs::*;
+#[cfg(feature = "std")]
+pub use checkpoint::*;
 pub use hiding_mmcs::*;
+pub use incremental::*;
 pub use merkle_tree::*;
 pub use mmcs::*;
+#[cfg(feature = "persist")]
+pub use persist::*;
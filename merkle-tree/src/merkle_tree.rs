@@ -1,14 +1,18 @@
+use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::array;
 use core::cmp::Reverse;
+use core::fmt::{Display, Formatter};
+use core::iter;
 use core::marker::PhantomData;
 
 use itertools::Itertools;
 use p3_field::PackedValue;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
-use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_symmetric::{CryptographicHasher, Hash, MerkleCap, PseudoCompressionFunction};
+use p3_util::log2_ceil_usize;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
@@ -28,14 +32,94 @@ pub struct MerkleTree<F, W, M, const DIGEST_ELEMS: usize> {
     _phantom: PhantomData<F>,
 }
 
+/// The digest layers of a `MerkleTree`, without its leaves.
+///
+/// The leaves are typically matrices the prover holds anyway, while the digests take a full pass
+/// of hashing to recompute. This lets the prover persist only the digests, and bind them to the
+/// leaves again with `MerkleTree::from_parts`. With the `persist` feature, `to_bytes` and
+/// `from_bytes` give them a versioned byte encoding.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleTreeDigests<W, const DIGEST_ELEMS: usize> {
+    #[serde(bound(serialize = "[W; DIGEST_ELEMS]: Serialize"))]
+    #[serde(bound(deserialize = "[W; DIGEST_ELEMS]: Deserialize<'de>"))]
+    pub(crate) digest_layers: Vec<Vec<[W; DIGEST_ELEMS]>>,
+}
+
+/// The reasons `MerkleTree::from_parts` and `MerkleTreeDigests::from_bytes` can refuse digests.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DigestsError {
+    /// The digests do not have the shape of a tree over the leaves.
+    ShapeMismatch,
+    /// The bytes could not be decoded, e.g. an element was not canonical, or had bytes left over
+    /// after the digests.
+    Malformed,
+    /// The digests were encoded in another version of the format.
+    UnsupportedVersion(u32),
+    /// The digests have another number of elements.
+    DigestWidthMismatch { expected: usize, found: usize },
+}
+
+impl Display for DigestsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ShapeMismatch => write!(f, "the digests do not match the shape of the leaves"),
+            Self::Malformed => write!(f, "the digests are malformed"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported digests format version {version}")
+            }
+            Self::DigestWidthMismatch { expected, found } => write!(
+                f,
+                "the digests have {found} elements, but {expected} were expected"
+            ),
+        }
+    }
+}
+
+/// Which digest layers a `MerkleTree` keeps.
+///
+/// The bottom layers hold almost all of the digests: for a tree over `2^24` rows with 32-byte
+/// digests, about a gigabyte. They are cheap to recompute from the leaves for a single query, so a
+/// prover which opens few indices can drop them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DigestStorage {
+    /// Keep every layer.
+    #[default]
+    Full,
+    /// Keep only the top `top_layers` layers, counting the root layer, and always at least the
+    /// root. When the bottom `d` layers are dropped, opening an index recomputes the subtree of
+    /// `2^d` leaves containing it, so `d` trades memory for hashing in each query.
+    Pruned { top_layers: usize },
+}
+
 impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
     MerkleTree<F, W, M, DIGEST_ELEMS>
 {
     /// Matrix heights need not be powers of two. However, if the heights of two given matrices
     /// round up to the same power of two, they must be equal.
+    pub fn new<P, PW, H, C>(h: &H, c: &C, leaves: Vec<M>) -> Self
+    where
+        P: PackedValue<Value = F>,
+        PW: PackedValue<Value = W>,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+        H: Sync,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+        C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+        C: Sync,
+    {
+        Self::new_with_storage::<P, PW, H, C>(h, c, leaves, DigestStorage::Full)
+    }
+
+    /// As `new`, but keeping only the digest layers `storage` asks for. The dropped layers are
+    /// freed as soon as the layer above them is built, so they are never all held at once.
     #[instrument(name = "build merkle tree", level = "debug", skip_all,
                  fields(dimensions = alloc::format!("{:?}", leaves.iter().map(|l| l.dimensions()).collect::<Vec<_>>())))]
-    pub fn new<P, PW, H, C>(h: &H, c: &C, leaves: Vec<M>) -> Self
+    pub fn new_with_storage<P, PW, H, C>(
+        h: &H,
+        c: &C,
+        leaves: Vec<M>,
+        storage: DigestStorage,
+    ) -> Self
     where
         P: PackedValue<Value = F>,
         PW: PackedValue<Value = W>,
@@ -46,70 +130,333 @@ impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
         C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
         C: Sync,
     {
-        assert!(!leaves.is_empty(), "No matrices given?");
+        let mut builder = MerkleTreeBuilder::new(leaves, storage);
+        while !builder.is_complete() {
+            builder.build_layer::<P, PW, H, C>(h, c);
+        }
+        builder.finish()
+    }
 
-        assert_eq!(P::WIDTH, PW::WIDTH, "Packing widths must match");
+    /// Split the tree into its leaves and its digests.
+    pub fn into_parts(self) -> (Vec<M>, MerkleTreeDigests<W, DIGEST_ELEMS>) {
+        let digests = MerkleTreeDigests {
+            digest_layers: self.digest_layers,
+        };
+        (self.leaves, digests)
+    }
 
-        let mut leaves_largest_first = leaves
+    /// Rebuild a tree from the parts returned by `into_parts`, without rehashing.
+    ///
+    /// The leaves must be the matrices the digests were computed from, in the same order. Only
+    /// the shape of the digests is checked against them, and `DigestsError::ShapeMismatch` is
+    /// returned if it does not fit; any other mismatch will go unnoticed until the openings fail
+    /// to verify.
+    pub fn from_parts(
+        leaves: Vec<M>,
+        digests: MerkleTreeDigests<W, DIGEST_ELEMS>,
+    ) -> Result<Self, DigestsError> {
+        let max_height = leaves.iter().map(|m| m.height()).max().unwrap_or(0);
+        let digest_layers = digests.digest_layers;
+        let expected_lens = digest_layer_lens(max_height);
+        // Pruned layers are empty, and only ever at the bottom, below the root.
+        let num_pruned_layers = digest_layers.iter().take_while(|l| l.is_empty()).count();
+        if max_height == 0
+            || digest_layers.len() != expected_lens.len()
+            || num_pruned_layers == digest_layers.len()
+            || digest_layers[num_pruned_layers..]
+                .iter()
+                .zip(&expected_lens[num_pruned_layers..])
+                .any(|(layer, &len)| layer.len() != len)
+        {
+            return Err(DigestsError::ShapeMismatch);
+        }
+        Ok(Self {
+            leaves,
+            digest_layers,
+            _phantom: PhantomData,
+        })
+    }
+
+    #[must_use]
+    pub fn root(&self) -> Hash<F, W, DIGEST_ELEMS>
+    where
+        W: Copy,
+    {
+        self.digest_layers.last().unwrap()[0].into()
+    }
+
+    /// The number of layers at the bottom of the tree whose digests were dropped; see
+    /// `DigestStorage`.
+    pub fn num_pruned_layers(&self) -> usize {
+        self.digest_layers
             .iter()
-            .sorted_by_key(|l| Reverse(l.height()))
-            .peekable();
+            .take_while(|layer| layer.is_empty())
+            .count()
+    }
+
+    /// The number of digests the tree holds, across all of its layers.
+    pub fn num_stored_digests(&self) -> usize {
+        self.digest_layers.iter().map(Vec::len).sum()
+    }
+
+    /// The digests of the given `(layer, node)` pairs, looked up where they are stored, or
+    /// recomputed from the leaves where they were pruned. Each pruned subtree is recomputed at
+    /// most once, however many of its nodes are asked for.
+    pub(crate) fn digests_at<H, C>(
+        &self,
+        h: &H,
+        c: &C,
+        nodes: &[(usize, usize)],
+    ) -> Vec<[W; DIGEST_ELEMS]>
+    where
+        W: Copy + Default,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    {
+        let num_pruned_layers = self.num_pruned_layers();
+        let mut subtrees = BTreeMap::new();
+        nodes
+            .iter()
+            .map(|&(layer, node)| {
+                if layer >= num_pruned_layers {
+                    return self.digest_layers[layer][node];
+                }
+                let depth = num_pruned_layers - layer;
+                let subtree = subtrees
+                    .entry(node >> depth)
+                    .or_insert_with(|| self.recompute_subtree(h, c, node >> depth));
+                subtree[layer][node - ((node >> depth) << depth)]
+            })
+            .collect()
+    }
+
+    /// Recompute the pruned layers below node `root` of the lowest stored layer. The result has a
+    /// layer for each pruned one, holding the `2^(d - layer)` nodes under `root`, where `d` is the
+    /// number of pruned layers.
+    ///
+    /// This follows `MerkleTree::new` one node at a time: nodes past the end of a layer are
+    /// default digests, and a matrix whose height rounds up to `2^k` is hashed into the layer
+    /// with `2^k` nodes (before padding).
+    fn recompute_subtree<H, C>(&self, h: &H, c: &C, root: usize) -> Vec<Vec<[W; DIGEST_ELEMS]>>
+    where
+        W: Copy + Default,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    {
+        let depth = self.num_pruned_layers();
+        let log_max_height = self.digest_layers.len() - 1;
+        let layer_lens = digest_layer_lens(self.leaves.iter().map(|m| m.height()).max().unwrap());
+        let default_digest = [W::default(); DIGEST_ELEMS];
+        let matrices_in_layer = |layer: usize| {
+            self.leaves
+                .iter()
+                .filter(|m| log2_ceil_usize(m.height()) == log_max_height - layer)
+                .collect_vec()
+        };
+        let hash_rows = |matrices: &[&M], r: usize| {
+            if matrices.first().is_some_and(|m| r < m.height()) {
+                h.hash_iter(matrices.iter().flat_map(|m| m.row(r)))
+            } else {
+                default_digest
+            }
+        };
+
+        let tallest_matrices = matrices_in_layer(0);
+        let first_layer = ((root << depth)..((root + 1) << depth))
+            .map(|r| hash_rows(&tallest_matrices, r))
+            .collect_vec();
+        let mut layers = vec![first_layer];
+        for layer in 1..depth {
+            let num_nodes = layer_lens[layer - 1] / 2;
+            let injected = matrices_in_layer(layer);
+            let first_node = root << (depth - layer);
+            let next_layer = layers
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .enumerate()
+                .map(|(i, children)| {
+                    let node = first_node + i;
+                    if node >= num_nodes {
+                        return default_digest;
+                    }
+                    let digest = c.compress([children[0], children[1]]);
+                    if injected.is_empty() {
+                        digest
+                    } else {
+                        c.compress([digest, hash_rows(&injected, node)])
+                    }
+                })
+                .collect();
+            layers.push(next_layer);
+        }
+        layers
+    }
+
+    /// The cap of the tree `cap_height` layers below the root, or lower if a matrix is injected
+    /// above that layer; see `effective_cap_height`.
+    #[must_use]
+    pub fn cap(&self, cap_height: usize) -> MerkleCap<F, W, DIGEST_ELEMS>
+    where
+        W: Copy + Default,
+    {
+        let cap_height = effective_cap_height(cap_height, self.leaves.iter().map(|m| m.height()));
+        let layer = &self.digest_layers[self.digest_layers.len() - 1 - cap_height];
+        // The layer was padded to an even length with default digests; pad it the rest of the way.
+        let roots = layer
+            .iter()
+            .copied()
+            .chain(iter::repeat([W::default(); DIGEST_ELEMS]))
+            .take(1 << cap_height)
+            .map(Hash::from)
+            .collect_vec();
+        roots.into()
+    }
+}
+
+/// A `MerkleTree` whose digest layers are built one at a time, from the leaves up.
+///
+/// `MerkleTree::new` builds every layer in one go. Building them one at a time lets a prover stop
+/// between layers, e.g. to checkpoint the layers built so far and pick up from them later rather
+/// than hash all of the leaves again.
+#[derive(Debug)]
+pub struct MerkleTreeBuilder<F, W, M, const DIGEST_ELEMS: usize> {
+    pub(crate) leaves: Vec<M>,
+    pub(crate) digest_layers: Vec<Vec<[W; DIGEST_ELEMS]>>,
+    pub(crate) num_pruned_layers: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
+    MerkleTreeBuilder<F, W, M, DIGEST_ELEMS>
+{
+    /// Start a tree over `leaves`, with no layers built yet.
+    ///
+    /// Matrix heights need not be powers of two. However, if the heights of two given matrices
+    /// round up to the same power of two, they must be equal.
+    pub fn new(leaves: Vec<M>, storage: DigestStorage) -> Self {
+        assert!(!leaves.is_empty(), "No matrices given?");
 
         // check height property
         assert!(
-            leaves_largest_first
-                .clone()
+            leaves
+                .iter()
                 .map(|m| m.height())
+                .sorted_by_key(|&h| Reverse(h))
                 .tuple_windows()
                 .all(|(curr, next)| curr == next
                     || curr.next_power_of_two() != next.next_power_of_two()),
             "matrix heights that round up to the same power of two must be equal"
         );
 
-        let max_height = leaves_largest_first.peek().unwrap().height();
-        let tallest_matrices = leaves_largest_first
-            .peeking_take_while(|m| m.height() == max_height)
+        let mut builder = Self {
+            leaves,
+            digest_layers: vec![],
+            num_pruned_layers: 0,
+            _phantom: PhantomData,
+        };
+        let num_layers = builder.num_layers();
+        builder.num_pruned_layers = match storage {
+            DigestStorage::Full => 0,
+            DigestStorage::Pruned { top_layers } => num_layers - top_layers.clamp(1, num_layers),
+        };
+        builder
+    }
+
+    /// The number of digest layers of the finished tree, from the leaf digests to the root.
+    pub fn num_layers(&self) -> usize {
+        let max_height = self.leaves.iter().map(|m| m.height()).max().unwrap();
+        log2_ceil_usize(max_height) + 1
+    }
+
+    /// The number of digest layers built so far, counting any which were pruned.
+    pub fn num_built_layers(&self) -> usize {
+        self.digest_layers.len()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.num_built_layers() == self.num_layers()
+    }
+
+    /// Build the next digest layer: hash the rows of the tallest matrices for the first layer,
+    /// and compress the previous layer, injecting any matrices of the matching height, for the
+    /// others. Once a layer is built, the one below it is pruned if the storage asks for that.
+    ///
+    /// Panics if the tree is already complete.
+    pub fn build_layer<P, PW, H, C>(&mut self, h: &H, c: &C)
+    where
+        P: PackedValue<Value = F>,
+        PW: PackedValue<Value = W>,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+        H: Sync,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+        C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+        C: Sync,
+    {
+        assert_eq!(P::WIDTH, PW::WIDTH, "Packing widths must match");
+        assert!(!self.is_complete(), "the tree is already complete");
+
+        let log_max_height = self.num_layers() - 1;
+        let layer = self.digest_layers.len();
+        // The matrices whose rows are hashed into this layer. A matrix of height `h` belongs in
+        // the layer with `h.next_power_of_two()` nodes, before padding.
+        let matrices = self
+            .leaves
+            .iter()
+            .filter(|m| log2_ceil_usize(m.height()) == log_max_height - layer)
             .collect_vec();
 
-        let mut digest_layers = vec![first_digest_layer::<P, PW, H, M, DIGEST_ELEMS>(
-            h,
-            tallest_matrices,
-        )];
-        loop {
-            let prev_layer = digest_layers.last().unwrap().as_slice();
-            if prev_layer.len() == 1 {
-                break;
+        let digests = match self.digest_layers.last() {
+            None => first_digest_layer::<P, PW, H, M, DIGEST_ELEMS>(h, matrices),
+            Some(prev_layer) => {
+                compress_and_inject::<P, PW, H, C, M, DIGEST_ELEMS>(prev_layer, matrices, h, c)
             }
-            let next_layer_len = (prev_layer.len() / 2).next_power_of_two();
-
-            // The matrices that get injected at this layer.
-            let matrices_to_inject = leaves_largest_first
-                .peeking_take_while(|m| m.height().next_power_of_two() == next_layer_len)
-                .collect_vec();
+        };
+        self.digest_layers.push(digests);
 
-            let next_digests = compress_and_inject::<P, PW, H, C, M, DIGEST_ELEMS>(
-                prev_layer,
-                matrices_to_inject,
-                h,
-                c,
-            );
-            digest_layers.push(next_digests);
+        if layer > 0 && layer - 1 < self.num_pruned_layers {
+            self.digest_layers[layer - 1] = vec![];
         }
+    }
 
-        Self {
-            leaves,
-            digest_layers,
+    /// Panics unless every layer has been built.
+    pub fn finish(self) -> MerkleTree<F, W, M, DIGEST_ELEMS> {
+        assert!(self.is_complete(), "the tree is not complete");
+        MerkleTree {
+            leaves: self.leaves,
+            digest_layers: self.digest_layers,
             _phantom: PhantomData,
         }
     }
+}
 
-    #[must_use]
-    pub fn root(&self) -> Hash<F, W, DIGEST_ELEMS>
-    where
-        W: Copy,
-    {
-        self.digest_layers.last().unwrap()[0].into()
+/// The lengths of the digest layers of a tree whose tallest matrix has height `max_height`,
+/// including the padding of each layer other than the root to an even length.
+pub(crate) fn digest_layer_lens(max_height: usize) -> Vec<usize> {
+    let mut lens = vec![if max_height <= 1 {
+        max_height
+    } else {
+        max_height + max_height % 2
+    }];
+    while let Some(&prev_len) = lens.last().filter(|&&len| len > 1) {
+        lens.push(if prev_len == 2 {
+            1
+        } else {
+            (prev_len / 2 + 1) & !1
+        });
     }
+    lens
+}
+
+/// The height of the cap a tree of matrices of the given heights actually has, when asked for a
+/// cap of height `cap_height`.
+///
+/// A matrix of height `h` is injected into the layer with `h.next_power_of_two()` nodes, so the
+/// cap can be no taller than the shortest matrix, or that matrix would not be committed to.
+pub fn effective_cap_height(cap_height: usize, heights: impl IntoIterator<Item = usize>) -> usize {
+    let min_log_height = heights.into_iter().map(log2_ceil_usize).min().unwrap_or(0);
+    cap_height.min(min_log_height)
 }
 
 #[instrument(name = "first digest layer", level = "debug", skip_all)]
@@ -165,6 +512,10 @@ where
 
 /// Compress `n` digests from the previous layer into `n/2` digests, while potentially mixing in
 /// some leaf data, if there are input matrices with (padded) height `n/2`.
+///
+/// Nodes are computed in parallel, `PW::WIDTH` at a time with packed hashing, except for the
+/// chunk in which the injected matrices end and the last few nodes, which fall back to scalar
+/// code. Beyond the height of the injected matrices, nodes mix in the default digest.
 fn compress_and_inject<P, PW, H, C, M, const DIGEST_ELEMS: usize>(
     prev_layer: &[[PW::Value; DIGEST_ELEMS]],
     matrices_to_inject: Vec<&M>,
@@ -188,6 +539,7 @@ where
 
     let width = PW::WIDTH;
     let next_len = matrices_to_inject[0].height();
+    let num_nodes = prev_layer.len() / 2;
     // We always want to return an even number of digests, except when it's the root.
     let next_len_padded = if prev_layer.len() == 2 {
         1
@@ -196,44 +548,52 @@ where
     };
 
     let default_digest: [PW::Value; DIGEST_ELEMS] = [PW::Value::default(); DIGEST_ELEMS];
+    let packed_default_digest: [PW; DIGEST_ELEMS] =
+        array::from_fn(|j| PW::from_fn(|_| default_digest[j]));
+
+    let scalar_node = |i: usize| {
+        let digest = c.compress([prev_layer[2 * i], prev_layer[2 * i + 1]]);
+        let rows_digest = if i < next_len {
+            h.hash_iter(matrices_to_inject.iter().flat_map(|m| m.row(i)))
+        } else {
+            default_digest
+        };
+        c.compress([digest, rows_digest])
+    };
+
     let mut next_digests = vec![default_digest; next_len_padded];
-    next_digests[0..next_len]
+    next_digests[0..num_nodes]
         .par_chunks_exact_mut(width)
         .enumerate()
         .for_each(|(i, digests_chunk)| {
             let first_row = i * width;
-            let left = array::from_fn(|j| PW::from_fn(|k| prev_layer[2 * (first_row + k)][j]));
-            let right = array::from_fn(|j| PW::from_fn(|k| prev_layer[2 * (first_row + k) + 1][j]));
-            let mut packed_digest = c.compress([left, right]);
-            let tallest_digest = h.hash_iter(
-                matrices_to_inject
-                    .iter()
-                    .flat_map(|m| m.vertically_packed_row(first_row)),
-            );
-            packed_digest = c.compress([packed_digest, tallest_digest]);
+            let rows_digest = if first_row + width <= next_len {
+                h.hash_iter(
+                    matrices_to_inject
+                        .iter()
+                        .flat_map(|m| m.vertically_packed_row(first_row)),
+                )
+            } else if first_row >= next_len {
+                packed_default_digest
+            } else {
+                // The injected matrices end partway through this chunk.
+                for (k, dst) in digests_chunk.iter_mut().enumerate() {
+                    *dst = scalar_node(first_row + k);
+                }
+                return;
+            };
+            let siblings_digest =
+                c.compress(pack_siblings::<PW, DIGEST_ELEMS>(prev_layer, first_row));
+            let packed_digest = c.compress([siblings_digest, rows_digest]);
             for (dst, src) in digests_chunk.iter_mut().zip(unpack_array(packed_digest)) {
                 *dst = src;
             }
         });
 
-    // If our packing width did not divide next_len, fall back to single-threaded scalar code
-    // for the last bit.
-    for i in (next_len / width * width)..next_len {
-        let left = prev_layer[2 * i];
-        let right = prev_layer[2 * i + 1];
-        let digest = c.compress([left, right]);
-        let rows_digest = h.hash_iter(matrices_to_inject.iter().flat_map(|m| m.row(i)));
-        next_digests[i] = c.compress([digest, rows_digest]);
-    }
-
-    // At this point, we've exceeded the height of the matrices to inject, so we continue the
-    // process above except with default_digest in place of an input digest.
-    // We only need go as far as half the length of the previous layer.
-    for i in next_len..(prev_layer.len() / 2) {
-        let left = prev_layer[2 * i];
-        let right = prev_layer[2 * i + 1];
-        let digest = c.compress([left, right]);
-        next_digests[i] = c.compress([digest, default_digest]);
+    // If our packing width did not divide the number of nodes, fall back to scalar code for the
+    // last bit.
+    for i in (num_nodes / width * width)..num_nodes {
+        next_digests[i] = scalar_node(i);
     }
 
     next_digests
@@ -266,10 +626,7 @@ where
         .par_chunks_exact_mut(width)
         .enumerate()
         .for_each(|(i, digests_chunk)| {
-            let first_row = i * width;
-            let left = array::from_fn(|j| P::from_fn(|k| prev_layer[2 * (first_row + k)][j]));
-            let right = array::from_fn(|j| P::from_fn(|k| prev_layer[2 * (first_row + k) + 1][j]));
-            let packed_digest = c.compress([left, right]);
+            let packed_digest = c.compress(pack_siblings::<P, DIGEST_ELEMS>(prev_layer, i * width));
             for (dst, src) in digests_chunk.iter_mut().zip(unpack_array(packed_digest)) {
                 *dst = src;
             }
@@ -287,6 +644,18 @@ where
     next_digests
 }
 
+/// Transposes the `P::WIDTH` pairs of siblings starting at pair `first_pair` into lanes, giving
+/// the packed left and right children of `P::WIDTH` consecutive nodes.
+#[inline]
+fn pack_siblings<P: PackedValue, const N: usize>(
+    prev_layer: &[[P::Value; N]],
+    first_pair: usize,
+) -> [[P; N]; 2] {
+    let left = array::from_fn(|j| P::from_fn(|k| prev_layer[2 * (first_pair + k)][j]));
+    let right = array::from_fn(|j| P::from_fn(|k| prev_layer[2 * (first_pair + k) + 1][j]));
+    [left, right]
+}
+
 /// Converts a packed array `[P; N]` into its underlying `P::WIDTH` scalar arrays.
 #[inline]
 fn unpack_array<P: PackedValue, const N: usize>(
@@ -294,3 +663,109 @@ fn unpack_array<P: PackedValue, const N: usize>(
 ) -> impl Iterator<Item = [P::Value; N]> {
     (0..P::WIDTH).map(move |j| packed_digest.map(|p| p.as_slice()[j]))
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_field::Field;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+    use rand::thread_rng;
+
+    use super::*;
+
+    type F = BabyBear;
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+
+    /// Build the digest layers one node at a time, without packing or parallelism.
+    fn sequential_digest_layers(
+        h: &MyHash,
+        c: &MyCompress,
+        leaves: &[RowMajorMatrix<F>],
+    ) -> Vec<Vec<[F; 8]>> {
+        let default_digest = [F::default(); 8];
+        let pad = |mut layer: Vec<[F; 8]>| {
+            if layer.len() > 1 && layer.len() % 2 == 1 {
+                layer.push(default_digest);
+            }
+            layer
+        };
+
+        let mut leaves_largest_first = leaves
+            .iter()
+            .sorted_by_key(|l| Reverse(l.height()))
+            .peekable();
+        let max_height = leaves_largest_first.peek().unwrap().height();
+        let tallest = leaves_largest_first
+            .peeking_take_while(|m| m.height() == max_height)
+            .collect_vec();
+        let first_layer = (0..max_height)
+            .map(|r| h.hash_iter(tallest.iter().flat_map(|m| m.row(r))))
+            .collect();
+
+        let mut layers = vec![pad(first_layer)];
+        while layers.last().unwrap().len() > 1 {
+            let prev = layers.last().unwrap();
+            let num_nodes = prev.len() / 2;
+            let injected = leaves_largest_first
+                .peeking_take_while(|m| {
+                    m.height().next_power_of_two() == num_nodes.next_power_of_two()
+                })
+                .collect_vec();
+            let next = (0..num_nodes)
+                .map(|i| {
+                    let digest = c.compress([prev[2 * i], prev[2 * i + 1]]);
+                    match injected.first() {
+                        None => digest,
+                        Some(m) if i < m.height() => c.compress([
+                            digest,
+                            h.hash_iter(injected.iter().flat_map(|m| m.row(i))),
+                        ]),
+                        Some(_) => c.compress([digest, default_digest]),
+                    }
+                })
+                .collect();
+            layers.push(pad(next));
+        }
+        layers
+    }
+
+    #[test]
+    fn matches_sequential_build() {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        let h = MyHash::new(perm.clone());
+        let c = MyCompress::new(perm);
+        let mut rng = thread_rng();
+
+        for heights in [
+            vec![1],
+            vec![2],
+            vec![13],
+            vec![1 << 10],
+            // Injected matrices which end partway through a packed chunk, and before the end of
+            // their layer.
+            vec![1024, 300],
+            vec![37, 17, 9, 3],
+            vec![1000, 499, 250, 60, 3],
+        ] {
+            let leaves = heights
+                .iter()
+                .enumerate()
+                .map(|(i, &height)| RowMajorMatrix::<F>::rand(&mut rng, height, 3 + i))
+                .collect_vec();
+            let expected = sequential_digest_layers(&h, &c, &leaves);
+
+            let packed = MerkleTree::new::<<F as Field>::Packing, <F as Field>::Packing, _, _>(
+                &h,
+                &c,
+                leaves.clone(),
+            );
+            assert_eq!(packed.digest_layers, expected, "heights {heights:?}");
+
+            let scalar = MerkleTree::new::<F, F, _, _>(&h, &c, leaves);
+            assert_eq!(scalar.digest_layers, expected, "heights {heights:?}");
+        }
+    }
+}
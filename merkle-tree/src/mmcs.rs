@@ -1,20 +1,39 @@
+use alloc::vec;
 use alloc::vec::Vec;
-use core::cmp::Reverse;
+use core::fmt::{Display, Formatter};
 use core::marker::PhantomData;
+use core::ops::Range;
 
 use itertools::Itertools;
 use p3_commit::Mmcs;
 use p3_field::PackedValue;
 use p3_matrix::{Dimensions, Matrix};
-use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_symmetric::{CryptographicHasher, MerkleCap, PseudoCompressionFunction};
 use p3_util::log2_ceil_usize;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::MerkleTree;
-use crate::MerkleTreeError::{EmptyBatch, RootMismatch, WrongBatchSize, WrongHeight};
+use crate::MerkleTreeError::{
+    EmptyBatch, InconsistentOpenings, IndexOutOfBounds, MismatchedHeights, RootMismatch,
+    WrongBatchSize, WrongCapSize, WrongHeight, WrongWidth,
+};
+use crate::{effective_cap_height, DigestStorage, MerkleTree};
 
 /// A vector commitment scheme backed by a `MerkleTree`.
 ///
+/// The commitment is the `MerkleCap` of the tree, of height 0 (just the root) unless set with
+/// `with_cap_height`. A cap of height `k` shortens every authentication path by `k` digests. The
+/// cap is lowered to fit the shortest committed matrix, whose rows would otherwise be injected
+/// above it; see `effective_cap_height`.
+///
+/// Matrix heights need not be powers of two. The tree has a leaf for each row of the tallest
+/// matrices, and each layer is padded to an even length with the default digest. An index `i`
+/// below the tallest height opens row `i >> k` of a matrix whose height rounds up to `2^k` times
+/// less, if that row exists. Past the end of a shorter matrix there is no row: the opening for it
+/// is empty, and the tree mixes in the default digest in place of the hash of its rows.
+///
+/// The prover data keeps every digest of the tree unless told otherwise with
+/// `with_digest_storage`. Openings do not depend on the storage, only the cost of producing them.
+///
 /// Generics:
 /// - `P`: a leaf value
 /// - `PW`: an element of a digest
@@ -24,19 +43,162 @@ use crate::MerkleTreeError::{EmptyBatch, RootMismatch, WrongBatchSize, WrongHeig
 pub struct MerkleTreeMmcs<P, PW, H, C, const DIGEST_ELEMS: usize> {
     hash: H,
     compress: C,
+    cap_height: usize,
+    digest_storage: DigestStorage,
     _phantom: PhantomData<(P, PW)>,
 }
 
-#[derive(Debug)]
+/// The reasons a `MerkleTreeMmcs` opening can fail to verify.
+///
+/// The shape of an opening is checked before any hashing, so a malformed opening is reported as
+/// such. Once the shape is right, a wrong opened row and a wrong sibling at any level both just
+/// lead to a different root, and the verifier cannot tell them apart: both are `RootMismatch`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MerkleTreeError {
+    /// The number of opened rows does not match the number of matrices.
     WrongBatchSize,
+    /// An opened row does not have the width of its matrix, e.g. a row past the end of a matrix
+    /// which is not empty.
     WrongWidth,
+    /// The number of siblings does not match the height of the tree, less the cap height.
     WrongHeight {
         max_height: usize,
         num_siblings: usize,
     },
+    /// The opened rows and siblings hash to a different root, or cap entry, than the commitment.
     RootMismatch,
+    /// No matrices were given.
     EmptyBatch,
+    /// A multi-opening was for a row beyond the padded height of the tallest matrix.
+    IndexOutOfBounds { index: usize, max_height: usize },
+    /// Two queries of a multi-opening which share a node disagree on the rows hashed into it.
+    InconsistentOpenings,
+    /// The commitment does not have the number of digests the cap height calls for.
+    WrongCapSize { expected: usize, actual: usize },
+    /// Two matrices whose heights round up to the same power of two, and so would be hashed into
+    /// the same layer (counted from the leaves), have different heights. No tree commits to such
+    /// a batch.
+    MismatchedHeights {
+        layer: usize,
+        height: usize,
+        other_height: usize,
+    },
+}
+
+impl Display for MerkleTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::WrongBatchSize => write!(f, "the number of opened rows does not match the batch"),
+            Self::WrongWidth => write!(f, "an opened row has the wrong width"),
+            Self::WrongHeight {
+                max_height,
+                num_siblings,
+            } => write!(
+                f,
+                "{num_siblings} siblings do not fit a tree of matrices of height up to {max_height}"
+            ),
+            Self::RootMismatch => write!(f, "the opening does not hash to the commitment"),
+            Self::EmptyBatch => write!(f, "the batch has no matrices"),
+            Self::IndexOutOfBounds { index, max_height } => write!(
+                f,
+                "index {index} is out of bounds for matrices of height up to {max_height}"
+            ),
+            Self::InconsistentOpenings => {
+                write!(f, "queries sharing a node disagree on its opened rows")
+            }
+            Self::WrongCapSize { expected, actual } => write!(
+                f,
+                "the commitment has {actual} cap digests, but {expected} were expected"
+            ),
+            Self::MismatchedHeights {
+                layer,
+                height,
+                other_height,
+            } => write!(
+                f,
+                "matrices of heights {height} and {other_height} would share layer {layer}"
+            ),
+        }
+    }
+}
+
+/// The openings of several rows of a batch of committed matrices, with a single set of
+/// authentication nodes shared between them (a "multiproof").
+///
+/// The authentication path of each query is the same as that of `open_batch`, except that
+/// siblings which the verifier can compute from the other queries are left out, as is any node
+/// already included for an earlier query.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize, [W; DIGEST_ELEMS]: Serialize"))]
+#[serde(bound(deserialize = "T: Deserialize<'de>, [W; DIGEST_ELEMS]: Deserialize<'de>"))]
+pub struct MultiBatchOpening<T, W, const DIGEST_ELEMS: usize> {
+    /// For each query, in the order of the indices, the opened row of each matrix.
+    pub opened_values: Vec<Vec<Vec<T>>>,
+    /// The siblings which cannot be computed from the opened rows, from the leaves up, and in
+    /// increasing order of node index within each layer.
+    pub proof: Vec<[W; DIGEST_ELEMS]>,
+}
+
+/// The node indices of the siblings a multiproof for the distinct, sorted leaf indices `indices`
+/// must include, layer by layer from the leaves up to just below the cap.
+fn multiproof_siblings(indices: &[usize], num_layers: usize) -> Vec<(usize, usize)> {
+    let mut nodes = indices.to_vec();
+    let mut siblings = vec![];
+    for layer in 0..num_layers {
+        let mut i = 0;
+        while i < nodes.len() {
+            if nodes.get(i + 1) == Some(&(nodes[i] ^ 1)) {
+                // Both children are known, so neither is needed.
+                i += 2;
+            } else {
+                siblings.push((layer, nodes[i] ^ 1));
+                i += 1;
+            }
+        }
+        nodes = nodes.into_iter().map(|node| node >> 1).dedup().collect();
+    }
+    siblings
+}
+
+/// The matrices whose rows are hashed into each layer of the tree, from the leaves up, with their
+/// common height. A matrix whose height rounds up to `2^k` belongs in the layer with `2^k` nodes,
+/// so the tallest matrices are hashed into the leaves and the others injected above them.
+///
+/// The prover refuses to commit to two matrices which would share a layer but have different
+/// heights, so the verifier does too, rather than authenticate only one of them.
+fn matrices_by_layer(
+    dimensions: &[Dimensions],
+) -> Result<Vec<(usize, Vec<usize>)>, MerkleTreeError> {
+    let max_height = dimensions
+        .iter()
+        .map(|dims| dims.height)
+        .max()
+        .ok_or(EmptyBatch)?;
+    let log_max_height = log2_ceil_usize(max_height);
+    let mut layers = vec![(0, vec![]); log_max_height + 1];
+    for (matrix, dims) in dimensions.iter().enumerate() {
+        let layer = log_max_height - log2_ceil_usize(dims.height);
+        let (height, matrices) = &mut layers[layer];
+        if matrices.is_empty() {
+            *height = dims.height;
+        } else if *height != dims.height {
+            return Err(MismatchedHeights {
+                layer,
+                height: *height,
+                other_height: dims.height,
+            });
+        }
+        matrices.push(matrix);
+    }
+    Ok(layers)
+}
+
+/// A node of the tree which a multiproof verifier has computed, with the range of queries (in
+/// increasing order of index) below it.
+struct KnownNode<D> {
+    index: usize,
+    digest: D,
+    queries: Range<usize>,
 }
 
 impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS> {
@@ -44,9 +206,52 @@ impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_
         Self {
             hash,
             compress,
+            cap_height: 0,
+            digest_storage: DigestStorage::Full,
             _phantom: PhantomData,
         }
     }
+
+    /// Commit to the cap of height `cap_height` rather than the root.
+    #[must_use]
+    pub fn with_cap_height(mut self, cap_height: usize) -> Self {
+        self.cap_height = cap_height;
+        self
+    }
+
+    pub const fn cap_height(&self) -> usize {
+        self.cap_height
+    }
+
+    /// Keep only the digest layers `digest_storage` asks for in the prover data, and at least
+    /// those down to the cap.
+    #[must_use]
+    pub fn with_digest_storage(mut self, digest_storage: DigestStorage) -> Self {
+        self.digest_storage = digest_storage;
+        self
+    }
+
+    pub const fn digest_storage(&self) -> DigestStorage {
+        self.digest_storage
+    }
+}
+
+/// The row of each matrix opened at `index`, or an empty row past the end of a shorter matrix.
+fn opened_rows<T: Send + Sync, M: Matrix<T>>(leaves: &[M], index: usize) -> Vec<Vec<T>> {
+    let log_max_height = log2_ceil_usize(leaves.iter().map(|m| m.height()).max().unwrap());
+    leaves
+        .iter()
+        .map(|matrix| {
+            let log2_height = log2_ceil_usize(matrix.height());
+            let bits_reduced = log_max_height - log2_height;
+            let reduced_index = index >> bits_reduced;
+            if reduced_index < matrix.height() {
+                matrix.row(reduced_index).collect()
+            } else {
+                vec![]
+            }
+        })
+        .collect()
 }
 
 impl<P, PW, H, C, const DIGEST_ELEMS: usize> Mmcs<P::Value>
@@ -64,17 +269,30 @@ where
     [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
 {
     type ProverData<M> = MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>;
-    type Commitment = Hash<P::Value, PW::Value, DIGEST_ELEMS>;
+    type Commitment = MerkleCap<P::Value, PW::Value, DIGEST_ELEMS>;
     type Proof = Vec<[PW::Value; DIGEST_ELEMS]>;
     type Error = MerkleTreeError;
 
+    const DIGEST_WIDTH: usize = DIGEST_ELEMS;
+
     fn commit<M: Matrix<P::Value>>(
         &self,
         inputs: Vec<M>,
     ) -> (Self::Commitment, Self::ProverData<M>) {
-        let tree = MerkleTree::new::<P, PW, H, C>(&self.hash, &self.compress, inputs);
-        let root = tree.root();
-        (root, tree)
+        let storage = match self.digest_storage {
+            DigestStorage::Full => DigestStorage::Full,
+            DigestStorage::Pruned { top_layers } => DigestStorage::Pruned {
+                top_layers: top_layers.max(self.cap_height + 1),
+            },
+        };
+        let tree = MerkleTree::new_with_storage::<P, PW, H, C>(
+            &self.hash,
+            &self.compress,
+            inputs,
+            storage,
+        );
+        let cap = tree.cap(self.cap_height);
+        (cap, tree)
     }
 
     fn open_batch<M: Matrix<P::Value>>(
@@ -84,21 +302,16 @@ where
     ) -> (Vec<Vec<P::Value>>, Vec<[PW::Value; DIGEST_ELEMS]>) {
         let max_height = self.get_max_height(prover_data);
         let log_max_height = log2_ceil_usize(max_height);
+        let cap_height = effective_cap_height(
+            self.cap_height,
+            prover_data.leaves.iter().map(|m| m.height()),
+        );
 
-        let openings = prover_data
-            .leaves
-            .iter()
-            .map(|matrix| {
-                let log2_height = log2_ceil_usize(matrix.height());
-                let bits_reduced = log_max_height - log2_height;
-                let reduced_index = index >> bits_reduced;
-                matrix.row(reduced_index).collect()
-            })
+        let openings = opened_rows(&prover_data.leaves, index);
+        let siblings = (0..log_max_height - cap_height)
+            .map(|i| (i, (index >> i) ^ 1))
             .collect_vec();
-
-        let proof: Vec<_> = (0..log_max_height)
-            .map(|i| prover_data.digest_layers[i][(index >> i) ^ 1])
-            .collect();
+        let proof = prover_data.digests_at(&self.hash, &self.compress, &siblings);
 
         (openings, proof)
     }
@@ -110,6 +323,18 @@ where
         prover_data.leaves.iter().collect()
     }
 
+    fn commitment_cap_height(&self, dimensions: &[Dimensions]) -> usize {
+        effective_cap_height(self.cap_height, dimensions.iter().map(|dims| dims.height))
+    }
+
+    fn deserialize_commitment<'de, D: Deserializer<'de>>(
+        &self,
+        dimensions: &[Dimensions],
+        deserializer: D,
+    ) -> Result<Self::Commitment, D::Error> {
+        MerkleCap::deserialize_with_height(self.commitment_cap_height(dimensions), deserializer)
+    }
+
     fn verify_batch(
         &self,
         commit: &Self::Commitment,
@@ -136,36 +361,29 @@ where
             return Err(EmptyBatch);
         };
         let log_max_height = log2_ceil_usize(max_height);
-        if proof.len() != log_max_height {
+        let cap_height =
+            effective_cap_height(self.cap_height, dimensions.iter().map(|dims| dims.height));
+        if commit.roots().len() != 1 << cap_height {
+            return Err(WrongCapSize {
+                expected: 1 << cap_height,
+                actual: commit.roots().len(),
+            });
+        }
+        if proof.len() != log_max_height - cap_height {
             return Err(WrongHeight {
                 max_height,
                 num_siblings: proof.len(),
             });
         }
 
-        let mut heights_tallest_first = dimensions
-            .iter()
-            .enumerate()
-            .sorted_by_key(|(_, dims)| Reverse(dims.height))
-            .peekable();
-
-        let Some(mut curr_height_padded) = heights_tallest_first
-            .peek()
-            .map(|x| x.1.height.next_power_of_two())
-        else {
-            // dimensions is empty
-            return Err(EmptyBatch);
-        };
-
-        let mut root = self.hash.hash_iter_slices(
-            heights_tallest_first
-                .peeking_take_while(|(_, dims)| {
-                    dims.height.next_power_of_two() == curr_height_padded
-                })
-                .map(|(i, _)| opened_values[i].as_slice()),
-        );
+        let layers = matrices_by_layer(dimensions)?;
+        let (_, tallest) = &layers[0];
+        let mut root = self
+            .hash
+            .hash_iter_slices(tallest.iter().map(|&m| opened_values[m].as_slice()));
+        let mut num_hashed_matrices = tallest.len();
 
-        for &sibling in proof.iter() {
+        for (&sibling, (height, injected)) in proof.iter().zip(&layers[1..]) {
             let (left, right) = if index & 1 == 0 {
                 (root, sibling)
             } else {
@@ -174,24 +392,209 @@ where
 
             root = self.compress.compress([left, right]);
             index >>= 1;
-            curr_height_padded >>= 1;
-
-            let next_height = heights_tallest_first
-                .peek()
-                .map(|(_, dims)| dims.height)
-                .filter(|h| h.next_power_of_two() == curr_height_padded);
-            if let Some(next_height) = next_height {
-                let next_height_openings_digest = self.hash.hash_iter_slices(
-                    heights_tallest_first
-                        .peeking_take_while(|(_, dims)| dims.height == next_height)
-                        .map(|(i, _)| opened_values[i].as_slice()),
-                );
+
+            if !injected.is_empty() {
+                let rows = injected.iter().map(|&m| opened_values[m].as_slice());
+                let next_height_openings_digest = if index < *height {
+                    self.hash.hash_iter_slices(rows)
+                } else if rows.clone().all(<[_]>::is_empty) {
+                    // These matrices end before this row.
+                    [PW::Value::default(); DIGEST_ELEMS]
+                } else {
+                    return Err(WrongWidth);
+                };
 
                 root = self.compress.compress([root, next_height_openings_digest]);
+                num_hashed_matrices += injected.len();
+            }
+        }
+        // The cap is never above the shortest matrix, so every matrix was hashed in below it.
+        debug_assert_eq!(num_hashed_matrices, dimensions.len());
+
+        // As with a single root, the bits of the index above the height of the tree are ignored.
+        if commit.roots()[index & ((1 << cap_height) - 1)] == root {
+            Ok(())
+        } else {
+            Err(RootMismatch)
+        }
+    }
+}
+
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    P: PackedValue,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    PW::Value: Eq,
+    [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+{
+    /// Open the rows at each of `indices`, as `open_batch` would, but with the authentication
+    /// paths merged into a single multiproof. Duplicate indices are opened once per occurrence.
+    pub fn open_batch_multi<M: Matrix<P::Value>>(
+        &self,
+        indices: &[usize],
+        prover_data: &MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>,
+    ) -> MultiBatchOpening<P::Value, PW::Value, DIGEST_ELEMS> {
+        let log_max_height = log2_ceil_usize(self.get_max_height(prover_data));
+        let cap_height = effective_cap_height(
+            self.cap_height,
+            prover_data.leaves.iter().map(|m| m.height()),
+        );
+        let opened_values = indices
+            .iter()
+            .map(|&index| opened_rows(&prover_data.leaves, index))
+            .collect();
+
+        let distinct_indices = indices.iter().copied().sorted().dedup().collect_vec();
+        let siblings = multiproof_siblings(&distinct_indices, log_max_height - cap_height);
+        let proof = prover_data.digests_at(&self.hash, &self.compress, &siblings);
+
+        MultiBatchOpening {
+            opened_values,
+            proof,
+        }
+    }
+
+    /// Verify a multi-opening produced by `open_batch_multi` for the same `indices`.
+    ///
+    /// Besides everything `verify_batch` checks for each query, this rejects queries which share
+    /// a node but disagree on the rows hashed into it, e.g. the rows of a duplicated index.
+    pub fn verify_batch_multi(
+        &self,
+        commit: &MerkleCap<P::Value, PW::Value, DIGEST_ELEMS>,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opening: &MultiBatchOpening<P::Value, PW::Value, DIGEST_ELEMS>,
+    ) -> Result<(), MerkleTreeError> {
+        let opened_values = &opening.opened_values;
+        if indices.is_empty() || dimensions.is_empty() {
+            return Err(EmptyBatch);
+        }
+        if opened_values.len() != indices.len()
+            || opened_values
+                .iter()
+                .any(|rows| rows.len() != dimensions.len())
+        {
+            return Err(WrongBatchSize);
+        }
+
+        let max_height = dimensions.iter().map(|dims| dims.height).max().unwrap();
+        let log_max_height = log2_ceil_usize(max_height);
+        let cap_height =
+            effective_cap_height(self.cap_height, dimensions.iter().map(|dims| dims.height));
+        if commit.roots().len() != 1 << cap_height {
+            return Err(WrongCapSize {
+                expected: 1 << cap_height,
+                actual: commit.roots().len(),
+            });
+        }
+        if let Some(&index) = indices.iter().find(|&&index| index >> log_max_height != 0) {
+            return Err(IndexOutOfBounds { index, max_height });
+        }
+
+        // The queries in increasing order of index, so that those below any node are contiguous.
+        let queries = (0..indices.len())
+            .sorted_by_key(|&q| indices[q])
+            .collect_vec();
+
+        let layers = matrices_by_layer(dimensions)?;
+
+        // Hash the rows of `matrices` opened by the queries `queries[below]`, which are those below
+        // a single node, checking that the queries agree on them.
+        let hash_rows = |matrices: &[usize], below: Range<usize>| {
+            let first = queries[below.start];
+            let agree = queries[below].iter().all(|&q| {
+                matrices
+                    .iter()
+                    .all(|&m| opened_values[q][m] == opened_values[first][m])
+            });
+            agree.then(|| {
+                self.hash
+                    .hash_iter_slices(matrices.iter().map(|&m| opened_values[first][m].as_slice()))
+            })
+        };
+
+        let (_, tallest) = &layers[0];
+        let mut num_hashed_matrices = tallest.len();
+        let mut nodes = vec![];
+        for (index, group) in &queries.iter().enumerate().chunk_by(|&(_, &q)| indices[q]) {
+            let positions = group.map(|(pos, _)| pos).collect_vec();
+            let range = positions[0]..positions[positions.len() - 1] + 1;
+            let digest = hash_rows(tallest, range.clone()).ok_or(InconsistentOpenings)?;
+            nodes.push(KnownNode {
+                index,
+                digest,
+                queries: range,
+            });
+        }
+
+        let mut siblings = opening.proof.iter();
+        let wrong_height = || WrongHeight {
+            max_height,
+            num_siblings: opening.proof.len(),
+        };
+        for (next_height, injected) in &layers[1..=log_max_height - cap_height] {
+            let injected = Some((next_height, injected)).filter(|(_, m)| !m.is_empty());
+            num_hashed_matrices += injected.map_or(0, |(_, m)| m.len());
+
+            let mut parents = vec![];
+            let mut nodes_iter = nodes.into_iter().peekable();
+            while let Some(node) = nodes_iter.next() {
+                let (left, right, below) =
+                    match nodes_iter.next_if(|next| next.index == node.index ^ 1) {
+                        Some(right) => (
+                            node.digest,
+                            right.digest,
+                            node.queries.start..right.queries.end,
+                        ),
+                        None => {
+                            let sibling = *siblings.next().ok_or_else(wrong_height)?;
+                            if node.index & 1 == 0 {
+                                (node.digest, sibling, node.queries)
+                            } else {
+                                (sibling, node.digest, node.queries)
+                            }
+                        }
+                    };
+                let mut digest = self.compress.compress([left, right]);
+                if let Some((next_height, injected)) = injected {
+                    let rows_digest = if node.index >> 1 < *next_height {
+                        hash_rows(injected, below.clone()).ok_or(InconsistentOpenings)?
+                    } else if queries[below.clone()]
+                        .iter()
+                        .all(|&q| injected.iter().all(|&m| opened_values[q][m].is_empty()))
+                    {
+                        // These matrices end before this row.
+                        [PW::Value::default(); DIGEST_ELEMS]
+                    } else {
+                        return Err(WrongWidth);
+                    };
+                    digest = self.compress.compress([digest, rows_digest]);
+                }
+                parents.push(KnownNode {
+                    index: node.index >> 1,
+                    digest,
+                    queries: below,
+                });
             }
+            nodes = parents;
         }
 
-        if commit == &root {
+        if siblings.next().is_some() {
+            return Err(wrong_height());
+        }
+        // The cap is never above the shortest matrix, so every matrix was hashed in below it.
+        debug_assert_eq!(num_hashed_matrices, dimensions.len());
+        // The indices are in bounds, so each node left is below the cap.
+        if nodes
+            .iter()
+            .all(|node| commit.roots()[node.index] == node.digest)
+        {
             Ok(())
         } else {
             Err(RootMismatch)
@@ -201,20 +604,30 @@ where
 
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString;
     use alloc::vec;
+    use alloc::vec::Vec;
 
     use itertools::Itertools;
     use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
     use p3_commit::Mmcs;
     use p3_field::{Field, FieldAlgebra};
+    use p3_goldilocks::{hl_goldilocks_poseidon2_12, Goldilocks, Poseidon2GoldilocksHL};
+    use p3_matrix::bitrev::BitReversableMatrix;
     use p3_matrix::dense::RowMajorMatrix;
+    use p3_matrix::stack::VerticalPair;
     use p3_matrix::{Dimensions, Matrix};
     use p3_symmetric::{
-        CryptographicHasher, PaddingFreeSponge, PseudoCompressionFunction, TruncatedPermutation,
+        CryptographicHasher, MerkleCap, PaddingFreeSponge, PseudoCompressionFunction,
+        TruncatedCompressor, TruncatedHasher, TruncatedPermutation,
     };
-    use rand::thread_rng;
+    use p3_util::log2_ceil_usize;
+    use rand::{thread_rng, Rng};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
 
-    use super::MerkleTreeMmcs;
+    use super::{multiproof_siblings, MerkleTreeMmcs};
+    use crate::{DigestStorage, DigestsError, MerkleTree, MerkleTreeError};
 
     type F = BabyBear;
 
@@ -254,7 +667,7 @@ mod tests {
                 compress.compress([hash.hash_item(v[6]), hash.hash_item(v[7])]),
             ]),
         ]);
-        assert_eq!(commit, expected_result);
+        assert_eq!(commit.roots(), [expected_result]);
     }
 
     #[test]
@@ -268,7 +681,7 @@ mod tests {
         let (commit, _) = mmcs.commit(vec![mat.clone()]);
 
         let expected_result = hash.hash_iter(mat.clone().vertically_packed_row(0));
-        assert_eq!(commit, expected_result);
+        assert_eq!(commit.roots(), [expected_result]);
     }
 
     #[test]
@@ -290,7 +703,7 @@ mod tests {
             hash.hash_slice(&[F::ZERO, F::ONE]),
             hash.hash_slice(&[F::TWO, F::ONE]),
         ]);
-        assert_eq!(commit, expected_result);
+        assert_eq!(commit.roots(), [expected_result]);
     }
 
     #[test]
@@ -317,7 +730,60 @@ mod tests {
             ]),
             compress.compress([hash.hash_slice(&[F::TWO, F::TWO]), default_digest]),
         ]);
-        assert_eq!(commit, expected_result);
+        assert_eq!(commit.roots(), [expected_result]);
+    }
+
+    #[test]
+    fn commit_bit_reversed_view() {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mut rng = thread_rng();
+        let mats = [
+            RowMajorMatrix::<F>::rand(&mut rng, 64, 9),
+            RowMajorMatrix::<F>::rand(&mut rng, 16, 3),
+        ];
+
+        // Committing to the lazy views gives the same tree as committing to permuted copies.
+        let (view_commit, view_data) =
+            mmcs.commit(mats.iter().cloned().map(|m| m.bit_reverse_rows()).collect());
+        let (copy_commit, copy_data) = mmcs.commit(
+            mats.iter()
+                .cloned()
+                .map(|m| m.bit_reverse_rows().to_row_major_matrix())
+                .collect(),
+        );
+        assert_eq!(view_commit, copy_commit);
+        assert_eq!(
+            mmcs.open_batch(5, &view_data),
+            mmcs.open_batch(5, &copy_data)
+        );
+    }
+
+    #[test]
+    fn commit_stacked_matrices() {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mut rng = thread_rng();
+        let top = RowMajorMatrix::<F>::rand(&mut rng, 24, 5);
+        let bottom = RowMajorMatrix::<F>::rand(&mut rng, 8, 5);
+        let stacked = VerticalPair::new(top.as_view(), bottom.as_view());
+
+        // Committing to the view gives the same tree as committing to the concatenation.
+        let mut values = top.values.clone();
+        values.extend_from_slice(&bottom.values);
+        let (view_commit, view_data) = mmcs.commit(vec![stacked]);
+        let (copy_commit, copy_data) = mmcs.commit(vec![RowMajorMatrix::new(values, 5)]);
+        assert_eq!(view_commit, copy_commit);
+        assert_eq!(
+            mmcs.open_batch(27, &view_data),
+            mmcs.open_batch(27, &copy_data)
+        );
     }
 
     #[test]
@@ -405,7 +871,7 @@ mod tests {
             ]),
         ]);
 
-        assert_eq!(commit, expected_result);
+        assert_eq!(commit.roots(), [expected_result]);
 
         let (opened_values, _proof) = mmcs.open_batch(2, &prover_data);
         assert_eq!(
@@ -563,4 +1029,730 @@ mod tests {
         mmcs.verify_batch(&commit, &dims, 17, &opened_values, &proof)
             .expect("expected verification to succeed");
     }
+
+    #[test]
+    fn truncated_digests() {
+        type ShortHash = TruncatedHasher<MyHash, 8, 4>;
+        type ShortCompress = TruncatedCompressor<MyCompress, 8, 4>;
+        type ShortMmcs = MerkleTreeMmcs<
+            <F as Field>::Packing,
+            <F as Field>::Packing,
+            ShortHash,
+            ShortCompress,
+            4,
+        >;
+
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let full_hash = MyHash::new(perm.clone());
+        let hash = ShortHash::new(full_hash.clone());
+        let compress = ShortCompress::new(MyCompress::new(perm));
+        let mmcs = ShortMmcs::new(hash, compress);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 64, 5),
+            RowMajorMatrix::<F>::rand(&mut rng, 16, 3),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        // Only the tallest matrix feeds the leaf layer, so the first sibling of leaf 37 is the
+        // digest of row 36 of `mats[0]`, cut down to its first 4 elements.
+        let full_sibling: [F; 8] = full_hash.hash_iter(mats[0].row(36));
+
+        let (commit, prover_data) = mmcs.commit(mats);
+        let (opened_values, proof) = mmcs.open_batch(37, &prover_data);
+        assert_eq!(proof.len(), 6);
+        assert_eq!(proof[0], full_sibling[..4]);
+        mmcs.verify_batch(&commit, &dims, 37, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        let mut tampered_values = opened_values.clone();
+        tampered_values[1][0] += F::ONE;
+        assert!(mmcs
+            .verify_batch(&commit, &dims, 37, &tampered_values, &proof)
+            .is_err());
+    }
+
+    #[test]
+    fn goldilocks_poseidon2() {
+        type G = Goldilocks;
+        type GPerm = Poseidon2GoldilocksHL<12>;
+        type GHash = PaddingFreeSponge<GPerm, 12, 8, 4>;
+        type GCompress = TruncatedPermutation<GPerm, 2, 4, 12>;
+        type GMmcs =
+            MerkleTreeMmcs<<G as Field>::Packing, <G as Field>::Packing, GHash, GCompress, 4>;
+
+        let perm = hl_goldilocks_poseidon2_12();
+        let hash = GHash::new(perm.clone());
+        let compress = GCompress::new(perm);
+        let mmcs = GMmcs::new(hash, compress);
+
+        let mut rng = thread_rng();
+        let mats = vec![
+            RowMajorMatrix::<G>::rand(&mut rng, 32, 7),
+            RowMajorMatrix::<G>::rand(&mut rng, 8, 3),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+        let (commit, prover_data) = mmcs.commit(mats);
+        let (opened_values, proof) = mmcs.open_batch(21, &prover_data);
+        mmcs.verify_batch(&commit, &dims, 21, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        let mut tampered_values = opened_values.clone();
+        tampered_values[0][3] += G::ONE;
+        assert!(mmcs
+            .verify_batch(&commit, &dims, 21, &tampered_values, &proof)
+            .is_err());
+    }
+
+    /// Commit to matrices of several heights, including ones injected below the leaves, and
+    /// return the mmcs, commitment, dimensions and prover data.
+    fn multi_opening_setup() -> (
+        MyMmcs,
+        <MyMmcs as Mmcs<F>>::Commitment,
+        Vec<Dimensions>,
+        <MyMmcs as Mmcs<F>>::ProverData<RowMajorMatrix<F>>,
+    ) {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 100, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 64, 2),
+            RowMajorMatrix::<F>::rand(&mut rng, 20, 5),
+            RowMajorMatrix::<F>::rand(&mut rng, 3, 1),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+        (mmcs, commit, dims, prover_data)
+    }
+
+    #[test]
+    fn multi_opening_verifies() {
+        let (mmcs, commit, dims, prover_data) = multi_opening_setup();
+        for indices in [
+            vec![37],
+            // Overlapping paths, including siblings of each other.
+            vec![36, 37, 38, 40, 99],
+            // Disjoint paths, out of order.
+            vec![99, 0, 64],
+            // Duplicates.
+            vec![5, 90, 5, 5, 91],
+        ] {
+            let opening = mmcs.open_batch_multi(&indices, &prover_data);
+            for (&index, opened_values) in indices.iter().zip(&opening.opened_values) {
+                assert_eq!(opened_values, &mmcs.open_batch(index, &prover_data).0);
+            }
+            mmcs.verify_batch_multi(&commit, &dims, &indices, &opening)
+                .expect("expected verification to succeed");
+        }
+
+        // Sibling leaves authenticate each other, so they need one node fewer than either alone.
+        let opening = mmcs.open_batch_multi(&[36, 37], &prover_data);
+        assert_eq!(opening.proof.len(), 6);
+    }
+
+    #[test]
+    fn multi_opening_rejects_tampering() {
+        let (mmcs, commit, dims, prover_data) = multi_opening_setup();
+        // 36 and 38 share every node above the second layer.
+        let indices = [36, 38, 36];
+        let opening = mmcs.open_batch_multi(&indices, &prover_data);
+
+        for i in 0..opening.proof.len() {
+            let mut tampered = opening.clone();
+            tampered.proof[i][0] += F::ONE;
+            assert!(mmcs
+                .verify_batch_multi(&commit, &dims, &indices, &tampered)
+                .is_err());
+        }
+
+        let mut tampered = opening.clone();
+        tampered.opened_values[1][2][0] += F::ONE;
+        assert!(mmcs
+            .verify_batch_multi(&commit, &dims, &indices, &tampered)
+            .is_err());
+
+        // The two openings of row 36 must agree, even on a matrix injected above the leaves.
+        let mut tampered = opening.clone();
+        tampered.opened_values[2][3][0] += F::ONE;
+        assert!(matches!(
+            mmcs.verify_batch_multi(&commit, &dims, &indices, &tampered),
+            Err(MerkleTreeError::InconsistentOpenings)
+        ));
+
+        let mut truncated = opening.clone();
+        truncated.proof.pop();
+        assert!(mmcs
+            .verify_batch_multi(&commit, &dims, &indices, &truncated)
+            .is_err());
+
+        assert!(matches!(
+            mmcs.verify_batch_multi(&commit, &dims, &[36, 128, 36], &opening),
+            Err(MerkleTreeError::IndexOutOfBounds { index: 128, .. })
+        ));
+        assert!(mmcs
+            .verify_batch_multi(&commit, &dims, &[36, 37, 36], &opening)
+            .is_err());
+    }
+
+    #[test]
+    fn multiproof_is_smaller() {
+        // 40 queries into a tree of 2^20 leaves, as in a typical FRI proof.
+        let log_height = 20;
+        let mut rng = thread_rng();
+        for _ in 0..10 {
+            let indices = (0..40)
+                .map(|_| rng.gen_range(0..1 << log_height))
+                .sorted()
+                .dedup()
+                .collect_vec();
+            let multi = multiproof_siblings(&indices, log_height).len();
+            let separate = 40 * log_height;
+            assert!(4 * multi <= 3 * separate, "{multi} of {separate} digests");
+        }
+    }
+
+    #[test]
+    fn caps_shorten_paths() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 64, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 30, 2),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 1),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+        let (root, root_data) = mmcs.commit(mats.clone());
+        for cap_height in [0, 1, 3] {
+            let capped = mmcs.clone().with_cap_height(cap_height);
+            let (cap, prover_data) = capped.commit(mats.clone());
+            assert_eq!(cap.roots().len(), 1 << cap_height);
+            // The cap is a layer of the same tree.
+            assert_eq!(cap.roots(), &root_data.digest_layers[6 - cap_height][..]);
+
+            for index in [0, 45, 63] {
+                let (opened_values, proof) = capped.open_batch(index, &prover_data);
+                let (full_opened_values, full_proof) = mmcs.open_batch(index, &root_data);
+                assert_eq!(opened_values, full_opened_values);
+                assert_eq!(proof, full_proof[..6 - cap_height]);
+                capped
+                    .verify_batch(&cap, &dims, index, &opened_values, &proof)
+                    .expect("expected verification to succeed");
+            }
+        }
+
+        // A cap of height 0 is the root, and the openings are those of a plain Merkle tree.
+        assert_eq!(root.roots(), [root_data.root()]);
+        let (_, proof) = mmcs.open_batch(45, &root_data);
+        assert_eq!(proof.len(), 6);
+    }
+
+    #[test]
+    fn cap_entry_is_selected_by_high_bits() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm)).with_cap_height(3);
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 64, 3);
+        let dims = vec![mat.dimensions()];
+        let (cap, prover_data) = mmcs.commit(vec![mat]);
+
+        // Row 45 = 0b101101 is below the cap entry 0b101.
+        let (opened_values, proof) = mmcs.open_batch(45, &prover_data);
+        let mut roots = cap.roots().to_vec();
+        roots[2] = roots[7];
+        mmcs.verify_batch(&roots.clone().into(), &dims, 45, &opened_values, &proof)
+            .expect("other cap entries should not matter");
+        roots[5] = roots[4];
+        assert!(matches!(
+            mmcs.verify_batch(&roots.into(), &dims, 45, &opened_values, &proof),
+            Err(MerkleTreeError::RootMismatch)
+        ));
+        // The path of row 45 does not lead to the entry of row 37.
+        assert!(mmcs
+            .verify_batch(&cap, &dims, 37, &opened_values, &proof)
+            .is_err());
+    }
+
+    #[test]
+    fn cap_is_lowered_to_fit_short_matrices() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm)).with_cap_height(3);
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 64, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 2, 4),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (cap, prover_data) = mmcs.commit(mats);
+        assert_eq!(cap.roots().len(), 2);
+        assert_eq!(mmcs.commitment_cap_height(&dims), 1);
+
+        // The cap is encoded as its digests alone, and read back knowing what it commits to.
+        let bytes = postcard::to_allocvec(&cap).unwrap();
+        let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+        assert_eq!(
+            mmcs.deserialize_commitment(&dims, &mut deserializer)
+                .unwrap(),
+            cap
+        );
+        assert!(deserializer.finalize().unwrap().is_empty());
+
+        let (opened_values, proof) = mmcs.open_batch(50, &prover_data);
+        assert_eq!(proof.len(), 5);
+        mmcs.verify_batch(&cap, &dims, 50, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        // The short matrix is still committed to.
+        let mut tampered_values = opened_values.clone();
+        tampered_values[1][0] += F::ONE;
+        assert!(mmcs
+            .verify_batch(&cap, &dims, 50, &tampered_values, &proof)
+            .is_err());
+
+        let wrong_cap = MerkleCap::from(vec![cap.roots()[0]; 8]);
+        assert!(matches!(
+            mmcs.verify_batch(&wrong_cap, &dims, 50, &opened_values, &proof),
+            Err(MerkleTreeError::WrongCapSize {
+                expected: 2,
+                actual: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn multi_opening_with_cap() {
+        let (mmcs, _, dims, _) = multi_opening_setup();
+        let mmcs = mmcs.with_cap_height(2);
+        let mut rng = thread_rng();
+        let mats = dims
+            .iter()
+            .map(|dims| RowMajorMatrix::<F>::rand(&mut rng, dims.height, dims.width))
+            .collect_vec();
+        let (cap, prover_data) = mmcs.commit(mats);
+        // The shortest matrix has height 3, so the cap keeps its height of 2.
+        assert_eq!(cap.roots().len(), 4);
+
+        let indices = [3, 40, 41, 99];
+        let opening = mmcs.open_batch_multi(&indices, &prover_data);
+        mmcs.verify_batch_multi(&cap, &dims, &indices, &opening)
+            .expect("expected verification to succeed");
+
+        let mut roots = cap.roots().to_vec();
+        roots[1] = roots[0];
+        assert!(mmcs
+            .verify_batch_multi(&roots.into(), &dims, &indices, &opening)
+            .is_err());
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        bincode::serde::encode_to_vec(value, bincode::config::standard()).unwrap()
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+        bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .ok()
+            .map(|(value, _)| value)
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let (mmcs, commit, dims, prover_data) = multi_opening_setup();
+        let commit_bytes = encode(&commit);
+        let (opened_values, proof) = mmcs.open_batch(77, &prover_data);
+        let proof_bytes = encode(&proof);
+
+        let commit: <MyMmcs as Mmcs<F>>::Commitment = decode(&commit_bytes).unwrap();
+        let proof: <MyMmcs as Mmcs<F>>::Proof = decode(&proof_bytes).unwrap();
+        mmcs.verify_batch(&commit, &dims, 77, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        let truncated = &proof_bytes[..proof_bytes.len() - 1];
+        assert!(decode::<<MyMmcs as Mmcs<F>>::Proof>(truncated).is_none());
+        let truncated = &commit_bytes[..commit_bytes.len() - 1];
+        assert!(decode::<<MyMmcs as Mmcs<F>>::Commitment>(truncated).is_none());
+    }
+
+    #[test]
+    fn prover_data_round_trip() {
+        let (mmcs, commit, dims, prover_data) = multi_opening_setup();
+        let expected = mmcs.open_batch(42, &prover_data);
+
+        let bytes = encode(&prover_data);
+        let restored: <MyMmcs as Mmcs<F>>::ProverData<RowMajorMatrix<F>> = decode(&bytes).unwrap();
+        assert_eq!(mmcs.open_batch(42, &restored), expected);
+
+        // Keep only the digests, and bind them to the same matrices again.
+        let (leaves, digests) = prover_data.into_parts();
+        let restored = MerkleTree::from_parts(leaves, digests).unwrap();
+        let (opened_values, proof) = mmcs.open_batch(42, &restored);
+        assert_eq!((opened_values.clone(), proof.clone()), expected);
+        mmcs.verify_batch(&commit, &dims, 42, &opened_values, &proof)
+            .expect("expected verification to succeed");
+    }
+
+    #[cfg(feature = "persist")]
+    #[test]
+    fn digests_round_trip_through_bytes() {
+        use crate::{MerkleTreeDigests, DIGESTS_FORMAT_VERSION};
+
+        let (mmcs, _, _, prover_data) = multi_opening_setup();
+        let expected = mmcs.open_batch(42, &prover_data);
+        let (leaves, digests) = prover_data.into_parts();
+        let bytes = digests.to_bytes();
+
+        assert_eq!(
+            MerkleTreeDigests::<F, 8>::from_bytes(&bytes[..bytes.len() / 2]),
+            Err(DigestsError::Malformed)
+        );
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(
+            MerkleTreeDigests::<F, 8>::from_bytes(&extended),
+            Err(DigestsError::Malformed)
+        );
+        // The first element of the first layer, after the header and the number of digests.
+        let mut non_canonical = bytes.clone();
+        non_canonical[20..28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            MerkleTreeDigests::<F, 8>::from_bytes(&non_canonical),
+            Err(DigestsError::Malformed)
+        );
+        let mut other_version = bytes.clone();
+        other_version[0] = DIGESTS_FORMAT_VERSION as u8 + 1;
+        assert_eq!(
+            MerkleTreeDigests::<F, 8>::from_bytes(&other_version),
+            Err(DigestsError::UnsupportedVersion(DIGESTS_FORMAT_VERSION + 1))
+        );
+        assert_eq!(
+            MerkleTreeDigests::<F, 4>::from_bytes(&bytes),
+            Err(DigestsError::DigestWidthMismatch {
+                expected: 4,
+                found: 8
+            })
+        );
+
+        let digests = MerkleTreeDigests::<F, 8>::from_bytes(&bytes).unwrap();
+        let restored = MerkleTree::from_parts(leaves, digests).unwrap();
+        assert_eq!(mmcs.open_batch(42, &restored), expected);
+    }
+
+    #[test]
+    fn digests_must_match_leaves() {
+        let (_, _, _, prover_data) = multi_opening_setup();
+        let (mut leaves, digests) = prover_data.into_parts();
+        leaves[0] = RowMajorMatrix::rand(&mut thread_rng(), 50, 3);
+        assert!(matches!(
+            MerkleTree::<F, F, _, 8>::from_parts(leaves, digests),
+            Err(DigestsError::ShapeMismatch)
+        ));
+    }
+
+    #[test]
+    fn verification_errors_name_the_failure() {
+        let (mmcs, commit, dims, prover_data) = multi_opening_setup();
+        let (opened_values, proof) = mmcs.open_batch(61, &prover_data);
+        let verify = |dims: &[Dimensions], opened_values: &[Vec<F>], proof: &Vec<[F; 8]>| {
+            mmcs.verify_batch(&commit, dims, 61, opened_values, proof)
+        };
+        assert_eq!(verify(&dims, &opened_values, &proof), Ok(()));
+
+        for matrix in 0..dims.len() {
+            let mut tampered = opened_values.clone();
+            tampered[matrix][0] += F::ONE;
+            assert_eq!(
+                verify(&dims, &tampered, &proof),
+                Err(MerkleTreeError::RootMismatch)
+            );
+        }
+        for level in 0..proof.len() {
+            let mut tampered = proof.clone();
+            tampered[level][0] += F::ONE;
+            assert_eq!(
+                verify(&dims, &opened_values, &tampered),
+                Err(MerkleTreeError::RootMismatch)
+            );
+        }
+
+        let mut short = proof.clone();
+        short.pop();
+        let err = verify(&dims, &opened_values, &short).unwrap_err();
+        assert_eq!(
+            err,
+            MerkleTreeError::WrongHeight {
+                max_height: 100,
+                num_siblings: 6
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "6 siblings do not fit a tree of matrices of height up to 100"
+        );
+
+        assert_eq!(
+            verify(&dims, &opened_values[1..], &proof),
+            Err(MerkleTreeError::WrongBatchSize)
+        );
+        assert_eq!(verify(&[], &[], &proof), Err(MerkleTreeError::EmptyBatch));
+
+        let two_roots = MerkleCap::from(vec![commit.roots()[0]; 2]);
+        assert_eq!(
+            mmcs.verify_batch(&two_roots, &dims, 61, &opened_values, &proof),
+            Err(MerkleTreeError::WrongCapSize {
+                expected: 1,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn non_power_of_two_heights() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+
+        for heights in [
+            vec![1],
+            vec![3],
+            vec![6],
+            vec![31],
+            vec![6, 3, 1],
+            vec![63, 16, 6, 3, 1],
+            vec![64, 31, 8, 3],
+        ] {
+            let mats = heights
+                .iter()
+                .map(|&h| RowMajorMatrix::<F>::rand(&mut rng, h, 2))
+                .collect_vec();
+            let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+            let (commit, prover_data) = mmcs.commit(mats.clone());
+            let max_height = heights[0];
+            let log_max_height = log2_ceil_usize(max_height);
+
+            for index in [0, max_height / 2, max_height - 1] {
+                let (opened_values, proof) = mmcs.open_batch(index, &prover_data);
+                for (mat, opened) in mats.iter().zip(&opened_values) {
+                    let row = index >> (log_max_height - log2_ceil_usize(mat.height()));
+                    if row < mat.height() {
+                        assert_eq!(opened, &mat.row_slice(row).to_vec());
+                    } else {
+                        assert!(opened.is_empty(), "heights {heights:?}, index {index}");
+                    }
+                }
+                mmcs.verify_batch(&commit, &dims, index, &opened_values, &proof)
+                    .expect("expected verification to succeed");
+
+                let multi = mmcs.open_batch_multi(&[index], &prover_data);
+                mmcs.verify_batch_multi(&commit, &dims, &[index], &multi)
+                    .expect("expected verification to succeed");
+            }
+        }
+    }
+
+    #[test]
+    fn rows_past_the_end_must_be_empty() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 16, 2),
+            RowMajorMatrix::<F>::rand(&mut rng, 3, 2),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        // Row 12 of the first matrix exists, but is above row 12 >> 2 = 3 of the second, which
+        // does not.
+        let (opened_values, proof) = mmcs.open_batch(12, &prover_data);
+        assert_eq!(opened_values[0].len(), 2);
+        assert!(opened_values[1].is_empty());
+        mmcs.verify_batch(&commit, &dims, 12, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        let mut padded = opened_values.clone();
+        padded[1] = vec![F::ZERO; 2];
+        assert_eq!(
+            mmcs.verify_batch(&commit, &dims, 12, &padded, &proof),
+            Err(MerkleTreeError::WrongWidth)
+        );
+    }
+
+    #[test]
+    fn pruned_openings_match_full() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let full = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+
+        for heights in [
+            vec![1],
+            vec![13],
+            vec![37, 17, 9, 3],
+            vec![64, 31, 8, 3],
+            vec![1000, 499, 250, 60, 3],
+        ] {
+            let mats = heights
+                .iter()
+                .map(|&h| RowMajorMatrix::<F>::rand(&mut rng, h, 2))
+                .collect_vec();
+            let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+            let max_height = heights[0];
+            let log_max_height = log2_ceil_usize(max_height);
+
+            for cap_height in [0, 2] {
+                let full = full.clone().with_cap_height(cap_height);
+                let (commit, full_data) = full.commit(mats.clone());
+                for top_layers in [0, 1, 3, log_max_height + 1] {
+                    let pruned = full
+                        .clone()
+                        .with_digest_storage(DigestStorage::Pruned { top_layers });
+                    let (pruned_commit, pruned_data) = pruned.commit(mats.clone());
+                    assert_eq!(pruned_commit, commit);
+                    // The layers down to the cap are always kept.
+                    let kept = top_layers.max(cap_height + 1).min(log_max_height + 1);
+                    assert_eq!(pruned_data.num_pruned_layers(), log_max_height + 1 - kept);
+
+                    for index in (0..max_height).step_by(max_height / 16 + 1) {
+                        let expected = full.open_batch(index, &full_data);
+                        let opening = pruned.open_batch(index, &pruned_data);
+                        assert_eq!(
+                            postcard::to_allocvec(&opening).unwrap(),
+                            postcard::to_allocvec(&expected).unwrap(),
+                            "heights {heights:?}, top layers {top_layers}, index {index}"
+                        );
+                        pruned
+                            .verify_batch(&commit, &dims, index, &opening.0, &opening.1)
+                            .expect("expected verification to succeed");
+                    }
+
+                    let indices = (0..8).map(|_| rng.gen_range(0..max_height)).collect_vec();
+                    let opening = pruned.open_batch_multi(&indices, &pruned_data);
+                    let expected = full.open_batch_multi(&indices, &full_data);
+                    assert_eq!(opening.opened_values, expected.opened_values);
+                    assert_eq!(opening.proof, expected.proof);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pruned_storage_saves_memory() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let full = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+        // Keep 8 of the 19 layers, so that each opening rehashes 2^11 rows.
+        let pruned = full
+            .clone()
+            .with_digest_storage(DigestStorage::Pruned { top_layers: 8 });
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 1 << 18, 4);
+
+        let (commit, full_data) = full.commit_matrix(mat.clone());
+        let (pruned_commit, pruned_data) = pruned.commit_matrix(mat);
+        assert_eq!(pruned_commit, commit);
+
+        let digest_bytes = core::mem::size_of::<[F; 8]>();
+        let full_bytes = full_data.num_stored_digests() * digest_bytes;
+        let pruned_bytes = pruned_data.num_stored_digests() * digest_bytes;
+        // 2^19 - 1 digests against 2^8 - 1: about 16 MiB against 8 KiB.
+        assert_eq!(full_bytes, ((1 << 19) - 1) * digest_bytes);
+        assert_eq!(pruned_bytes, ((1 << 8) - 1) * digest_bytes);
+
+        for _ in 0..4 {
+            let index = rng.gen_range(0..1 << 18);
+            assert_eq!(
+                pruned.open_batch(index, &pruned_data),
+                full.open_batch(index, &full_data)
+            );
+        }
+    }
+
+    #[test]
+    fn mixed_heights_at_every_index() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+
+        // Two pairs of matrices share a height, and the shortest has a single row, which also
+        // lowers any cap to the root.
+        for heights in [vec![1, 2, 2, 8, 8, 32], vec![8, 2, 32, 1, 8, 2]] {
+            let mats = heights
+                .iter()
+                .map(|&h| RowMajorMatrix::<F>::rand(&mut rng, h, 2))
+                .collect_vec();
+            let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+            for mmcs in [mmcs.clone(), mmcs.clone().with_cap_height(2)] {
+                let (commit, prover_data) = mmcs.commit(mats.clone());
+                assert_eq!(commit.roots().len(), 1);
+
+                for index in 0..32 {
+                    let (opened_values, proof) = mmcs.open_batch(index, &prover_data);
+                    for (mat, opened) in mats.iter().zip(&opened_values) {
+                        let row = index >> (5 - log2_ceil_usize(mat.height()));
+                        assert_eq!(opened, &mat.row_slice(row).to_vec());
+                    }
+                    mmcs.verify_batch(&commit, &dims, index, &opened_values, &proof)
+                        .expect("expected verification to succeed");
+
+                    // Every row is authenticated, including those of matrices which share a layer.
+                    for m in 0..mats.len() {
+                        let mut tampered = opened_values.clone();
+                        tampered[m][0] += F::ONE;
+                        assert_eq!(
+                            mmcs.verify_batch(&commit, &dims, index, &tampered, &proof),
+                            Err(MerkleTreeError::RootMismatch),
+                            "heights {heights:?}, index {index}, matrix {m}"
+                        );
+                    }
+                    // So is their order.
+                    let (a, b) = heights
+                        .iter()
+                        .positions(|&h| h == 8)
+                        .collect_tuple()
+                        .unwrap();
+                    let mut swapped = opened_values.clone();
+                    swapped.swap(a, b);
+                    assert_eq!(
+                        mmcs.verify_batch(&commit, &dims, index, &swapped, &proof),
+                        Err(MerkleTreeError::RootMismatch)
+                    );
+                }
+
+                // Indices whose rows of the shorter matrices collide.
+                let indices = [0, 1, 3, 4, 7, 8, 15, 16, 31, 31];
+                let opening = mmcs.open_batch_multi(&indices, &prover_data);
+                mmcs.verify_batch_multi(&commit, &dims, &indices, &opening)
+                    .expect("expected verification to succeed");
+            }
+        }
+    }
+
+    #[test]
+    fn heights_sharing_a_layer_must_match() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mats = [16, 6, 6].map(|h| RowMajorMatrix::<F>::rand(&mut rng, h, 2));
+        let (commit, prover_data) = mmcs.commit(mats.to_vec());
+
+        // Heights 6 and 5 both round up to 8, so they would share layer 1.
+        let mut dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        dims[2].height = 5;
+        let expected = Err(MerkleTreeError::MismatchedHeights {
+            layer: 1,
+            height: 6,
+            other_height: 5,
+        });
+        let (opened_values, proof) = mmcs.open_batch(3, &prover_data);
+        assert_eq!(
+            mmcs.verify_batch(&commit, &dims, 3, &opened_values, &proof),
+            expected
+        );
+        let opening = mmcs.open_batch_multi(&[3, 9], &prover_data);
+        assert_eq!(
+            mmcs.verify_batch_multi(&commit, &dims, &[3, 9], &opening),
+            expected
+        );
+    }
 }
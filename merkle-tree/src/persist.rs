@@ -0,0 +1,91 @@
+//! A versioned byte encoding of `MerkleTreeDigests`.
+//!
+//! The encoding starts with a header holding the version of the format and the number of elements
+//! in each digest, so that digests from another version or of another width are refused before
+//! the rest is decoded. The header ends with the number of digest layers, and each layer follows
+//! as its number of digests and then its elements, encoded with `p3_field::codec`, which rejects
+//! non-canonical elements. The version, width and number of layers are 4 little-endian bytes, and
+//! each number of digests 8.
+
+use alloc::vec::Vec;
+
+use p3_field::codec::{decode_slice, encode_iter};
+use p3_field::PrimeField64;
+
+use crate::{DigestsError, MerkleTreeDigests};
+
+/// The version of the digests format, bumped whenever it changes.
+pub const DIGESTS_FORMAT_VERSION: u32 = 2;
+
+/// Split `N` bytes off the start of `bytes`.
+fn take<const N: usize>(bytes: &[u8]) -> Result<([u8; N], &[u8]), DigestsError> {
+    if bytes.len() < N {
+        return Err(DigestsError::Malformed);
+    }
+    let (head, rest) = bytes.split_at(N);
+    Ok((head.try_into().unwrap(), rest))
+}
+
+fn take_u32(bytes: &[u8]) -> Result<(u32, &[u8]), DigestsError> {
+    take(bytes).map(|(head, rest)| (u32::from_le_bytes(head), rest))
+}
+
+fn take_u64(bytes: &[u8]) -> Result<(u64, &[u8]), DigestsError> {
+    take(bytes).map(|(head, rest)| (u64::from_le_bytes(head), rest))
+}
+
+impl<W: PrimeField64, const DIGEST_ELEMS: usize> MerkleTreeDigests<W, DIGEST_ELEMS> {
+    /// Encode the digests, with a header for `from_bytes` to check.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(DIGESTS_FORMAT_VERSION.to_le_bytes());
+        bytes.extend((DIGEST_ELEMS as u32).to_le_bytes());
+        bytes.extend((self.digest_layers.len() as u32).to_le_bytes());
+        for layer in &self.digest_layers {
+            bytes.extend((layer.len() as u64).to_le_bytes());
+            bytes.extend(encode_iter(layer.iter().flatten().copied()));
+        }
+        bytes
+    }
+
+    /// Decode digests encoded by `to_bytes`, checking that they were encoded in this version of
+    /// the format, with `DIGEST_ELEMS` elements per digest.
+    ///
+    /// Whether they fit the leaves is checked when they are bound to them with
+    /// `MerkleTree::from_parts`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DigestsError> {
+        let (version, rest) = take_u32(bytes)?;
+        if version != DIGESTS_FORMAT_VERSION {
+            return Err(DigestsError::UnsupportedVersion(version));
+        }
+        let (digest_elems, rest) = take_u32(rest)?;
+        if digest_elems as usize != DIGEST_ELEMS {
+            return Err(DigestsError::DigestWidthMismatch {
+                expected: DIGEST_ELEMS,
+                found: digest_elems as usize,
+            });
+        }
+        let (num_layers, mut rest) = take_u32(rest)?;
+        let mut digest_layers = Vec::new();
+        for _ in 0..num_layers {
+            let (num_digests, layer_bytes) = take_u64(rest)?;
+            let len = usize::try_from(num_digests)
+                .ok()
+                .and_then(|n| n.checked_mul(DIGEST_ELEMS * 8))
+                .filter(|&len| len <= layer_bytes.len())
+                .ok_or(DigestsError::Malformed)?;
+            let (layer_bytes, after) = layer_bytes.split_at(len);
+            let elems = decode_slice::<W>(layer_bytes).map_err(|_| DigestsError::Malformed)?;
+            let layer = elems
+                .chunks_exact(DIGEST_ELEMS)
+                .map(|digest| digest.try_into().unwrap())
+                .collect();
+            digest_layers.push(layer);
+            rest = after;
+        }
+        if !rest.is_empty() {
+            return Err(DigestsError::Malformed);
+        }
+        Ok(Self { digest_layers })
+    }
+}
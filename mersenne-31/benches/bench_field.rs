@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
-use p3_field::FieldAlgebra;
+use p3_field::{exp_u64_by_squaring, Field};
 use p3_field_testing::bench_func::{
     benchmark_add_latency, benchmark_add_throughput, benchmark_inv, benchmark_iter_sum,
     benchmark_sub_latency, benchmark_sub_throughput,
@@ -25,9 +25,19 @@ fn bench_field(c: &mut Criterion) {
     benchmark_sub_throughput::<F, REPS>(c, name);
 
     c.bench_function("5th_root", |b| {
+        b.iter_batched(rand::random::<F>, F::exp_inv_5, BatchSize::SmallInput)
+    });
+    c.bench_function("5th_root_by_squaring", |b| {
         b.iter_batched(
             rand::random::<F>,
-            |x| x.exp_u64(1717986917),
+            |x| exp_u64_by_squaring(x, F::EXP_INV_5),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("5th_root_packed", |b| {
+        b.iter_batched(
+            rand::random::<<F as Field>::Packing>,
+            F::exp_inv_5,
             BatchSize::SmallInput,
         )
     });
@@ -19,6 +19,12 @@ const P: uint32x4_t = unsafe { transmute::<[u32; WIDTH], _>([0x7fffffff; WIDTH])
 #[repr(transparent)] // This needed to make `transmute`s safe.
 pub struct PackedMersenne31Neon(pub [Mersenne31; WIDTH]);
 
+// Safety: `PackedMersenne31Neon` is `repr(transparent)` over an array of `Mersenne31`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for PackedMersenne31Neon {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for PackedMersenne31Neon {}
+
 impl PackedMersenne31Neon {
     #[inline]
     #[must_use]
@@ -239,4 +239,9 @@ mod tests {
 
         assert_eq!(c.values, conv);
     }
+
+    #[test]
+    fn complex_dft_matches_naive() {
+        p3_field_testing::test_all_transforms_match_naive::<Complex<Base>, Dft>();
+    }
 }
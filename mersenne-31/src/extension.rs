@@ -108,7 +108,7 @@ impl HasTwoAdicComplexBinomialExtension<3> for Mersenne31 {
 #[cfg(test)]
 mod test_cubic_extension {
     use p3_field::extension::{BinomialExtensionField, Complex};
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
 
     use crate::Mersenne31;
 
@@ -118,13 +118,14 @@ mod test_cubic_extension {
     test_field!(super::EF);
 
     test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
 }
 
 #[cfg(test)]
 mod test_quadratic_extension {
 
     use p3_field::extension::{BinomialExtensionField, Complex};
-    use p3_field_testing::{test_field, test_two_adic_extension_field};
+    use p3_field_testing::{test_extension_frobenius, test_field, test_two_adic_extension_field};
 
     use crate::Mersenne31;
 
@@ -134,4 +135,5 @@ mod test_quadratic_extension {
     test_field!(super::EF);
 
     test_two_adic_extension_field!(super::F, super::EF);
+    test_extension_frobenius!(super::F, super::EF);
 }
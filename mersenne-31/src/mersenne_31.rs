@@ -9,8 +9,8 @@ use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use num_bigint::BigUint;
 use p3_field::{
-    exp_1717986917, exp_u64_by_squaring, halve_u32, Field, FieldAlgebra, Packable, PrimeField,
-    PrimeField32, PrimeField64,
+    exp_1717986917, exp_u64_by_squaring, halve_u32, sample_uniform, Field, FieldAlgebra, Packable,
+    PrimeField, PrimeField32, PrimeField64,
 };
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
@@ -28,6 +28,23 @@ pub struct Mersenne31 {
     pub(crate) value: u32,
 }
 
+// Safety: `Mersenne31` is `repr(transparent)` over a `u32`. Words which do not fit in 31 bits are
+// memory-safe but give wrong results, which `FieldWord::is_canonical_word` rejects.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mersenne31 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Mersenne31 {}
+
+#[cfg(feature = "bytemuck")]
+impl p3_field::FieldWord for Mersenne31 {
+    type Word = u32;
+
+    #[inline]
+    fn is_canonical_word(word: u32) -> bool {
+        word < P
+    }
+}
+
 impl Mersenne31 {
     #[inline]
     pub const fn new(value: u32) -> Self {
@@ -53,6 +70,20 @@ impl Mersenne31 {
         }
         output
     }
+
+    /// The inverse of the quintic S-box exponent, `5^{-1} mod (P - 1)`.
+    pub const EXP_INV_5: u64 = 1717986917;
+
+    /// Compute `x^{1/5}`, the inverse of the quintic S-box `x -> x^5`.
+    ///
+    /// This is generic over `FieldAlgebra`, so it applies equally to packed Mersenne31 vectors.
+    /// It uses the addition chain in `exp_1717986917`, which costs 30 squarings and 7
+    /// multiplications, against roughly 30 squarings and 16 multiplications for square-and-multiply
+    /// (the exponent has Hamming weight 16). `exp_u64(Self::EXP_INV_5)` dispatches here too.
+    #[inline]
+    pub fn exp_inv_5<FA: FieldAlgebra<F = Self>>(x: FA) -> FA {
+        exp_1717986917(x)
+    }
 }
 
 impl PartialEq for Mersenne31 {
@@ -100,32 +131,21 @@ impl Debug for Mersenne31 {
 
 impl Distribution<Mersenne31> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Mersenne31 {
-        loop {
-            let next_u31 = rng.next_u32() >> 1;
-            let is_canonical = next_u31 != Mersenne31::ORDER_U32;
-            if is_canonical {
-                return Mersenne31::new(next_u31);
-            }
-        }
+        sample_uniform(rng)
     }
 }
 
 impl Serialize for Mersenne31 {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        // No need to convert to canonical.
-        serializer.serialize_u32(self.value)
+        serializer.serialize_u32(self.as_canonical_u32())
     }
 }
 
 impl<'a> Deserialize<'a> for Mersenne31 {
     fn deserialize<D: Deserializer<'a>>(d: D) -> Result<Self, D::Error> {
         let val = u32::deserialize(d)?;
-        // Ensure that `val` satisfies our invariant. i.e. Not necessarily canonical, but must fit in 31 bits.
-        if val <= P {
-            Ok(Mersenne31::new(val))
-        } else {
-            Err(D::Error::custom("Value is out of range"))
-        }
+        Self::from_canonical_checked(val.into())
+            .ok_or_else(|| D::Error::custom("Value is out of range"))
     }
 }
 
@@ -267,7 +287,7 @@ impl Field for Mersenne31 {
     #[inline]
     fn exp_u64_generic<FA: FieldAlgebra<F = Self>>(val: FA, power: u64) -> FA {
         match power {
-            1717986917 => exp_1717986917(val), // used in x^{1/5}
+            Self::EXP_INV_5 => Self::exp_inv_5(val),
             _ => exp_u64_by_squaring(val, power),
         }
     }
@@ -309,6 +329,19 @@ impl PrimeField for Mersenne31 {
     fn as_canonical_biguint(&self) -> BigUint {
         <Self as PrimeField32>::as_canonical_u32(self).into()
     }
+
+    #[inline]
+    fn from_canonical_checked(n: u64) -> Option<Self> {
+        if n < P as u64 {
+            Some(Self::new(n as u32))
+        } else {
+            None
+        }
+    }
+
+    fn from_canonical_biguint_checked(n: &BigUint) -> Option<Self> {
+        u64::try_from(n).ok().and_then(Self::from_canonical_checked)
+    }
 }
 
 impl PrimeField32 for Mersenne31 {
@@ -333,6 +366,18 @@ impl PrimeField64 for Mersenne31 {
     fn as_canonical_u64(&self) -> u64 {
         self.as_canonical_u32().into()
     }
+
+    #[inline]
+    fn sum_slice(xs: &[Self]) -> Self {
+        // Each value is below 2^31, so a u64 holds the sum of fewer than 2^33 of them. The sum of
+        // each chunk is reduced, and then the sum of those, so any slice of fewer than 2^30
+        // elements is reduced just once.
+        let sum: u64 = xs
+            .chunks(1 << 30)
+            .map(|chunk| chunk.iter().map(|x| x.value as u64).sum::<u64>() % P as u64)
+            .sum();
+        Self::new((sum % P as u64) as u32)
+    }
 }
 
 impl Add for Mersenne31 {
@@ -452,10 +497,14 @@ pub(crate) fn from_u62(input: u64) -> Mersenne31 {
     Mersenne31::new(input_lo) + Mersenne31::new(input_high)
 }
 
+#[cfg(all(test, feature = "bytemuck"))]
+p3_field_testing::test_field_bytemuck!(crate::Mersenne31, u32, 0x7FFF_FFFF);
+
 #[cfg(test)]
 mod tests {
-    use p3_field::{Field, FieldAlgebra, PrimeField32};
-    use p3_field_testing::test_field;
+    use p3_field::{exp_u64_by_squaring, Field, FieldAlgebra, PackedValue, PrimeField32};
+    use p3_field_testing::{test_field, test_prime_field_32};
+    use rand::Rng;
 
     use crate::Mersenne31;
 
@@ -510,5 +559,32 @@ mod tests {
         assert_eq!(F::TWO.exp_u64(1717986917).exp_const_u64::<5>(), F::TWO);
     }
 
+    #[test]
+    fn exp_inv_5() {
+        let mut rng = rand::thread_rng();
+        let edges = [F::ZERO, F::ONE, F::NEG_ONE];
+        let randoms: [F; 100] = core::array::from_fn(|_| rng.gen());
+        for x in edges.into_iter().chain(randoms) {
+            assert_eq!(F::exp_inv_5(x.exp_const_u64::<5>()), x);
+            assert_eq!(F::exp_inv_5(x).exp_const_u64::<5>(), x);
+            assert_eq!(F::exp_inv_5(x), exp_u64_by_squaring(x, F::EXP_INV_5));
+        }
+    }
+
+    #[test]
+    fn exp_inv_5_packed() {
+        type P = <F as Field>::Packing;
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let x: P = rng.gen();
+            let y = F::exp_inv_5(x);
+            for (&xi, &yi) in x.as_slice().iter().zip(y.as_slice()) {
+                assert_eq!(yi, F::exp_inv_5(xi));
+            }
+            assert_eq!(F::exp_inv_5(x.exp_const_u64::<5>()), x);
+        }
+    }
+
     test_field!(crate::Mersenne31);
+    test_prime_field_32!(crate::Mersenne31);
 }
@@ -15,11 +15,11 @@
 
 use core::ops::Mul;
 
-use p3_field::{Field, FieldAlgebra};
+use p3_field::{Field, FieldAlgebra, PrimeField32};
 use p3_poseidon2::{
     add_rc_and_sbox_generic, external_initial_permute_state, external_terminal_permute_state,
-    internal_permute_state, ExternalLayer, GenericPoseidon2LinearLayers, InternalLayer, MDSMat4,
-    Poseidon2,
+    internal_permute_state, ExternalLayer, ExternalLayerConstants, GenericPoseidon2LinearLayers,
+    InternalLayer, MDSMat4, Poseidon2,
 };
 
 use crate::{
@@ -33,6 +33,10 @@ use crate::{
 /// this should no longer be public.
 pub(crate) const MERSENNE31_S_BOX_DEGREE: u64 = 5;
 
+// The S-box `x -> x^5` is a permutation iff gcd(5, p - 1) = 1. As 5 is prime that means 5 must not
+// divide p - 1, which we check at compile time.
+const _: () = assert!((Mersenne31::ORDER_U32 - 1) as u64 % MERSENNE31_S_BOX_DEGREE != 0);
+
 /// An implementation of the Poseidon2 hash function specialised to run on the current architecture.
 ///
 /// It acts on arrays of the form either `[Mersenne31::Packing; WIDTH]` or `[Mersenne31; WIDTH]`. For speed purposes,
@@ -172,10 +176,85 @@ where
     }
 }
 
+/// Create a width 16 Poseidon2 permutation on Mersenne31 with fixed round constants.
+///
+/// These are the constants `Poseidon2Mersenne31::<16>::new_from_rng_128` samples from
+/// `Xoroshiro128Plus::seed_from_u64(1)`, as described on `Poseidon2::new_from_rng`.
+pub fn default_mersenne31_poseidon2_16() -> Poseidon2Mersenne31<16> {
+    Poseidon2::new(
+        ExternalLayerConstants::new(
+            MERSENNE31_RC16_EXTERNAL_INITIAL.to_vec(),
+            MERSENNE31_RC16_EXTERNAL_FINAL.to_vec(),
+        ),
+        MERSENNE31_RC16_INTERNAL.to_vec(),
+    )
+}
+
+/// Initial external round constants for the width 16 Poseidon2 permutation on Mersenne31.
+///
+/// See [`default_mersenne31_poseidon2_16`] for how these were generated.
+pub const MERSENNE31_RC16_EXTERNAL_INITIAL: [[Mersenne31; 16]; 4] = [
+    Mersenne31::new_array([
+        0x27faddc6, 0x7a5db1b1, 0x33b673a5, 0x42d2f10a, 0x673f8501, 0x69f3172a, 0x3a1aacc7,
+        0x331eb8f1, 0x59f4d770, 0x37a6ab46, 0x12670d59, 0x5fc1f45b, 0x5330d05e, 0x4a71d722,
+        0x2bb9509f, 0x603fd075,
+    ]),
+    Mersenne31::new_array([
+        0x56e28c10, 0x1292583d, 0x7a86e245, 0x676cdcc6, 0x5d35299e, 0x155d1779, 0x24adc6d8,
+        0x3cf871aa, 0x680eec9e, 0x56871ac5, 0x3c37e068, 0x1c843775, 0x3a19ec82, 0x31dd09f8,
+        0x2104cdea, 0x1524a31c,
+    ]),
+    Mersenne31::new_array([
+        0x418c75fd, 0x3910d1e5, 0x45cd2ac2, 0x4e56be1e, 0x5de561e7, 0x1e7a90c3, 0x69efcab2,
+        0x2c5564be, 0x30fb3400, 0x15c59742, 0x7d2e8ad3, 0x5ffef99a, 0x6a33b2f1, 0x61e641c2,
+        0x39b4da8e, 0x066c5840,
+    ]),
+    Mersenne31::new_array([
+        0x12203c68, 0x3e838218, 0x27f09a5c, 0x6cc75f6a, 0x29aecefc, 0x163b0f26, 0x504af1b3,
+        0x3ca3d195, 0x2974d608, 0x58e07d2e, 0x62b933c5, 0x37b07761, 0x5baccad0, 0x42d55017,
+        0x4c60fe28, 0x5fea04a0,
+    ]),
+];
+
+/// Terminal external round constants for the width 16 Poseidon2 permutation on Mersenne31.
+///
+/// See [`default_mersenne31_poseidon2_16`] for how these were generated.
+pub const MERSENNE31_RC16_EXTERNAL_FINAL: [[Mersenne31; 16]; 4] = [
+    Mersenne31::new_array([
+        0x120a3490, 0x7a7e3f52, 0x00f5b66b, 0x2fcdcdb2, 0x15668866, 0x58e539c9, 0x090b66f8,
+        0x0751a868, 0x2fd9e62c, 0x7fc3f22f, 0x786ab79e, 0x1a0eb734, 0x5ff7116d, 0x488bf048,
+        0x6d43f9f6, 0x7f47ab30,
+    ]),
+    Mersenne31::new_array([
+        0x039ea33d, 0x0a542c0d, 0x5e2ef77b, 0x7aacdfb7, 0x6943cd03, 0x1b59071f, 0x591f2926,
+        0x2981b81b, 0x15e41d00, 0x00456d49, 0x2ad73822, 0x73dd7653, 0x5b96641b, 0x5dde21db,
+        0x6770c76f, 0x328c0f18,
+    ]),
+    Mersenne31::new_array([
+        0x64b56ddf, 0x0a5221bc, 0x197aacc7, 0x3bda7671, 0x7bd2207a, 0x2ea61c53, 0x67fcb669,
+        0x40877690, 0x11bf83f2, 0x452be67d, 0x24e4e154, 0x64983472, 0x0237cbba, 0x7e34a5c5,
+        0x6ab6443f, 0x4688f18b,
+    ]),
+    Mersenne31::new_array([
+        0x208137a6, 0x494437fb, 0x2b1ab68e, 0x72e7ab1c, 0x406b3331, 0x716fc541, 0x72fef186,
+        0x05b5d20e, 0x260278f7, 0x48615db2, 0x66a58fa8, 0x6caabb2b, 0x37339907, 0x0c8f5079,
+        0x635ff76c, 0x29073284,
+    ]),
+];
+
+/// Internal round constants for the width 16 Poseidon2 permutation on Mersenne31.
+///
+/// See [`default_mersenne31_poseidon2_16`] for how these were generated.
+pub const MERSENNE31_RC16_INTERNAL: [Mersenne31; 14] = Mersenne31::new_array([
+    0x07b0c0ef, 0x4c6facb1, 0x7779de32, 0x1feeb7c0, 0x765b150d, 0x3567db60, 0x421316ea, 0x2e4e7398,
+    0x420e7c17, 0x223b99a5, 0x6cc8f5ac, 0x47384772, 0x593b6ad3, 0x633abab2,
+]);
+
 #[cfg(test)]
 mod tests {
+    use p3_field::PackedValue;
     use p3_symmetric::Permutation;
-    use rand::SeedableRng;
+    use rand::{Rng, SeedableRng};
     use rand_xoshiro::Xoroshiro128Plus;
 
     use super::*;
@@ -191,7 +270,7 @@ mod tests {
     /// vector([M31.random_element() for t in range(16)]).
     #[test]
     fn test_poseidon2_width_16_random() {
-        let mut input: [F; 16] = Mersenne31::new_array([
+        let input: [F; 16] = Mersenne31::new_array([
             894848333, 1437655012, 1200606629, 1690012884, 71131202, 1749206695, 1717947831,
             120589055, 19776022, 42382981, 1831865506, 724844064, 171220207, 1299207443, 227047920,
             1783754913,
@@ -205,9 +284,10 @@ mod tests {
 
         let mut rng = Xoroshiro128Plus::seed_from_u64(1);
         let perm = Poseidon2Mersenne31::new_from_rng_128(&mut rng);
+        assert_eq!(perm.permute(input), expected);
 
-        perm.permute_mut(&mut input);
-        assert_eq!(input, expected);
+        // The default constants were sampled the same way, so give the same output.
+        assert_eq!(default_mersenne31_poseidon2_16().permute(input), expected);
     }
 
     /// Test on a roughly random input.
@@ -236,4 +316,19 @@ mod tests {
         perm.permute_mut(&mut input);
         assert_eq!(input, expected);
     }
+
+    /// Test the packed permutation against the scalar one, lane by lane.
+    #[test]
+    fn test_default_poseidon2_width_16_packed() {
+        type P = <F as Field>::Packing;
+
+        let perm = default_mersenne31_poseidon2_16();
+        let input: [P; 16] = rand::thread_rng().gen();
+        let output = perm.permute(input);
+
+        for lane in 0..P::WIDTH {
+            let expected = perm.permute(input.map(|x| x.as_slice()[lane]));
+            assert_eq!(output.map(|x| x.as_slice()[lane]), expected);
+        }
+    }
 }
@@ -20,6 +20,9 @@ impl TwoAdicSubgroupDft<C> for Mersenne31ComplexRadix2Dit {
     type Evaluations = RowMajorMatrix<C>;
     fn dft_batch(&self, mut mat: RowMajorMatrix<C>) -> RowMajorMatrix<C> {
         let h = mat.height();
+        if h == 0 {
+            return mat;
+        }
         let log_h = log2_strict_usize(h);
 
         let root = C::two_adic_generator(log_h);
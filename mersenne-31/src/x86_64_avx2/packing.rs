@@ -4,7 +4,9 @@ use core::iter::{Product, Sum};
 use core::mem::transmute;
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use p3_field::{Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue};
+use p3_field::{
+    exp_const_u64_by_chain, Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue,
+};
 use p3_util::convert_vec;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
@@ -19,6 +21,12 @@ pub(crate) const P: __m256i = unsafe { transmute::<[u32; WIDTH], _>([0x7fffffff;
 #[repr(transparent)] // This needed to make `transmute`s safe.
 pub struct PackedMersenne31AVX2(pub [Mersenne31; WIDTH]);
 
+// Safety: `PackedMersenne31AVX2` is `repr(transparent)` over an array of `Mersenne31`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for PackedMersenne31AVX2 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for PackedMersenne31AVX2 {}
+
 impl PackedMersenne31AVX2 {
     #[inline]
     #[must_use]
@@ -462,7 +470,7 @@ impl FieldAlgebra for PackedMersenne31AVX2 {
                 let x4 = x2.square();
                 x3 * x4
             }
-            _ => self.exp_u64(POWER),
+            _ => exp_const_u64_by_chain::<POWER, Self>(self),
         }
     }
 
@@ -4,7 +4,9 @@ use core::iter::{Product, Sum};
 use core::mem::transmute;
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use p3_field::{Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue};
+use p3_field::{
+    exp_const_u64_by_chain, Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue,
+};
 use p3_util::convert_vec;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
@@ -22,6 +24,12 @@ const EVENS4: __mmask16 = 0x0f0f;
 #[repr(transparent)] // This needed to make `transmute`s safe.
 pub struct PackedMersenne31AVX512(pub [Mersenne31; WIDTH]);
 
+// Safety: `PackedMersenne31AVX512` is `repr(transparent)` over an array of `Mersenne31`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for PackedMersenne31AVX512 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for PackedMersenne31AVX512 {}
+
 impl PackedMersenne31AVX512 {
     #[inline]
     #[must_use]
@@ -488,7 +496,7 @@ impl FieldAlgebra for PackedMersenne31AVX512 {
                 let x4 = x2.square();
                 x3 * x4
             }
-            _ => self.exp_u64(POWER),
+            _ => exp_const_u64_by_chain::<POWER, Self>(self),
         }
     }
 }
@@ -841,7 +849,7 @@ unsafe impl PackedFieldPow2 for PackedMersenne31AVX512 {
 
 #[cfg(test)]
 mod tests {
-    use p3_field_testing::test_packed_field;
+    use p3_field_testing::{test_packed_edge_operands, test_packed_field};
 
     use super::{Mersenne31, WIDTH};
 
@@ -863,4 +871,16 @@ mod tests {
         crate::PackedMersenne31AVX512(super::ZEROS),
         crate::PackedMersenne31AVX512(super::SPECIAL_VALS)
     );
+
+    /// Operands around zero, one half and the top of the field. Sums and differences of these
+    /// hit both vector representations of zero, `0` and `P`.
+    const EDGE_VALS: [Mersenne31; 10] = Mersenne31::new_array([
+        0x00000000, 0x00000001, 0x00000002, 0x0000ffff, 0x3fffffff, 0x40000000, 0x40000001,
+        0x7ffffffc, 0x7ffffffd, 0x7ffffffe,
+    ]);
+
+    #[test]
+    fn test_edge_operands() {
+        test_packed_edge_operands::<crate::PackedMersenne31AVX512>(&EDGE_VALS);
+    }
 }
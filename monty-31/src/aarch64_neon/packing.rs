@@ -25,6 +25,12 @@ pub trait MontyParametersNeon {
 #[repr(transparent)] // This needed to make `transmute`s safe.
 pub struct PackedMontyField31Neon<PMP: PackedMontyParameters>(pub [MontyField31<PMP>; WIDTH]);
 
+// Safety: `PackedMontyField31Neon` is `repr(transparent)` over an array of `MontyField31`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl<PMP: PackedMontyParameters> bytemuck::Zeroable for PackedMontyField31Neon<PMP> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl<PMP: PackedMontyParameters> bytemuck::Pod for PackedMontyField31Neon<PMP> {}
+
 impl<PMP: PackedMontyParameters> PackedMontyField31Neon<PMP> {
     #[inline]
     #[must_use]
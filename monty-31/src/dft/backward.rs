@@ -109,7 +109,11 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
     }
 
     #[inline]
-    fn backward_iterative_packed_radix_16(input: &mut [<Self as Field>::Packing]) {
+    fn backward_iterative_packed_radix_16(
+        input: &mut [<Self as Field>::Packing],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         // Rather surprisingly, a version similar where the separate
         // loops in each call to backward_iterative_packed() are
         // combined into one, was not only not faster, but was
@@ -119,7 +123,7 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
         backward_iterative_packed_radix_2(input);
 
         // Radix 4
-        let roots4 = [MP::INV_ROOTS_8.as_ref()[0], MP::INV_ROOTS_8.as_ref()[2]];
+        let roots4 = [roots_8[0], roots_8[2]];
         if <Self as Field>::Packing::WIDTH >= 4 {
             backward_iterative_packed::<2, _>(input, &roots4);
         } else {
@@ -128,20 +132,25 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
 
         // Radix 8
         if <Self as Field>::Packing::WIDTH >= 8 {
-            backward_iterative_packed::<4, _>(input, MP::INV_ROOTS_8.as_ref());
+            backward_iterative_packed::<4, _>(input, roots_8);
         } else {
-            Self::backward_iterative_layer(input, MP::INV_ROOTS_8.as_ref(), 4);
+            Self::backward_iterative_layer(input, roots_8, 4);
         }
 
         // Radix 16
         if <Self as Field>::Packing::WIDTH >= 16 {
-            backward_iterative_packed::<8, _>(input, MP::INV_ROOTS_16.as_ref());
+            backward_iterative_packed::<8, _>(input, roots_16);
         } else {
-            Self::backward_iterative_layer(input, MP::INV_ROOTS_16.as_ref(), 8);
+            Self::backward_iterative_layer(input, roots_16, 8);
         }
     }
 
-    fn backward_iterative(packed_input: &mut [<Self as Field>::Packing], root_table: &[Vec<Self>]) {
+    fn backward_iterative(
+        packed_input: &mut [<Self as Field>::Packing],
+        root_table: &[Vec<Self>],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         assert!(packed_input.len() >= 2);
         let packing_width = <Self as Field>::Packing::WIDTH;
         let n = packed_input.len() * packing_width;
@@ -159,7 +168,7 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
         // with the radix-16 specialisation at the end of the loop
         assert!(lg_n >= FIRST_LOOP_LAYER + NUM_SPECIALISATIONS);
 
-        Self::backward_iterative_packed_radix_16(packed_input);
+        Self::backward_iterative_packed_radix_16(packed_input, roots_8, roots_16);
 
         for lg_m in FIRST_LOOP_LAYER..(lg_n - NUM_SPECIALISATIONS) {
             let s = lg_n - lg_m - 1;
@@ -204,7 +213,7 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
     }
 
     #[inline(always)]
-    fn backward_4(a: &mut [Self]) {
+    fn backward_4(a: &mut [Self], roots_8: &[Self]) {
         assert_eq!(a.len(), 4);
 
         // Read in bit-reversed order
@@ -215,9 +224,7 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
 
         // Expanding the calculation of t3 saves one instruction
         let t1 = MP::PRIME + a1.value - a3.value;
-        let t3 = MontyField31::new_monty(monty_reduce::<MP>(
-            t1 as u64 * MP::INV_ROOTS_8.as_ref()[2].value as u64,
-        ));
+        let t3 = MontyField31::new_monty(monty_reduce::<MP>(t1 as u64 * roots_8[2].value as u64));
         let t5 = a1 + a3;
         let t4 = a0 + a2;
         let t2 = a0 - a2;
@@ -229,37 +236,37 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
     }
 
     #[inline(always)]
-    fn backward_8(a: &mut [Self]) {
+    fn backward_8(a: &mut [Self], roots_8: &[Self]) {
         assert_eq!(a.len(), 8);
 
         // Safe because a.len() == 8
         let (a0, a1) = unsafe { a.split_at_mut_unchecked(a.len() / 2) };
-        Self::backward_4(a0);
-        Self::backward_4(a1);
+        Self::backward_4(a0, roots_8);
+        Self::backward_4(a1, roots_8);
 
-        Self::backward_pass(a, MP::INV_ROOTS_8.as_ref());
+        Self::backward_pass(a, roots_8);
     }
 
     #[inline(always)]
-    fn backward_16(a: &mut [Self]) {
+    fn backward_16(a: &mut [Self], roots_8: &[Self], roots_16: &[Self]) {
         assert_eq!(a.len(), 16);
 
         // Safe because a.len() == 16
         let (a0, a1) = unsafe { a.split_at_mut_unchecked(a.len() / 2) };
-        Self::backward_8(a0);
-        Self::backward_8(a1);
+        Self::backward_8(a0, roots_8);
+        Self::backward_8(a1, roots_8);
 
-        Self::backward_pass(a, MP::INV_ROOTS_16.as_ref());
+        Self::backward_pass(a, roots_16);
     }
 
     #[inline(always)]
-    fn backward_32(a: &mut [Self], root_table: &[Vec<Self>]) {
+    fn backward_32(a: &mut [Self], root_table: &[Vec<Self>], roots_8: &[Self], roots_16: &[Self]) {
         assert_eq!(a.len(), 32);
 
         // Safe because a.len() == 32
         let (a0, a1) = unsafe { a.split_at_mut_unchecked(a.len() / 2) };
-        Self::backward_16(a0);
-        Self::backward_16(a1);
+        Self::backward_16(a0, roots_8, roots_16);
+        Self::backward_16(a1, roots_8, roots_16);
 
         Self::backward_pass(a, &root_table[0]);
     }
@@ -267,19 +274,24 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
     /// Assumes `input.len() >= 64`.
     /// current packing widths.
     #[inline]
-    fn backward_fft_recur(input: &mut [<Self as Field>::Packing], root_table: &[Vec<Self>]) {
+    fn backward_fft_recur(
+        input: &mut [<Self as Field>::Packing],
+        root_table: &[Vec<Self>],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         const ITERATIVE_FFT_THRESHOLD: usize = 1024;
 
         let n = input.len() * <Self as Field>::Packing::WIDTH;
         if n <= ITERATIVE_FFT_THRESHOLD {
-            Self::backward_iterative(input, root_table);
+            Self::backward_iterative(input, root_table, roots_8, roots_16);
         } else {
             assert_eq!(n, 1 << (root_table.len() + 1));
 
             // Safe because input.len() > ITERATIVE_FFT_THRESHOLD
             let (a0, a1) = unsafe { input.split_at_mut_unchecked(input.len() / 2) };
-            Self::backward_fft_recur(a0, &root_table[1..]);
-            Self::backward_fft_recur(a1, &root_table[1..]);
+            Self::backward_fft_recur(a0, &root_table[1..], roots_8, roots_16);
+            Self::backward_fft_recur(a1, &root_table[1..], roots_8, roots_16);
 
             backward_pass_packed(input, &root_table[0]);
         }
@@ -287,6 +299,38 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
 
     #[inline]
     pub fn backward_fft(input: &mut [Self], root_table: &[Vec<Self>]) {
+        Self::backward_fft_with_roots(
+            input,
+            root_table,
+            MP::INV_ROOTS_8.as_ref(),
+            MP::INV_ROOTS_16.as_ref(),
+        );
+    }
+
+    /// As `backward_fft`, but with the forward roots of unity, as in the table of twiddles kept
+    /// by `RecursiveDft`.
+    ///
+    /// This computes the DFT of an input in bit-reversed order, leaving its output in natural
+    /// order, i.e. it is the Bowers G network.
+    #[inline]
+    pub(crate) fn backward_fft_forward_roots(input: &mut [Self], root_table: &[Vec<Self>]) {
+        Self::backward_fft_with_roots(
+            input,
+            root_table,
+            MP::ROOTS_8.as_ref(),
+            MP::ROOTS_16.as_ref(),
+        );
+    }
+
+    /// A DIT FFT using `roots_8`, `roots_16` for the first four layers and the roots in
+    /// `root_table` for the rest, which must be powers of the same roots of unity.
+    #[inline]
+    fn backward_fft_with_roots(
+        input: &mut [Self],
+        root_table: &[Vec<Self>],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         let n = input.len();
         if n == 1 {
             return;
@@ -294,14 +338,14 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
 
         assert_eq!(n, 1 << (root_table.len() + 1));
         match n {
-            32 => Self::backward_32(input, root_table),
-            16 => Self::backward_16(input),
-            8 => Self::backward_8(input),
-            4 => Self::backward_4(input),
+            32 => Self::backward_32(input, root_table, roots_8, roots_16),
+            16 => Self::backward_16(input, roots_8, roots_16),
+            8 => Self::backward_8(input, roots_8),
+            4 => Self::backward_4(input, roots_8),
             2 => Self::backward_2(input),
             _ => {
                 let packed_input = <Self as Field>::Packing::pack_slice_mut(input);
-                Self::backward_fft_recur(packed_input, root_table)
+                Self::backward_fft_recur(packed_input, root_table, roots_8, roots_16)
             }
         }
     }
@@ -0,0 +1,229 @@
+//! The Bowers G and G^T networks for `MontyField31`, built from the kernels of `RecursiveDft`.
+
+use alloc::vec::Vec;
+
+use p3_dft::TwoAdicSubgroupDft;
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::util::reverse_matrix_index_bits;
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::*;
+use p3_util::{log2_strict_usize, reverse_slice_index_bits};
+use tracing::{debug_span, instrument};
+
+use super::RecursiveDft;
+use crate::{FieldParameters, MontyField31, MontyParameters, TwoAdicData};
+
+/// A transform of a single row, given the twiddles for rows of its length.
+type Network<MP> = fn(&mut [MontyField31<MP>], &[Vec<MontyField31<MP>>]);
+
+/// Bowers DFT, using the packed butterflies of `RecursiveDft`.
+///
+/// The decimation-in-frequency kernel run with inverse roots of unity is the Bowers G^T network,
+/// which takes evaluations in natural order to (unscaled) coefficients in bit-reversed order, and
+/// the decimation-in-time kernel run with the forward roots is the Bowers G network, which takes
+/// coefficients in bit-reversed order back to evaluations in natural order. Zero padding in
+/// bit-reversed order just spreads the coefficients out, so a (coset) LDE
+///
+///   - G^T on the input
+///   - coset shift, rescale and zero pad in bit-reversed order
+///   - G on the result
+///
+/// needs no bit-reversal passes at all, and unlike `RecursiveDft` its output is in natural order.
+/// As with `RecursiveDft`, the columns are transposed into rows and back around the transforms.
+///
+/// Plain DFTs and inverse DFTs still need one bit reversal each, as with `Radix2Bowers`.
+#[derive(Clone, Debug, Default)]
+pub struct RecursiveBowers<F> {
+    /// Holds the memoized twiddle factors.
+    dft: RecursiveDft<F>,
+}
+
+impl<MP: FieldParameters + TwoAdicData> RecursiveBowers<MontyField31<MP>> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            dft: RecursiveDft::new(n),
+        }
+    }
+
+    /// Apply `network` to each row of `mat`, whose rows have length `ncols`.
+    #[inline]
+    fn apply_to_rows(
+        mat: &mut [MontyField31<MP>],
+        ncols: usize,
+        twiddles: &[Vec<MontyField31<MP>>],
+        network: Network<MP>,
+    ) {
+        if ncols > 1 {
+            let lg_fft_len = log2_strict_usize(ncols);
+            let roots_idx = (twiddles.len() + 1) - lg_fft_len;
+            let twiddles = &twiddles[roots_idx..];
+
+            mat.par_chunks_exact_mut(ncols)
+                .for_each(|v| network(v, twiddles));
+        }
+    }
+
+    /// Transpose `mat`, apply `network` to each of its columns and transpose back.
+    fn apply_to_columns(
+        mat: &mut RowMajorMatrix<MontyField31<MP>>,
+        twiddles: &[Vec<MontyField31<MP>>],
+        network: Network<MP>,
+    ) {
+        let nrows = mat.height();
+        let ncols = mat.width();
+
+        let mut scratch = debug_span!("allocate scratch space")
+            .in_scope(|| MontyField31::<MP>::zero_vec(nrows * ncols));
+
+        debug_span!("pre-transpose", nrows, ncols)
+            .in_scope(|| transpose::transpose(&mat.values, &mut scratch, ncols, nrows));
+
+        debug_span!("network", n_dfts = ncols, fft_len = nrows)
+            .in_scope(|| Self::apply_to_rows(&mut scratch, nrows, twiddles, network));
+
+        debug_span!("post-transpose", nrows = ncols, ncols = nrows)
+            .in_scope(|| transpose::transpose(&scratch, &mut mat.values, nrows, ncols));
+    }
+}
+
+impl<MP: MontyParameters + FieldParameters + TwoAdicData> TwoAdicSubgroupDft<MontyField31<MP>>
+    for RecursiveBowers<MontyField31<MP>>
+{
+    type Evaluations = RowMajorMatrix<MontyField31<MP>>;
+
+    #[instrument(skip_all, fields(dims = %mat.dimensions()))]
+    fn dft_batch(&self, mut mat: RowMajorMatrix<MontyField31<MP>>) -> Self::Evaluations {
+        let nrows = mat.height();
+        if nrows <= 1 {
+            return mat;
+        }
+
+        // G takes its input in bit-reversed order.
+        debug_span!("initial bitrev").in_scope(|| reverse_matrix_index_bits(&mut mat));
+
+        self.dft.update_twiddles(nrows);
+        let twiddles = self.dft.twiddles.borrow();
+        Self::apply_to_columns(
+            &mut mat,
+            &twiddles,
+            MontyField31::backward_fft_forward_roots,
+        );
+        mat
+    }
+
+    #[instrument(skip_all, fields(dims = %mat.dimensions()))]
+    fn idft_batch(
+        &self,
+        mut mat: RowMajorMatrix<MontyField31<MP>>,
+    ) -> RowMajorMatrix<MontyField31<MP>> {
+        let nrows = mat.height();
+        if nrows <= 1 {
+            return mat;
+        }
+
+        self.dft.update_twiddles(nrows);
+        let inv_twiddles = self.dft.inv_twiddles.borrow();
+        Self::apply_to_columns(
+            &mut mat,
+            &inv_twiddles,
+            MontyField31::forward_fft_inverse_roots,
+        );
+
+        // G^T leaves its output in bit-reversed order.
+        debug_span!("final bitrev").in_scope(|| reverse_matrix_index_bits(&mut mat));
+
+        let inv_len = MontyField31::from_canonical_usize(nrows).inverse();
+        debug_span!("scale").in_scope(|| mat.scale(inv_len));
+        mat
+    }
+
+    fn lde_batch(
+        &self,
+        mat: RowMajorMatrix<MontyField31<MP>>,
+        added_bits: usize,
+    ) -> Self::Evaluations {
+        self.coset_lde_batch(mat, added_bits, MontyField31::ONE)
+    }
+
+    #[instrument(skip_all, fields(dims = %mat.dimensions(), added_bits))]
+    fn coset_lde_batch(
+        &self,
+        mat: RowMajorMatrix<MontyField31<MP>>,
+        added_bits: usize,
+        shift: MontyField31<MP>,
+    ) -> Self::Evaluations {
+        let nrows = mat.height();
+        let ncols = mat.width();
+        let result_nrows = nrows << added_bits;
+
+        if nrows <= 1 {
+            let dupd_rows = core::iter::repeat(mat.values)
+                .take(result_nrows)
+                .flatten()
+                .collect();
+            return RowMajorMatrix::new(dupd_rows, ncols);
+        }
+
+        let input_size = nrows * ncols;
+        let output_size = result_nrows * ncols;
+
+        let (mut output, mut padded) = debug_span!("allocate scratch space").in_scope(|| {
+            let output = MontyField31::<MP>::zero_vec(output_size);
+            let padded = MontyField31::<MP>::zero_vec(output_size);
+            (output, padded)
+        });
+
+        // `coeffs` will hold the result of G^T; use the output storage as scratch space.
+        let coeffs = &mut output[..input_size];
+
+        debug_span!("pre-transpose", nrows, ncols)
+            .in_scope(|| transpose::transpose(&mat.values, coeffs, ncols, nrows));
+
+        self.dft.update_twiddles(result_nrows);
+        let inv_twiddles = self.dft.inv_twiddles.borrow();
+        debug_span!("G^T", n_dfts = ncols, fft_len = nrows).in_scope(|| {
+            Self::apply_to_rows(
+                coeffs,
+                nrows,
+                &inv_twiddles,
+                MontyField31::forward_fft_inverse_roots,
+            )
+        });
+
+        // Each row of `coeffs` now holds the unnormalised coefficients of a column of `mat`, in
+        // bit-reversed order. Normalise and coset shift them in one go, and zero pad in
+        // bit-reversed order, which puts the coefficient at index `i` of a row of length `nrows`
+        // at index `i << added_bits` of a row of length `result_nrows`.
+        let inv_len = MontyField31::from_canonical_usize(nrows).inverse();
+        let mut weights: Vec<_> = shift.shifted_powers(inv_len).take(nrows).collect();
+        reverse_slice_index_bits(&mut weights);
+        debug_span!("shift, scale and pad").in_scope(|| {
+            padded
+                .par_chunks_exact_mut(result_nrows)
+                .zip(coeffs.par_chunks_exact(nrows))
+                .for_each(|(out_row, in_row)| {
+                    out_row
+                        .iter_mut()
+                        .step_by(1 << added_bits)
+                        .zip(in_row.iter().zip(&weights))
+                        .for_each(|(out, (&coeff, &weight))| *out = coeff * weight);
+                });
+        });
+
+        let twiddles = self.dft.twiddles.borrow();
+        debug_span!("G", n_dfts = ncols, fft_len = result_nrows).in_scope(|| {
+            Self::apply_to_rows(
+                &mut padded,
+                result_nrows,
+                &twiddles,
+                MontyField31::backward_fft_forward_roots,
+            )
+        });
+
+        debug_span!("post-transpose", nrows = ncols, ncols = result_nrows)
+            .in_scope(|| transpose::transpose(&padded, &mut output, result_nrows, ncols));
+
+        RowMajorMatrix::new(output, ncols)
+    }
+}
@@ -0,0 +1,245 @@
+//! A DFT which chooses between the available backends at runtime.
+
+use core::ops::Deref;
+
+use p3_dft::{Radix2Bowers, Radix2Dit, Radix2DitParallel, TwoAdicSubgroupDft};
+use p3_field::TwoAdicField;
+use p3_matrix::bitrev::{BitReversableMatrix, BitReversedMatrixView};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::stack::EitherRow;
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::current_num_threads;
+
+use super::{RecursiveBowers, RecursiveDft};
+use crate::{FieldParameters, MontyField31, MontyParameters, TwoAdicData};
+
+/// Matrices shorter than this are routed to `Radix2Dit` by the default policy.
+const SMALL_HEIGHT: usize = 1 << 6;
+
+/// One of the backends a `DftDispatcher` can route a transform to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DftRoute {
+    Radix2Dit,
+    Radix2DitParallel,
+    Radix2Bowers,
+    Recursive,
+    RecursiveBowers,
+}
+
+/// A policy for `DftDispatcher`, choosing a backend from the height and width of the input.
+pub type DftPolicy = fn(usize, usize) -> DftRoute;
+
+/// The default policy of `DftDispatcher`.
+///
+/// `RecursiveDft` transposes its input so that it can run packed butterflies along each column,
+/// which only pays off once the columns are long enough, so short matrices go to `Radix2Dit`. It
+/// also only splits the columns between threads, so matrices with fewer columns than there are
+/// threads go to `Radix2DitParallel`, which splits each column between them instead.
+/// This is a simple heuristic rather than a tuned one; for a particular workload, benchmark the
+/// routes and pass a policy of your own.
+pub fn default_dft_policy(height: usize, width: usize) -> DftRoute {
+    if height < SMALL_HEIGHT {
+        DftRoute::Radix2Dit
+    } else if width < current_num_threads() {
+        DftRoute::Radix2DitParallel
+    } else {
+        DftRoute::Recursive
+    }
+}
+
+/// The evaluations returned by a `DftDispatcher`, in whichever order the backend it routed to
+/// returned them: natural order for `Radix2Dit`, `Radix2Bowers` and `RecursiveBowers`, and a
+/// bit-reversed view for `Radix2DitParallel` and `RecursiveDft`. Either way, no rows are moved.
+#[derive(Clone, Debug)]
+pub enum DftEvaluations<T> {
+    NaturalOrder(RowMajorMatrix<T>),
+    BitReversed(BitReversedMatrixView<RowMajorMatrix<T>>),
+}
+
+impl<T: Clone + Send + Sync> Matrix<T> for DftEvaluations<T> {
+    fn width(&self) -> usize {
+        match self {
+            Self::NaturalOrder(mat) => mat.width(),
+            Self::BitReversed(view) => view.width(),
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Self::NaturalOrder(mat) => mat.height(),
+            Self::BitReversed(view) => view.height(),
+        }
+    }
+
+    fn get(&self, r: usize, c: usize) -> T {
+        match self {
+            Self::NaturalOrder(mat) => mat.get(r, c),
+            Self::BitReversed(view) => view.get(r, c),
+        }
+    }
+
+    type Row<'a>
+        = EitherRow<
+        <RowMajorMatrix<T> as Matrix<T>>::Row<'a>,
+        <BitReversedMatrixView<RowMajorMatrix<T>> as Matrix<T>>::Row<'a>,
+    >
+    where
+        Self: 'a;
+
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        match self {
+            Self::NaturalOrder(mat) => EitherRow::Left(mat.row(r)),
+            Self::BitReversed(view) => EitherRow::Right(view.row(r)),
+        }
+    }
+
+    fn row_slice(&self, r: usize) -> impl Deref<Target = [T]> {
+        match self {
+            Self::NaturalOrder(mat) => EitherRow::Left(mat.row_slice(r)),
+            Self::BitReversed(view) => EitherRow::Right(view.row_slice(r)),
+        }
+    }
+
+    fn to_row_major_matrix(self) -> RowMajorMatrix<T>
+    where
+        Self: Sized,
+        T: Clone,
+    {
+        match self {
+            Self::NaturalOrder(mat) => mat,
+            Self::BitReversed(view) => view.to_row_major_matrix(),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync> BitReversableMatrix<T> for DftEvaluations<T> {
+    type BitRev = Self;
+
+    fn bit_reverse_rows(self) -> Self::BitRev {
+        match self {
+            Self::NaturalOrder(mat) => Self::BitReversed(mat.bit_reverse_rows()),
+            Self::BitReversed(view) => Self::NaturalOrder(view.bit_reverse_rows()),
+        }
+    }
+}
+
+/// A DFT which holds an instance of each backend, and routes each call to one of them according
+/// to a policy.
+///
+/// All routes compute the same transforms, and the evaluations are returned in the order of the
+/// backend, see `DftEvaluations`.
+#[derive(Clone, Debug)]
+pub struct DftDispatcher<F: TwoAdicField> {
+    radix_2_dit: Radix2Dit<F>,
+    radix_2_dit_parallel: Radix2DitParallel<F>,
+    recursive: RecursiveDft<F>,
+    recursive_bowers: RecursiveBowers<F>,
+    policy: DftPolicy,
+}
+
+impl<F: TwoAdicField> Default for DftDispatcher<F> {
+    fn default() -> Self {
+        Self::new(default_dft_policy)
+    }
+}
+
+impl<F: TwoAdicField> DftDispatcher<F> {
+    /// Create a dispatcher which routes with `policy`, e.g. `|_, _| DftRoute::Radix2Bowers`.
+    pub fn new(policy: DftPolicy) -> Self {
+        Self {
+            radix_2_dit: Radix2Dit::default(),
+            radix_2_dit_parallel: Radix2DitParallel::default(),
+            recursive: RecursiveDft::default(),
+            recursive_bowers: RecursiveBowers::default(),
+            policy,
+        }
+    }
+
+    fn route(&self, mat: &RowMajorMatrix<F>) -> DftRoute {
+        (self.policy)(mat.height(), mat.width())
+    }
+}
+
+impl<MP: MontyParameters + FieldParameters + TwoAdicData> TwoAdicSubgroupDft<MontyField31<MP>>
+    for DftDispatcher<MontyField31<MP>>
+{
+    type Evaluations = DftEvaluations<MontyField31<MP>>;
+
+    fn dft_batch(&self, mat: RowMajorMatrix<MontyField31<MP>>) -> Self::Evaluations {
+        match self.route(&mat) {
+            DftRoute::Radix2Dit => DftEvaluations::NaturalOrder(self.radix_2_dit.dft_batch(mat)),
+            DftRoute::Radix2DitParallel => {
+                DftEvaluations::BitReversed(self.radix_2_dit_parallel.dft_batch(mat))
+            }
+            DftRoute::Radix2Bowers => DftEvaluations::NaturalOrder(Radix2Bowers.dft_batch(mat)),
+            DftRoute::Recursive => DftEvaluations::BitReversed(self.recursive.dft_batch(mat)),
+            DftRoute::RecursiveBowers => {
+                DftEvaluations::NaturalOrder(self.recursive_bowers.dft_batch(mat))
+            }
+        }
+    }
+
+    fn idft_batch(
+        &self,
+        mat: RowMajorMatrix<MontyField31<MP>>,
+    ) -> RowMajorMatrix<MontyField31<MP>> {
+        match self.route(&mat) {
+            DftRoute::Radix2Dit => self.radix_2_dit.idft_batch(mat),
+            DftRoute::Radix2DitParallel => self.radix_2_dit_parallel.idft_batch(mat),
+            DftRoute::Radix2Bowers => Radix2Bowers.idft_batch(mat),
+            DftRoute::Recursive => self.recursive.idft_batch(mat),
+            DftRoute::RecursiveBowers => self.recursive_bowers.idft_batch(mat),
+        }
+    }
+
+    fn lde_batch(
+        &self,
+        mat: RowMajorMatrix<MontyField31<MP>>,
+        added_bits: usize,
+    ) -> Self::Evaluations {
+        match self.route(&mat) {
+            DftRoute::Radix2Dit => {
+                DftEvaluations::NaturalOrder(self.radix_2_dit.lde_batch(mat, added_bits))
+            }
+            DftRoute::Radix2DitParallel => {
+                DftEvaluations::BitReversed(self.radix_2_dit_parallel.lde_batch(mat, added_bits))
+            }
+            DftRoute::Radix2Bowers => {
+                DftEvaluations::NaturalOrder(Radix2Bowers.lde_batch(mat, added_bits))
+            }
+            DftRoute::Recursive => {
+                DftEvaluations::BitReversed(self.recursive.lde_batch(mat, added_bits))
+            }
+            DftRoute::RecursiveBowers => {
+                DftEvaluations::NaturalOrder(self.recursive_bowers.lde_batch(mat, added_bits))
+            }
+        }
+    }
+
+    fn coset_lde_batch(
+        &self,
+        mat: RowMajorMatrix<MontyField31<MP>>,
+        added_bits: usize,
+        shift: MontyField31<MP>,
+    ) -> Self::Evaluations {
+        match self.route(&mat) {
+            DftRoute::Radix2Dit => DftEvaluations::NaturalOrder(
+                self.radix_2_dit.coset_lde_batch(mat, added_bits, shift),
+            ),
+            DftRoute::Radix2DitParallel => DftEvaluations::BitReversed(
+                self.radix_2_dit_parallel
+                    .coset_lde_batch(mat, added_bits, shift),
+            ),
+            DftRoute::Radix2Bowers => {
+                DftEvaluations::NaturalOrder(Radix2Bowers.coset_lde_batch(mat, added_bits, shift))
+            }
+            DftRoute::Recursive => {
+                DftEvaluations::BitReversed(self.recursive.coset_lde_batch(mat, added_bits, shift))
+            }
+            DftRoute::RecursiveBowers => DftEvaluations::NaturalOrder(
+                self.recursive_bowers
+                    .coset_lde_batch(mat, added_bits, shift),
+            ),
+        }
+    }
+}
@@ -130,7 +130,11 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
     }
 
     #[inline]
-    fn forward_iterative_packed_radix_16(input: &mut [<Self as Field>::Packing]) {
+    fn forward_iterative_packed_radix_16(
+        input: &mut [<Self as Field>::Packing],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         // Rather surprisingly, a version similar where the separate
         // loops in each call to forward_iterative_packed() are
         // combined into one, was not only not faster, but was
@@ -138,20 +142,20 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
 
         // Radix 16
         if <Self as Field>::Packing::WIDTH >= 16 {
-            forward_iterative_packed::<8, _>(input, MP::ROOTS_16.as_ref());
+            forward_iterative_packed::<8, _>(input, roots_16);
         } else {
-            Self::forward_iterative_layer(input, MP::ROOTS_16.as_ref(), 8);
+            Self::forward_iterative_layer(input, roots_16, 8);
         }
 
         // Radix 8
         if <Self as Field>::Packing::WIDTH >= 8 {
-            forward_iterative_packed::<4, _>(input, MP::ROOTS_8.as_ref());
+            forward_iterative_packed::<4, _>(input, roots_8);
         } else {
-            Self::forward_iterative_layer(input, MP::ROOTS_8.as_ref(), 4);
+            Self::forward_iterative_layer(input, roots_8, 4);
         }
 
         // Radix 4
-        let roots4 = [MP::ROOTS_8.as_ref()[0], MP::ROOTS_8.as_ref()[2]];
+        let roots4 = [roots_8[0], roots_8[2]];
         if <Self as Field>::Packing::WIDTH >= 4 {
             forward_iterative_packed::<2, _>(input, &roots4);
         } else {
@@ -164,7 +168,12 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
 
     /// Breadth-first DIF FFT for smallish vectors (must be >= 64)
     #[inline]
-    fn forward_iterative(packed_input: &mut [<Self as Field>::Packing], root_table: &[Vec<Self>]) {
+    fn forward_iterative(
+        packed_input: &mut [<Self as Field>::Packing],
+        root_table: &[Vec<Self>],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         assert!(packed_input.len() >= 2);
         let packing_width = <Self as Field>::Packing::WIDTH;
         let n = packed_input.len() * packing_width;
@@ -198,7 +207,7 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
         }
 
         // Last 4 layers
-        Self::forward_iterative_packed_radix_16(packed_input);
+        Self::forward_iterative_packed_radix_16(packed_input, roots_8, roots_16);
     }
 
     #[inline(always)]
@@ -218,12 +227,16 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
         // Safe because 0 <= half_n < a.len()
         let (xs, ys) = unsafe { input.split_at_mut_unchecked(half_n) };
 
-        let s = xs[0] + ys[0];
-        let t = xs[0] - ys[0];
-        xs[0] = s;
-        ys[0] = t;
+        // half_n need not be a multiple of the packing width, so butterfly whatever
+        // packs and finish off the remainder with scalars.
+        let (xs_packed, xs_suffix) = <Self as Field>::Packing::pack_slice_with_suffix_mut(xs);
+        let (ys_packed, ys_suffix) = <Self as Field>::Packing::pack_slice_with_suffix_mut(ys);
+        let (roots_packed, roots_suffix) = <Self as Field>::Packing::pack_slice_with_suffix(roots);
 
-        izip!(&mut xs[1..], &mut ys[1..], &roots[1..]).for_each(|(x, y, &root)| {
+        // Using the general butterfly on roots[0] == 1 is harmless.
+        izip!(xs_packed, ys_packed, roots_packed)
+            .for_each(|(x, y, &root)| (*x, *y) = forward_butterfly(*x, *y, root));
+        izip!(xs_suffix, ys_suffix, roots_suffix).for_each(|(x, y, &root)| {
             (*x, *y) = Self::forward_butterfly(*x, *y, root);
         });
     }
@@ -239,14 +252,12 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
     }
 
     #[inline(always)]
-    fn forward_4(a: &mut [Self]) {
+    fn forward_4(a: &mut [Self], roots_8: &[Self]) {
         assert_eq!(a.len(), 4);
 
         // Expanding the calculation of t3 saves one instruction
         let t1 = MP::PRIME + a[1].value - a[3].value;
-        let t3 = MontyField31::new_monty(monty_reduce::<MP>(
-            t1 as u64 * MP::ROOTS_8.as_ref()[2].value as u64,
-        ));
+        let t3 = MontyField31::new_monty(monty_reduce::<MP>(t1 as u64 * roots_8[2].value as u64));
         let t5 = a[1] + a[3];
         let t4 = a[0] + a[2];
         let t2 = a[0] - a[2];
@@ -259,49 +270,54 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
     }
 
     #[inline(always)]
-    fn forward_8(a: &mut [Self]) {
+    fn forward_8(a: &mut [Self], roots_8: &[Self]) {
         assert_eq!(a.len(), 8);
 
-        Self::forward_pass(a, MP::ROOTS_8.as_ref());
+        Self::forward_pass(a, roots_8);
 
         // Safe because a.len() == 8
         let (a0, a1) = unsafe { a.split_at_mut_unchecked(a.len() / 2) };
-        Self::forward_4(a0);
-        Self::forward_4(a1);
+        Self::forward_4(a0, roots_8);
+        Self::forward_4(a1, roots_8);
     }
 
     #[inline(always)]
-    fn forward_16(a: &mut [Self]) {
+    fn forward_16(a: &mut [Self], roots_8: &[Self], roots_16: &[Self]) {
         assert_eq!(a.len(), 16);
 
-        Self::forward_pass(a, MP::ROOTS_16.as_ref());
+        Self::forward_pass(a, roots_16);
 
         // Safe because a.len() == 16
         let (a0, a1) = unsafe { a.split_at_mut_unchecked(a.len() / 2) };
-        Self::forward_8(a0);
-        Self::forward_8(a1);
+        Self::forward_8(a0, roots_8);
+        Self::forward_8(a1, roots_8);
     }
 
     #[inline(always)]
-    fn forward_32(a: &mut [Self], root_table: &[Vec<Self>]) {
+    fn forward_32(a: &mut [Self], root_table: &[Vec<Self>], roots_8: &[Self], roots_16: &[Self]) {
         assert_eq!(a.len(), 32);
 
         Self::forward_pass(a, &root_table[0]);
 
         // Safe because a.len() == 32
         let (a0, a1) = unsafe { a.split_at_mut_unchecked(a.len() / 2) };
-        Self::forward_16(a0);
-        Self::forward_16(a1);
+        Self::forward_16(a0, roots_8, roots_16);
+        Self::forward_16(a1, roots_8, roots_16);
     }
 
     /// Assumes `input.len() >= 64`.
     #[inline]
-    fn forward_fft_recur(input: &mut [<Self as Field>::Packing], root_table: &[Vec<Self>]) {
+    fn forward_fft_recur(
+        input: &mut [<Self as Field>::Packing],
+        root_table: &[Vec<Self>],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         const ITERATIVE_FFT_THRESHOLD: usize = 1024;
 
         let n = input.len() * <Self as Field>::Packing::WIDTH;
         if n <= ITERATIVE_FFT_THRESHOLD {
-            Self::forward_iterative(input, root_table);
+            Self::forward_iterative(input, root_table, roots_8, roots_16);
         } else {
             assert_eq!(n, 1 << (root_table.len() + 1));
             forward_pass_packed(input, &root_table[0]);
@@ -309,27 +325,60 @@ impl<MP: FieldParameters + TwoAdicData> MontyField31<MP> {
             // Safe because input.len() > ITERATIVE_FFT_THRESHOLD
             let (a0, a1) = unsafe { input.split_at_mut_unchecked(input.len() / 2) };
 
-            Self::forward_fft_recur(a0, &root_table[1..]);
-            Self::forward_fft_recur(a1, &root_table[1..]);
+            Self::forward_fft_recur(a0, &root_table[1..], roots_8, roots_16);
+            Self::forward_fft_recur(a1, &root_table[1..], roots_8, roots_16);
         }
     }
 
     #[inline]
     pub fn forward_fft(input: &mut [Self], root_table: &[Vec<Self>]) {
+        Self::forward_fft_with_roots(
+            input,
+            root_table,
+            MP::ROOTS_8.as_ref(),
+            MP::ROOTS_16.as_ref(),
+        );
+    }
+
+    /// As `forward_fft`, but with the inverse roots of unity, as in the table of inverse twiddles
+    /// kept by `RecursiveDft`.
+    ///
+    /// This computes the inverse DFT without the division by `input.len()`, taking its input in
+    /// natural order and leaving its output in bit-reversed order, i.e. it is the Bowers G^T
+    /// network.
+    #[inline]
+    pub(crate) fn forward_fft_inverse_roots(input: &mut [Self], inv_root_table: &[Vec<Self>]) {
+        Self::forward_fft_with_roots(
+            input,
+            inv_root_table,
+            MP::INV_ROOTS_8.as_ref(),
+            MP::INV_ROOTS_16.as_ref(),
+        );
+    }
+
+    /// A DIF FFT using the roots in `root_table` for the large layers and `roots_8`, `roots_16`
+    /// for the last four, which must be powers of the same roots of unity.
+    #[inline]
+    fn forward_fft_with_roots(
+        input: &mut [Self],
+        root_table: &[Vec<Self>],
+        roots_8: &[Self],
+        roots_16: &[Self],
+    ) {
         let n = input.len();
         if n == 1 {
             return;
         }
         assert_eq!(n, 1 << (root_table.len() + 1));
         match n {
-            32 => Self::forward_32(input, root_table),
-            16 => Self::forward_16(input),
-            8 => Self::forward_8(input),
-            4 => Self::forward_4(input),
+            32 => Self::forward_32(input, root_table, roots_8, roots_16),
+            16 => Self::forward_16(input, roots_8, roots_16),
+            8 => Self::forward_8(input, roots_8),
+            4 => Self::forward_4(input, roots_8),
             2 => Self::forward_2(input),
             _ => {
                 let packed_input = <Self as Field>::Packing::pack_slice_mut(input);
-                Self::forward_fft_recur(packed_input, root_table)
+                Self::forward_fft_recur(packed_input, root_table, roots_8, roots_16)
             }
         }
     }
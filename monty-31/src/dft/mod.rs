@@ -15,8 +15,13 @@ use p3_maybe_rayon::prelude::*;
 use tracing::{debug_span, instrument};
 
 mod backward;
+mod bowers;
+mod dispatcher;
 mod forward;
 
+pub use bowers::RecursiveBowers;
+pub use dispatcher::*;
+
 use crate::{FieldParameters, MontyField31, MontyParameters, TwoAdicData};
 
 /// Multiply each element of column `j` of `mat` by `shift**j`.
@@ -239,7 +244,7 @@ impl<MP: MontyParameters + FieldParameters + TwoAdicData> TwoAdicSubgroupDft<Mon
         let ncols = mat.width();
         let result_nrows = nrows << added_bits;
 
-        if nrows == 1 {
+        if nrows <= 1 {
             let dupd_rows = core::iter::repeat(mat.values)
                 .take(result_nrows)
                 .flatten()
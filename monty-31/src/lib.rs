@@ -13,11 +13,13 @@ extern crate alloc;
 mod data_traits;
 pub mod dft;
 mod extension;
+mod macros;
 mod mds;
 mod monty_31;
 mod poseidon2;
 mod utils;
 pub use data_traits::*;
+pub use macros::__private;
 pub use mds::*;
 pub use monty_31::*;
 pub use poseidon2::*;
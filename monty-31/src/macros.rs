@@ -0,0 +1,360 @@
+//! A macro for declaring new 31-bit MONTY fields, and the compile-time helpers it relies on.
+
+/// Declare a 31-bit prime field in MONTY form, along with its parameter struct.
+///
+/// All constants needed by `MontyParameters`, `FieldParameters` and `TwoAdicData` (`MONTY_MU`, the
+/// MONTY form of the generator, the two-adic generators and the roots of unity used by the DFTs)
+/// are computed at compile time from `PRIME` and `GENERATOR`, and the packed parameters for the
+/// target architecture are filled in. Exponentiation and inversion use the generic
+/// square-and-multiply; fields that need faster ones should implement the traits by hand.
+///
+/// `PRIME` must be a prime less than `2^31`, `GENERATOR` a generator of its multiplicative group
+/// and `TWO_ADICITY` the number of trailing zeros of `PRIME - 1`, which must be at least 4. The
+/// last two, along with `GENERATOR` being a quadratic non-residue, are checked at compile time;
+/// primality and the full order of `GENERATOR` are not.
+///
+/// ```
+/// p3_monty_31::declare_monty_field!(
+///     /// The prime field `63 * 2^25 + 1`.
+///     pub MyField,
+///     MyFieldParameters,
+///     PRIME = 0x7e000001,
+///     GENERATOR = 5,
+///     TWO_ADICITY = 25,
+/// );
+///
+/// use p3_field::FieldAlgebra;
+/// assert_eq!(MyField::TWO * MyField::NEG_ONE, -MyField::TWO);
+/// ```
+#[macro_export]
+macro_rules! declare_monty_field {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $field:ident,
+        $params:ident,
+        PRIME = $prime:expr,
+        GENERATOR = $generator:expr,
+        TWO_ADICITY = $two_adicity:expr $(,)?
+    ) => {
+        $(#[$meta])*
+        $vis type $field = $crate::MontyField31<$params>;
+
+        #[derive(Copy, Clone, Default, Debug, Eq, Hash, PartialEq)]
+        $vis struct $params;
+
+        const _: () = $crate::__private::check_monty_field_parameters(
+            $prime,
+            $generator,
+            $two_adicity,
+        );
+
+        impl $crate::MontyParameters for $params {
+            const PRIME: u32 = $prime;
+
+            const MONTY_BITS: u32 = 32;
+            const MONTY_MU: u32 = $crate::__private::monty_mu($prime);
+        }
+
+        impl $crate::PackedMontyParameters for $params {}
+
+        $crate::__impl_packed_monty_parameters!($params);
+
+        impl $crate::BarrettParameters for $params {}
+
+        impl $crate::FieldParameters for $params {
+            const MONTY_GEN: $crate::MontyField31<Self> = $crate::MontyField31::new($generator);
+
+            fn exp_u64_generic<FA: $crate::__private::FieldAlgebra>(val: FA, power: u64) -> FA {
+                $crate::__private::exp_u64_by_squaring(val, power)
+            }
+
+            fn try_inverse<F: $crate::__private::Field>(p1: F) -> ::core::option::Option<F> {
+                if p1.is_zero() {
+                    return ::core::option::Option::None;
+                }
+                // From Fermat's little theorem, the inverse of `a` is `a^(p-2)`.
+                let power = u64::from(<Self as $crate::MontyParameters>::PRIME - 2);
+                ::core::option::Option::Some($crate::__private::exp_u64_by_squaring(p1, power))
+            }
+        }
+
+        impl $crate::TwoAdicData for $params {
+            const TWO_ADICITY: usize = $two_adicity;
+
+            type ArrayLike = &'static [$crate::MontyField31<Self>];
+
+            const TWO_ADIC_GENERATORS: Self::ArrayLike = &$crate::MontyField31::new_array(
+                $crate::__private::two_adic_generators::<{ $two_adicity + 1 }>(
+                    $prime, $generator,
+                ),
+            );
+
+            const ROOTS_8: Self::ArrayLike = &$crate::MontyField31::new_array(
+                $crate::__private::roots_of_unity::<4>($prime, $generator, 3, false),
+            );
+            const INV_ROOTS_8: Self::ArrayLike = &$crate::MontyField31::new_array(
+                $crate::__private::roots_of_unity::<4>($prime, $generator, 3, true),
+            );
+
+            const ROOTS_16: Self::ArrayLike = &$crate::MontyField31::new_array(
+                $crate::__private::roots_of_unity::<8>($prime, $generator, 4, false),
+            );
+            const INV_ROOTS_16: Self::ArrayLike = &$crate::MontyField31::new_array(
+                $crate::__private::roots_of_unity::<8>($prime, $generator, 4, true),
+            );
+        }
+    };
+}
+
+// The architecture-specific packed parameters. These are selected here, rather than in the
+// expansion of `declare_monty_field!`, so that the `nightly-features` check refers to this crate's
+// feature and not to one of the calling crate.
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_packed_monty_parameters {
+    ($params:ident) => {
+        impl $crate::MontyParametersNeon for $params {
+            // SAFETY: These are valid packed representations of P and MU.
+            const PACKED_P: ::core::arch::aarch64::uint32x4_t = unsafe {
+                ::core::mem::transmute::<[u32; 4], _>(
+                    [<$params as $crate::MontyParameters>::PRIME; 4],
+                )
+            };
+            const PACKED_MU: ::core::arch::aarch64::int32x4_t = unsafe {
+                ::core::mem::transmute::<[u32; 4], _>(
+                    [<$params as $crate::MontyParameters>::MONTY_MU; 4],
+                )
+            };
+        }
+    };
+}
+
+#[cfg(all(
+    target_arch = "x86_64",
+    target_feature = "avx2",
+    not(all(feature = "nightly-features", target_feature = "avx512f"))
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_packed_monty_parameters {
+    ($params:ident) => {
+        impl $crate::MontyParametersAVX2 for $params {
+            const PACKED_P: ::core::arch::x86_64::__m256i = unsafe {
+                ::core::mem::transmute::<[u32; 8], _>(
+                    [<$params as $crate::MontyParameters>::PRIME; 8],
+                )
+            };
+            const PACKED_MU: ::core::arch::x86_64::__m256i = unsafe {
+                ::core::mem::transmute::<[u32; 8], _>(
+                    [<$params as $crate::MontyParameters>::MONTY_MU; 8],
+                )
+            };
+        }
+    };
+}
+
+#[cfg(all(
+    feature = "nightly-features",
+    target_arch = "x86_64",
+    target_feature = "avx512f"
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_packed_monty_parameters {
+    ($params:ident) => {
+        impl $crate::MontyParametersAVX512 for $params {
+            const PACKED_P: ::core::arch::x86_64::__m512i = unsafe {
+                ::core::mem::transmute::<[u32; 16], _>(
+                    [<$params as $crate::MontyParameters>::PRIME; 16],
+                )
+            };
+            const PACKED_MU: ::core::arch::x86_64::__m512i = unsafe {
+                ::core::mem::transmute::<[u32; 16], _>(
+                    [<$params as $crate::MontyParameters>::MONTY_MU; 16],
+                )
+            };
+        }
+    };
+}
+
+#[cfg(not(any(
+    all(target_arch = "aarch64", target_feature = "neon"),
+    all(
+        target_arch = "x86_64",
+        target_feature = "avx2",
+        not(all(feature = "nightly-features", target_feature = "avx512f"))
+    ),
+    all(
+        feature = "nightly-features",
+        target_arch = "x86_64",
+        target_feature = "avx512f"
+    ),
+)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_packed_monty_parameters {
+    ($params:ident) => {};
+}
+
+/// Implementation details of `declare_monty_field!`. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use p3_field::{exp_u64_by_squaring, Field, FieldAlgebra};
+
+    /// Panic, and so fail to compile when evaluated in a constant, if the parameters are invalid.
+    pub const fn check_monty_field_parameters(prime: u32, generator: u32, two_adicity: usize) {
+        assert!(prime < 1 << 31, "PRIME must be less than 2^31");
+        assert!(prime % 2 == 1, "PRIME must be odd");
+        assert!(
+            (prime - 1).trailing_zeros() as usize == two_adicity,
+            "TWO_ADICITY must be the number of trailing zeros of PRIME - 1"
+        );
+        assert!(two_adicity >= 4, "TWO_ADICITY must be at least 4");
+        // A non-residue has a 2-adic part of full order, which is all the two-adic generators need.
+        assert!(
+            exp_mod(generator, (prime - 1) / 2, prime) == prime - 1,
+            "GENERATOR must be a quadratic non-residue"
+        );
+    }
+
+    /// Compute `prime^-1 mod 2^32`, i.e. `MONTY_MU` for 32 `MONTY_BITS`.
+    pub const fn monty_mu(prime: u32) -> u32 {
+        // Every odd integer is its own inverse mod 8, and each Newton step doubles the number of
+        // correct bits: 3 -> 6 -> 12 -> 24 -> 48.
+        let mut inv = prime;
+        let mut i = 0;
+        while i < 4 {
+            inv = inv.wrapping_mul(2u32.wrapping_sub(prime.wrapping_mul(inv)));
+            i += 1;
+        }
+        inv
+    }
+
+    /// Compute `base^exp mod prime` on canonical representatives.
+    pub const fn exp_mod(base: u32, mut exp: u32, prime: u32) -> u32 {
+        let prime = prime as u64;
+        let mut base = base as u64 % prime;
+        let mut acc = 1;
+        while exp != 0 {
+            if exp & 1 == 1 {
+                acc = acc * base % prime;
+            }
+            base = base * base % prime;
+            exp >>= 1;
+        }
+        acc as u32
+    }
+
+    /// The canonical values of the generators of the two-adic subgroups of order `2^0, .., 2^(N-1)`,
+    /// each the square of the next.
+    pub const fn two_adic_generators<const N: usize>(prime: u32, generator: u32) -> [u32; N] {
+        let mut output = [0; N];
+        let mut bits = 0;
+        while bits < N {
+            output[bits] = exp_mod(generator, (prime - 1) >> bits, prime);
+            bits += 1;
+        }
+        output
+    }
+
+    /// The canonical values of the first `N` powers of the `2^bits`-th root of unity given by
+    /// `two_adic_generators`, or of its inverse.
+    pub const fn roots_of_unity<const N: usize>(
+        prime: u32,
+        generator: u32,
+        bits: usize,
+        inverse: bool,
+    ) -> [u32; N] {
+        let mut root = exp_mod(generator, (prime - 1) >> bits, prime);
+        if inverse {
+            root = exp_mod(root, (1 << bits) - 1, prime);
+        }
+        let mut output = [0; N];
+        let mut power = 1;
+        let mut i = 0;
+        while i < N {
+            output[i] = power;
+            power = (power as u64 * root as u64 % prime as u64) as u32;
+            i += 1;
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_field::testing::{check_field_axioms, check_two_adic_consistency};
+    use p3_field::{Field, FieldAlgebra, PrimeField32, TwoAdicField};
+
+    use super::__private::monty_mu;
+    use crate::{MontyParameters, TwoAdicData};
+
+    declare_monty_field!(
+        /// A toy prime field, `63 * 2^25 + 1`.
+        Toy,
+        ToyParameters,
+        PRIME = 0x7e000001,
+        GENERATOR = 5,
+        TWO_ADICITY = 25,
+    );
+
+    type F = Toy;
+
+    #[test]
+    fn monty_mu_matches_hand_written_constants() {
+        // The BabyBear and KoalaBear constants.
+        assert_eq!(monty_mu(0x78000001), 0x88000001);
+        assert_eq!(monty_mu(0x7f000001), 0x81000001);
+        assert_eq!(
+            ToyParameters::MONTY_MU.wrapping_mul(ToyParameters::PRIME),
+            1
+        );
+    }
+
+    #[test]
+    fn field_axioms() {
+        let samples: Vec<F> = [
+            0, 1, 2, 3, 5, 0x3f000000, 0x7e000000, 0x12345678, 0x7dffffff,
+        ]
+        .into_iter()
+        .map(F::from_canonical_u32)
+        .chain([F::GENERATOR, F::GENERATOR.inverse(), F::NEG_ONE.halve()])
+        .collect();
+        check_field_axioms(&samples);
+        assert_eq!(F::ORDER_U32, 0x7e000001);
+        assert_eq!(F::NEG_ONE.as_canonical_u32(), 0x7e000000);
+    }
+
+    #[test]
+    fn generator_has_full_order() {
+        let order = u64::from(F::ORDER_U32 - 1);
+        assert_eq!(F::GENERATOR.exp_u64(order), F::ONE);
+        // p - 1 = 2^25 * 3^2 * 7.
+        for q in [2, 3, 7] {
+            assert_ne!(F::GENERATOR.exp_u64(order / q), F::ONE);
+        }
+    }
+
+    #[test]
+    fn two_adic_consistency() {
+        check_two_adic_consistency::<F>();
+
+        let roots_8 = ToyParameters::ROOTS_8.as_ref();
+        let roots_16 = ToyParameters::ROOTS_16.as_ref();
+        assert_eq!(roots_8[1], F::two_adic_generator(3));
+        assert_eq!(roots_16[1], F::two_adic_generator(4));
+        for (roots, inv_roots) in [
+            (roots_8, ToyParameters::INV_ROOTS_8.as_ref()),
+            (roots_16, ToyParameters::INV_ROOTS_16.as_ref()),
+        ] {
+            for (i, (&root, &inv_root)) in roots.iter().zip(inv_roots).enumerate() {
+                assert_eq!(root, roots[1].exp_u64(i as u64));
+                assert_eq!(root * inv_root, F::ONE);
+            }
+        }
+    }
+}
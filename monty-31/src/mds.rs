@@ -8,15 +8,26 @@ use p3_symmetric::Permutation;
 use crate::{BarrettParameters, MontyField31, MontyParameters};
 
 /// A collection of circulant MDS matrices saved using their left most column.
+///
+/// The entries of the size 8 and 12 matrices must be small enough for `SmallConvolveMontyField31`.
+/// Those of the size 16 matrix may be as large as the field: it is multiplied by with the faster
+/// small convolution if they are small enough, and the large one otherwise.
 pub trait MDSUtils: Clone + Sync {
     const MATRIX_CIRC_MDS_8_COL: [i64; 8];
     const MATRIX_CIRC_MDS_12_COL: [i64; 12];
     const MATRIX_CIRC_MDS_16_COL: [i64; 16];
-    const MATRIX_CIRC_MDS_24_COL: [i64; 24];
     const MATRIX_CIRC_MDS_32_COL: [i64; 32];
     const MATRIX_CIRC_MDS_64_COL: [i64; 64];
 }
 
+/// A circulant MDS matrix of size 24, saved using its left most column.
+///
+/// This is split out of `MDSUtils` as not every field has one: KoalaBear implements only
+/// `MDSUtils`, and multiplies by a dense matrix of size 24 instead.
+pub trait MDSUtils24: MDSUtils {
+    const MATRIX_CIRC_MDS_24_COL: [i64; 24];
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct MdsMatrixMontyField31<MU: MDSUtils> {
     _phantom: PhantomData<MU>,
@@ -67,6 +78,21 @@ impl<FP: MontyParameters> Convolve<MontyField31<FP>, i64, i64, i64> for SmallCon
     }
 }
 
+/// Whether `SmallConvolveMontyField31` can multiply by the circulant matrix with first column
+/// `col`: whether its entries are non-negative and sum to less than `2^24`.
+const fn is_small_column<const N: usize>(col: &[i64; N]) -> bool {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < N {
+        if col[i] < 0 || col[i] >= 1 << 24 {
+            return false;
+        }
+        sum += col[i];
+        i += 1;
+    }
+    sum < 1 << 24
+}
+
 /// Given |x| < 2^80 compute x' such that:
 /// |x'| < 2**50
 /// x' = x mod p
@@ -304,27 +330,35 @@ impl<FP: MontyParameters, MU: MDSUtils> MdsPermutation<MontyField31<FP>, 12>
 {
 }
 
-impl<FP: MontyParameters, MU: MDSUtils> Permutation<[MontyField31<FP>; 16]>
+impl<FP: BarrettParameters, MU: MDSUtils> Permutation<[MontyField31<FP>; 16]>
     for MdsMatrixMontyField31<MU>
 {
     fn permute(&self, input: [MontyField31<FP>; 16]) -> [MontyField31<FP>; 16] {
-        SmallConvolveMontyField31::apply(
-            input,
-            MU::MATRIX_CIRC_MDS_16_COL,
-            <SmallConvolveMontyField31 as Convolve<MontyField31<FP>, i64, i64, i64>>::conv16,
-        )
+        if const { is_small_column(&MU::MATRIX_CIRC_MDS_16_COL) } {
+            SmallConvolveMontyField31::apply(
+                input,
+                MU::MATRIX_CIRC_MDS_16_COL,
+                <SmallConvolveMontyField31 as Convolve<MontyField31<FP>, i64, i64, i64>>::conv16,
+            )
+        } else {
+            LargeConvolveMontyField31::apply(
+                input,
+                MU::MATRIX_CIRC_MDS_16_COL,
+                <LargeConvolveMontyField31 as Convolve<MontyField31<FP>, i64, i64, i64>>::conv16,
+            )
+        }
     }
 
     fn permute_mut(&self, input: &mut [MontyField31<FP>; 16]) {
         *input = self.permute(*input);
     }
 }
-impl<FP: MontyParameters, MU: MDSUtils> MdsPermutation<MontyField31<FP>, 16>
+impl<FP: BarrettParameters, MU: MDSUtils> MdsPermutation<MontyField31<FP>, 16>
     for MdsMatrixMontyField31<MU>
 {
 }
 
-impl<FP, MU: MDSUtils> Permutation<[MontyField31<FP>; 24]> for MdsMatrixMontyField31<MU>
+impl<FP, MU: MDSUtils24> Permutation<[MontyField31<FP>; 24]> for MdsMatrixMontyField31<MU>
 where
     FP: BarrettParameters,
 {
@@ -340,7 +374,7 @@ where
         *input = self.permute(*input);
     }
 }
-impl<FP: BarrettParameters, MU: MDSUtils> MdsPermutation<MontyField31<FP>, 24>
+impl<FP: BarrettParameters, MU: MDSUtils24> MdsPermutation<MontyField31<FP>, 24>
     for MdsMatrixMontyField31<MU>
 {
 }
@@ -11,7 +11,8 @@ use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use num_bigint::BigUint;
 use p3_field::{
-    Field, FieldAlgebra, Packable, PrimeField, PrimeField32, PrimeField64, TwoAdicField,
+    Field, FieldAlgebra, Packable, PackedValue, PrimeField, PrimeField32, PrimeField64,
+    TwoAdicField,
 };
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
@@ -32,6 +33,24 @@ pub struct MontyField31<MP: MontyParameters> {
     _phantom: PhantomData<MP>,
 }
 
+// Safety: `MontyField31` is `repr(transparent)` over a `u32` (the `PhantomData` is zero-sized), and
+// the all-zero word is the MONTY form of zero.
+#[cfg(feature = "bytemuck")]
+unsafe impl<MP: MontyParameters> bytemuck::Zeroable for MontyField31<MP> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl<MP: MontyParameters> bytemuck::Pod for MontyField31<MP> {}
+
+#[cfg(feature = "bytemuck")]
+impl<FP: FieldParameters> p3_field::FieldWord for MontyField31<FP> {
+    /// The MONTY form of the element.
+    type Word = u32;
+
+    #[inline]
+    fn is_canonical_word(word: u32) -> bool {
+        word < FP::PRIME
+    }
+}
+
 impl<MP: MontyParameters> MontyField31<MP> {
     // The standard way to crate a new element.
     // Note that new converts the input into MONTY form so should be avoided in performance critical implementations.
@@ -106,6 +125,21 @@ impl<MP: MontyParameters> MontyField31<MP> {
         let value_mul_2exp_neg_n = (self.value as u64) << (32 - n);
         MontyField31::new_monty(monty_reduce::<MP>(value_mul_2exp_neg_n))
     }
+
+    /// Compute `sum + 2^{-n} * self` with a single monty reduction.
+    ///
+    /// The monty form of `sum` times `2^32` is added in before reducing, rather than adding `sum`
+    /// to the reduced product. The input to the reduction is then `< 2^33P`, which still fits in a
+    /// `u64`, and the output is `< 2P`, so one conditional subtraction makes it canonical.
+    #[inline]
+    #[must_use]
+    pub const fn mul_2exp_neg_n_add(&self, n: u32, sum: Self) -> Self {
+        assert!(n < 33);
+        let value = ((self.value as u64) << (32 - n)) + ((sum.value as u64) << 32);
+        let reduced = monty_reduce::<MP>(value);
+        let corr = if reduced >= MP::PRIME { MP::PRIME } else { 0 };
+        MontyField31::new_monty(reduced - corr)
+    }
 }
 
 impl<FP: MontyParameters> Ord for MontyField31<FP> {
@@ -149,21 +183,15 @@ impl<FP: MontyParameters> Distribution<MontyField31<FP>> for Standard {
 
 impl<FP: FieldParameters> Serialize for MontyField31<FP> {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        // It's faster to Serialize and Deserialize in monty form.
-        serializer.serialize_u32(self.value)
+        serializer.serialize_u32(self.as_canonical_u32())
     }
 }
 
 impl<'de, FP: FieldParameters> Deserialize<'de> for MontyField31<FP> {
     fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         let val = u32::deserialize(d)?;
-        // Ensure that `val` satisfies our invariant, namely is `< P`.
-        if val < FP::PRIME {
-            // It's faster to Serialize and Deserialize in monty form.
-            Ok(MontyField31::new_monty(val))
-        } else {
-            Err(D::Error::custom("Value is out of range"))
-        }
+        Self::from_canonical_checked(val.into())
+            .ok_or_else(|| D::Error::custom("Value is out of range"))
     }
 }
 
@@ -295,6 +323,19 @@ impl<FP: FieldParameters> PrimeField for MontyField31<FP> {
     fn as_canonical_biguint(&self) -> BigUint {
         <Self as PrimeField32>::as_canonical_u32(self).into()
     }
+
+    #[inline]
+    fn from_canonical_checked(n: u64) -> Option<Self> {
+        if n < FP::PRIME as u64 {
+            Some(Self::new(n as u32))
+        } else {
+            None
+        }
+    }
+
+    fn from_canonical_biguint_checked(n: &BigUint) -> Option<Self> {
+        u64::try_from(n).ok().and_then(Self::from_canonical_checked)
+    }
 }
 
 impl<FP: FieldParameters> PrimeField64 for MontyField31<FP> {
@@ -311,6 +352,19 @@ impl<FP: FieldParameters> PrimeField64 for MontyField31<FP> {
         // It's fine to hash things in monty form.
         self.value as u64
     }
+
+    #[inline]
+    fn sum_slice(xs: &[Self]) -> Self {
+        // Each Monty form is below 2^31, so a u64 holds the sum of fewer than 2^33 of them. The
+        // sum of each chunk is reduced, and then the sum of those, so any slice of fewer than
+        // 2^30 elements is reduced just once.
+        let prime = FP::PRIME as u64;
+        let sum: u64 = xs
+            .chunks(1 << 30)
+            .map(|chunk| chunk.iter().map(|x| x.value as u64).sum::<u64>() % prime)
+            .sum();
+        Self::new_monty((sum % prime) as u32)
+    }
 }
 
 impl<FP: FieldParameters> PrimeField32 for MontyField31<FP> {
@@ -327,6 +381,34 @@ impl<FP: FieldParameters> PrimeField32 for MontyField31<FP> {
         // It's fine to hash things in monty form.
         self.value
     }
+
+    fn to_canonical_u32_vec(xs: &[Self]) -> Vec<u32> {
+        // Multiplying by the element whose MONTY form is 1 computes x * R^{-1}, so the MONTY form
+        // of the product is the canonical form of x. This lets us use packed multiplication.
+        let mut out = xs.to_vec();
+        let r_inv = Self::new_monty(1);
+        let (packed, suffix) = <Self as Field>::Packing::pack_slice_with_suffix_mut(&mut out);
+        packed.iter_mut().for_each(|x| *x *= r_inv);
+        suffix.iter_mut().for_each(|x| *x *= r_inv);
+        out.into_iter().map(|x| x.value).collect()
+    }
+
+    fn from_canonical_u32_slice(xs: &[u32]) -> Vec<Self> {
+        // The reverse of the above: treating a canonical value x as a MONTY form and multiplying by
+        // the element whose MONTY form is R^2 computes x * R, the MONTY form of x.
+        let mut out: Vec<Self> = xs
+            .iter()
+            .map(|&x| {
+                debug_assert!(x < FP::PRIME);
+                Self::new_monty(x)
+            })
+            .collect();
+        let r_squared = Self::new(((1u64 << 32) % FP::PRIME as u64) as u32);
+        let (packed, suffix) = <Self as Field>::Packing::pack_slice_with_suffix_mut(&mut out);
+        packed.iter_mut().for_each(|x| *x *= r_squared);
+        suffix.iter_mut().for_each(|x| *x *= r_squared);
+        out
+    }
 }
 
 impl<FP: FieldParameters + TwoAdicData> TwoAdicField for MontyField31<FP> {
@@ -1,7 +1,7 @@
 use core::marker::PhantomData;
 use core::ops::Mul;
 
-use p3_field::FieldAlgebra;
+use p3_field::{FieldAlgebra, PrimeField64};
 use p3_poseidon2::{
     add_rc_and_sbox_generic, external_initial_permute_state, external_terminal_permute_state,
     ExternalLayer, GenericPoseidon2LinearLayers, InternalLayer, MDSMat4,
@@ -89,7 +89,7 @@ where
         self.internal_constants.iter().for_each(|rc| {
             state[0] += *rc;
             state[0] = state[0].exp_const_u64::<D>();
-            let part_sum: MontyField31<FP> = state[1..].iter().cloned().sum();
+            let part_sum = MontyField31::sum_slice(&state[1..]);
             let full_sum = part_sum + state[0];
             state[0] = part_sum - state[0];
             P2P::internal_layer_mat_mul(state, full_sum);
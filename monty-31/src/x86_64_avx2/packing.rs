@@ -4,7 +4,9 @@ use core::iter::{Product, Sum};
 use core::mem::transmute;
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use p3_field::{Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue};
+use p3_field::{
+    exp_const_u64_by_chain, Field, FieldAlgebra, PackedField, PackedFieldPow2, PackedValue,
+};
 use p3_util::convert_vec;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
@@ -23,6 +25,12 @@ pub trait MontyParametersAVX2 {
 #[repr(transparent)] // This is needed to make `transmute`s safe.
 pub struct PackedMontyField31AVX2<PMP: PackedMontyParameters>(pub [MontyField31<PMP>; WIDTH]);
 
+// Safety: `PackedMontyField31AVX2` is `repr(transparent)` over an array of `MontyField31`, which is `Pod`.
+#[cfg(feature = "bytemuck")]
+unsafe impl<PMP: PackedMontyParameters> bytemuck::Zeroable for PackedMontyField31AVX2<PMP> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl<PMP: PackedMontyParameters> bytemuck::Pod for PackedMontyField31AVX2<PMP> {}
+
 impl<PMP: PackedMontyParameters> PackedMontyField31AVX2<PMP> {
     #[inline]
     #[must_use]
@@ -555,7 +563,7 @@ impl<FP: FieldParameters> FieldAlgebra for PackedMontyField31AVX2<FP> {
                     Self::from_vector(res)
                 }
             }
-            _ => self.exp_u64(POWER),
+            _ => exp_const_u64_by_chain::<POWER, Self>(self),
         }
     }
 
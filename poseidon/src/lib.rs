@@ -6,7 +6,7 @@ extern crate alloc;
 
 use alloc::vec::Vec;
 
-use p3_field::{FieldAlgebra, PrimeField};
+use p3_field::{exp_small, FieldAlgebra, PrimeField};
 use p3_mds::MdsPermutation;
 use p3_symmetric::{CryptographicPermutation, Permutation};
 use rand::distributions::Standard;
@@ -100,7 +100,7 @@ where
         FA: FieldAlgebra<F = F>,
     {
         for x in state.iter_mut() {
-            *x = x.exp_const_u64::<ALPHA>();
+            *x = exp_small::<ALPHA, _>(x.clone());
         }
     }
 
@@ -108,7 +108,7 @@ where
     where
         FA: FieldAlgebra<F = F>,
     {
-        state[0] = state[0].exp_const_u64::<ALPHA>();
+        state[0] = exp_small::<ALPHA, _>(state[0].clone());
     }
 
     fn constant_layer<FA>(&self, state: &mut [FA; WIDTH], round: usize)
@@ -144,3 +144,84 @@ where
     Mds: MdsPermutation<FA, WIDTH>,
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::{Goldilocks, MdsMatrixGoldilocks};
+    use p3_symmetric::{DynPermutation, Permutation, SlicePermutation, WrongLength};
+    use rand::{thread_rng, Rng};
+
+    use crate::Poseidon;
+
+    type F = Goldilocks;
+    type Perm8 = Poseidon<F, MdsMatrixGoldilocks, 8, 7>;
+    type Perm12 = Poseidon<F, MdsMatrixGoldilocks, 12, 7>;
+
+    #[test]
+    fn dyn_permutation_matches_array_permutation() {
+        let mut rng = thread_rng();
+        let poseidon = Perm12::new_from_rng(4, 22, MdsMatrixGoldilocks, &mut rng);
+        let input: [F; 12] = rng.gen();
+
+        let expected = poseidon.permute(input);
+
+        let dyn_poseidon = SlicePermutation::<_, 12>::new(poseidon);
+        assert_eq!(DynPermutation::<F>::width(&dyn_poseidon), 12);
+        let mut output = input.to_vec();
+        dyn_poseidon
+            .permute_slice_mut(output.as_mut_slice())
+            .unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn dyn_permutation_wrong_length() {
+        let mut rng = thread_rng();
+        let poseidon = Perm12::new_from_rng(4, 22, MdsMatrixGoldilocks, &mut rng);
+        let dyn_poseidon = SlicePermutation::<_, 12>::new(poseidon);
+
+        for len in [0, 11, 13] {
+            let mut input = vec![F::ONE; len];
+            assert_eq!(
+                dyn_poseidon.permute_slice_mut(input.as_mut_slice()),
+                Err(WrongLength {
+                    expected: 12,
+                    actual: len,
+                })
+            );
+            // The input must be left untouched on error.
+            assert!(input.iter().all(|&x| x == F::ONE));
+        }
+    }
+
+    #[test]
+    fn dyn_permutation_trait_objects() {
+        let mut rng = thread_rng();
+        let perm8 = Perm8::new_from_rng(4, 22, MdsMatrixGoldilocks, &mut rng);
+        let perm12 = Perm12::new_from_rng(4, 22, MdsMatrixGoldilocks, &mut rng);
+        let input8: [F; 8] = rng.gen();
+        let input12: [F; 12] = rng.gen();
+        let expected8 = perm8.permute(input8);
+        let expected12 = perm12.permute(input12);
+
+        let perms: Vec<Box<dyn DynPermutation<F>>> = vec![
+            Box::new(SlicePermutation::<_, 8>::new(perm8)),
+            Box::new(SlicePermutation::<_, 12>::new(perm12)),
+        ];
+        assert_eq!(perms.iter().map(|p| p.width()).collect::<Vec<_>>(), [8, 12]);
+
+        let mut state8 = input8.to_vec();
+        let mut state12 = input12.to_vec();
+        perms[0].permute_slice_mut(state8.as_mut_slice()).unwrap();
+        perms[1].permute_slice_mut(state12.as_mut_slice()).unwrap();
+        assert_eq!(state8, expected8);
+        assert_eq!(state12, expected12);
+
+        assert!(perms[0].permute_slice_mut(state12.as_mut_slice()).is_err());
+    }
+}
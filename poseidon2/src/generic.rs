@@ -12,7 +12,7 @@
 //!
 //! This file implements the two matrix multiplications methods from which Poseidon2 can be built.
 
-use p3_field::FieldAlgebra;
+use p3_field::{exp_small, FieldAlgebra};
 
 use crate::{mds_light_permutation, MDSMat4};
 
@@ -25,7 +25,7 @@ use crate::{mds_light_permutation, MDSMat4};
 #[inline(always)]
 pub fn add_rc_and_sbox_generic<FA: FieldAlgebra, const D: u64>(val: &mut FA, rc: FA::F) {
     *val += FA::from_f(rc);
-    *val = val.exp_const_u64::<D>();
+    *val = exp_small::<D, _>(val.clone());
 }
 
 pub trait GenericPoseidon2LinearLayers<FA: FieldAlgebra, const WIDTH: usize>: Sync {
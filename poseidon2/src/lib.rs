@@ -64,6 +64,12 @@ where
     }
 
     /// Create a new Poseidon2 configuration with random parameters.
+    ///
+    /// The initial external, terminal external and internal round constants are sampled from
+    /// `rng` in that order, with the `Standard` distribution of the field. The fixed constants
+    /// which the field crates ship were sampled this way from `Xoroshiro128Plus::seed_from_u64(1)`,
+    /// as are the permutations the Sage scripts at https://github.com/0xPolygonZero/hash-constants
+    /// compute their test vectors with.
     pub fn new_from_rng<R: Rng>(rounds_f: usize, rounds_p: usize, rng: &mut R) -> Self
     where
         Standard: Distribution<FA::F> + Distribution<[FA::F; WIDTH]>,
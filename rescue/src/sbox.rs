@@ -1,6 +1,6 @@
 use core::marker::PhantomData;
 
-use p3_field::{FieldAlgebra, PrimeField, PrimeField64};
+use p3_field::{exp_small, FieldAlgebra, PrimeField, PrimeField64};
 
 use crate::util::get_inverse;
 
@@ -44,14 +44,63 @@ where
     FA::F: PrimeField,
 {
     fn sbox_layer(&self, state: &mut [FA; WIDTH]) {
+        // Dispatch to the addition chains in `exp_small` for the usual choices of alpha.
+        let sbox: fn(FA) -> FA = match self.alpha {
+            3 => exp_small::<3, FA>,
+            5 => exp_small::<5, FA>,
+            7 => exp_small::<7, FA>,
+            9 => exp_small::<9, FA>,
+            11 => exp_small::<11, FA>,
+            13 => exp_small::<13, FA>,
+            17 => exp_small::<17, FA>,
+            alpha => {
+                for x in state.iter_mut() {
+                    *x = x.exp_u64(alpha);
+                }
+                return;
+            }
+        };
         for x in state.iter_mut() {
-            *x = x.exp_u64(self.alpha);
+            *x = sbox(x.clone());
         }
     }
 
     fn inverse_sbox_layer(&self, state: &mut [FA; WIDTH]) {
+        // `exp_u64` dispatches through `Field::exp_u64_generic`, so fields with a dedicated
+        // addition chain for the inverse exponent (e.g. `Mersenne31::exp_inv_5`) use it here.
         for x in state.iter_mut() {
             *x = x.exp_u64(self.alpha_inv);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_field::Field;
+    use p3_mersenne_31::Mersenne31;
+    use rand::Rng;
+
+    use super::*;
+
+    type F = Mersenne31;
+    type P = <F as Field>::Packing;
+
+    #[test]
+    fn inverse_sbox_layer_mersenne31() {
+        let sbox = BasicSboxLayer::<F>::for_alpha(5);
+        assert_eq!(sbox.alpha_inv, F::EXP_INV_5);
+
+        let mut rng = rand::thread_rng();
+        let input: [P; 8] = rng.gen();
+
+        let mut state = input;
+        sbox.sbox_layer(&mut state);
+        assert_eq!(state, input.map(|x| x.exp_const_u64::<5>()));
+        sbox.inverse_sbox_layer(&mut state);
+        assert_eq!(state, input);
+
+        let mut state = input;
+        sbox.inverse_sbox_layer(&mut state);
+        assert_eq!(state, input.map(F::exp_inv_5));
+    }
+}
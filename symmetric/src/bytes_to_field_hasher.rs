@@ -0,0 +1,94 @@
+use core::iter;
+use core::marker::PhantomData;
+
+use p3_field::PrimeField64;
+
+use crate::CryptographicHasher;
+
+/// Hashes arbitrary byte strings with a hasher over field elements, by first encoding the bytes
+/// as field elements in an injective way.
+///
+/// Let `b = floor(log2(p)) / 8` be the number of whole bytes that always fit in an element of the
+/// field, e.g. 3 for 31-bit fields or 7 for Goldilocks. A byte string of length `L` is encoded as:
+///
+/// 1. The bytes, split into chunks of `b` bytes. Each chunk is read as a little-endian integer,
+///    which is then a canonical field element. The final chunk is zero-padded if `b` does not
+///    divide `L`.
+/// 2. `L` as a `u64`, split into `ceil(8 / b)` chunks of `b` bytes in the same manner.
+/// 3. A single `F::ONE`.
+///
+/// The length suffix distinguishes inputs which only differ in trailing zero bytes, and as it has
+/// a fixed size, it can always be located from the end of the encoding. The final `F::ONE` ensures
+/// that the encoding never ends in zero, so that distinct inputs stay distinct even when the inner
+/// hasher (like `PaddingFreeSponge`) does not itself distinguish trailing zeros.
+#[derive(Copy, Clone, Debug)]
+pub struct BytesToFieldHasher<F, Inner> {
+    inner: Inner,
+    _phantom: PhantomData<F>,
+}
+
+impl<F, Inner> BytesToFieldHasher<F, Inner> {
+    pub const fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField64, Inner> BytesToFieldHasher<F, Inner> {
+    /// The number of bytes packed into each field element.
+    pub const BYTES_PER_ELEM: usize = ((63 - F::ORDER_U64.leading_zeros()) / 8) as usize;
+
+    /// The number of field elements used to encode the length of the input.
+    const LEN_ELEMS: usize = 8usize.div_ceil(Self::BYTES_PER_ELEM);
+
+    /// Encode `input` as a sequence of field elements, as described in the type's documentation.
+    pub fn encode<I>(input: I) -> impl Iterator<Item = F>
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        let mut input = input.into_iter();
+        let mut len = 0u64;
+        let mut suffix = None;
+
+        iter::from_fn(move || {
+            if suffix.is_none() {
+                let mut chunk = 0u64;
+                let mut num_bytes = 0;
+                for byte in input.by_ref().take(Self::BYTES_PER_ELEM) {
+                    chunk |= (byte as u64) << (8 * num_bytes);
+                    num_bytes += 1;
+                }
+                if num_bytes != 0 {
+                    len += num_bytes as u64;
+                    return Some(F::from_canonical_u64(chunk));
+                }
+                // The input is exhausted, so switch over to the length suffix.
+                suffix = Some(Self::encode_len(len));
+            }
+            suffix.as_mut().unwrap().next()
+        })
+    }
+
+    fn encode_len(len: u64) -> impl Iterator<Item = F> {
+        let bits_per_elem = 8 * Self::BYTES_PER_ELEM;
+        let mask = (1 << bits_per_elem) - 1;
+        (0..Self::LEN_ELEMS)
+            .map(move |i| F::from_canonical_u64((len >> (i * bits_per_elem)) & mask))
+            .chain(iter::once(F::ONE))
+    }
+}
+
+impl<F, Inner, Out> CryptographicHasher<u8, Out> for BytesToFieldHasher<F, Inner>
+where
+    F: PrimeField64,
+    Inner: CryptographicHasher<F, Out>,
+{
+    fn hash_iter<I>(&self, input: I) -> Out
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        self.inner.hash_iter(Self::encode(input))
+    }
+}
@@ -0,0 +1,60 @@
+use p3_field::FieldAlgebra;
+
+use crate::{CryptographicHasher, PseudoCompressionFunction};
+
+/// A Merkle–Damgård style hasher, which hashes arbitrary-length sequences by chaining calls to a
+/// 2-to-1 compression function over `[F; CHUNK]`.
+///
+/// The chaining value starts at the IV `[F::ZERO; CHUNK]`. The input is split into blocks of
+/// `CHUNK` elements, and each full block is absorbed as `state = compress([state, block])`. The
+/// remaining `k < CHUNK` elements form the final block, which is zero-padded and has the total
+/// element count (reduced into the field) written to its last position, so that the final block
+/// of an input whose length is a multiple of `CHUNK` is `[0, ..., 0, len]`. The digest is the
+/// chaining value after absorbing the final block.
+///
+/// The length strengthening prevents collisions between an input and its zero-extensions. Note
+/// that the resulting hasher is only collision-resistant if the compression function is
+/// collision-resistant in general, which is a stronger requirement than that of
+/// `PseudoCompressionFunction`.
+#[derive(Copy, Clone, Debug)]
+pub struct ChainedHasher<C, const CHUNK: usize> {
+    compress: C,
+}
+
+impl<C, const CHUNK: usize> ChainedHasher<C, CHUNK> {
+    pub const fn new(compress: C) -> Self {
+        Self { compress }
+    }
+}
+
+impl<F, C, const CHUNK: usize> CryptographicHasher<F, [F; CHUNK]> for ChainedHasher<C, CHUNK>
+where
+    F: FieldAlgebra + Copy,
+    C: PseudoCompressionFunction<[F; CHUNK], 2>,
+{
+    fn hash_iter<I>(&self, input: I) -> [F; CHUNK]
+    where
+        I: IntoIterator<Item = F>,
+    {
+        const { assert!(CHUNK > 0, "ChainedHasher needs a non-empty chunk") };
+        let mut state = [F::ZERO; CHUNK];
+        let mut block = [F::ZERO; CHUNK];
+        let mut len = 0u64;
+        let mut i = 0;
+
+        for x in input {
+            block[i] = x;
+            i += 1;
+            len += 1;
+            if i == CHUNK {
+                state = self.compress.compress([state, block]);
+                block = [F::ZERO; CHUNK];
+                i = 0;
+            }
+        }
+
+        // There is always room for the length, since a full block is absorbed immediately.
+        block[CHUNK - 1] = F::from_wrapped_u64(len);
+        self.compress.compress([state, block])
+    }
+}
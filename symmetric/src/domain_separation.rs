@@ -0,0 +1,81 @@
+use p3_field::FieldAlgebra;
+
+use crate::{CryptographicHasher, PseudoCompressionFunction};
+
+/// Encodes a `u64` domain tag as four field elements, each holding 16 bits of the tag (least
+/// significant limb first). This is injective for any field with more than `2^16` elements.
+pub fn field_domain_tag<F: FieldAlgebra>(tag: u64) -> [F; 4] {
+    core::array::from_fn(|i| F::from_canonical_u16((tag >> (16 * i)) as u16))
+}
+
+/// Wraps a hasher so that a fixed tag is absorbed before every input, giving a hasher for a
+/// separate domain.
+///
+/// For field-based hashers the tag can be built with `field_domain_tag`; for byte-based hashers,
+/// something like `tag.to_le_bytes()` works. Distinct tags of the same length give distinct
+/// domains. Wrappers may be nested, in which case the outermost tag is absorbed first.
+#[derive(Copy, Clone, Debug)]
+pub struct DomainSeparatedHasher<H, T, const TAG_LEN: usize> {
+    inner: H,
+    tag: [T; TAG_LEN],
+}
+
+impl<H, T, const TAG_LEN: usize> DomainSeparatedHasher<H, T, TAG_LEN> {
+    pub const fn new(inner: H, tag: [T; TAG_LEN]) -> Self {
+        Self { inner, tag }
+    }
+}
+
+impl<T, H, Out, const TAG_LEN: usize> CryptographicHasher<T, Out>
+    for DomainSeparatedHasher<H, T, TAG_LEN>
+where
+    T: Clone,
+    H: CryptographicHasher<T, Out>,
+{
+    fn hash_iter<I>(&self, input: I) -> Out
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.inner.hash_iter(self.tag.iter().cloned().chain(input))
+    }
+}
+
+/// Wraps an `INNER_N`-to-1 compression function to give an `N`-to-1 compression function for a
+/// separate domain, where `INNER_N = N + 1`, by passing a fixed tag as the last input, i.e.
+/// `compress(input) = inner.compress([input[0], ..., input[N - 1], tag])`.
+///
+/// For a `TruncatedPermutation` the tag fills the part of the state that the `N`-to-1 compression
+/// with the same permutation leaves at zero, so any non-zero tag gives a domain separate from it,
+/// and distinct tags give distinct domains. Compression functions built from a hasher can instead
+/// wrap its hasher in a `DomainSeparatedHasher`.
+#[derive(Copy, Clone, Debug)]
+pub struct DomainSeparatedCompression<C, T, const INNER_N: usize> {
+    inner: C,
+    tag: T,
+}
+
+impl<C, T, const INNER_N: usize> DomainSeparatedCompression<C, T, INNER_N> {
+    pub const fn new(inner: C, tag: T) -> Self {
+        Self { inner, tag }
+    }
+}
+
+impl<T, C, const N: usize, const INNER_N: usize> PseudoCompressionFunction<T, N>
+    for DomainSeparatedCompression<C, T, INNER_N>
+where
+    T: Clone,
+    C: PseudoCompressionFunction<T, INNER_N>,
+{
+    fn compress(&self, input: [T; N]) -> T {
+        const {
+            assert!(
+                INNER_N == N + 1,
+                "the inner compression must take one more input than the outer one"
+            )
+        };
+        let mut input = input.into_iter();
+        self.inner.compress(core::array::from_fn(|_| {
+            input.next().unwrap_or_else(|| self.tag.clone())
+        }))
+    }
+}
@@ -1,7 +1,12 @@
+use alloc::vec;
+use alloc::vec::Vec;
 use core::borrow::Borrow;
+use core::fmt::Formatter;
 use core::marker::PhantomData;
 
-use serde::{Deserialize, Serialize};
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// A wrapper around an array digest, with a phantom type parameter to ensure that the digest is
 /// associated with a particular field.
@@ -56,3 +61,130 @@ impl<F, W, const DIGEST_ELEMS: usize> AsRef<[W; DIGEST_ELEMS]> for Hash<F, W, DI
         &self.value
     }
 }
+
+/// The commitment of a Merkle tree which stops `k` layers below the root: the `2^k` digests of
+/// that layer, in order. A cap of height 0 is just the root.
+///
+/// Publishing a cap rather than the root makes the commitment `2^k` digests long, but saves `k`
+/// digests in each authentication path.
+///
+/// A cap serializes as its digests one after another, with no length prefix, so a cap of height 0
+/// serializes exactly as its root `Hash`. The height is not recorded: `Deserialize` reads a cap of
+/// height 0, and caps of other heights are read with `deserialize_with_height`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleCap<F, W, const DIGEST_ELEMS: usize> {
+    roots: Vec<Hash<F, W, DIGEST_ELEMS>>,
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> MerkleCap<F, W, DIGEST_ELEMS> {
+    /// The digests of the cap, from left to right.
+    pub fn roots(&self) -> &[Hash<F, W, DIGEST_ELEMS>] {
+        &self.roots
+    }
+
+    /// Read a cap of height `cap_height`, as written by `Serialize`.
+    pub fn deserialize_with_height<'de, D>(
+        cap_height: usize,
+        deserializer: D,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+        [W; DIGEST_ELEMS]: Deserialize<'de>,
+    {
+        if cap_height == 0 {
+            return Self::deserialize(deserializer);
+        }
+        let len = u32::try_from(cap_height)
+            .ok()
+            .and_then(|height| 1usize.checked_shl(height))
+            .ok_or_else(|| {
+                D::Error::custom(format_args!("cap height {cap_height} is too large"))
+            })?;
+        deserializer.deserialize_tuple(
+            len,
+            CapVisitor {
+                len,
+                _phantom: PhantomData,
+            },
+        )
+    }
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> Serialize for MerkleCap<F, W, DIGEST_ELEMS>
+where
+    [W; DIGEST_ELEMS]: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // A one-element tuple is not the same as its element in every format, e.g. JSON.
+        if let [root] = self.roots.as_slice() {
+            return root.serialize(serializer);
+        }
+        let mut tuple = serializer.serialize_tuple(self.roots.len())?;
+        for root in &self.roots {
+            tuple.serialize_element(root)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de, F, W, const DIGEST_ELEMS: usize> Deserialize<'de> for MerkleCap<F, W, DIGEST_ELEMS>
+where
+    [W; DIGEST_ELEMS]: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Hash::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Reads the `len` digests of a cap of height `log2(len) > 0`.
+struct CapVisitor<F, W, const DIGEST_ELEMS: usize> {
+    len: usize,
+    _phantom: PhantomData<(F, W)>,
+}
+
+impl<'de, F, W, const DIGEST_ELEMS: usize> Visitor<'de> for CapVisitor<F, W, DIGEST_ELEMS>
+where
+    [W; DIGEST_ELEMS]: Deserialize<'de>,
+{
+    type Value = MerkleCap<F, W, DIGEST_ELEMS>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(formatter, "a Merkle cap of {} digests", self.len)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut roots = Vec::with_capacity(self.len);
+        for i in 0..self.len {
+            let root = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            roots.push(root);
+        }
+        Ok(MerkleCap { roots })
+    }
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> From<Vec<Hash<F, W, DIGEST_ELEMS>>>
+    for MerkleCap<F, W, DIGEST_ELEMS>
+{
+    fn from(roots: Vec<Hash<F, W, DIGEST_ELEMS>>) -> Self {
+        Self { roots }
+    }
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> From<Hash<F, W, DIGEST_ELEMS>>
+    for MerkleCap<F, W, DIGEST_ELEMS>
+{
+    fn from(root: Hash<F, W, DIGEST_ELEMS>) -> Self {
+        Self { roots: vec![root] }
+    }
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> IntoIterator for MerkleCap<F, W, DIGEST_ELEMS> {
+    type Item = Hash<F, W, DIGEST_ELEMS>;
+    type IntoIter = vec::IntoIter<Hash<F, W, DIGEST_ELEMS>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.roots.into_iter()
+    }
+}
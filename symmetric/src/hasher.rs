@@ -1,3 +1,10 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::array;
+
+use p3_field::PackedValue;
+use p3_maybe_rayon::prelude::*;
+
 pub trait CryptographicHasher<Item: Clone, Out>: Clone {
     fn hash_iter<I>(&self, input: I) -> Out
     where
@@ -18,4 +25,59 @@ pub trait CryptographicHasher<Item: Clone, Out>: Clone {
     fn hash_item(&self, input: Item) -> Out {
         self.hash_slice(&[input])
     }
+
+    /// Hash each of the given inputs independently, in parallel if the `parallel` feature is
+    /// enabled. The result is the same as calling `hash_slice` on each input in turn.
+    fn hash_many(&self, inputs: &[&[Item]]) -> Vec<Out>
+    where
+        Self: Sync,
+        Item: Sync,
+        Out: Send,
+    {
+        inputs
+            .par_iter()
+            .map(|input| self.hash_slice(input))
+            .collect()
+    }
+}
+
+/// Like `CryptographicHasher::hash_many`, but additionally hashes `P::WIDTH` inputs at a time
+/// using the packed implementation of the hasher, with one input in each lane.
+///
+/// Inputs are batched in consecutive groups of `P::WIDTH`. Groups whose inputs all have the same
+/// length use the packed hasher, while any other group (including a final incomplete group) falls
+/// back to scalar hashing. The output matches sequential scalar hashing of each input.
+pub fn hash_many_packed<P, PW, H, const OUT: usize>(
+    h: &H,
+    inputs: &[&[P::Value]],
+) -> Vec<[PW::Value; OUT]>
+where
+    P: PackedValue,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; OUT]>,
+    H: CryptographicHasher<P, [PW; OUT]>,
+    H: Sync,
+{
+    assert_eq!(P::WIDTH, PW::WIDTH, "Packing widths must match");
+    let width = P::WIDTH;
+
+    let mut digests = vec![[PW::Value::default(); OUT]; inputs.len()];
+    digests
+        .par_chunks_mut(width)
+        .zip(inputs.par_chunks(width))
+        .for_each(|(digests_chunk, inputs_chunk)| {
+            let len = inputs_chunk[0].len();
+            if inputs_chunk.len() == width && inputs_chunk.iter().all(|input| input.len() == len) {
+                let packed_digest: [PW; OUT] =
+                    h.hash_iter((0..len).map(|j| P::from_fn(|lane| inputs_chunk[lane][j])));
+                for (lane, dst) in digests_chunk.iter_mut().enumerate() {
+                    *dst = array::from_fn(|k| packed_digest[k].as_slice()[lane]);
+                }
+            } else {
+                for (dst, input) in digests_chunk.iter_mut().zip(inputs_chunk) {
+                    *dst = h.hash_slice(input);
+                }
+            }
+        });
+    digests
 }
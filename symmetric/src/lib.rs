@@ -4,16 +4,24 @@
 
 extern crate alloc;
 
+mod bytes_to_field_hasher;
+mod chained_hasher;
 mod compression;
+mod domain_separation;
 mod hash;
 mod hasher;
 mod permutation;
 mod serializing_hasher;
 mod sponge;
+mod truncation;
 
+pub use bytes_to_field_hasher::*;
+pub use chained_hasher::*;
 pub use compression::*;
+pub use domain_separation::*;
 pub use hash::*;
 pub use hasher::*;
 pub use permutation::*;
 pub use serializing_hasher::*;
 pub use sponge::*;
+pub use truncation::*;
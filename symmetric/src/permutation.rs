@@ -11,3 +11,57 @@ pub trait Permutation<T: Clone>: Clone + Sync {
 /// A permutation thought to be cryptographically secure, in the sense that it is thought to be
 /// difficult to distinguish (in a nontrivial way) from a random permutation.
 pub trait CryptographicPermutation<T: Clone>: Permutation<T> {}
+
+/// The error returned when a slice passed to a `DynPermutation` does not match its width.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WrongLength {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+/// An object-safe permutation over slices whose length is only checked at runtime.
+///
+/// This is useful at FFI boundaries, or when permutations of different widths need to be stored
+/// side by side, e.g. as `Box<dyn DynPermutation<F>>`.
+pub trait DynPermutation<T>: Sync {
+    /// The length of the slices accepted by `permute_slice_mut`.
+    fn width(&self) -> usize;
+
+    /// Permute `input` in place, or return an error if `input.len() != self.width()`.
+    fn permute_slice_mut(&self, input: &mut [T]) -> Result<(), WrongLength>;
+}
+
+/// Wraps a `Permutation<[T; WIDTH]>` so that it can be used as a `DynPermutation<T>`.
+///
+/// A direct blanket impl over `P: Permutation<[T; WIDTH]>` is not possible, since `WIDTH` would not
+/// be constrained by the implementing type; this wrapper pins it down.
+#[derive(Copy, Clone, Debug)]
+pub struct SlicePermutation<P, const WIDTH: usize> {
+    inner: P,
+}
+
+impl<P, const WIDTH: usize> SlicePermutation<P, WIDTH> {
+    pub const fn new(inner: P) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, P, const WIDTH: usize> DynPermutation<T> for SlicePermutation<P, WIDTH>
+where
+    T: Clone,
+    P: Permutation<[T; WIDTH]>,
+{
+    fn width(&self) -> usize {
+        WIDTH
+    }
+
+    fn permute_slice_mut(&self, input: &mut [T]) -> Result<(), WrongLength> {
+        let actual = input.len();
+        let input: &mut [T; WIDTH] = input.try_into().map_err(|_| WrongLength {
+            expected: WIDTH,
+            actual,
+        })?;
+        self.inner.permute_mut(input);
+        Ok(())
+    }
+}
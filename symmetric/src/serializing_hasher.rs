@@ -1,5 +1,6 @@
 use core::iter;
 
+use p3_field::codec::{encode_iter, encode_iter_u32};
 use p3_field::{PackedValue, PrimeField32, PrimeField64};
 
 use crate::CryptographicHasher;
@@ -52,11 +53,7 @@ where
     where
         I: IntoIterator<Item = F>,
     {
-        self.inner.hash_iter(
-            input
-                .into_iter()
-                .flat_map(|x| x.to_unique_u32().to_le_bytes()),
-        )
+        self.inner.hash_iter(encode_iter_u32(input))
     }
 }
 
@@ -74,7 +71,7 @@ where
         self.inner.hash_iter(
             input
                 .into_iter()
-                .map(|x| PW::from_fn(|i| x.as_slice()[i].to_unique_u32())),
+                .map(|x| PW::from_fn(|i| x.as_slice()[i].as_canonical_u32())),
         )
     }
 }
@@ -99,13 +96,13 @@ where
                 let b = input.next();
                 if let (Some(a), Some(b)) = (a, b) {
                     let ab = PW::from_fn(|i| {
-                        let a_i = a.as_slice()[i].to_unique_u64();
-                        let b_i = b.as_slice()[i].to_unique_u64();
+                        let a_i = a.as_slice()[i].as_canonical_u64();
+                        let b_i = b.as_slice()[i].as_canonical_u64();
                         a_i | (b_i << 32)
                     });
                     Some(ab)
                 } else {
-                    a.map(|a| PW::from_fn(|i| a.as_slice()[i].to_unique_u64()))
+                    a.map(|a| PW::from_fn(|i| a.as_slice()[i].as_canonical_u64()))
                 }
             },
         ))
@@ -121,11 +118,7 @@ where
     where
         I: IntoIterator<Item = F>,
     {
-        self.inner.hash_iter(
-            input
-                .into_iter()
-                .flat_map(|x| x.to_unique_u64().to_le_bytes()),
-        )
+        self.inner.hash_iter(encode_iter(input))
     }
 }
 
@@ -143,7 +136,7 @@ where
         self.inner.hash_iter(
             input
                 .into_iter()
-                .map(|x| PW::from_fn(|i| x.as_slice()[i].to_unique_u64())),
+                .map(|x| PW::from_fn(|i| x.as_slice()[i].as_canonical_u64())),
         )
     }
 }
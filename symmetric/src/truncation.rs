@@ -0,0 +1,72 @@
+use crate::{CryptographicHasher, PseudoCompressionFunction};
+
+/// Wraps a hasher with `[T; N]` digests, keeping only the first `M` digest elements.
+///
+/// Note that truncation reduces security: an `M`-element digest over a field `F` offers at most
+/// about `M * log2(|F|) / 2` bits of collision resistance, regardless of `N`. `M` should be
+/// chosen with this in mind.
+#[derive(Copy, Clone, Debug)]
+pub struct TruncatedHasher<H, const N: usize, const M: usize> {
+    inner: H,
+}
+
+impl<H, const N: usize, const M: usize> TruncatedHasher<H, N, M> {
+    const M_LE_N: () = assert!(M <= N, "cannot truncate a digest to a larger size");
+
+    pub const fn new(inner: H) -> Self {
+        let () = Self::M_LE_N;
+        Self { inner }
+    }
+}
+
+impl<Item, T, H, const N: usize, const M: usize> CryptographicHasher<Item, [T; M]>
+    for TruncatedHasher<H, N, M>
+where
+    Item: Clone,
+    T: Copy,
+    H: CryptographicHasher<Item, [T; N]>,
+{
+    fn hash_iter<I>(&self, input: I) -> [T; M]
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let digest = self.inner.hash_iter(input);
+        digest[..M].try_into().unwrap()
+    }
+}
+
+/// Wraps a compression function over `[T; N]` to give one over `[T; M]`, by zero-padding
+/// (with `T::default()`) each input to `N` elements and keeping only the first `M` elements of the
+/// output. This is the counterpart of `TruncatedHasher` for hash tree nodes.
+///
+/// The same security caveat as for `TruncatedHasher` applies.
+#[derive(Copy, Clone, Debug)]
+pub struct TruncatedCompressor<C, const N: usize, const M: usize> {
+    inner: C,
+}
+
+impl<C, const N: usize, const M: usize> TruncatedCompressor<C, N, M> {
+    const M_LE_N: () = assert!(M <= N, "cannot truncate a digest to a larger size");
+
+    pub const fn new(inner: C) -> Self {
+        let () = Self::M_LE_N;
+        Self { inner }
+    }
+}
+
+impl<T, C, const N: usize, const M: usize> PseudoCompressionFunction<[T; M], 2>
+    for TruncatedCompressor<C, N, M>
+where
+    T: Copy + Default,
+    C: PseudoCompressionFunction<[T; N], 2>,
+{
+    fn compress(&self, input: [[T; M]; 2]) -> [T; M] {
+        let padded = input.map(|x| {
+            let mut padded = [T::default(); N];
+            padded[..M].copy_from_slice(&x);
+            padded
+        });
+        let digest = self.inner.compress(padded);
+        digest[..M].try_into().unwrap()
+    }
+}
@@ -0,0 +1,114 @@
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_field::{FieldAlgebra, PrimeField64};
+use p3_goldilocks::Goldilocks;
+use p3_symmetric::{BytesToFieldHasher, CryptographicHasher, PaddingFreeSponge};
+use rand::{thread_rng, Rng};
+
+/// A "hasher" which returns its input unchanged, so that we can inspect the encoding.
+#[derive(Clone)]
+struct Identity;
+
+impl<F: Clone> CryptographicHasher<F, Vec<F>> for Identity {
+    fn hash_iter<I>(&self, input: I) -> Vec<F>
+    where
+        I: IntoIterator<Item = F>,
+    {
+        input.into_iter().collect()
+    }
+}
+
+type Perm = Poseidon2BabyBear<16>;
+type Sponge = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyHash = BytesToFieldHasher<BabyBear, Sponge>;
+
+fn sponge_hasher() -> MyHash {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    MyHash::new(Sponge::new(perm))
+}
+
+#[test]
+fn bytes_per_elem() {
+    assert_eq!(BytesToFieldHasher::<BabyBear, Identity>::BYTES_PER_ELEM, 3);
+    assert_eq!(
+        BytesToFieldHasher::<Goldilocks, Identity>::BYTES_PER_ELEM,
+        7
+    );
+}
+
+#[test]
+fn encoding_layout() {
+    type F = BabyBear;
+    let hasher = BytesToFieldHasher::<F, Identity>::new(Identity);
+    let encoded: Vec<F> = hasher.hash_slice(&[1, 2, 3, 4]);
+    let expected = [0x030201, 0x04, 4, 0, 0, 1].map(F::from_canonical_u32);
+    assert_eq!(encoded, expected);
+
+    let encoded: Vec<F> = hasher.hash_slice(&[]);
+    let expected = [0, 0, 0, 1].map(F::from_canonical_u32);
+    assert_eq!(encoded, expected);
+
+    type G = Goldilocks;
+    let hasher = BytesToFieldHasher::<G, Identity>::new(Identity);
+    let bytes = [0xff; 8];
+    let encoded: Vec<G> = hasher.hash_slice(&bytes);
+    let expected = [(1 << 56) - 1, 0xff, 8, 0, 1].map(G::from_canonical_u64);
+    assert_eq!(encoded, expected);
+    assert!(encoded.iter().all(|x| x.as_canonical_u64() < G::ORDER_U64));
+}
+
+#[test]
+fn trailing_zero_bytes() {
+    let hasher = sponge_hasher();
+    let mut input = vec![7u8, 0, 0];
+    let mut digests = vec![hasher.hash_slice(&input)];
+    for _ in 0..10 {
+        input.push(0);
+        digests.push(hasher.hash_slice(&input));
+    }
+    for (i, a) in digests.iter().enumerate() {
+        for b in &digests[..i] {
+            assert_ne!(a, b);
+        }
+    }
+
+    // Also check the all-zero inputs, including the empty input.
+    let zeros: Vec<[BabyBear; 8]> = (0..10).map(|n| hasher.hash_slice(&vec![0; n])).collect();
+    for (i, a) in zeros.iter().enumerate() {
+        for b in &zeros[..i] {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+fn length_suffix_prevents_aliasing() {
+    // Without the length suffix, these would both pack to [0x000001], and a padding-free sponge
+    // would not distinguish the encoded length either.
+    let hasher = BytesToFieldHasher::<BabyBear, Identity>::new(Identity);
+    let a: Vec<BabyBear> = hasher.hash_slice(&[1]);
+    let b: Vec<BabyBear> = hasher.hash_slice(&[1, 0, 0]);
+    assert_eq!(a[0], b[0]);
+    assert_ne!(a, b);
+
+    // An input whose body looks like another input's encoding.
+    let c: Vec<BabyBear> = hasher.hash_slice(&[1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0]);
+    assert_eq!(&c[..a.len()], &a[..]);
+    assert_ne!(a, c);
+
+    let hasher = sponge_hasher();
+    assert_ne!(hasher.hash_slice(&[1]), hasher.hash_slice(&[1, 0, 0]));
+}
+
+#[test]
+fn deterministic() {
+    let hasher = sponge_hasher();
+    let mut rng = thread_rng();
+    for len in [0, 1, 2, 3, 23, 24, 25, 100] {
+        let input: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        let digest = hasher.hash_slice(&input);
+        assert_eq!(hasher.hash_slice(&input), digest);
+        assert_eq!(hasher.hash_iter(input.iter().copied()), digest);
+        let (l, r) = input.split_at(len / 2);
+        assert_eq!(hasher.hash_iter_slices([l, r]), digest);
+    }
+}
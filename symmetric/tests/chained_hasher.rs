@@ -0,0 +1,108 @@
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_field::FieldAlgebra;
+use p3_symmetric::{
+    ChainedHasher, CryptographicHasher, PseudoCompressionFunction, TruncatedPermutation,
+};
+use rand::{thread_rng, Rng};
+
+type F = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type MyHash = ChainedHasher<MyCompress, 8>;
+
+fn setup() -> (MyCompress, MyHash) {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let compress = MyCompress::new(perm);
+    (compress.clone(), MyHash::new(compress))
+}
+
+fn random_input(len: usize) -> Vec<F> {
+    let mut rng = thread_rng();
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+#[test]
+fn empty_input() {
+    let (compress, hasher) = setup();
+    // The only block is the length block, [0, ..., 0, 0].
+    assert_eq!(
+        hasher.hash_iter(core::iter::empty()),
+        compress.compress([[F::ZERO; 8], [F::ZERO; 8]])
+    );
+}
+
+#[test]
+fn shorter_than_one_block() {
+    let (compress, hasher) = setup();
+    for len in 1..8 {
+        let input = random_input(len);
+        let mut block = [F::ZERO; 8];
+        block[..len].copy_from_slice(&input);
+        block[7] = F::from_canonical_usize(len);
+        assert_eq!(
+            hasher.hash_slice(&input),
+            compress.compress([[F::ZERO; 8], block])
+        );
+    }
+}
+
+#[test]
+fn exact_multiple_of_block() {
+    let (compress, hasher) = setup();
+    let input = random_input(16);
+    let mut state = [F::ZERO; 8];
+    for chunk in input.chunks_exact(8) {
+        state = compress.compress([state, chunk.try_into().unwrap()]);
+    }
+    let mut block = [F::ZERO; 8];
+    block[7] = F::from_canonical_usize(16);
+    assert_eq!(hasher.hash_slice(&input), compress.compress([state, block]));
+}
+
+#[test]
+fn split_input_agrees() {
+    let (_, hasher) = setup();
+    let input = random_input(29);
+    let digest = hasher.hash_slice(&input);
+    assert_eq!(hasher.hash_iter(input.iter().copied()), digest);
+    assert_eq!(
+        hasher.hash_iter_slices([&input[..3], &input[3..20], &input[20..]]),
+        digest
+    );
+}
+
+#[test]
+fn zero_extensions_differ() {
+    let (compress, hasher) = setup();
+    let mut input = random_input(5);
+
+    // Plain zero-padded chaining without the length, for comparison.
+    let unstrengthened = |input: &[F]| {
+        input.chunks(8).fold([F::ZERO; 8], |state, chunk| {
+            let mut block = [F::ZERO; 8];
+            block[..chunk.len()].copy_from_slice(chunk);
+            compress.compress([state, block])
+        })
+    };
+
+    let digest = hasher.hash_slice(&input);
+    let unstrengthened_digest = unstrengthened(&input);
+    for _ in 0..3 {
+        input.push(F::ZERO);
+        assert_eq!(unstrengthened(&input), unstrengthened_digest);
+        assert_ne!(hasher.hash_slice(&input), digest);
+    }
+}
+
+#[test]
+fn padding_block_as_input_differs() {
+    let (_, hasher) = setup();
+    let input = random_input(5);
+
+    // Feed the final padded block of `input` as an explicit input.
+    let mut padded = input.clone();
+    padded.resize(7, F::ZERO);
+    padded.push(F::from_canonical_usize(input.len()));
+
+    assert_ne!(hasher.hash_slice(&input), hasher.hash_slice(&padded));
+}
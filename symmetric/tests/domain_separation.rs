@@ -0,0 +1,113 @@
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_field::FieldAlgebra;
+use p3_keccak::Keccak256Hash;
+use p3_symmetric::{
+    field_domain_tag, CryptographicHasher, DomainSeparatedCompression, DomainSeparatedHasher,
+    PaddingFreeSponge, PseudoCompressionFunction, TruncatedPermutation,
+};
+use rand::{thread_rng, Rng};
+
+type F = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type Perm24 = Poseidon2BabyBear<24>;
+type MyCompress = TruncatedPermutation<Perm24, 2, 8, 24>;
+type MyInnerCompress = TruncatedPermutation<Perm24, 3, 8, 24>;
+type MyTaggedCompress = DomainSeparatedCompression<MyInnerCompress, [F; 8], 3>;
+
+fn random_input(len: usize) -> Vec<F> {
+    let mut rng = thread_rng();
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+#[test]
+fn field_tag_encoding() {
+    let tag = field_domain_tag::<F>(0x0004_0003_0002_0001);
+    assert_eq!(tag, [1, 2, 3, 4].map(F::from_canonical_u32));
+}
+
+#[test]
+fn different_tags_differ() {
+    let hash = MyHash::new(Perm::new_from_rng_128(&mut thread_rng()));
+    let leaf_hash = DomainSeparatedHasher::new(hash.clone(), field_domain_tag::<F>(0));
+    let node_hash = DomainSeparatedHasher::new(hash.clone(), field_domain_tag::<F>(1));
+
+    let input = random_input(20);
+    let leaf_digest = leaf_hash.hash_slice(&input);
+    assert_ne!(leaf_digest, node_hash.hash_slice(&input));
+    assert_ne!(leaf_digest, hash.hash_slice(&input));
+
+    // Tagging is equivalent to prepending the tag to the input.
+    let mut tagged_input = field_domain_tag::<F>(0).to_vec();
+    tagged_input.extend(&input);
+    assert_eq!(leaf_digest, hash.hash_slice(&tagged_input));
+}
+
+#[test]
+fn nested_tags_deterministic() {
+    let hash = MyHash::new(Perm::new_from_rng_128(&mut thread_rng()));
+    let inner = DomainSeparatedHasher::new(hash.clone(), field_domain_tag::<F>(1));
+    let outer = DomainSeparatedHasher::new(inner, field_domain_tag::<F>(2));
+    let swapped = DomainSeparatedHasher::new(
+        DomainSeparatedHasher::new(hash.clone(), field_domain_tag::<F>(2)),
+        field_domain_tag::<F>(1),
+    );
+
+    let input = random_input(9);
+    let digest = outer.hash_slice(&input);
+    assert_eq!(outer.clone().hash_slice(&input), digest);
+    assert_ne!(swapped.hash_slice(&input), digest);
+
+    let mut tagged_input = field_domain_tag::<F>(2).to_vec();
+    tagged_input.extend(field_domain_tag::<F>(1));
+    tagged_input.extend(&input);
+    assert_eq!(hash.hash_slice(&tagged_input), digest);
+}
+
+#[test]
+fn byte_hasher() {
+    let leaf_hash = DomainSeparatedHasher::new(Keccak256Hash, 0u64.to_le_bytes());
+    let node_hash = DomainSeparatedHasher::new(Keccak256Hash, 1u64.to_le_bytes());
+
+    let input = b"some bytes";
+    assert_ne!(leaf_hash.hash_slice(input), node_hash.hash_slice(input));
+    assert_ne!(leaf_hash.hash_slice(input), Keccak256Hash.hash_slice(input));
+
+    let mut tagged_input = 1u64.to_le_bytes().to_vec();
+    tagged_input.extend(input);
+    assert_eq!(
+        node_hash.hash_slice(input),
+        Keccak256Hash.hash_slice(&tagged_input)
+    );
+}
+
+#[test]
+fn compression() {
+    let perm = Perm24::new_from_rng_128(&mut thread_rng());
+    let compress = MyCompress::new(perm.clone());
+    let inner = MyInnerCompress::new(perm.clone());
+    let mut tag_a = [F::ZERO; 8];
+    tag_a[..4].copy_from_slice(&field_domain_tag(1));
+    let mut tag_b = [F::ZERO; 8];
+    tag_b[..4].copy_from_slice(&field_domain_tag(2));
+    let compress_a = MyTaggedCompress::new(inner.clone(), tag_a);
+    let compress_b = MyTaggedCompress::new(inner.clone(), tag_b);
+
+    let left: [F; 8] = thread_rng().gen();
+    let right: [F; 8] = thread_rng().gen();
+    let digest_a = compress_a.compress([left, right]);
+    assert_ne!(digest_a, compress_b.compress([left, right]));
+
+    // The tag is the last input of the inner compression.
+    assert_eq!(digest_a, inner.compress([left, right, tag_a]));
+
+    // It differs from every untagged composition of the inputs and the tag.
+    let untagged = [
+        compress.compress([left, right]),
+        compress.compress([tag_a, compress.compress([left, right])]),
+        compress.compress([compress.compress([left, right]), tag_a]),
+        compress.compress([compress.compress([tag_a, left]), right]),
+        compress.compress([left, compress.compress([right, tag_a])]),
+    ];
+    assert!(untagged.iter().all(|&digest| digest != digest_a));
+}
@@ -0,0 +1,44 @@
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_field::{Field, PackedValue};
+use p3_symmetric::{hash_many_packed, CryptographicHasher, PaddingFreeSponge};
+use rand::{thread_rng, Rng};
+
+type F = BabyBear;
+type P = <F as Field>::Packing;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+
+fn random_rows(lens: impl IntoIterator<Item = usize>) -> Vec<Vec<F>> {
+    let mut rng = thread_rng();
+    lens.into_iter()
+        .map(|len| (0..len).map(|_| rng.gen()).collect())
+        .collect()
+}
+
+fn check(hasher: &MyHash, rows: &[Vec<F>]) {
+    let inputs: Vec<&[F]> = rows.iter().map(|row| row.as_slice()).collect();
+    let expected: Vec<[F; 8]> = inputs.iter().map(|row| hasher.hash_slice(row)).collect();
+    assert_eq!(hasher.hash_many(&inputs), expected);
+    assert_eq!(hash_many_packed::<P, P, _, 8>(hasher, &inputs), expected);
+}
+
+#[test]
+fn hash_many_matches_sequential() {
+    let hasher = MyHash::new(Perm::new_from_rng_128(&mut thread_rng()));
+
+    // A count which is not divisible by the packing width (unless the width is 1).
+    let n = 3 * P::WIDTH + 1;
+    check(&hasher, &random_rows(core::iter::repeat(13).take(n)));
+
+    // Differing lengths, including empty rows, force the scalar fallback in some groups.
+    check(&hasher, &random_rows((0..n).map(|i| i % 5)));
+
+    // Groups of equal length, followed by a group of differing lengths.
+    let lens = core::iter::repeat(8)
+        .take(P::WIDTH)
+        .chain(core::iter::repeat(20).take(P::WIDTH))
+        .chain(0..P::WIDTH);
+    check(&hasher, &random_rows(lens));
+
+    check(&hasher, &[]);
+}
@@ -0,0 +1,62 @@
+use p3_baby_bear::BabyBear;
+use p3_symmetric::{Hash, MerkleCap};
+use rand::{thread_rng, Rng};
+
+type F = BabyBear;
+type Digest = Hash<F, F, 8>;
+type Cap = MerkleCap<F, F, 8>;
+
+fn random_roots(n: usize) -> Vec<Digest> {
+    let mut rng = thread_rng();
+    (0..n).map(|_| rng.gen::<[F; 8]>().into()).collect()
+}
+
+#[test]
+fn height_0_cap_serializes_as_its_root() {
+    let root = random_roots(1)[0];
+    let cap = Cap::from(root);
+
+    let bytes = postcard::to_allocvec(&cap).unwrap();
+    assert_eq!(bytes, postcard::to_allocvec(&root).unwrap());
+    assert_eq!(postcard::from_bytes::<Cap>(&bytes).unwrap(), cap);
+
+    let json = serde_json::to_string(&cap).unwrap();
+    assert_eq!(json, serde_json::to_string(&root).unwrap());
+    assert_eq!(serde_json::from_str::<Cap>(&json).unwrap(), cap);
+}
+
+#[test]
+fn caps_round_trip_given_their_height() {
+    for cap_height in [0, 1, 3] {
+        let roots = random_roots(1 << cap_height);
+        let cap = Cap::from(roots.clone());
+
+        // The roots are written one after another, with no length prefix.
+        let bytes = postcard::to_allocvec(&cap).unwrap();
+        let concatenated: Vec<u8> = roots
+            .iter()
+            .flat_map(|root| postcard::to_allocvec(root).unwrap())
+            .collect();
+        assert_eq!(bytes, concatenated);
+
+        let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+        assert_eq!(
+            Cap::deserialize_with_height(cap_height, &mut deserializer).unwrap(),
+            cap
+        );
+
+        let json = serde_json::to_string(&cap).unwrap();
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert_eq!(
+            Cap::deserialize_with_height(cap_height, &mut deserializer).unwrap(),
+            cap
+        );
+    }
+}
+
+#[test]
+fn short_caps_are_rejected() {
+    let json = serde_json::to_string(&Cap::from(random_roots(2))).unwrap();
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    assert!(Cap::deserialize_with_height(2, &mut deserializer).is_err());
+}
@@ -4,13 +4,14 @@ use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
-use p3_dft::Radix2DitParallel;
+use p3_dft::{Radix2DitParallel, TwoAdicSubgroupDft};
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{Field, FieldAlgebra, PrimeField64};
 use p3_fri::{create_test_fri_config, TwoAdicFriPcs};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_merkle_tree::MerkleTreeMmcs;
+use p3_monty_31::dft::{DftDispatcher, DftRoute};
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
 use p3_uni_stark::{prove, verify, StarkConfig};
 use rand::thread_rng;
@@ -113,16 +114,19 @@ type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
 
 /// n-th Fibonacci number expected to be x
 fn test_public_value_impl(n: usize, x: u64) {
+    test_public_value_with_dft(Dft::default(), n, x);
+}
+
+fn test_public_value_with_dft<D: TwoAdicSubgroupDft<Val>>(dft: D, n: usize, x: u64) {
     let perm = Perm::new_from_rng_128(&mut thread_rng());
     let hash = MyHash::new(perm.clone());
     let compress = MyCompress::new(perm.clone());
     let val_mmcs = ValMmcs::new(hash, compress);
     let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-    let dft = Dft::default();
     let trace = generate_trace_rows::<Val>(0, 1, n);
     let fri_config = create_test_fri_config(challenge_mmcs);
-    let pcs = Pcs::new(dft, val_mmcs, fri_config);
-    let config = MyConfig::new(pcs);
+    let pcs = TwoAdicFriPcs::<Val, D, ValMmcs, ChallengeMmcs>::new(dft, val_mmcs, fri_config);
+    let config = StarkConfig::<_, Challenge, Challenger>::new(pcs);
     let mut challenger = Challenger::new(perm.clone());
     let pis = vec![
         BabyBear::from_canonical_u64(0),
@@ -144,6 +148,19 @@ fn test_public_value() {
     test_public_value_impl(1 << 3, 21);
 }
 
+#[test]
+fn test_public_value_dft_dispatcher() {
+    // Route the trace and the quotient chunks to different backends.
+    let dft = DftDispatcher::new(|height, _| {
+        if height < 1 << 5 {
+            DftRoute::Radix2Bowers
+        } else {
+            DftRoute::Recursive
+        }
+    });
+    test_public_value_with_dft(dft, 1 << 5, 2_178_309);
+}
+
 #[cfg(debug_assertions)]
 #[test]
 #[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
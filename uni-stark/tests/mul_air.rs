@@ -4,7 +4,9 @@ use std::marker::PhantomData;
 use itertools::Itertools;
 use p3_air::{Air, AirBuilder, BaseAir};
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger32};
+use p3_challenger::{
+    DuplexChallenger, HashChallenger, SerializingChallenger32, SerializingChallenger64,
+};
 use p3_circle::CirclePcs;
 use p3_commit::testing::TrivialPcs;
 use p3_commit::ExtensionMmcs;
@@ -12,13 +14,15 @@ use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{Field, FieldAlgebra};
 use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_goldilocks::Goldilocks;
 use p3_keccak::Keccak256Hash;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_merkle_tree::MerkleTreeMmcs;
 use p3_mersenne_31::Mersenne31;
 use p3_symmetric::{
-    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, SerializingHasher64,
+    TruncatedPermutation,
 };
 use p3_uni_stark::{prove, verify, StarkConfig, StarkGenericConfig, Val};
 use rand::distributions::{Distribution, Standard};
@@ -217,6 +221,8 @@ fn do_test_bb_twoadic(log_blowup: usize, degree: u64, log_n: usize) -> Result<()
     let fri_config = FriConfig {
         log_blowup,
         log_final_poly_len: 5,
+        log_folding_factor: 1,
+        folding_schedule: vec![],
         num_queries: 40,
         proof_of_work_bits: 8,
         mmcs: challenge_mmcs,
@@ -255,6 +261,71 @@ fn prove_bb_twoadic_deg5() -> Result<(), impl Debug> {
     do_test_bb_twoadic(2, 5, 6)
 }
 
+fn do_test_goldilocks_quintic(
+    log_blowup: usize,
+    degree: u64,
+    log_n: usize,
+) -> Result<(), impl Debug> {
+    type Val = Goldilocks;
+    type Challenge = BinomialExtensionField<Val, 5>;
+
+    type ByteHash = Keccak256Hash;
+    type FieldHash = SerializingHasher64<ByteHash>;
+    let byte_hash = ByteHash {};
+    let field_hash = FieldHash::new(byte_hash);
+
+    type MyCompress = CompressionFunctionFromHasher<ByteHash, 2, 32>;
+    let compress = MyCompress::new(byte_hash);
+
+    type ValMmcs = MerkleTreeMmcs<Val, u8, FieldHash, MyCompress, 32>;
+    let val_mmcs = ValMmcs::new(field_hash, compress);
+
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+    type Dft = Radix2DitParallel<Val>;
+    let dft = Dft::default();
+
+    type Challenger = SerializingChallenger64<Val, HashChallenger<u8, ByteHash, 32>>;
+
+    let fri_config = FriConfig {
+        log_blowup,
+        log_final_poly_len: 3,
+        log_folding_factor: 1,
+        folding_schedule: vec![],
+        num_queries: 40,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+    let pcs = Pcs::new(dft, val_mmcs, fri_config);
+
+    type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+    let config = MyConfig::new(pcs);
+
+    let air = MulAir {
+        degree,
+        ..Default::default()
+    };
+
+    do_test(
+        config,
+        air,
+        1 << log_n,
+        Challenger::from_hasher(vec![], byte_hash),
+    )
+}
+
+#[test]
+fn prove_goldilocks_quintic_deg2() -> Result<(), impl Debug> {
+    do_test_goldilocks_quintic(1, 2, 7)
+}
+
+#[test]
+fn prove_goldilocks_quintic_deg3() -> Result<(), impl Debug> {
+    do_test_goldilocks_quintic(1, 3, 7)
+}
+
 fn do_test_m31_circle(log_blowup: usize, degree: u64, log_n: usize) -> Result<(), impl Debug> {
     type Val = Mersenne31;
     type Challenge = BinomialExtensionField<Val, 3>;
@@ -278,6 +349,8 @@ fn do_test_m31_circle(log_blowup: usize, degree: u64, log_n: usize) -> Result<()
     let fri_config = FriConfig {
         log_blowup,
         log_final_poly_len: 0,
+        log_folding_factor: 1,
+        folding_schedule: vec![],
         num_queries: 40,
         proof_of_work_bits: 8,
         mmcs: challenge_mmcs,